@@ -0,0 +1,271 @@
+//! Checks local batch definitions before they reach Interface5.
+//!
+//! ```text
+//! i5req validate [--api-version v1|v2] [--strict] <file>...
+//! i5req lint [--api-version v1|v2] [--strict] <dir>...
+//! ```
+//!
+//! `validate` checks the given JSON or YAML batch definitions, `lint` every `.json`,
+//! `.yaml` and `.yml` file below the given folders. Every definition is validated with
+//! the rules of the API version and its JSON is checked against the schema bundled
+//! for it, if any.
+//!
+//! Exits with 0 if every definition is valid, 1 if one is invalid and 2 if the command
+//! line is wrong or a definition cannot be read.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use i5_req::types::{
+    i5_api_version::I5ApiVersion, i5_error::I5RequestError, i5_request::I5Request,
+    i5_validation::I5ValidationOptions,
+};
+
+const USAGE: &str = "\
+usage: i5req validate [--api-version v1|v2] [--strict] <file>...
+       i5req lint [--api-version v1|v2] [--strict] <dir>...";
+
+/// The result of checking one definition.
+enum Outcome {
+    Valid,
+    Invalid(Vec<String>),
+    Unreadable(I5RequestError),
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    exit_code(&args)
+}
+
+/// Runs the command line `args` and reports command line errors.
+fn exit_code(args: &[String]) -> ExitCode {
+    match run(args) {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("i5req: {}\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<ExitCode, String> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return Ok(ExitCode::SUCCESS);
+    }
+    let (command, args) = args.split_first().ok_or("missing command")?;
+    let lint = match command.as_str() {
+        "validate" => false,
+        "lint" => true,
+        other => return Err(format!("unknown command '{}'", other)),
+    };
+    let mut version = I5ApiVersion::default();
+    let mut strict = false;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--api-version" => {
+                let value = args.next().ok_or("--api-version needs a value")?;
+                version = value.parse().map_err(|e: I5RequestError| e.to_string())?;
+            }
+            "--strict" => strict = true,
+            option if option.starts_with('-') => {
+                return Err(format!("unknown option '{}'", option));
+            }
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    if paths.is_empty() {
+        return Err(String::from("no paths given"));
+    }
+
+    let files = if lint {
+        let mut files = Vec::new();
+        for dir in &paths {
+            collect(dir, &mut files).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        files
+    } else {
+        paths
+    };
+
+    let options = I5ValidationOptions::for_version(version).strict_names(strict);
+    let (mut invalid, mut unreadable) = (0, 0);
+    for file in &files {
+        match check(file, version, &options) {
+            Outcome::Valid => println!("{}: ok", file.display()),
+            Outcome::Invalid(issues) => {
+                invalid += 1;
+                println!("{}: invalid", file.display());
+                for issue in issues {
+                    println!("  {}", issue);
+                }
+            }
+            Outcome::Unreadable(err) => {
+                unreadable += 1;
+                println!("{}: error", file.display());
+                println!("  {}", err);
+            }
+        }
+    }
+    println!(
+        "{} checked, {} valid, {} invalid, {} unreadable",
+        files.len(),
+        files.len() - invalid - unreadable,
+        invalid,
+        unreadable
+    );
+
+    Ok(if unreadable > 0 {
+        ExitCode::from(2)
+    } else if invalid > 0 {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Adds the batch definitions below `dir` to `files`, sorted by path.
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect(&path, files)?;
+        } else if is_definition(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_definition(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            ["json", "yaml", "yml"]
+                .iter()
+                .any(|known| extension.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Reads the definition at `path` and checks its structure and contract.
+fn check(path: &Path, version: I5ApiVersion, options: &I5ValidationOptions) -> Outcome {
+    let request = match read(path) {
+        Ok(request) => request,
+        Err(err) => return Outcome::Unreadable(err),
+    };
+    let report = request.validation_report_with(options);
+    if !report.is_valid() {
+        return Outcome::Invalid(report.issues().iter().map(|i| i.to_string()).collect());
+    }
+    let validated = match request.validate_with(options) {
+        Ok(validated) => validated,
+        Err(err) => return Outcome::Unreadable(err),
+    };
+    if version.schema().is_none() {
+        return Outcome::Valid;
+    }
+    match validated.check_contract(version) {
+        Ok(()) => Outcome::Valid,
+        Err(I5RequestError::ContractError(violations)) => {
+            Outcome::Invalid(violations.split("; ").map(String::from).collect())
+        }
+        Err(err) => Outcome::Unreadable(err),
+    }
+}
+
+fn read(path: &Path) -> Result<I5Request, I5RequestError> {
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    if !is_json {
+        return I5Request::from_yaml_file(path);
+    }
+    let json = fs::read(path).map_err(I5RequestError::IoError)?;
+    let value: serde_json::Value = serde_json::from_slice(&json)
+        .map_err(|e| I5RequestError::ConversionError(format!("invalid JSON: {}", e)))?;
+    I5Request::try_from(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A folder of its own below the temporary directory, with the given files.
+    fn folder(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("i5req-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (file, content) in files {
+            fs::write(dir.join(file), content).unwrap();
+        }
+        dir
+    }
+
+    fn exit(args: &[&str]) -> ExitCode {
+        exit_code(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    const VALID: &str =
+        "name: Batch\ndocuments:\n  - name: Invoice\n    fields:\n      No: '4711'\n";
+    const INVALID: &str = "name: Batch\ndocuments: []\n";
+
+    #[test]
+    fn help_succeeds_without_paths() {
+        assert_eq!(exit(&["--help"]), ExitCode::SUCCESS);
+        assert_eq!(exit(&["-h"]), ExitCode::SUCCESS);
+        assert_eq!(exit(&["validate", "--help"]), ExitCode::SUCCESS);
+        assert_eq!(exit(&["lint", "--strict", "-h"]), ExitCode::SUCCESS);
+    }
+
+    #[test]
+    fn valid_definitions_exit_with_0() {
+        let dir = folder("valid", &[("batch.yaml", VALID)]);
+        assert_eq!(
+            exit(&["validate", dir.join("batch.yaml").to_str().unwrap()]),
+            ExitCode::SUCCESS
+        );
+        assert_eq!(exit(&["lint", dir.to_str().unwrap()]), ExitCode::SUCCESS);
+    }
+
+    #[test]
+    fn invalid_definitions_exit_with_1() {
+        let dir = folder("invalid", &[("a.yaml", VALID), ("b.yml", INVALID)]);
+        assert_eq!(
+            exit(&["validate", dir.join("b.yml").to_str().unwrap()]),
+            ExitCode::from(1)
+        );
+        assert_eq!(exit(&["lint", dir.to_str().unwrap()]), ExitCode::from(1));
+    }
+
+    #[test]
+    fn usage_and_read_errors_exit_with_2() {
+        let dir = folder("unreadable", &[("batch.json", "{")]);
+        assert_eq!(exit(&[]), ExitCode::from(2));
+        assert_eq!(exit(&["check", "batch.yaml"]), ExitCode::from(2));
+        assert_eq!(exit(&["validate"]), ExitCode::from(2));
+        assert_eq!(
+            exit(&["validate", "--bogus", "batch.yaml"]),
+            ExitCode::from(2)
+        );
+        assert_eq!(
+            exit(&["validate", "--api-version", "v9", "batch.yaml"]),
+            ExitCode::from(2)
+        );
+        assert_eq!(
+            exit(&["validate", dir.join("batch.json").to_str().unwrap()]),
+            ExitCode::from(2)
+        );
+        assert_eq!(
+            exit(&["lint", dir.join("missing").to_str().unwrap()]),
+            ExitCode::from(2)
+        );
+    }
+}
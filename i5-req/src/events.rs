@@ -0,0 +1,23 @@
+//! Crate internal event macro.
+//!
+//! Key events are always emitted through `tracing`. With the `log` feature the same
+//! events are additionally emitted through the `log` facade (target `i5_req`), with the
+//! fields rendered as `key=value` pairs after the message.
+
+/// Emits an event at the given level, e.g.
+/// `i5_event!(warn, "Interface5 rejected the request", status = status.as_u16())`.
+///
+/// Field values must implement `Display`.
+macro_rules! i5_event {
+    ($level:ident, $message:literal $(, $key:ident = $value:expr)* $(,)?) => {{
+        tracing::$level!($($key = %$value,)* $message);
+        #[cfg(feature = "log")]
+        log::$level!(
+            target: "i5_req",
+            concat!($message $(, " ", stringify!($key), "={}")*)
+            $(, $value)*
+        );
+    }};
+}
+
+pub(crate) use i5_event;
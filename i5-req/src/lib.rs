@@ -1,2 +1,32 @@
+//! A client for the Interface5 web service input.
+//!
+//! Requests are built with [`I5Request`], checked with [`I5Request::validate`] and
+//! sent with an [`I5Client`] or the functions in [`request`] and [`request::blocking`].
+//! The most common types are re-exported at the crate root and in [`prelude`].
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+compile_error!("i5-req needs a TLS backend, enable the `native-tls` or the `rustls` feature");
+
+mod events;
+mod macros;
+pub mod prelude;
 pub mod request;
+mod schema;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod toml;
 pub mod types;
+
+mod util;
+mod xml;
+mod yaml;
+
+pub use crate::{
+    request::client::I5Client,
+    types::{
+        i5_config::I5Config,
+        i5_error::I5RequestError,
+        i5_request::{Document, I5Request, ValidatedI5Request},
+        i5_request_url::I5RequestUrl,
+    },
+};
@@ -0,0 +1,83 @@
+//! Declarative macros for building requests.
+
+/// Builds an [`I5Request`](crate::types::i5_request::I5Request) inline.
+///
+/// The batch name is followed by its documents. Each document may contain, in this
+/// order, `header` fields, `items` (numbered from 1) and `files` read from disk. The
+/// number of fields is counted at compile time and reserved up front.
+///
+/// The macro evaluates to `Result<I5Request, I5RequestError>`; it fails with
+/// [`I5RequestError::IoError`](crate::types::i5_error::I5RequestError::IoError) if a
+/// file cannot be read. The request is not validated.
+///
+/// # Example
+///
+/// ```rust
+/// let request = i5_req::i5_request!("Batch" => {
+///     "Invoice" => {
+///         header: { "InvoiceNo" => "4711", "Currency" => "EUR" },
+///         items: [
+///             { "Article" => "A-1", "Quantity" => "2" },
+///             { "Article" => "B-7", "Quantity" => "1" },
+///         ],
+///     },
+///     "DeliveryNote" => {
+///         header: { "DeliveryNo" => format!("L-{}", 4711) },
+///     },
+/// })?;
+///
+/// assert_eq!(request.documents().len(), 2);
+/// assert_eq!(request.documents()[0].max_item_number(), 2);
+/// assert!(request.is_valid());
+///
+/// let missing = i5_req::i5_request!("Batch" => {
+///     "Invoice" => { files: ["does/not/exist.pdf"] },
+/// });
+/// assert!(missing.is_err());
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+#[macro_export]
+macro_rules! i5_request {
+    ($batch:expr => {
+        $($document:expr => {
+            $(header: { $($header_name:expr => $header_value:expr),* $(,)? } $(,)?)?
+            $(items: [ $({ $($item_name:expr => $item_value:expr),* $(,)? }),* $(,)? ] $(,)?)?
+            $(files: [ $($file:expr),* $(,)? ] $(,)?)?
+        }),* $(,)?
+    }) => {
+        (|| -> ::core::result::Result<
+            $crate::types::i5_request::I5Request,
+            $crate::types::i5_error::I5RequestError,
+        > {
+            let mut request = $crate::types::i5_request::I5Request::new($batch);
+            $(
+                let index = request.add_document($document);
+                let document = request
+                    .get_document_mut(index)
+                    .expect("the document was just added");
+                document.reserve_fields(
+                    $crate::__i5_count!($($(($header_name))*)? $($($(($item_name))*)*)?),
+                );
+                $($(document.add_header_field($header_name, $header_value);)*)?
+                $(
+                    let mut item_number = 0;
+                    $(
+                        item_number += 1;
+                        $(document.add_item_field($item_name, $item_value, item_number);)*
+                    )*
+                    let _ = item_number;
+                )?
+                $($(document.add_path_file($file)?;)*)?
+            )*
+            Ok(request)
+        })()
+    };
+}
+
+/// Counts the token trees passed to it as a constant expression.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __i5_count {
+    () => { 0usize };
+    ($head:tt $($tail:tt)*) => { 1usize + $crate::__i5_count!($($tail)*) };
+}
@@ -0,0 +1,31 @@
+//! The types needed to build, validate and send a request.
+//!
+//! ```rust
+//! use i5_req::prelude::*;
+//!
+//! let mut request = I5Request::new("Batch");
+//! let id = request.add_document("Invoice");
+//! request
+//!     .get_document_mut(id)
+//!     .unwrap()
+//!     .add_header_field("InvoiceNo", "4711")
+//!     .add_amount_field("Total", I5Amount::new(12345, 2), &I5AmountFormat::german());
+//! let validated: ValidatedI5Request = request.validate()?;
+//! # Ok::<(), I5RequestError>(())
+//! ```
+
+pub use crate::{
+    request::{blocking::BlockingI5Client, client::I5Client, options::I5SendOptions},
+    types::{
+        i5_amount::{I5Amount, I5AmountFormat},
+        i5_auth::I5Auth,
+        i5_batch_name::I5BatchNamer,
+        i5_config::I5Config,
+        i5_date::{I5Date, I5DateFormat},
+        i5_error::I5RequestError,
+        i5_field_value::I5FieldValue,
+        i5_receipt::I5BatchReceipt,
+        i5_request::{Document, I5Request, ValidatedI5Request},
+        i5_request_url::I5RequestUrl,
+    },
+};
@@ -1,25 +1,484 @@
-use reqwest::blocking::Response;
+use reqwest::{ResponseBuilderExt, Url, blocking::Response};
+use std::{
+    borrow::Borrow,
+    io,
+    path::Path,
+    pin::pin,
+    sync::{Arc, OnceLock},
+    task::{Context, Poll, Wake, Waker},
+    thread,
+    time::{Duration, Instant},
+};
 
-use crate::types::{
-    i5_error::I5RequestError, i5_request::ValidatedI5Request, i5_request_url::I5RequestUrl,
+use crate::{
+    request::{
+        self, Pooled,
+        client::{self, I5Client},
+        decompress::ResponseDecoding,
+        options::I5SendOptions,
+        timing::TimingResolver,
+        transport::{I5Transport, I5TransportRequest, I5TransportResponse, configure_http_client},
+        wire,
+    },
+    types::{
+        i5_batch_set::{I5BatchSetOutcome, ValidatedI5BatchSet},
+        i5_config::{DEFAULT_MAX_REQUEST_SIZE, I5Config},
+        i5_discovery::{I5DocumentClass, I5Scenario, I5Tenant},
+        i5_error::I5RequestError,
+        i5_field_definition::I5FieldDefinitions,
+        i5_receipt::I5BatchReceipt,
+        i5_request::{I5Request, ValidatedI5Request},
+        i5_request_url::I5RequestUrl,
+        i5_tls_backend::I5TlsBackend,
+    },
 };
 
+/// Posts a validated request to Interface5 and blocks until the response arrives.
+///
+/// Returns [`I5RequestError::ServerError`] if Interface5 answers with a non-success
+/// status.
+///
+/// The request takes the same steps as with [`i5_http_post_with_options`]; the response
+/// is read before it is returned, so its body is already in memory. The HTTP client is
+/// created on first use and shared by all calls with the same certificate policy.
 pub fn i5_http_post(
-    valid_body: ValidatedI5Request,
+    valid_body: impl Borrow<ValidatedI5Request>,
     url: I5RequestUrl,
     allow_untrusted_cert: bool,
 ) -> Result<Response, I5RequestError> {
-    let body = valid_body.to_json_string()?;
-    let client = reqwest::blocking::Client::builder()
+    let target = Url::parse(&url.to_url())
+        .map_err(|err| I5RequestError::ConfigError(format!("invalid URL: {}", err)))?;
+    let options = I5SendOptions::new()
+        .allow_untrusted_cert(allow_untrusted_cert)
+        .keep_raw_response(usize::MAX);
+    let receipt = i5_http_post_with_options(valid_body, url, &options)?;
+    let raw = receipt
+        .raw_response()
+        .expect("the raw response is kept for every accepted request");
+    let mut response = http::Response::builder().status(raw.status()).url(target);
+    for (name, value) in raw.headers() {
+        response = response.header(name, value);
+    }
+    let response = response
+        .body(raw.body().to_vec())
+        .expect("the status and headers come from a response");
+    Ok(Response::from(response))
+}
+
+/// Posts a validated request to Interface5 using the given [`I5SendOptions`] and blocks
+/// until the response arrives.
+///
+/// See [`crate::request::i5_http_post_with_options`] for the correlation ID handling.
+/// The HTTP client is shared like in [`i5_http_post`]; use a [`BlockingI5Client`] to
+/// send with a configuration.
+pub fn i5_http_post_with_options(
+    valid_body: impl Borrow<ValidatedI5Request>,
+    url: I5RequestUrl,
+    options: &I5SendOptions,
+) -> Result<I5BatchReceipt, I5RequestError> {
+    let transport = shared_transport(options.accepts_untrusted_cert())?;
+    block_on(request::submit(
+        transport,
+        valid_body.borrow(),
+        &url,
+        options,
+    ))
+}
+
+/// A reusable blocking Interface5 client, the blocking counterpart of [`I5Client`].
+///
+/// The client is created once from an [`I5Config`] and keeps its HTTP connections and
+/// its internal runtime between submissions. Like all blocking `reqwest` clients it
+/// must not be created or dropped within an asynchronous runtime.
+///
+/// Submissions, upload sessions and lookups take the same steps as with an
+/// [`I5Client`], only sent with a blocking HTTP client, so every setting of the
+/// configuration applies alike. The blocking HTTP client resolves hostnames on a
+/// runtime of its own, so [`I5TransferStats::dns`] is always `None`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use i5_req::{request::blocking::BlockingI5Client, types::i5_config::I5Config};
+///
+/// let client = BlockingI5Client::new(I5Config::new("i5.example.com", 43001, "Scan", "Default"))?;
+///
+/// for tenant in client.tenants()? {
+///     println!("{}", tenant.name());
+/// }
+/// for number in ["4711", "4712"] {
+///     let mut request = client.new_request()?;
+///     let id = request.add_document("Invoice");
+///     request.get_document_mut(id).unwrap().add_header_field("No", number);
+///     client.send(request.validate()?)?;
+/// }
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+///
+/// [`I5Client`]: crate::request::client::I5Client
+pub struct BlockingI5Client {
+    client: I5Client<BlockingTransport>,
+}
+
+impl BlockingI5Client {
+    /// Creates a client from the given configuration.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] if no host is configured or a
+    /// configured CA certificate cannot be loaded.
+    pub fn new(config: I5Config) -> Result<BlockingI5Client, I5RequestError> {
+        client::check_endpoint(&config)?;
+        let builder = configure_http_client!(reqwest::blocking::Client::builder(), &config)?;
+
+        let http = builder.build().map_err(I5RequestError::RequestError)?;
+        let transport = BlockingTransport {
+            http,
+            decoding: ResponseDecoding::new(&config),
+        };
+        Ok(BlockingI5Client {
+            client: I5Client::with_transport(config, transport),
+        })
+    }
+
+    /// Creates a client configured from `I5REQ_*` environment variables.
+    ///
+    /// See [`I5Config::from_env`] for the supported variables.
+    pub fn from_env() -> Result<BlockingI5Client, I5RequestError> {
+        BlockingI5Client::new(I5Config::from_env()?)
+    }
+
+    /// The configuration the client was created with.
+    pub fn config(&self) -> &I5Config {
+        self.client.config()
+    }
+
+    /// Creates a new empty request, see
+    /// [`I5Client::new_request`](crate::request::client::I5Client::new_request).
+    pub fn new_request(&self) -> Result<I5Request, I5RequestError> {
+        self.client.new_request()
+    }
+
+    /// Validates `request` and checks it against the configured maximum request size,
+    /// see [`I5Client::validate`](crate::request::client::I5Client::validate).
+    pub fn validate(&self, request: I5Request) -> Result<Vec<ValidatedI5Request>, I5RequestError> {
+        self.client.validate(request)
+    }
+
+    /// Opens a connection to the server ahead of the first submission, see
+    /// [`I5Client::warm_up`](crate::request::client::I5Client::warm_up).
+    pub fn warm_up(&self) -> Result<(), I5RequestError> {
+        block_on(self.client.warm_up())
+    }
+
+    /// Lists the tenants of the server, see
+    /// [`I5Client::tenants`](crate::request::client::I5Client::tenants).
+    pub fn tenants(&self) -> Result<Vec<I5Tenant>, I5RequestError> {
+        block_on(self.client.tenants())
+    }
+
+    /// Lists the scenarios of the configured tenant, see
+    /// [`I5Client::scenarios`](crate::request::client::I5Client::scenarios).
+    pub fn scenarios(&self) -> Result<Vec<I5Scenario>, I5RequestError> {
+        block_on(self.client.scenarios())
+    }
+
+    /// Lists the document classes of the configured scenario, see
+    /// [`I5Client::document_classes`](crate::request::client::I5Client::document_classes).
+    pub fn document_classes(&self) -> Result<Vec<I5DocumentClass>, I5RequestError> {
+        block_on(self.client.document_classes())
+    }
+
+    /// Fetches the index fields of `scenario` in the configured tenant, cached for the
+    /// configured time to live, see
+    /// [`I5Client::get_field_definitions`](crate::request::client::I5Client::get_field_definitions).
+    pub fn get_field_definitions(
+        &self,
+        scenario: &str,
+    ) -> Result<Arc<I5FieldDefinitions>, I5RequestError> {
+        block_on(self.client.get_field_definitions(scenario))
+    }
+
+    /// Posts a validated request to the configured endpoint and blocks until the
+    /// response arrives.
+    ///
+    /// Like [`I5Client::send`](crate::request::client::I5Client::send), the request
+    /// can be passed by value, by reference or in an [`Arc`].
+    pub fn send(
+        &self,
+        valid_body: impl Borrow<ValidatedI5Request>,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        self.send_with_options(valid_body, &I5SendOptions::new())
+    }
+
+    /// Posts a validated request to the configured endpoint using the given
+    /// [`I5SendOptions`] and blocks until the response arrives.
+    ///
+    /// The request is processed as configured, see
+    /// [`I5Client::send_with_options`](crate::request::client::I5Client::send_with_options).
+    pub fn send_with_options(
+        &self,
+        valid_body: impl Borrow<ValidatedI5Request>,
+        options: &I5SendOptions,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        let config = self.client.config();
+        let valid_body = client::prepare(config, valid_body.borrow())?;
+        client::check_size(config, &valid_body)?;
+        self.send_prepared(&valid_body, options)
+    }
+
+    /// Posts a request that went through `prepare` and `check_size`, retrying it as
+    /// configured.
+    fn send_prepared(
+        &self,
+        valid_body: &ValidatedI5Request,
+        options: &I5SendOptions,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        let config = self.client.config();
+        let options = options.clone().with_default_format(config.payload_format());
+        let Some(policy) = config.retry_policy() else {
+            return block_on(self.client.submit_authorized(valid_body, &options));
+        };
+
+        let correlation_id = options.resolve_correlation_id();
+        let mut options = options.correlation_id(correlation_id);
+        policy.start();
+        let (mut retries, mut delay) = (0, Duration::ZERO);
+        loop {
+            let error = match block_on(self.client.submit_authorized(valid_body, &options)) {
+                Err(error) => error,
+                result => return result,
+            };
+            delay = match policy.next_delay(retries, delay, &error) {
+                Some(delay) => delay,
+                None => return Err(error),
+            };
+            retries += 1;
+            options = options.with_retry_count(retries);
+            client::log_retry(retries, delay, &error);
+            thread::sleep(delay);
+        }
+    }
+
+    /// Posts a validated request from asynchronous code, on a thread of the blocking
+    /// pool of the current Tokio runtime.
+    ///
+    /// Blocking clients panic when they send on a thread of an asynchronous runtime,
+    /// so applications that mostly block but also run async tasks can use this
+    /// instead of [`BlockingI5Client::send_with_options`] there. Returns
+    /// [`I5RequestError::ConfigError`] if called outside of a Tokio runtime.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::{
+    ///     request::{blocking::BlockingI5Client, options::I5SendOptions},
+    ///     types::i5_request::ValidatedI5Request,
+    /// };
+    /// use std::sync::Arc;
+    ///
+    /// async fn forward(
+    ///     client: &Arc<BlockingI5Client>,
+    ///     request: ValidatedI5Request,
+    /// ) -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    ///     let receipt = client
+    ///         .send_blocking_on_runtime(request, I5SendOptions::new())
+    ///         .await?;
+    ///     println!("{}", receipt.correlation_id());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn send_blocking_on_runtime(
+        self: &Arc<Self>,
+        valid_body: ValidatedI5Request,
+        options: I5SendOptions,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        let runtime = tokio::runtime::Handle::try_current().map_err(|_| {
+            I5RequestError::ConfigError(String::from(
+                "send_blocking_on_runtime must be called within a Tokio runtime",
+            ))
+        })?;
+        let client = Arc::clone(self);
+        runtime
+            .spawn_blocking(move || client.send_with_options(valid_body, &options))
+            .await
+            .map_err(|err| match err.try_into_panic() {
+                Ok(panic) => std::panic::resume_unwind(panic),
+                Err(_) => I5RequestError::IoError(io::Error::new(
+                    io::ErrorKind::Interrupted,
+                    "the runtime shut down before the submission finished",
+                )),
+            })?
+    }
+
+    /// Loads a request staged with [`ValidatedI5Request::save`], validates it again and
+    /// posts it, see
+    /// [`I5Client::resend_from_file`](crate::request::client::I5Client::resend_from_file).
+    pub fn resend_from_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        self.resend_from_file_with_options(path, &I5SendOptions::new())
+    }
+
+    /// Loads a staged request and posts it using the given [`I5SendOptions`], see
+    /// [`I5Client::resend_from_file`](crate::request::client::I5Client::resend_from_file).
+    pub fn resend_from_file_with_options(
+        &self,
+        path: impl AsRef<Path>,
+        options: &I5SendOptions,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        let valid_body = client::load_staged(self.client.config(), path.as_ref())?;
+        self.send_with_options(valid_body, options)
+    }
+
+    /// Posts a validated request of any size, opening an upload session if it is too
+    /// large for a single submission, see
+    /// [`I5Client::send_large`](crate::request::client::I5Client::send_large).
+    pub fn send_large(
+        &self,
+        valid_body: impl Borrow<ValidatedI5Request>,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        self.send_large_with_options(valid_body, &I5SendOptions::new())
+    }
+
+    /// Posts a validated request of any size using the given [`I5SendOptions`], see
+    /// [`I5Client::send_large_with_options`](crate::request::client::I5Client::send_large_with_options).
+    pub fn send_large_with_options(
+        &self,
+        valid_body: impl Borrow<ValidatedI5Request>,
+        options: &I5SendOptions,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        let config = self.client.config();
+        let limit = config
+            .request_size_limit()
+            .unwrap_or(DEFAULT_MAX_REQUEST_SIZE);
+        let prepared = client::prepare(config, valid_body.borrow())?;
+        if prepared.estimated_encoded_size() as u64 <= limit {
+            client::check_size(config, &prepared)?;
+            return self.send_prepared(&prepared, options);
+        }
+        block_on(self.client.send_in_session(&prepared, options, limit))
+    }
+
+    /// Posts every request of `set`, one after the other, see
+    /// [`I5Client::send_batch_set`](crate::request::client::I5Client::send_batch_set).
+    pub fn send_batch_set(&self, set: &ValidatedI5BatchSet) -> I5BatchSetOutcome {
+        client::batch_set_outcome(
+            set,
+            set.requests()
+                .iter()
+                .map(|valid_body| self.send(valid_body)),
+        )
+    }
+}
+
+/// The [`I5Transport`] of a [`BlockingI5Client`], sending with a blocking HTTP client.
+///
+/// Its futures complete without ever waiting, so that the client can run the steps it
+/// shares with [`I5Client`] with [`block_on`] instead of a runtime.
+struct BlockingTransport {
+    http: reqwest::blocking::Client,
+    decoding: ResponseDecoding,
+}
+
+impl I5Transport for BlockingTransport {
+    async fn send(
+        &self,
+        request: I5TransportRequest,
+    ) -> Result<I5TransportResponse, I5RequestError> {
+        let request = self.decoding.prepare(request);
+        wire::request(
+            request.method().as_str(),
+            request.url(),
+            request.headers(),
+            request.body().in_memory(),
+            request.body().len(),
+        );
+        let mut builder = self.http.request(request.method().clone(), request.url());
+        for (name, value) in request.headers() {
+            builder = builder.header(name, value);
+        }
+
+        let started = Instant::now();
+        let res = builder
+            .body(reqwest::blocking::Body::from(request.into_body()))
+            .send()
+            .map_err(I5RequestError::RequestError)?;
+        let time_to_first_byte = started.elapsed();
+
+        let status = res.status().as_u16();
+        let mut headers: Vec<(String, String)> = res
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = res.bytes().map_err(I5RequestError::RequestError)?;
+        let body = self.decoding.decode(status, &mut headers, body)?;
+        wire::response(
+            status,
+            headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+            &body,
+        );
+        let mut response =
+            I5TransportResponse::new(status, body).time_to_first_byte(time_to_first_byte);
+        for (name, value) in headers {
+            response = response.with_header(name, value);
+        }
+        Ok(response)
+    }
+}
+
+/// Runs `future` to completion on the current thread.
+///
+/// Meant for the futures of [`BlockingTransport`], which do not wait on a runtime; the
+/// thread is parked only while a future is pending, until it is woken.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// The transports used by the free functions, one for verified and one for untrusted
+/// certificates.
+static SHARED_TRANSPORTS: [OnceLock<Pooled<BlockingTransport>>; 2] =
+    [OnceLock::new(), OnceLock::new()];
+
+/// The shared blocking transport for the given certificate policy, created on first
+/// use.
+fn shared_transport(
+    allow_untrusted_cert: bool,
+) -> Result<&'static Pooled<BlockingTransport>, I5RequestError> {
+    let shared = &SHARED_TRANSPORTS[usize::from(allow_untrusted_cert)];
+    if let Some(transport) = shared.get() {
+        return Ok(transport);
+    }
+    let backend = client::tls_backend(I5TlsBackend::compiled_default())?;
+    let mut builder = reqwest::blocking::Client::builder()
         .danger_accept_invalid_certs(allow_untrusted_cert)
-        .build()
-        .map_err(I5RequestError::RequestError)?;
-
-    let res = client
-        .post(url.to_url())
-        .header("Content-Type", "application/json")
-        .body(body)
-        .send()
-        .map_err(I5RequestError::RequestError)?;
-    Ok(res)
+        .dns_resolver(Arc::new(TimingResolver::default()));
+    for certificate in client::backend_root_certificates(backend)? {
+        builder = builder.add_root_certificate(certificate);
+    }
+    let http = builder.build().map_err(I5RequestError::RequestError)?;
+    Ok(shared.get_or_init(|| {
+        Pooled::new(BlockingTransport {
+            http,
+            decoding: ResponseDecoding::default(),
+        })
+    }))
 }
@@ -0,0 +1,363 @@
+//! Request bodies that are encoded while they are sent.
+//!
+//! A body of at least [`STREAMING_THRESHOLD`] bytes is not buffered before it is sent.
+//! The JSON around the files is written up front, and the files are encoded to base64
+//! chunk by chunk while the connection drains, so the first bytes hit the wire at
+//! once and memory stays flat for multi-gigabyte batches. The length of the body is
+//! known in advance from [`I5Request::estimated_encoded_size`], so it is sent with a
+//! `Content-Length` header rather than chunked transfer encoding.
+
+use base64::{Engine, engine::general_purpose};
+use bytes::Bytes;
+use std::{
+    convert::Infallible,
+    io::{self, Read},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use crate::types::{
+    i5_content_format::I5ContentFormat,
+    i5_error::I5RequestError,
+    i5_request::{FileData, I5Request, ValidatedI5Request},
+};
+
+/// The size in bytes of the JSON body from which a submission encodes the body while
+/// it is sent instead of buffering it first.
+///
+/// A body that is already [cached](ValidatedI5Request::body) is always sent from the
+/// cache, and so is every body when a failure dump is configured.
+pub const STREAMING_THRESHOLD: usize = 32 << 20;
+
+/// The size of the chunks handed to the connection.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The number of raw bytes encoded into one chunk of [`CHUNK_SIZE`] bytes.
+const RAW_CHUNK_SIZE: usize = CHUNK_SIZE / 4 * 3;
+
+/// The body of a submission.
+pub(crate) enum EncodedBody {
+    /// The serialized body.
+    Buffered(Bytes),
+    /// A body encoded while it is sent.
+    Streamed(BodyChunks),
+}
+
+impl EncodedBody {
+    /// The body of `valid_body`: buffered if it is cached, smaller than
+    /// [`STREAMING_THRESHOLD`] or `buffered` is set, and streamed otherwise.
+    pub(crate) fn of(
+        valid_body: &ValidatedI5Request,
+        buffered: bool,
+    ) -> Result<EncodedBody, I5RequestError> {
+        if let Some(body) = valid_body.cached_body() {
+            return Ok(EncodedBody::Buffered(body));
+        }
+        if buffered || valid_body.estimated_encoded_size() < STREAMING_THRESHOLD {
+            return valid_body.body().map(EncodedBody::Buffered);
+        }
+        Ok(EncodedBody::Streamed(BodyChunks::new(
+            valid_body.shared_request(),
+        )))
+    }
+
+    /// The JSON body of `valid_body`, sent from the cache if it is cached and encoded
+    /// while it is sent otherwise, which cannot fail.
+    pub(crate) fn unbuffered(valid_body: &ValidatedI5Request) -> EncodedBody {
+        match valid_body.cached_body() {
+            Some(body) => EncodedBody::Buffered(body),
+            None => EncodedBody::Streamed(BodyChunks::new(valid_body.shared_request())),
+        }
+    }
+
+    /// The body of `valid_body` in `format`. XML bodies are always buffered; JSON
+    /// bodies are encoded as described for [`EncodedBody::of`].
+    pub(crate) fn in_format(
+        valid_body: &ValidatedI5Request,
+        format: I5ContentFormat,
+        buffered: bool,
+    ) -> Result<EncodedBody, I5RequestError> {
+        match format {
+            I5ContentFormat::Json => EncodedBody::of(valid_body, buffered),
+            I5ContentFormat::Xml => Ok(EncodedBody::Buffered(Bytes::from(
+                valid_body.to_xml_string()?,
+            ))),
+        }
+    }
+
+    /// The JSON body to write to a failure dump, if the body is buffered. The dump is
+    /// JSON in every content format, so it can be redacted.
+    pub(crate) fn failure_dump(
+        &self,
+        valid_body: &ValidatedI5Request,
+        format: I5ContentFormat,
+    ) -> Result<Option<Bytes>, I5RequestError> {
+        match (self, format) {
+            (EncodedBody::Streamed(_), _) => Ok(None),
+            (EncodedBody::Buffered(body), I5ContentFormat::Json) => Ok(Some(body.clone())),
+            (EncodedBody::Buffered(_), _) => valid_body.body().map(Some),
+        }
+    }
+
+    /// The length of the body in bytes.
+    pub(crate) fn len(&self) -> u64 {
+        match self {
+            EncodedBody::Buffered(body) => body.len() as u64,
+            EncodedBody::Streamed(chunks) => chunks.remaining,
+        }
+    }
+
+    /// The body if it is in memory rather than encoded while it is sent.
+    pub(crate) fn in_memory(&self) -> Option<&[u8]> {
+        match self {
+            EncodedBody::Buffered(body) => Some(body),
+            EncodedBody::Streamed(_) => None,
+        }
+    }
+}
+
+impl From<EncodedBody> for reqwest::Body {
+    fn from(body: EncodedBody) -> reqwest::Body {
+        match body {
+            EncodedBody::Buffered(body) => reqwest::Body::from(body),
+            EncodedBody::Streamed(chunks) => reqwest::Body::wrap(chunks),
+        }
+    }
+}
+
+impl From<EncodedBody> for reqwest::blocking::Body {
+    fn from(body: EncodedBody) -> reqwest::blocking::Body {
+        match body {
+            EncodedBody::Buffered(body) => reqwest::blocking::Body::from(body),
+            EncodedBody::Streamed(chunks) => {
+                let len = chunks.remaining;
+                reqwest::blocking::Body::sized(ChunkReader::new(chunks), len)
+            }
+        }
+    }
+}
+
+/// A piece of the JSON body.
+enum Segment {
+    /// JSON text that is sent as is.
+    Text(Bytes),
+    /// Raw file content that is encoded to base64 when it is sent.
+    Raw(Bytes),
+}
+
+/// The JSON body of a request as a sequence of chunks of at most [`CHUNK_SIZE`] bytes.
+pub(crate) struct BodyChunks {
+    segments: Vec<Segment>,
+    segment: usize,
+    offset: usize,
+    remaining: u64,
+}
+
+impl BodyChunks {
+    /// Lays out the body of `request`. Only the JSON around the files is written;
+    /// base64 content supplied by the caller is shared with the request.
+    fn new(request: Arc<I5Request>) -> BodyChunks {
+        let mut layout = Layout::default();
+        layout.text(br#"{"Name":"#);
+        layout.json(request.name());
+        layout.text(br#","Documents":["#);
+        for (document_index, document) in request.documents().iter().enumerate() {
+            if document_index > 0 {
+                layout.text(b",");
+            }
+            layout.text(br#"{"Name":"#);
+            layout.json(document.name());
+            layout.text(br#","Fields":["#);
+            for (field_index, field) in document.fields().iter().enumerate() {
+                if field_index > 0 {
+                    layout.text(b",");
+                }
+                layout.json(field);
+            }
+            layout.text(br#"],"Files":["#);
+            for (file_index, file) in document.files().iter().enumerate() {
+                if file_index > 0 {
+                    layout.text(b",");
+                }
+                layout.text(br#"{"Name":"#);
+                layout.json(file.name());
+                layout.text(br#","Key":"#);
+                layout.json(&file.key());
+                layout.text(br#","Data":"#);
+                match file.data() {
+                    FileData::Base64(data) if !needs_escaping(data) => {
+                        layout.text(b"\"");
+                        layout.push(Segment::Text(Bytes::from_owner(SharedData {
+                            request: Arc::clone(&request),
+                            document: document_index,
+                            file: file_index,
+                        })));
+                        layout.text(b"\"");
+                    }
+                    FileData::Base64(data) => layout.json(data),
+                    FileData::Bytes(bytes) => {
+                        layout.text(b"\"");
+                        layout.push(Segment::Raw(bytes.clone()));
+                        layout.text(b"\"");
+                    }
+                }
+                layout.text(b"}");
+            }
+            layout.text(b"]}");
+        }
+        layout.text(b"]}");
+        layout.flush();
+
+        let remaining = layout
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.len() as u64,
+                Segment::Raw(bytes) => bytes.len().div_ceil(3) as u64 * 4,
+            })
+            .sum();
+        BodyChunks {
+            segments: layout.segments,
+            segment: 0,
+            offset: 0,
+            remaining,
+        }
+    }
+}
+
+impl Iterator for BodyChunks {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        let segment = self.segments.get(self.segment)?;
+        let (chunk, end, len) = match segment {
+            Segment::Text(text) => {
+                let end = text.len().min(self.offset + CHUNK_SIZE);
+                (text.slice(self.offset..end), end, text.len())
+            }
+            Segment::Raw(bytes) => {
+                let end = bytes.len().min(self.offset + RAW_CHUNK_SIZE);
+                let encoded = general_purpose::STANDARD.encode(&bytes[self.offset..end]);
+                (Bytes::from(encoded), end, bytes.len())
+            }
+        };
+        if end == len {
+            self.segment += 1;
+            self.offset = 0;
+        } else {
+            self.offset = end;
+        }
+        self.remaining -= chunk.len() as u64;
+        Some(chunk)
+    }
+}
+
+impl http_body::Body for BodyChunks {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Bytes>, Infallible>>> {
+        Poll::Ready(
+            self.get_mut()
+                .next()
+                .map(|chunk| Ok(http_body::Frame::data(chunk))),
+        )
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.segment == self.segments.len()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        http_body::SizeHint::with_exact(self.remaining)
+    }
+}
+
+/// Collects the segments of a body, merging adjacent JSON text.
+#[derive(Default)]
+struct Layout {
+    segments: Vec<Segment>,
+    pending: Vec<u8>,
+}
+
+impl Layout {
+    fn text(&mut self, text: &[u8]) {
+        self.pending.extend_from_slice(text);
+    }
+
+    fn json<T: serde::Serialize + ?Sized>(&mut self, value: &T) {
+        serde_json::to_writer(&mut self.pending, value)
+            .expect("strings and fields serialize to JSON");
+    }
+
+    /// Appends `segment` after the pending text, skipping it if it is empty.
+    fn push(&mut self, segment: Segment) {
+        self.flush();
+        let (Segment::Text(bytes) | Segment::Raw(bytes)) = &segment;
+        if !bytes.is_empty() {
+            self.segments.push(segment);
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.pending.is_empty() {
+            let text = std::mem::take(&mut self.pending);
+            self.segments.push(Segment::Text(Bytes::from(text)));
+        }
+    }
+}
+
+/// The base64 content of a file, kept alive by the request it belongs to.
+struct SharedData {
+    request: Arc<I5Request>,
+    document: usize,
+    file: usize,
+}
+
+impl AsRef<[u8]> for SharedData {
+    fn as_ref(&self) -> &[u8] {
+        match self.request.documents()[self.document].files()[self.file].data() {
+            FileData::Base64(data) => data.as_bytes(),
+            FileData::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+/// Returns `true` if `data` cannot be written between quotes as is.
+fn needs_escaping(data: &str) -> bool {
+    data.bytes()
+        .any(|byte| byte == b'"' || byte == b'\\' || byte < 0x20)
+}
+
+/// Reads the chunks of a body for a blocking request.
+struct ChunkReader {
+    chunks: BodyChunks,
+    current: Bytes,
+}
+
+impl ChunkReader {
+    fn new(chunks: BodyChunks) -> ChunkReader {
+        ChunkReader {
+            chunks,
+            current: Bytes::new(),
+        }
+    }
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        while self.current.is_empty() {
+            match self.chunks.next() {
+                Some(chunk) => self.current = chunk,
+                None => return Ok(0),
+            }
+        }
+        let len = buffer.len().min(self.current.len());
+        buffer[..len].copy_from_slice(&self.current.split_to(len));
+        Ok(len)
+    }
+}
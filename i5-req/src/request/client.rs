@@ -0,0 +1,1187 @@
+use futures_util::{Stream, StreamExt, stream};
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::{
+    events::i5_event,
+    request::{
+        Pooled, discovery,
+        options::I5SendOptions,
+        submit,
+        token::{self, TokenCache},
+        transport::{I5Body, I5HttpTransport, I5Transport, I5TransportRequest},
+        upload,
+    },
+    types::{
+        i5_auth::I5Auth,
+        i5_batch_name::{I5BatchNamer, I5UuidNamer},
+        i5_batch_set::{I5BatchSetOutcome, ValidatedI5BatchSet},
+        i5_config::{DEFAULT_MAX_REQUEST_SIZE, I5Config},
+        i5_discovery::{self, I5DocumentClass, I5Scenario, I5Tenant},
+        i5_error::I5RequestError,
+        i5_field_definition::I5FieldDefinitions,
+        i5_receipt::I5BatchReceipt,
+        i5_request::{I5Request, ValidatedI5Request},
+        i5_request_url::I5RequestUrl,
+        i5_secret::I5Secret,
+        i5_tls_backend::I5TlsBackend,
+        i5_tls_version::I5TlsVersion,
+        i5_validation::I5ValidationOptions,
+    },
+    util,
+};
+
+/// A reusable asynchronous Interface5 client.
+///
+/// The client is created once from an [`I5Config`] and keeps its HTTP connections open
+/// between submissions. The HTTP calls go through an [`I5Transport`], by default an
+/// [`I5HttpTransport`]; see [`I5Client::with_transport`] to supply another one.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use i5_req::{request::client::I5Client, types::i5_request::I5Request};
+///
+/// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+/// let client = I5Client::from_env()?;
+///
+/// let mut request = I5Request::new("Batch");
+/// let id = request.add_document("Invoice");
+/// request.get_document_mut(id).unwrap().add_header_field("No", "4711");
+///
+/// let receipt = client.send(request.validate()?).await?;
+/// println!("{}", receipt.correlation_id());
+/// # Ok(())
+/// # }
+/// ```
+pub struct I5Client<T = I5HttpTransport> {
+    transport: Pooled<T>,
+    config: I5Config,
+    url: I5RequestUrl,
+    field_definitions: Mutex<HashMap<String, CachedDefinitions>>,
+    tokens: TokenCache,
+    in_flight: Option<InFlightBudget>,
+}
+
+/// Field definitions fetched from the server, and when.
+type CachedDefinitions = (Instant, Arc<I5FieldDefinitions>);
+
+/// The bytes concurrent submissions may keep in flight, see
+/// [`I5Config::max_in_flight_bytes`], counted in KiB.
+struct InFlightBudget {
+    permits: Semaphore,
+    total: u32,
+}
+
+impl InFlightBudget {
+    fn new(bytes: u64) -> InFlightBudget {
+        let max = Semaphore::MAX_PERMITS.min(u32::MAX as usize) as u64;
+        let total = bytes.div_ceil(1024).clamp(1, max) as u32;
+        InFlightBudget {
+            permits: Semaphore::new(total as usize),
+            total,
+        }
+    }
+
+    /// Waits until `bytes` fit into the budget, or the whole budget is free if they
+    /// exceed it.
+    async fn acquire(&self, bytes: usize) -> SemaphorePermit<'_> {
+        let permits = (bytes as u64)
+            .div_ceil(1024)
+            .clamp(1, u64::from(self.total)) as u32;
+        self.permits
+            .acquire_many(permits)
+            .await
+            .expect("the in-flight budget is never closed")
+    }
+}
+
+impl I5Client {
+    /// Creates a client from the given configuration.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] if no host is configured or a
+    /// configured CA certificate cannot be loaded.
+    pub fn new(config: I5Config) -> Result<I5Client, I5RequestError> {
+        let transport = I5HttpTransport::new(&config)?;
+        Ok(I5Client::with_transport(config, transport))
+    }
+
+    /// Creates a client configured from `I5REQ_*` environment variables.
+    ///
+    /// See [`I5Config::from_env`] for the supported variables.
+    pub fn from_env() -> Result<I5Client, I5RequestError> {
+        I5Client::new(I5Config::from_env()?)
+    }
+}
+
+impl<T: I5Transport> I5Client<T> {
+    /// Creates a client that sends its submissions through `transport`, e.g. a test
+    /// double or a tunnel. The certificate options of the configuration only apply to
+    /// transports that read them.
+    pub fn with_transport(config: I5Config, transport: T) -> I5Client<T> {
+        I5Client {
+            transport: Pooled::new(transport),
+            url: config.url(),
+            field_definitions: Mutex::default(),
+            tokens: TokenCache::default(),
+            in_flight: config.in_flight_limit().map(InFlightBudget::new),
+            config,
+        }
+    }
+
+    /// The transport the client sends its submissions through.
+    pub fn transport(&self) -> &T {
+        self.transport.inner()
+    }
+
+    /// The configuration the client was created with.
+    pub fn config(&self) -> &I5Config {
+        &self.config
+    }
+
+    /// Creates a new empty request named by the configured
+    /// [batch namer](I5Config::batch_namer), or by a random UUID if none is configured.
+    ///
+    /// Returns the error of the namer if it cannot generate a name.
+    pub fn new_request(&self) -> Result<I5Request, I5RequestError> {
+        new_request(&self.config)
+    }
+
+    /// Validates `request` and checks it against the configured
+    /// [maximum request size](I5Config::max_request_size).
+    ///
+    /// A request that fits, or any request if no maximum is configured, is returned as
+    /// the only element. A larger request is split by documents if
+    /// [enabled](I5Config::split_oversized_requests), and rejected with
+    /// [`I5RequestError::PayloadTooLarge`] otherwise, before anything is uploaded.
+    /// Fields added when sending, such as the configured
+    /// [default fields](I5Config::default_field), are checked again by
+    /// [`I5Client::send`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::{request::client::I5Client, types::i5_config::I5Config};
+    ///
+    /// let config = I5Config::new("i5.example.com", 43001, "Scan", "Default")
+    ///     .max_request_size(10_000)
+    ///     .split_oversized_requests(true);
+    /// let client = I5Client::new(config)?;
+    ///
+    /// let mut request = client.new_request()?;
+    /// for number in ["4711", "4712", "4713"] {
+    ///     let id = request.add_document("Invoice");
+    ///     let document = request.get_document_mut(id).unwrap();
+    ///     document.add_header_field("No", number);
+    ///     document.add_bytes_file("scan.pdf", &[0; 3000]);
+    /// }
+    ///
+    /// let parts = client.validate(request)?;
+    /// assert_eq!(parts.len(), 2);
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn validate(&self, request: I5Request) -> Result<Vec<ValidatedI5Request>, I5RequestError> {
+        validate(&self.config, request)
+    }
+
+    /// Opens a connection to the server ahead of the first submission, so that it does
+    /// not wait for the TCP and TLS handshakes, e.g. when a scan station starts.
+    ///
+    /// Sends `HEAD /api/{version}/Input` without credentials; whatever the status of
+    /// the response, the connection is kept open for the next submission as long as
+    /// the [idle timeout](I5Config::pool_idle_timeout) allows. Returns
+    /// [`I5RequestError::RequestError`] if the server cannot be reached.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::request::client::I5Client;
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let client = I5Client::from_env()?;
+    /// client.warm_up().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn warm_up(&self) -> Result<(), I5RequestError> {
+        let started = Instant::now();
+        let request = I5TransportRequest::new(
+            reqwest::Method::HEAD,
+            self.url.input_url(),
+            Vec::new(),
+            I5Body::empty(),
+        );
+        let response = self.transport.send(request).await?;
+        i5_event!(
+            debug,
+            "opened connection to Interface5",
+            status = response.status(),
+            elapsed_ms = started.elapsed().as_millis()
+        );
+        Ok(())
+    }
+
+    /// Lists the tenants of the server, e.g. to offer them in a configuration UI.
+    ///
+    /// Queries `GET /api/{version}/Input` with the configured credentials. Servers
+    /// that do not offer the lookup answer with [`I5RequestError::ServerError`],
+    /// usually with status `404`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::request::client::I5Client;
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let client = I5Client::from_env()?;
+    /// for tenant in client.tenants().await? {
+    ///     println!("{}", tenant.name());
+    /// }
+    /// for scenario in client.scenarios().await? {
+    ///     println!("{}: {}", scenario.name(), scenario.description().unwrap_or(""));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn tenants(&self) -> Result<Vec<I5Tenant>, I5RequestError> {
+        let auth = self.lookup_auth().await?;
+        let value =
+            discovery::get_json(&self.transport, self.url.input_url(), auth.as_ref()).await?;
+        Ok(i5_discovery::tenants(&value))
+    }
+
+    /// Lists the scenarios of the configured tenant, see [`I5Client::tenants`].
+    ///
+    /// Queries `GET /api/{version}/Input/{tenant}`.
+    pub async fn scenarios(&self) -> Result<Vec<I5Scenario>, I5RequestError> {
+        let url = format!(
+            "{}/{}",
+            self.url.input_url(),
+            util::percent_encode(self.url.tenant())
+        );
+        let auth = self.lookup_auth().await?;
+        let value = discovery::get_json(&self.transport, url, auth.as_ref()).await?;
+        Ok(i5_discovery::scenarios(&value))
+    }
+
+    /// Lists the document classes of the configured scenario, for scenarios that accept
+    /// several kinds of documents. The class of a document is set with
+    /// [`Document::set_class`](crate::types::i5_request::Document::set_class).
+    ///
+    /// Queries `GET /api/{version}/Input/{tenant}/{scenario}/DocumentClasses`. Servers
+    /// that do not offer the lookup answer with [`I5RequestError::ServerError`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::request::client::I5Client;
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let client = I5Client::from_env()?;
+    /// let classes = client.document_classes().await?;
+    ///
+    /// let mut request = client.new_request()?;
+    /// let id = request.add_document("Invoice");
+    /// if let Some(class) = classes.first() {
+    ///     request.get_document_mut(id).unwrap().set_class(class.name());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn document_classes(&self) -> Result<Vec<I5DocumentClass>, I5RequestError> {
+        let url = format!(
+            "{}/{}/{}/DocumentClasses",
+            self.url.input_url(),
+            util::percent_encode(self.url.tenant()),
+            util::percent_encode(self.url.scenario())
+        );
+        let auth = self.lookup_auth().await?;
+        let value = discovery::get_json(&self.transport, url, auth.as_ref()).await?;
+        Ok(i5_discovery::document_classes(&value))
+    }
+
+    /// Fetches the index fields of `scenario` in the configured tenant, e.g. to
+    /// [validate](I5FieldDefinitions::validate) requests against the live server
+    /// configuration.
+    ///
+    /// Queries `GET /api/{version}/Input/{tenant}/{scenario}/Fields`. The definitions
+    /// are cached per scenario for the configured
+    /// [time to live](I5Config::field_definitions_ttl), so the client can call this for
+    /// every request. Servers that do not offer the lookup answer with
+    /// [`I5RequestError::ServerError`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::request::client::I5Client;
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let client = I5Client::from_env()?;
+    /// let definitions = client.get_field_definitions(client.config().url().scenario()).await?;
+    ///
+    /// let mut request = client.new_request()?;
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("InvoiceNo", "4711");
+    ///
+    /// let report = definitions.validate(&request);
+    /// if !report.is_valid() {
+    ///     eprintln!("{}", report);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_field_definitions(
+        &self,
+        scenario: &str,
+    ) -> Result<Arc<I5FieldDefinitions>, I5RequestError> {
+        let ttl = self.config.field_definitions_max_age();
+        if let Some((fetched, definitions)) = self.cached_definitions().get(scenario)
+            && fetched.elapsed() < ttl
+        {
+            return Ok(definitions.clone());
+        }
+
+        let url = format!(
+            "{}/{}/{}/Fields",
+            self.url.input_url(),
+            util::percent_encode(self.url.tenant()),
+            util::percent_encode(scenario)
+        );
+        let auth = self.lookup_auth().await?;
+        let value = discovery::get_json(&self.transport, url, auth.as_ref()).await?;
+        let definitions = Arc::new(I5FieldDefinitions::from_json(&value));
+        self.cached_definitions()
+            .insert(scenario.to_string(), (Instant::now(), definitions.clone()));
+        Ok(definitions)
+    }
+
+    /// The options with the credentials of a call: the ones set in `options`, a bearer
+    /// token from the configured [token endpoint](I5Config::token_endpoint), or the
+    /// configured credentials. Also returns the token, if one is used.
+    async fn resolve_auth(
+        &self,
+        options: &I5SendOptions,
+    ) -> Result<(I5SendOptions, Option<I5Secret>), I5RequestError> {
+        if options.credentials().is_none()
+            && let Some(endpoint) = self.config.token_source()
+        {
+            let token = token::token(&self.tokens, &self.transport, endpoint).await?;
+            let auth = I5Auth::bearer(token.clone());
+            return Ok((options.with_default_auth(Some(&auth)), Some(token)));
+        }
+        Ok((options.with_default_auth(self.config.credentials()), None))
+    }
+
+    /// The credentials of lookups, see [`I5Client::resolve_auth`].
+    async fn lookup_auth(&self) -> Result<Option<I5Auth>, I5RequestError> {
+        let (options, _) = self.resolve_auth(&I5SendOptions::new()).await?;
+        Ok(options.credentials().cloned())
+    }
+
+    /// Submits a prepared request, requesting a new token and submitting once more if
+    /// Interface5 rejects the cached one with `401`.
+    pub(crate) async fn submit_authorized(
+        &self,
+        valid_body: &ValidatedI5Request,
+        options: &I5SendOptions,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        let (authorized, token) = self.resolve_auth(options).await?;
+        match (
+            submit(&self.transport, valid_body, &self.url, &authorized).await,
+            token,
+        ) {
+            (Err(I5RequestError::ServerError(error)), Some(token)) if error.status() == 401 => {
+                i5_event!(
+                    info,
+                    "Interface5 rejected the bearer token, requesting a new one"
+                );
+                self.tokens.invalidate(&token);
+                let (authorized, _) = self.resolve_auth(options).await?;
+                submit(&self.transport, valid_body, &self.url, &authorized).await
+            }
+            (result, _) => result,
+        }
+    }
+
+    fn cached_definitions(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedDefinitions>> {
+        self.field_definitions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Posts a validated request to the configured endpoint.
+    ///
+    /// The request can be passed by value, by reference or in an
+    /// [`Arc`](std::sync::Arc). Passing a reference keeps it available for another
+    /// target or a retry; its body is serialized once and reused for every submission
+    /// the configuration does not change.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::{request::client::I5Client, types::i5_config::I5Config};
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let production = I5Client::new(I5Config::new("i5.example.com", 43001, "Scan", "Default"))?;
+    /// let archive = I5Client::new(I5Config::new("i5.example.com", 43001, "Scan", "Archive"))?;
+    ///
+    /// let mut request = production.new_request()?;
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("No", "4711");
+    /// let validated = request.validate()?;
+    ///
+    /// production.send(&validated).await?;
+    /// archive.send(&validated).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send(
+        &self,
+        valid_body: impl Borrow<ValidatedI5Request>,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        self.send_with_options(valid_body, &I5SendOptions::new())
+            .await
+    }
+
+    /// Posts a validated request to the configured endpoint using the given
+    /// [`I5SendOptions`].
+    ///
+    /// Fields are renamed with the configured [mapping](I5Config::field_mapping), then
+    /// the configured [default fields](I5Config::default_field) are added to every
+    /// document, with their templates resolved, and the configured
+    /// [transformations](I5Config::transforms) and [sanitizer](I5Config::sanitizer)
+    /// are applied to all field values. Last, fields rejected by the configured
+    /// [rules](I5Config::field_rules) are removed.
+    ///
+    /// If a step changed the request, it is validated again with the options it was
+    /// validated with; this returns [`I5RequestError::ValidationError`] if the steps
+    /// left it invalid. A request above the configured
+    /// [maximum size](I5Config::max_request_size) is then rejected with
+    /// [`I5RequestError::PayloadTooLarge`].
+    ///
+    /// Credentials set in the options take precedence over a configured
+    /// [token endpoint](I5Config::token_endpoint), which takes precedence over the
+    /// configured credentials. The certificate options are always taken from the
+    /// configuration.
+    pub async fn send_with_options(
+        &self,
+        valid_body: impl Borrow<ValidatedI5Request>,
+        options: &I5SendOptions,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        let valid_body = prepare(&self.config, valid_body.borrow())?;
+        check_size(&self.config, &valid_body)?;
+        self.send_prepared(&valid_body, options).await
+    }
+
+    /// Posts a request that went through [`prepare`] and [`check_size`], retrying it
+    /// as configured.
+    async fn send_prepared(
+        &self,
+        valid_body: &ValidatedI5Request,
+        options: &I5SendOptions,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        let options = options
+            .clone()
+            .with_default_format(self.config.payload_format());
+        let Some(policy) = self.config.retry_policy() else {
+            return self.submit_authorized(valid_body, &options).await;
+        };
+
+        let correlation_id = options.resolve_correlation_id();
+        let mut options = options.correlation_id(correlation_id);
+        policy.start();
+        let (mut retries, mut delay) = (0, Duration::ZERO);
+        loop {
+            let error = match self.submit_authorized(valid_body, &options).await {
+                Err(error) => error,
+                result => return result,
+            };
+            delay = match policy.next_delay(retries, delay, &error) {
+                Some(delay) => delay,
+                None => return Err(error),
+            };
+            retries += 1;
+            options = options.with_retry_count(retries);
+            log_retry(retries, delay, &error);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Loads a request staged with [`ValidatedI5Request::save`], validates it again
+    /// with the rules of the [target API version](I5Config::target_api_version) and
+    /// posts it like [`I5Client::send`].
+    ///
+    /// This way, requests staged by one process, or before a configuration change,
+    /// are checked against the current rules before they reach Interface5. Returns the
+    /// errors of [`ValidatedI5Request::load_with`] if the file cannot be loaded.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::request::client::I5Client;
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let client = I5Client::from_env()?;
+    /// let receipt = client.resend_from_file("/var/spool/i5-req/4711.i5").await?;
+    /// println!("{}", receipt.correlation_id());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resend_from_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        self.resend_from_file_with_options(path, &I5SendOptions::new())
+            .await
+    }
+
+    /// Loads a staged request and posts it using the given [`I5SendOptions`], see
+    /// [`I5Client::resend_from_file`].
+    pub async fn resend_from_file_with_options(
+        &self,
+        path: impl AsRef<Path>,
+        options: &I5SendOptions,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        let valid_body = load_staged(&self.config, path.as_ref())?;
+        self.send_with_options(valid_body, options).await
+    }
+
+    /// Posts a validated request of any size, opening an upload session if it is too
+    /// large for a single submission and [upload sessions](I5Config::upload_sessions)
+    /// are enabled.
+    ///
+    /// Requests up to the configured [maximum size](I5Config::max_request_size) are
+    /// sent like with [`I5Client::send`]. Larger requests are rejected with
+    /// [`I5RequestError::PayloadTooLarge`] unless upload sessions are enabled, as they
+    /// are an assumed server extension.
+    /// With them, the attachments are uploaded in chunks of the configured
+    /// [size](I5Config::upload_chunk_size), then the batch is submitted without their
+    /// content. Every step carries the same correlation ID. A session that fails is
+    /// deleted on the server and not retried, so the request can be sent again as a
+    /// whole.
+    ///
+    /// Fields are processed as described for [`I5Client::send_with_options`] in both
+    /// cases.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::{request::client::I5Client, types::i5_config::I5Config};
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let config = I5Config::new("i5.example.com", 43001, "Scan", "Default")
+    ///     .max_request_size(16 << 20)
+    ///     .upload_sessions(true)
+    ///     .upload_chunk_size(4 << 20);
+    /// let client = I5Client::new(config)?;
+    ///
+    /// let mut request = client.new_request()?;
+    /// let id = request.add_document("Drawing");
+    /// request
+    ///     .get_document_mut(id)
+    ///     .unwrap()
+    ///     .add_header_field("No", "4711")
+    ///     .add_path_file("plans/site.tiff")?;
+    ///
+    /// let receipt = client.send_large(request.validate()?).await?;
+    /// println!("sent {} bytes", receipt.stats().bytes_sent());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_large(
+        &self,
+        valid_body: impl Borrow<ValidatedI5Request>,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        self.send_large_with_options(valid_body, &I5SendOptions::new())
+            .await
+    }
+
+    /// Posts a validated request of any size using the given [`I5SendOptions`], see
+    /// [`I5Client::send_large`]. Every step of an upload session is sent with the
+    /// credentials of the options, if set.
+    pub async fn send_large_with_options(
+        &self,
+        valid_body: impl Borrow<ValidatedI5Request>,
+        options: &I5SendOptions,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        let limit = self
+            .config
+            .request_size_limit()
+            .unwrap_or(DEFAULT_MAX_REQUEST_SIZE);
+        // Decide on the processed request, which the size check of `send` sees.
+        let prepared = prepare(&self.config, valid_body.borrow())?;
+        if prepared.estimated_encoded_size() as u64 <= limit {
+            check_size(&self.config, &prepared)?;
+            return self.send_prepared(&prepared, options).await;
+        }
+        self.send_in_session(&prepared, options, limit).await
+    }
+
+    /// Sends a prepared request above the single submission `limit` in an upload
+    /// session, see [`I5Client::send_large`].
+    ///
+    /// Returns [`I5RequestError::PayloadTooLarge`] if upload sessions are not enabled.
+    pub(crate) async fn send_in_session(
+        &self,
+        valid_body: &ValidatedI5Request,
+        options: &I5SendOptions,
+        limit: u64,
+    ) -> Result<I5BatchReceipt, I5RequestError> {
+        if !self.config.uses_upload_sessions() {
+            return Err(I5RequestError::PayloadTooLarge {
+                size: valid_body.estimated_encoded_size() as u64,
+                limit,
+            });
+        }
+        let (options, _) = self.resolve_auth(options).await?;
+        let options = options.with_default_format(self.config.payload_format());
+        i5_event!(
+            info,
+            "request exceeds the single submission limit, using an upload session",
+            request = valid_body.name(),
+            limit = limit
+        );
+        upload::send_in_session(
+            &self.transport,
+            valid_body,
+            &self.url,
+            options,
+            self.config.upload_chunk_bytes(),
+        )
+        .await
+    }
+
+    /// Posts the requests with at most `limit` submissions in flight and yields the
+    /// outcome of each as soon as it completes.
+    ///
+    /// Every item carries the index of its request in `requests`, since the outcomes
+    /// arrive in completion order. Requests are taken from `requests` only as
+    /// submissions finish, so a lazy iterator is never drained ahead of the server. A
+    /// `limit` of zero is treated as one. With a
+    /// [memory budget](I5Config::max_in_flight_bytes), fewer submissions are in flight
+    /// while large requests are sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use futures_util::StreamExt;
+    /// use i5_req::request::client::I5Client;
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let client = I5Client::from_env()?;
+    ///
+    /// let mut requests = Vec::new();
+    /// for number in ["4711", "4712", "4713"] {
+    ///     let mut request = client.new_request()?;
+    ///     let id = request.add_document("Invoice");
+    ///     request.get_document_mut(id).unwrap().add_header_field("No", number);
+    ///     requests.push(request.validate()?);
+    /// }
+    ///
+    /// let mut outcomes = client.send_concurrent(&requests, 2);
+    /// while let Some((index, outcome)) = outcomes.next().await {
+    ///     match outcome {
+    ///         Ok(receipt) => println!("{}: {}", index, receipt.correlation_id()),
+    ///         Err(err) => eprintln!("{}: {}", index, err),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn send_concurrent<'a, I>(
+        &'a self,
+        requests: I,
+        limit: usize,
+    ) -> impl Stream<Item = (usize, Result<I5BatchReceipt, I5RequestError>)> + 'a
+    where
+        I: IntoIterator,
+        I::IntoIter: 'a,
+        I::Item: Borrow<ValidatedI5Request> + 'a,
+    {
+        stream::iter(requests.into_iter().enumerate())
+            .map(move |(index, valid_body)| async move {
+                let outcome = async {
+                    // The budget is taken for the request as it is sent, after the
+                    // configured steps added, renamed or removed fields.
+                    let valid_body = prepare(&self.config, valid_body.borrow())?;
+                    check_size(&self.config, &valid_body)?;
+                    let _permit = match &self.in_flight {
+                        Some(budget) => {
+                            Some(budget.acquire(valid_body.estimated_encoded_size()).await)
+                        }
+                        None => None,
+                    };
+                    self.send_prepared(&valid_body, &I5SendOptions::new()).await
+                };
+                (index, outcome.await)
+            })
+            .buffer_unordered(limit.max(1))
+    }
+
+    /// Posts every request of `set` with at most `limit` submissions in flight, see
+    /// [`I5Client::send_concurrent`], and waits until all of them completed.
+    ///
+    /// A failed request does not stop the others; the outcome reports each request
+    /// with its name, in the order of the set.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::{request::client::I5Client, types::i5_batch_set::I5BatchSet};
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let client = I5Client::from_env()?;
+    ///
+    /// let mut set = I5BatchSet::new("export-2025-06-27");
+    /// for company in ["1000", "2000", "3000"] {
+    ///     let mut request = client.new_request()?;
+    ///     let id = request.add_document("Invoice");
+    ///     request.get_document_mut(id).unwrap().add_header_field("CompanyCode", company);
+    ///     set.push(request);
+    /// }
+    ///
+    /// let outcome = client.send_batch_set(&set.validate()?, 2).await;
+    /// for (name, err) in outcome.failures() {
+    ///     eprintln!("{}: {}", name, err);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_batch_set(
+        &self,
+        set: &ValidatedI5BatchSet,
+        limit: usize,
+    ) -> I5BatchSetOutcome {
+        let mut outcomes: Vec<_> = self.send_concurrent(set.requests(), limit).collect().await;
+        outcomes.sort_by_key(|(index, _)| *index);
+        batch_set_outcome(set, outcomes.into_iter().map(|(_, outcome)| outcome))
+    }
+}
+
+/// Pairs the `outcomes` of the requests of `set` with their names and logs failures.
+pub(crate) fn batch_set_outcome(
+    set: &ValidatedI5BatchSet,
+    outcomes: impl Iterator<Item = Result<I5BatchReceipt, I5RequestError>>,
+) -> I5BatchSetOutcome {
+    let outcomes: Vec<_> = set
+        .requests()
+        .iter()
+        .map(|valid_body| valid_body.request().name().to_string())
+        .zip(outcomes)
+        .collect();
+    let failed = outcomes
+        .iter()
+        .filter(|(_, outcome)| outcome.is_err())
+        .count();
+    if failed > 0 {
+        i5_event!(
+            warn,
+            "batch set submitted with failures",
+            set = set.name(),
+            failed = failed,
+            requests = outcomes.len()
+        );
+    }
+    I5BatchSetOutcome::new(set.name().to_string(), outcomes)
+}
+
+/// Loads the request staged at `path` and validates it with the rules of the target
+/// API version of `config`.
+pub(crate) fn load_staged(
+    config: &I5Config,
+    path: &Path,
+) -> Result<ValidatedI5Request, I5RequestError> {
+    let options = I5ValidationOptions::for_version(config.target_api_version());
+    let valid_body = ValidatedI5Request::load_with(path, &options)?;
+    i5_event!(
+        info,
+        "resending staged request",
+        request = valid_body.request().name(),
+        path = path.display()
+    );
+    Ok(valid_body)
+}
+
+/// Logs that a failed submission is retried for the `retry`th time after `delay`.
+pub(crate) fn log_retry(retry: u32, delay: Duration, error: &I5RequestError) {
+    i5_event!(
+        info,
+        "retrying submission to Interface5",
+        retry = retry,
+        delay_ms = delay.as_millis(),
+        error = error
+    );
+}
+
+/// Checks that `config` names a host to send to.
+///
+/// Returns [`I5RequestError::ConfigError`] for a configuration read from a file whose
+/// top-level settings only hold profiles.
+pub(crate) fn check_endpoint(config: &I5Config) -> Result<(), I5RequestError> {
+    if !config.url().hostname().is_empty() {
+        return Ok(());
+    }
+    Err(I5RequestError::ConfigError(
+        match config.profile_names().as_slice() {
+            [] => String::from("no host is configured"),
+            names => format!(
+                "no host is configured, select one of the profiles {} with I5Config::profile",
+                names.join(", ")
+            ),
+        },
+    ))
+}
+
+/// The TLS versions configured in `config`.
+///
+/// Returns [`I5RequestError::ConfigError`] if the minimum is above the maximum or
+/// cannot be enforced by the TLS backend.
+pub(crate) fn tls_versions(
+    config: &I5Config,
+) -> Result<(Option<reqwest::tls::Version>, Option<reqwest::tls::Version>), I5RequestError> {
+    let (min, max) = config.tls_versions();
+    if min == Some(I5TlsVersion::Tls1_3) && config.effective_tls_backend() != I5TlsBackend::Rustls {
+        return Err(I5RequestError::ConfigError(String::from(
+            "the native TLS backend cannot require TLS 1.3 as the minimum version",
+        )));
+    }
+    if let (Some(min), Some(max)) = (min, max)
+        && min > max
+    {
+        return Err(I5RequestError::ConfigError(format!(
+            "the minimum TLS version {} is above the maximum {}",
+            min.as_str(),
+            max.as_str()
+        )));
+    }
+    Ok((
+        min.map(I5TlsVersion::to_reqwest),
+        max.map(I5TlsVersion::to_reqwest),
+    ))
+}
+
+/// Loads the CA certificates configured in `config`.
+///
+/// Returns [`I5RequestError::ConfigError`] if a certificate cannot be loaded.
+pub(crate) fn root_certificates(
+    config: &I5Config,
+) -> Result<Vec<reqwest::Certificate>, I5RequestError> {
+    let mut certificates = Vec::new();
+    for path in config.root_certificates() {
+        certificates.extend(pem_certificates(path)?);
+    }
+    Ok(certificates)
+}
+
+/// The CA bundles of common Linux distributions and Alpine, in the order they are
+/// looked for.
+const SYSTEM_CA_BUNDLES: [&str; 5] = [
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/pki/tls/cacert.pem",
+    "/etc/ssl/cert.pem",
+];
+
+/// Checks that `backend` is compiled in and prepares it, installing the `ring`
+/// provider for rustls if no process-wide provider is installed.
+///
+/// Returns [`I5RequestError::ConfigError`] if the crate was built without the backend.
+pub(crate) fn tls_backend(backend: I5TlsBackend) -> Result<I5TlsBackend, I5RequestError> {
+    if !backend.is_available() {
+        return Err(I5RequestError::ConfigError(format!(
+            "the {} TLS backend requires the `{}` feature",
+            backend,
+            backend.feature()
+        )));
+    }
+    #[cfg(feature = "rustls")]
+    if backend == I5TlsBackend::Rustls {
+        // Fails if a provider is installed, which is then used instead.
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+    Ok(backend)
+}
+
+/// The CA certificates `backend` trusts besides the configured ones: for rustls,
+/// which does not read the system trust store, those of the system CA bundle at
+/// `SSL_CERT_FILE` or a [common location](SYSTEM_CA_BUNDLES).
+///
+/// Returns [`I5RequestError::ConfigError`] if the bundle cannot be loaded.
+pub(crate) fn backend_root_certificates(
+    backend: I5TlsBackend,
+) -> Result<Vec<reqwest::Certificate>, I5RequestError> {
+    if backend != I5TlsBackend::Rustls {
+        return Ok(Vec::new());
+    }
+    let bundle = env::var_os("SSL_CERT_FILE").map(PathBuf::from).or_else(|| {
+        SYSTEM_CA_BUNDLES
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.is_file())
+    });
+    match bundle {
+        Some(path) => pem_certificates(&path),
+        None => {
+            i5_event!(
+                warn,
+                "found no system CA bundle for rustls, trusting configured CA certificates only"
+            );
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Loads the certificates in the PEM file at `path`.
+///
+/// Returns [`I5RequestError::ConfigError`] if the file cannot be read or parsed.
+fn pem_certificates(path: &Path) -> Result<Vec<reqwest::Certificate>, I5RequestError> {
+    let pem = fs::read(path).map_err(|err| {
+        I5RequestError::ConfigError(format!(
+            "failed to read CA certificate {}: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    reqwest::Certificate::from_pem_bundle(&pem).map_err(|err| {
+        I5RequestError::ConfigError(format!(
+            "invalid CA certificate {}: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+/// Creates a new empty request named as configured in `config`.
+pub(crate) fn new_request(config: &I5Config) -> Result<I5Request, I5RequestError> {
+    let name = match config.next_batch_name()? {
+        Some(name) => name,
+        None => I5UuidNamer::default().next_name()?,
+    };
+    Ok(I5Request::new(name))
+}
+
+/// Validates `request` for `config`, see [`I5Client::validate`].
+pub(crate) fn validate(
+    config: &I5Config,
+    request: I5Request,
+) -> Result<Vec<ValidatedI5Request>, I5RequestError> {
+    let options = I5ValidationOptions::for_version(config.target_api_version());
+    let size = request.estimated_encoded_size() as u64;
+    match config.request_size_limit() {
+        Some(limit) if size > limit => {
+            if !config.splits_oversized_requests() {
+                return Err(I5RequestError::PayloadTooLarge { size, limit });
+            }
+            let parts = request.split_by_size(limit)?;
+            i5_event!(
+                info,
+                "split request exceeding the maximum request size",
+                size = size,
+                limit = limit,
+                parts = parts.len()
+            );
+            parts
+                .into_iter()
+                .map(|part| part.validate_with(&options))
+                .collect()
+        }
+        _ => Ok(vec![request.validate_with(&options)?]),
+    }
+}
+
+/// Validates `request` with the rules of the target API version of `config`, without
+/// splitting it.
+pub(crate) fn validate_for_target(
+    config: &I5Config,
+    request: I5Request,
+) -> Result<ValidatedI5Request, I5RequestError> {
+    request.validate_with(&I5ValidationOptions::for_version(
+        config.target_api_version(),
+    ))
+}
+
+/// Rejects a request above the maximum request size configured in `config`.
+pub(crate) fn check_size(
+    config: &I5Config,
+    valid_body: &ValidatedI5Request,
+) -> Result<(), I5RequestError> {
+    let size = valid_body.estimated_encoded_size() as u64;
+    match config.request_size_limit() {
+        Some(limit) if size > limit => Err(I5RequestError::PayloadTooLarge { size, limit }),
+        _ => Ok(()),
+    }
+}
+
+/// Runs the processing steps configured in `config` on a copy of a request before it
+/// is sent. The copy shares the request and its cached body until a step changes it.
+///
+/// A changed copy is validated again with the options the request was validated
+/// with, as a renamed field, a transformed value or a removed field can leave it
+/// invalid.
+pub(crate) fn prepare(
+    config: &I5Config,
+    valid_body: &ValidatedI5Request,
+) -> Result<ValidatedI5Request, I5RequestError> {
+    valid_body
+        .clone()
+        .with_field_mapping(config.field_mappings())
+        .with_default_header_fields(config.default_fields())?
+        .with_transforms(config.field_transforms())
+        .with_sanitizer(config.value_sanitizer())
+        .with_field_rules(config.field_inclusion_rules())
+        .revalidate(valid_body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::transport::I5TransportResponse;
+    use crate::types::{
+        i5_field_mapping::I5FieldMapping,
+        i5_field_rules::I5FieldRules,
+        i5_transform::{I5Transform, I5Transforms},
+    };
+    use std::sync::Mutex;
+
+    fn config() -> I5Config {
+        I5Config::new("localhost", 43001, "Scan", "Default")
+    }
+
+    #[test]
+    fn prepare_keeps_the_validation_options_of_the_request() {
+        let mut request = I5Request::new("Batch");
+        request.add_document("Placeholder");
+        let options = I5ValidationOptions::new().allow_empty_documents(true);
+        let validated = request.validate_with(&options).unwrap();
+
+        let config = config().field_rules(I5FieldRules::new().omit_if_empty("Note"));
+        assert!(prepare(&config, &validated).is_ok());
+    }
+
+    #[test]
+    fn prepare_rejects_a_request_the_rules_leave_invalid() {
+        let mut request = I5Request::new("Batch");
+        let id = request.add_document("Invoice");
+        request
+            .get_document_mut(id)
+            .unwrap()
+            .add_header_field("Note", "");
+        let validated = request.validate().unwrap();
+
+        let config = config().field_rules(I5FieldRules::new().omit_if_empty("Note"));
+        assert!(matches!(
+            prepare(&config, &validated),
+            Err(I5RequestError::ValidationError)
+        ));
+    }
+
+    #[test]
+    fn prepare_checks_mapped_names_with_the_stored_options() {
+        let mut request = I5Request::new("Batch");
+        let id = request.add_document("Invoice");
+        request
+            .get_document_mut(id)
+            .unwrap()
+            .add_header_field("No", "4711");
+        let validated = request
+            .validate_with(&I5ValidationOptions::strict())
+            .unwrap();
+
+        let mapping = I5FieldMapping::new().map("No", "Invoice No");
+        assert!(matches!(
+            prepare(&config().field_mapping(mapping), &validated),
+            Err(I5RequestError::ValidationError)
+        ));
+    }
+
+    #[test]
+    fn prepare_shares_an_unchanged_request() {
+        let mut request = I5Request::new("Batch");
+        let id = request.add_document("Invoice");
+        request
+            .get_document_mut(id)
+            .unwrap()
+            .add_header_field("No", "4711");
+        let validated = request.validate().unwrap();
+        validated.body().unwrap();
+
+        let prepared = prepare(&config(), &validated).unwrap();
+        assert!(prepared.cached_body().is_some());
+    }
+
+    #[test]
+    fn send_large_needs_upload_sessions_enabled() {
+        let mut request = I5Request::new("Batch");
+        let id = request.add_document("Drawing");
+        request
+            .get_document_mut(id)
+            .unwrap()
+            .add_bytes_file("site.tiff", &[0; 4096]);
+        let validated = request.validate().unwrap();
+
+        let client = I5Client::new(config().max_request_size(1024)).unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        match runtime.block_on(client.send_large(&validated)) {
+            Err(I5RequestError::PayloadTooLarge { size, limit: 1024 }) => assert!(size > 4096),
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    /// A transport that records the bodies instead of sending them.
+    #[derive(Default)]
+    struct Recording(Mutex<Vec<String>>);
+
+    impl I5Transport for Recording {
+        async fn send(
+            &self,
+            request: I5TransportRequest,
+        ) -> Result<I5TransportResponse, I5RequestError> {
+            let body = request.into_body().into_bytes();
+            self.0
+                .lock()
+                .unwrap()
+                .push(String::from_utf8_lossy(&body).into_owned());
+            Ok(I5TransportResponse::new(200, "{}"))
+        }
+    }
+
+    #[test]
+    fn send_large_processes_a_small_request_once() {
+        let mut request = I5Request::new("Batch");
+        let id = request.add_document("Invoice");
+        request
+            .get_document_mut(id)
+            .unwrap()
+            .add_header_field("No", "4711");
+        let validated = request.validate().unwrap();
+
+        // Applied twice, the transformation would send `xx4711`.
+        let transforms = I5Transforms::new().field(
+            "No",
+            [I5Transform::Replace {
+                from: String::from("4711"),
+                to: String::from("x4711"),
+            }],
+        );
+        let client =
+            I5Client::with_transport(config().transforms(transforms), Recording::default());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(client.send_large(&validated)).unwrap();
+
+        let bodies = client.transport().0.lock().unwrap();
+        assert_eq!(bodies.len(), 1);
+        assert!(bodies[0].contains(r#""Value":"x4711""#), "{}", bodies[0]);
+    }
+}
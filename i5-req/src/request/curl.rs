@@ -0,0 +1,73 @@
+use tracing::Span;
+
+use crate::{
+    request::{options::I5SendOptions, submission_headers},
+    types::{
+        i5_error::I5RequestError, i5_request::ValidatedI5Request, i5_request_url::I5RequestUrl,
+    },
+};
+
+impl ValidatedI5Request {
+    /// Builds a runnable `curl` command that sends exactly the request this crate would
+    /// send to `url` with the given `options`.
+    ///
+    /// The command uses the correlation ID from the options (or a newly generated one)
+    /// and quotes everything for POSIX shells, so it can be pasted into a terminal to
+    /// reproduce a failing submission. Credentials are never included: the
+    /// `Authorization` header contains a placeholder and the values of
+    /// [query parameters](I5RequestUrl::query) read `<redacted>`, which have to be
+    /// filled in by hand.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::{
+    ///     request::options::I5SendOptions,
+    ///     types::{i5_request::I5Request, i5_request_url::I5RequestUrl},
+    /// };
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("No", "4711");
+    ///
+    /// let url = I5RequestUrl::new("localhost", 43001, "Processor", "Default");
+    /// let options = I5SendOptions::new().correlation_id("ticket-42");
+    /// let curl = request.validate().unwrap().to_curl(&url, &options).unwrap();
+    ///
+    /// assert!(curl.starts_with("curl -X POST"));
+    /// assert!(curl.contains("-H 'X-Correlation-ID: ticket-42'"));
+    /// ```
+    pub fn to_curl(
+        &self,
+        url: &I5RequestUrl,
+        options: &I5SendOptions,
+    ) -> Result<String, I5RequestError> {
+        let body = self.body()?;
+        let body = String::from_utf8_lossy(&body);
+        let correlation_id = options.resolve_correlation_id();
+
+        let target = crate::util::redact_query(&url.to_url()).into_owned();
+        let mut command = format!("curl -X POST {}", shell_quote(&target));
+        if options.accepts_untrusted_cert() {
+            command.push_str(" \\\n  --insecure");
+        }
+        for (name, value) in submission_headers(options, &correlation_id, &Span::none()) {
+            let value = match options.credentials() {
+                Some(auth) if name.eq_ignore_ascii_case(auth.header_name()) => {
+                    auth.redacted_header_value()
+                }
+                _ => value,
+            };
+            command.push_str(" \\\n  -H ");
+            command.push_str(&shell_quote(&format!("{}: {}", name, value)));
+        }
+        command.push_str(" \\\n  --data-binary ");
+        command.push_str(&shell_quote(&body));
+        Ok(command)
+    }
+}
+
+/// Quotes `value` as a single POSIX shell word.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
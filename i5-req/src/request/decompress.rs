@@ -0,0 +1,645 @@
+//! Decoding of compressed response bodies.
+//!
+//! Some gateways in front of Interface5 compress their replies with `gzip` or
+//! `deflate`, whether or not the request listed them in `Accept-Encoding`. The HTTP
+//! transports decode such bodies before anything parses them, and drop the
+//! `Content-Encoding` and `Content-Length` headers that described the compressed body.
+//! Other encodings, e.g. `br`, are passed on as they are.
+//!
+//! The decoder implements DEFLATE (RFC 1951) with the zlib (RFC 1950) and gzip
+//! (RFC 1952) framings, and verifies their checksums.
+
+use bytes::Bytes;
+
+use crate::{
+    events::i5_event,
+    request::transport::I5TransportRequest,
+    types::{i5_config::I5Config, i5_error::I5RequestError},
+};
+
+/// The size a decoded body may reach, so that a small compressed body cannot exhaust
+/// memory.
+const MAX_DECODED_LEN: usize = 256 * 1024 * 1024;
+
+/// How a transport negotiates and decodes compressed responses.
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseDecoding {
+    accept_encoding: Option<String>,
+    decompress: bool,
+}
+
+impl Default for ResponseDecoding {
+    /// Decodes compressed responses without asking for them.
+    fn default() -> ResponseDecoding {
+        ResponseDecoding {
+            accept_encoding: None,
+            decompress: true,
+        }
+    }
+}
+
+impl ResponseDecoding {
+    /// The decoding configured in `config`.
+    pub(crate) fn new(config: &I5Config) -> ResponseDecoding {
+        ResponseDecoding {
+            accept_encoding: config.accepted_encoding().map(String::from),
+            decompress: config.decompresses_responses(),
+        }
+    }
+
+    /// `request` with the configured `Accept-Encoding` header, unless it sets one.
+    pub(crate) fn prepare(&self, request: I5TransportRequest) -> I5TransportRequest {
+        match &self.accept_encoding {
+            Some(value) if request.header("Accept-Encoding").is_none() => {
+                request.with_header("Accept-Encoding", value.clone())
+            }
+            _ => request,
+        }
+    }
+
+    /// Decodes `body` of a response with status `status` as described by its
+    /// `Content-Encoding` in `headers`, and removes the headers describing the
+    /// compressed body.
+    ///
+    /// Returns [`I5RequestError::UnexpectedResponse`] if the body is corrupt.
+    pub(crate) fn decode(
+        &self,
+        status: u16,
+        headers: &mut Vec<(String, String)>,
+        body: Bytes,
+    ) -> Result<Bytes, I5RequestError> {
+        if !self.decompress {
+            return Ok(body);
+        }
+        let Some(encoding) = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Content-Encoding"))
+            .map(|(_, value)| value.trim().to_ascii_lowercase())
+        else {
+            return Ok(body);
+        };
+        let decoded = match encoding.as_str() {
+            "gzip" | "x-gzip" => gunzip(&body),
+            "deflate" => inflate_zlib(&body),
+            "identity" => Ok(body.to_vec()),
+            _ => return Ok(body),
+        }
+        .map_err(|reason| I5RequestError::UnexpectedResponse {
+            status,
+            details: format!("the {} body cannot be decoded: {}", encoding, reason),
+        })?;
+        i5_event!(
+            debug,
+            "decoded compressed response",
+            encoding = encoding,
+            compressed_bytes = body.len(),
+            decoded_bytes = decoded.len()
+        );
+        headers.retain(|(name, _)| {
+            !name.eq_ignore_ascii_case("Content-Encoding")
+                && !name.eq_ignore_ascii_case("Content-Length")
+        });
+        Ok(Bytes::from(decoded))
+    }
+}
+
+/// Decodes the gzip members in `data`.
+fn gunzip(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let header = gzip_header_len(rest)?;
+        let start = out.len();
+        let consumed = inflate(&rest[header..], &mut out)?;
+        let trailer = rest
+            .get(header + consumed..header + consumed + 8)
+            .ok_or("the gzip trailer is missing")?;
+        let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+        if crc32(&out[start..]) != crc || (out.len() - start) as u32 != size {
+            return Err(String::from("the gzip checksum does not match"));
+        }
+        rest = &rest[header + consumed + 8..];
+    }
+    Ok(out)
+}
+
+/// The length of the gzip member header at the start of `data`.
+fn gzip_header_len(data: &[u8]) -> Result<usize, String> {
+    const FHCRC: u8 = 0x02;
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+
+    if data.len() < 10 || data[..3] != [0x1f, 0x8b, 8] {
+        return Err(String::from("not gzip data"));
+    }
+    let flags = data[3];
+    let mut len = 10;
+    if flags & FEXTRA != 0 {
+        let extra = data.get(len..len + 2).ok_or("truncated gzip header")?;
+        len += 2 + usize::from(u16::from_le_bytes([extra[0], extra[1]]));
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = data
+                .get(len..)
+                .and_then(|rest| rest.iter().position(|byte| *byte == 0))
+                .ok_or("truncated gzip header")?;
+            len += end + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        len += 2;
+    }
+    if len > data.len() {
+        return Err(String::from("truncated gzip header"));
+    }
+    Ok(len)
+}
+
+/// Decodes `data` as zlib data, or as raw DEFLATE data as some servers send for
+/// `deflate`.
+fn inflate_zlib(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let zlib = data.len() >= 2
+        && data[0] & 0x0f == 8
+        && data[1] & 0x20 == 0
+        && (u16::from(data[0]) << 8 | u16::from(data[1])) % 31 == 0;
+    if !zlib {
+        inflate(data, &mut out)?;
+        return Ok(out);
+    }
+    let consumed = inflate(&data[2..], &mut out)?;
+    let trailer = data
+        .get(2 + consumed..2 + consumed + 4)
+        .ok_or("the zlib trailer is missing")?;
+    if adler32(&out) != u32::from_be_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]) {
+        return Err(String::from("the zlib checksum does not match"));
+    }
+    Ok(out)
+}
+
+/// The CRC-32 of `data`, as used by gzip.
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+/// The Adler-32 checksum of `data`, as used by zlib.
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + u32::from(*byte)) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+/// The bits of a DEFLATE stream, least significant first.
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buffer: u64,
+    count: u32,
+}
+
+impl Bits<'_> {
+    fn take(&mut self, n: u32) -> Result<u32, String> {
+        while self.count < n {
+            let byte = *self
+                .data
+                .get(self.pos)
+                .ok_or("the compressed data ends early")?;
+            self.buffer |= u64::from(byte) << self.count;
+            self.pos += 1;
+            self.count += 8;
+        }
+        let value = (self.buffer & ((1 << n) - 1)) as u32;
+        self.buffer >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    /// Drops the bits up to the next byte boundary.
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+}
+
+/// A canonical Huffman code, as the number of codes of each length and the symbols
+/// in code order.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Huffman, String> {
+        let mut counts = [0u16; 16];
+        for length in lengths {
+            counts[usize::from(*length)] += 1;
+        }
+        counts[0] = 0;
+        let mut left = 1i32;
+        for count in &counts[1..] {
+            left = (left << 1) - i32::from(*count);
+            if left < 0 {
+                return Err(String::from("invalid Huffman code"));
+            }
+        }
+        let mut offsets = [0u16; 16];
+        for length in 1..15 {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, length) in lengths.iter().enumerate() {
+            if *length != 0 {
+                let offset = &mut offsets[usize::from(*length)];
+                symbols[usize::from(*offset)] = symbol as u16;
+                *offset += 1;
+            }
+        }
+        Ok(Huffman { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut Bits<'_>) -> Result<u16, String> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for count in &self.counts[1..] {
+            code |= bits.take(1)? as i32;
+            let count = i32::from(*count);
+            if code - count < first {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(String::from("invalid Huffman code"))
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// The order in which the lengths of the code length code are stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Decodes the DEFLATE stream at the start of `data` into `out`, and returns the
+/// number of bytes it took.
+fn inflate(data: &[u8], out: &mut Vec<u8>) -> Result<usize, String> {
+    let mut bits = Bits {
+        data,
+        pos: 0,
+        buffer: 0,
+        count: 0,
+    };
+    loop {
+        let last = bits.take(1)? == 1;
+        match bits.take(2)? {
+            0 => {
+                bits.align();
+                let header = data
+                    .get(bits.pos..bits.pos + 4)
+                    .ok_or("the compressed data ends early")?;
+                let len = u16::from_le_bytes([header[0], header[1]]);
+                if len != !u16::from_le_bytes([header[2], header[3]]) {
+                    return Err(String::from("invalid stored block length"));
+                }
+                let start = bits.pos + 4;
+                let stored = data
+                    .get(start..start + usize::from(len))
+                    .ok_or("the compressed data ends early")?;
+                if out.len() + stored.len() > MAX_DECODED_LEN {
+                    return Err(format!("decoded body exceeds {} bytes", MAX_DECODED_LEN));
+                }
+                out.extend_from_slice(stored);
+                bits.pos = start + usize::from(len);
+            }
+            1 => {
+                let mut lengths = [0u8; 288 + 30];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..288].fill(8);
+                lengths[288..].fill(5);
+                let literals = Huffman::new(&lengths[..288])?;
+                let distances = Huffman::new(&lengths[288..])?;
+                inflate_block(&mut bits, out, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(&mut bits)?;
+                inflate_block(&mut bits, out, &literals, &distances)?;
+            }
+            _ => return Err(String::from("invalid block type")),
+        }
+        if last {
+            return Ok(bits.pos);
+        }
+    }
+}
+
+/// Reads the Huffman codes of a block with dynamic codes.
+fn dynamic_codes(bits: &mut Bits<'_>) -> Result<(Huffman, Huffman), String> {
+    let literal_count = bits.take(5)? as usize + 257;
+    let distance_count = bits.take(5)? as usize + 1;
+    let code_length_count = bits.take(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err(String::from("too many codes"));
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[*index] = bits.take(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths)?;
+
+    let mut lengths = vec![0u8; literal_count + distance_count];
+    let mut index = 0;
+    while index < lengths.len() {
+        let symbol = code_length_code.decode(bits)?;
+        let (length, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths[..index]
+                    .last()
+                    .ok_or("a repeated code length has no predecessor")?;
+                (previous, 3 + bits.take(2)? as usize)
+            }
+            17 => (0, 3 + bits.take(3)? as usize),
+            _ => (0, 11 + bits.take(7)? as usize),
+        };
+        let repeated = lengths
+            .get_mut(index..index + repeat)
+            .ok_or("too many code lengths")?;
+        repeated.fill(length);
+        index += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(String::from("the block has no end code"));
+    }
+    Ok((
+        Huffman::new(&lengths[..literal_count])?,
+        Huffman::new(&lengths[literal_count..])?,
+    ))
+}
+
+/// Decodes the symbols of a block with Huffman codes into `out`.
+fn inflate_block(
+    bits: &mut Bits<'_>,
+    out: &mut Vec<u8>,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<(), String> {
+    loop {
+        let symbol = usize::from(literals.decode(bits)?);
+        if symbol < 256 {
+            out.push(symbol as u8);
+        } else if symbol == 256 {
+            return Ok(());
+        } else {
+            let symbol = symbol - 257;
+            if symbol >= LENGTH_BASE.len() {
+                return Err(String::from("invalid length code"));
+            }
+            let len = usize::from(LENGTH_BASE[symbol])
+                + bits.take(u32::from(LENGTH_EXTRA[symbol]))? as usize;
+            let symbol = usize::from(distances.decode(bits)?);
+            if symbol >= DISTANCE_BASE.len() {
+                return Err(String::from("invalid distance code"));
+            }
+            let distance = usize::from(DISTANCE_BASE[symbol])
+                + bits.take(u32::from(DISTANCE_EXTRA[symbol]))? as usize;
+            if distance > out.len() {
+                return Err(String::from("distance reaches before the start"));
+            }
+            let start = out.len() - distance;
+            for offset in 0..len {
+                out.push(out[start + offset]);
+            }
+        }
+        if out.len() > MAX_DECODED_LEN {
+            return Err(format!("decoded body exceeds {} bytes", MAX_DECODED_LEN));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO: &[u8] = b"hello hello hello";
+
+    /// `HELLO` as raw DEFLATE data in a single stored block.
+    const STORED: &[u8] = &[
+        0x01, 0x11, 0x00, 0xee, 0xff, b'h', b'e', b'l', b'l', b'o', b' ', b'h', b'e', b'l', b'l',
+        b'o', b' ', b'h', b'e', b'l', b'l', b'o',
+    ];
+
+    /// `HELLO` as gzip data with a single block with fixed codes.
+    const FIXED_GZIP: &[u8] = &[
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xcb, 0x48, 0xcd, 0xc9, 0xc9,
+        0x57, 0xc8, 0x40, 0x90, 0x00, 0x80, 0x88, 0xf9, 0xe5, 0x11, 0x00, 0x00, 0x00,
+    ];
+
+    /// `DYNAMIC_TEXT` as zlib data with a single block with dynamic codes.
+    const DYNAMIC_ZLIB: &[u8] = &[
+        0x78, 0xda, 0x45, 0x8c, 0x81, 0x0d, 0x00, 0x40, 0x04, 0x03, 0x67, 0x75, 0xdd, 0x7f, 0x07,
+        0x5a, 0xf2, 0x2f, 0x42, 0x73, 0xaa, 0x85, 0xa0, 0xa6, 0x70, 0x47, 0x69, 0x14, 0xd1, 0x1c,
+        0xc9, 0xe4, 0xad, 0x25, 0xe7, 0x8d, 0x4d, 0x01, 0x26, 0x5a, 0x8f, 0x81, 0x2a, 0x09, 0xfb,
+        0x05, 0x3f, 0xc3, 0x87, 0x06, 0xcc, 0x50, 0x2d, 0xae,
+    ];
+    const DYNAMIC_TEXT: &[u8] =
+        b"abcbbaaaabaababaaaacaabbbabaabbabaaaaabaaabaabaaabaaaaaababaaaacab\
+        aabbcaababbaaacbaaabacaabcaaabbaabaaabbbbaaabaabaacaaa";
+
+    /// The bits of a DEFLATE stream, the opposite of [`Bits`].
+    #[derive(Default)]
+    struct BitWriter {
+        data: Vec<u8>,
+        count: u32,
+    }
+
+    impl BitWriter {
+        /// Writes the `n` low bits of `value`, least significant first.
+        fn put(&mut self, value: u32, n: u32) {
+            for bit in 0..n {
+                if self.count.is_multiple_of(8) {
+                    self.data.push(0);
+                }
+                let last = self.data.len() - 1;
+                self.data[last] |= (((value >> bit) & 1) as u8) << (self.count % 8);
+                self.count += 1;
+            }
+        }
+
+        /// Writes the Huffman `code` of `n` bits, most significant first.
+        fn code(&mut self, code: u32, n: u32) {
+            for bit in (0..n).rev() {
+                self.put(code >> bit, 1);
+            }
+        }
+    }
+
+    fn decode(encoding: &str, body: &[u8]) -> Result<Bytes, I5RequestError> {
+        let mut headers = vec![
+            (String::from("Content-Encoding"), String::from(encoding)),
+            (String::from("Content-Length"), body.len().to_string()),
+        ];
+        let decoded =
+            ResponseDecoding::default().decode(200, &mut headers, Bytes::copy_from_slice(body))?;
+        assert!(headers.is_empty());
+        Ok(decoded)
+    }
+
+    fn details(result: Result<Bytes, I5RequestError>) -> String {
+        match result {
+            Err(I5RequestError::UnexpectedResponse {
+                status: 200,
+                details,
+            }) => details,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decodes_stored_blocks() {
+        assert_eq!(decode("deflate", STORED).unwrap(), HELLO);
+        let mut out = Vec::new();
+        assert_eq!(inflate(STORED, &mut out).unwrap(), STORED.len());
+        assert_eq!(out, HELLO);
+    }
+
+    #[test]
+    fn decodes_fixed_codes() {
+        assert_eq!(decode("gzip", FIXED_GZIP).unwrap(), HELLO);
+        assert_eq!(decode("X-GZIP", FIXED_GZIP).unwrap(), HELLO);
+    }
+
+    #[test]
+    fn decodes_dynamic_codes() {
+        assert_eq!(decode("deflate", DYNAMIC_ZLIB).unwrap(), DYNAMIC_TEXT);
+    }
+
+    #[test]
+    fn decodes_concatenated_gzip_members() {
+        let body = [FIXED_GZIP, FIXED_GZIP].concat();
+        assert_eq!(decode("gzip", &body).unwrap(), [HELLO, HELLO].concat());
+    }
+
+    #[test]
+    fn keeps_other_encodings() {
+        let mut headers = vec![(String::from("Content-Encoding"), String::from("br"))];
+        let body = Bytes::from_static(b"\x0b\x02\x80");
+        let decoded = ResponseDecoding::default()
+            .decode(200, &mut headers, body.clone())
+            .unwrap();
+        assert_eq!(decoded, body);
+        assert_eq!(headers.len(), 1);
+    }
+
+    #[test]
+    fn rejects_corrupt_checksums() {
+        let mut gzip = FIXED_GZIP.to_vec();
+        gzip[20] ^= 0xff;
+        assert_eq!(
+            details(decode("gzip", &gzip)),
+            "the gzip body cannot be decoded: the gzip checksum does not match"
+        );
+
+        let mut zlib = DYNAMIC_ZLIB.to_vec();
+        *zlib.last_mut().unwrap() ^= 0xff;
+        assert_eq!(
+            details(decode("deflate", &zlib)),
+            "the deflate body cannot be decoded: the zlib checksum does not match"
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        assert_eq!(
+            details(decode("gzip", &FIXED_GZIP[..24])),
+            "the gzip body cannot be decoded: the gzip trailer is missing"
+        );
+        assert_eq!(
+            details(decode("gzip", &FIXED_GZIP[..15])),
+            "the gzip body cannot be decoded: the compressed data ends early"
+        );
+        assert_eq!(
+            details(decode("gzip", &FIXED_GZIP[..5])),
+            "the gzip body cannot be decoded: not gzip data"
+        );
+        assert_eq!(
+            details(decode("deflate", &DYNAMIC_ZLIB[..50])),
+            "the deflate body cannot be decoded: the zlib trailer is missing"
+        );
+        assert_eq!(
+            details(decode("deflate", &STORED[..10])),
+            "the deflate body cannot be decoded: the compressed data ends early"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_streams() {
+        let mut stored = STORED.to_vec();
+        stored[3] ^= 0x01;
+        assert_eq!(
+            details(decode("deflate", &stored)),
+            "the deflate body cannot be decoded: invalid stored block length"
+        );
+        assert_eq!(
+            details(decode("deflate", &[0x07])),
+            "the deflate body cannot be decoded: invalid block type"
+        );
+
+        // A fixed block copying from before the start of the output.
+        let mut bits = BitWriter::default();
+        bits.put(1, 1);
+        bits.put(1, 2);
+        bits.code(0b000_0001, 7);
+        bits.code(0, 5);
+        assert_eq!(
+            details(decode("deflate", &bits.data)),
+            "the deflate body cannot be decoded: distance reaches before the start"
+        );
+    }
+
+    #[test]
+    fn stops_at_the_decoded_size_limit() {
+        // A fixed block with a zero byte and then copies of 258 bytes at distance 1.
+        let mut bits = BitWriter::default();
+        bits.put(1, 1);
+        bits.put(1, 2);
+        bits.code(0x30, 8);
+        for _ in 0..MAX_DECODED_LEN / 258 + 1 {
+            bits.code(0xc5, 8);
+            bits.code(0, 5);
+        }
+        bits.code(0, 7);
+
+        assert_eq!(
+            details(decode("deflate", &bits.data)),
+            format!(
+                "the deflate body cannot be decoded: decoded body exceeds {} bytes",
+                MAX_DECODED_LEN
+            )
+        );
+    }
+}
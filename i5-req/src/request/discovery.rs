@@ -0,0 +1,35 @@
+//! Lookups of the configuration of an Interface5 server.
+
+use serde_json::Value;
+
+use crate::{
+    events::i5_event,
+    request::{
+        self, Pooled,
+        transport::{I5Body, I5Transport, I5TransportRequest},
+    },
+    types::{i5_auth::I5Auth, i5_error::I5RequestError},
+};
+
+/// Fetches the JSON document at `url`.
+///
+/// Returns [`I5RequestError::ServerError`] if the server answers with a non-success
+/// status, e.g. `404` if it does not offer the lookup, and
+/// [`I5RequestError::SerializeError`] if the response is not JSON.
+pub(crate) async fn get_json(
+    transport: &Pooled<impl I5Transport>,
+    url: String,
+    auth: Option<&I5Auth>,
+) -> Result<Value, I5RequestError> {
+    let mut headers = vec![("Accept".into(), String::from("application/json"))];
+    headers.extend(auth.and_then(I5Auth::header));
+    i5_event!(
+        debug,
+        "querying Interface5",
+        url = crate::util::redact_query(&url)
+    );
+    let request = I5TransportRequest::new(reqwest::Method::GET, url, headers, I5Body::empty());
+
+    let response = request::send_checked(transport, request, auth).await?;
+    serde_json::from_slice(response.body()).map_err(I5RequestError::SerializeError)
+}
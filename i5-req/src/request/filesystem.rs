@@ -0,0 +1,150 @@
+//! Delivery of batches through the filesystem input of Interface5.
+//!
+//! Besides the web service, Interface5 picks up batches dropped into a watched folder,
+//! which serves as a fallback when the web service is disabled. A batch is a folder
+//! with an `Index.json` and one numbered subfolder per document holding its files:
+//!
+//! ```text
+//! {batch}/
+//!     Index.json
+//!     0001/
+//!         0001_scan.pdf
+//!         0002_scan.xml
+//!     0002/
+//!         0001_photo.jpg
+//! ```
+//!
+//! The index has the layout of a web service submission, except that every file
+//! refers to its content by a `Path` relative to the batch folder instead of carrying
+//! it as `Data`.
+
+use serde_json::{Value, json};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    events::i5_event,
+    types::{i5_error::I5RequestError, i5_request::ValidatedI5Request},
+    util,
+};
+
+/// The name of the index file of an exported batch.
+pub const INDEX_FILE_NAME: &str = "Index.json";
+
+/// Writes a validated request into `directory` in the layout of the Interface5
+/// filesystem input and returns the path of the batch folder.
+///
+/// The batch folder is named after the request, with characters that are not safe in
+/// file names replaced by `_`. It is written under a temporary name and renamed when
+/// complete, so a watcher never picks up a partial batch. Returns
+/// [`I5RequestError::IoError`] if the folder already exists or cannot be written, and
+/// [`I5RequestError::ConversionError`] if a file holds invalid base64 data.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::{request::filesystem::{self, INDEX_FILE_NAME}, types::i5_request::I5Request};
+///
+/// let directory = std::env::temp_dir().join(format!("i5-doc-export-{}", std::process::id()));
+///
+/// let mut request = I5Request::new("Batch 4711/A");
+/// let id = request.add_document("Invoice");
+/// request
+///     .get_document_mut(id)
+///     .unwrap()
+///     .add_header_field("InvoiceNo", "4711")
+///     .add_bytes_file("scan.pdf", b"%PDF-1.7");
+///
+/// let batch = filesystem::i5_export(request.validate()?, &directory)?;
+/// assert_eq!(batch, directory.join("Batch 4711_A"));
+/// assert!(batch.join(INDEX_FILE_NAME).is_file());
+/// assert_eq!(std::fs::read(batch.join("0001/0001_scan.pdf"))?, b"%PDF-1.7");
+/// # std::fs::remove_dir_all(&directory)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn i5_export(
+    valid_body: impl std::borrow::Borrow<ValidatedI5Request>,
+    directory: impl AsRef<Path>,
+) -> Result<PathBuf, I5RequestError> {
+    let valid_body = valid_body.borrow();
+    let directory = directory.as_ref();
+    let folder = util::safe_file_name(valid_body.name());
+    let target = directory.join(folder.as_ref());
+    if target.exists() {
+        return Err(I5RequestError::IoError(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("batch folder {} already exists", target.display()),
+        )));
+    }
+
+    let partial = directory.join(format!(".{}.partial", folder));
+    if partial.exists() {
+        fs::remove_dir_all(&partial).map_err(I5RequestError::IoError)?;
+    }
+    let result = write_batch(valid_body, &partial)
+        .and_then(|()| fs::rename(&partial, &target).map_err(I5RequestError::IoError));
+    match result {
+        Ok(()) => {
+            i5_event!(
+                info,
+                "exported batch for the Interface5 filesystem input",
+                request = valid_body.name(),
+                path = target.display()
+            );
+            Ok(target)
+        }
+        Err(err) => {
+            let _ = fs::remove_dir_all(&partial);
+            Err(err)
+        }
+    }
+}
+
+/// Writes the files and then the index of the batch into `folder`.
+fn write_batch(valid_body: &ValidatedI5Request, folder: &Path) -> Result<(), I5RequestError> {
+    fs::create_dir_all(folder).map_err(I5RequestError::IoError)?;
+    let mut documents = Vec::new();
+    for (document_index, document) in valid_body.request().documents().iter().enumerate() {
+        let document_folder = format!("{:04}", document_index + 1);
+        if !document.files().is_empty() {
+            fs::create_dir(folder.join(&document_folder)).map_err(I5RequestError::IoError)?;
+        }
+
+        let mut files = Vec::new();
+        for (file_index, file) in document.files().iter().enumerate() {
+            let path = format!(
+                "{}/{:04}_{}",
+                document_folder,
+                file_index + 1,
+                util::safe_file_name(file.name())
+            );
+            let content = file.data().decoded().map_err(|err| {
+                I5RequestError::ConversionError(format!(
+                    "Documents[{}].Files[{}].Data: invalid base64: {}",
+                    document_index, file_index, err
+                ))
+            })?;
+            fs::write(folder.join(&path), content).map_err(I5RequestError::IoError)?;
+            files.push(json!({ "Name": file.name(), "Key": file.key(), "Path": path }));
+        }
+
+        let fields: Vec<Value> = document
+            .fields()
+            .iter()
+            .map(|field| {
+                json!({
+                    "Name": field.name(),
+                    "Value": field.value(),
+                    "ItemNo": field.item_number(),
+                })
+            })
+            .collect();
+        documents.push(json!({ "Name": document.name(), "Fields": fields, "Files": files }));
+    }
+
+    let index = json!({ "Name": valid_body.name(), "Documents": documents });
+    let index = serde_json::to_vec_pretty(&index).map_err(I5RequestError::SerializeError)?;
+    fs::write(folder.join(INDEX_FILE_NAME), index).map_err(I5RequestError::IoError)
+}
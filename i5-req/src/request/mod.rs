@@ -1,26 +1,362 @@
 pub mod blocking;
+mod body;
+pub mod client;
+mod curl;
+mod decompress;
+mod discovery;
+pub mod filesystem;
+mod negotiate;
+pub mod options;
+mod pinning;
+pub mod pipeline;
+pub mod telemetry;
+mod timing;
+mod token;
+pub mod transport;
+mod upload;
+mod wire;
 
-use crate::types::{
-    i5_error::I5RequestError, i5_request::ValidatedI5Request, i5_request_url::I5RequestUrl,
+pub use body::STREAMING_THRESHOLD;
+
+use serde_json::Value;
+use std::{
+    borrow::{Borrow, Cow},
+    time::Instant,
+};
+use tracing::{Instrument, Span};
+
+use crate::{
+    events::i5_event,
+    request::{
+        body::EncodedBody,
+        options::{CORRELATION_ID_HEADER, I5SendOptions},
+        transport::{I5HttpTransport, I5Transport, I5TransportRequest, I5TransportResponse},
+    },
+    schema,
+    types::{
+        i5_api_version::I5ApiVersion,
+        i5_auth::I5Auth,
+        i5_error::I5RequestError,
+        i5_receipt::{I5BatchReceipt, I5RawResponse, I5TransferStats},
+        i5_request::ValidatedI5Request,
+        i5_request_url::I5RequestUrl,
+        i5_server_error::I5ServerError,
+    },
 };
 
+/// Posts a validated request to Interface5.
+///
+/// Bodies of at least [`STREAMING_THRESHOLD`] bytes are encoded while they are sent.
+/// The request can be passed by value, by reference or in an [`Arc`](std::sync::Arc), so the same
+/// payload can be posted to several targets or retried without copying it.
+///
+/// Returns [`I5RequestError::ServerError`] if Interface5 answers with a non-success
+/// status.
 pub async fn i5_http_post(
-    valid_body: ValidatedI5Request,
+    valid_body: impl Borrow<ValidatedI5Request>,
     url: I5RequestUrl,
     allow_untrusted_cert: bool,
+) -> Result<I5BatchReceipt, I5RequestError> {
+    let options = I5SendOptions::new().allow_untrusted_cert(allow_untrusted_cert);
+    i5_http_post_with_options(valid_body, url, &options).await
+}
+
+/// Posts a validated request to Interface5 using the given [`I5SendOptions`].
+///
+/// Every submission carries a correlation ID in the [`CORRELATION_ID_HEADER`] header.
+/// It is generated unless supplied through the options, returned in the
+/// [`I5BatchReceipt`] and recorded on the `i5_http_post` tracing span.
+///
+/// This builds a new HTTP client for every call. Use
+/// [`I5Client`](crate::request::client::I5Client) to reuse connections.
+pub async fn i5_http_post_with_options(
+    valid_body: impl Borrow<ValidatedI5Request>,
+    url: I5RequestUrl,
+    options: &I5SendOptions,
+) -> Result<I5BatchReceipt, I5RequestError> {
+    let transport = Pooled::new(I5HttpTransport::untrusted(
+        options.accepts_untrusted_cert(),
+    )?);
+    submit(&transport, valid_body.borrow(), &url, options).await
+}
+
+/// Sends a validated request with the given transport, recording the submission in
+/// tracing, the audit log and the failure dump as configured in `options`.
+pub(crate) async fn submit(
+    transport: &Pooled<impl I5Transport>,
+    valid_body: &ValidatedI5Request,
+    url: &I5RequestUrl,
+    options: &I5SendOptions,
+) -> Result<I5BatchReceipt, I5RequestError> {
+    submit_to(transport, valid_body, url, url.to_url(), options).await
+}
+
+/// Sends a validated request like [`submit`], but to `target` instead of the URL of
+/// the endpoint, e.g. to finish an upload session.
+pub(crate) async fn submit_to(
+    transport: &Pooled<impl I5Transport>,
+    valid_body: &ValidatedI5Request,
+    url: &I5RequestUrl,
+    target: String,
+    options: &I5SendOptions,
+) -> Result<I5BatchReceipt, I5RequestError> {
+    let correlation_id = options.resolve_correlation_id();
+    let span = telemetry::submission_span(url, valid_body, &correlation_id);
+    telemetry::record_retry_count(&span, options.retry_count());
+
+    async {
+        let format = options.payload_format();
+        let body = EncodedBody::in_format(valid_body, format, options.dumps_on_failure())?;
+        let dump = if options.dumps_on_failure() {
+            body.failure_dump(valid_body, format)?
+        } else {
+            None
+        };
+
+        let result = post(
+            transport,
+            body,
+            target,
+            url.api_version(),
+            options,
+            &correlation_id,
+            &Span::current(),
+        )
+        .await;
+        telemetry::record_outcome(
+            &Span::current(),
+            result.as_ref().map(I5BatchReceipt::status),
+        );
+        options.audit_send(
+            valid_body.name(),
+            &correlation_id,
+            result.as_ref().map(|_| ()),
+        );
+        if let (Err(_), Some(dump)) = (&result, dump) {
+            options.write_failure_dump(&correlation_id, &String::from_utf8_lossy(&dump));
+        }
+        result
+    }
+    .instrument(span)
+    .await
+}
+
+async fn post(
+    transport: &Pooled<impl I5Transport>,
+    body: EncodedBody,
+    url: String,
+    version: I5ApiVersion,
+    options: &I5SendOptions,
+    correlation_id: &str,
+    span: &Span,
+) -> Result<I5BatchReceipt, I5RequestError> {
+    i5_event!(
+        debug,
+        "sending request to Interface5",
+        correlation_id = correlation_id,
+        url = crate::util::redact_query(&url)
+    );
+    let headers = submission_headers(options, correlation_id, span);
+    let bytes_sent = body.len();
+    let request = I5TransportRequest::new(reqwest::Method::POST, url, headers, body.into());
+
+    let started = Instant::now();
+    let res = send_authorized(transport, request, options.credentials())
+        .await
+        .map_err(|err| {
+            i5_event!(
+                warn,
+                "failed to send request to Interface5",
+                correlation_id = correlation_id,
+                error = err
+            );
+            err
+        })?;
+    let total = started.elapsed();
+
+    let status = res.status();
+    if !(200..300).contains(&status) {
+        i5_event!(
+            warn,
+            "Interface5 rejected the request",
+            correlation_id = correlation_id,
+            status = status
+        );
+        return Err(I5RequestError::ServerError(
+            I5ServerError::from_response(status, &String::from_utf8_lossy(res.body()))
+                .retry_after_header(res.header("Retry-After")),
+        ));
+    }
+
+    let stats = I5TransferStats::new(
+        res.measured_dns(),
+        res.measured_time_to_first_byte().unwrap_or(total),
+        total,
+        bytes_sent,
+        res.body().len() as u64,
+    );
+    if options.checks_response() {
+        check_response(version, status, res.body()).inspect_err(|err| {
+            i5_event!(
+                warn,
+                "Interface5 answered with an unexpected response",
+                correlation_id = correlation_id,
+                error = err
+            )
+        })?;
+    }
+
+    i5_event!(
+        info,
+        "Interface5 accepted the request",
+        correlation_id = correlation_id,
+        status = status
+    );
+    let raw_response = options
+        .raw_response_limit()
+        .map(|limit| I5RawResponse::capture(status, res.headers(), res.body(), limit));
+    Ok(I5BatchReceipt::new(status, correlation_id, stats)
+        .with_response_body(res.body())
+        .with_response_headers(res.headers())
+        .with_raw_response(raw_response))
+}
+
+/// Checks the body of the success response with status `status` against the response
+/// schema of `version`, see [`I5SendOptions::check_response`].
+pub(crate) fn check_response(
+    version: I5ApiVersion,
+    status: u16,
+    body: &[u8],
 ) -> Result<(), I5RequestError> {
-    let body = valid_body.to_json_string()?;
-    let client = reqwest::Client::builder()
-        .danger_accept_invalid_certs(allow_untrusted_cert)
-        .build()
-        .map_err(I5RequestError::RequestError)?;
-
-    client
-        .post(url.to_url())
-        .header("Content-Type", "application/json")
-        .body(body)
-        .send()
+    let Some(schema) = version.response_schema() else {
+        return Ok(());
+    };
+    let schema: Value = serde_json::from_str(schema).expect("bundled schemas are valid JSON");
+    let details = match serde_json::from_slice::<Value>(body) {
+        Ok(value) => schema::violations(&schema, &value, "the response").join("; "),
+        Err(_) if body.is_empty() => String::from("the response: empty body"),
+        Err(err) => format!("the response: not JSON ({})", err),
+    };
+    if details.is_empty() {
+        Ok(())
+    } else {
+        Err(I5RequestError::UnexpectedResponse { status, details })
+    }
+}
+
+/// A transport with the lock that sends its negotiated requests one at a time, see
+/// [`send_authorized`].
+///
+/// IIS binds an NTLM handshake to the connection it runs on, and a client configured
+/// for it keeps a single idle connection per host. A request sent by another task
+/// between two steps of a handshake would take that connection, and the handshake
+/// would continue on a new one and fail. The lock belongs to the connection pool of
+/// one transport, so the handshakes of different clients do not wait for each other.
+pub(crate) struct Pooled<T> {
+    inner: T,
+    handshakes: tokio::sync::Mutex<()>,
+}
+
+impl<T> Pooled<T> {
+    pub(crate) const fn new(inner: T) -> Pooled<T> {
+        Pooled {
+            inner,
+            handshakes: tokio::sync::Mutex::const_new(()),
+        }
+    }
+
+    /// The transport itself.
+    pub(crate) fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: I5Transport> I5Transport for Pooled<T> {
+    async fn send(
+        &self,
+        request: I5TransportRequest,
+    ) -> Result<I5TransportResponse, I5RequestError> {
+        self.inner.send(request).await
+    }
+}
+
+/// Sends `request` with the credentials `auth`, first negotiating the
+/// `Authorization` header for Windows integrated authentication, see [`negotiate`].
+///
+/// Negotiated requests are sent one at a time, see [`Pooled`].
+pub(crate) async fn send_authorized(
+    transport: &Pooled<impl I5Transport>,
+    request: I5TransportRequest,
+    auth: Option<&I5Auth>,
+) -> Result<I5TransportResponse, I5RequestError> {
+    let Some((mut handshake, mut token)) = negotiate::Handshake::start(auth, request.url())? else {
+        return transport.send(request).await;
+    };
+    let _connection = transport.handshakes.lock().await;
+    while !token.complete {
+        let probe = request
+            .without_body()
+            .with_header(AUTHORIZATION_HEADER, token.header);
+        let response = transport.send(probe).await?;
+        token = handshake.answer(
+            response.status(),
+            response.header_values(negotiate::WWW_AUTHENTICATE_HEADER),
+        )?;
+    }
+    transport
+        .send(request.with_header(AUTHORIZATION_HEADER, token.header))
         .await
-        .map_err(I5RequestError::RequestError)?;
-    Ok(())
+}
+
+/// Sends `request` with the credentials `auth` and returns the response if its
+/// status is a success.
+///
+/// Returns [`I5RequestError::ServerError`] if the server answers with a non-success
+/// status.
+pub(crate) async fn send_checked(
+    transport: &Pooled<impl I5Transport>,
+    request: I5TransportRequest,
+    auth: Option<&I5Auth>,
+) -> Result<I5TransportResponse, I5RequestError> {
+    let response = send_authorized(transport, request, auth).await?;
+    if !(200..300).contains(&response.status()) {
+        return Err(I5RequestError::ServerError(
+            I5ServerError::from_response(
+                response.status(),
+                &String::from_utf8_lossy(response.body()),
+            )
+            .retry_after_header(response.header("Retry-After")),
+        ));
+    }
+    Ok(response)
+}
+
+/// The HTTP header carrying the credentials of a submission.
+pub(crate) const AUTHORIZATION_HEADER: &str = "Authorization";
+
+/// The HTTP headers sent with every submission.
+pub(crate) fn submission_headers(
+    options: &I5SendOptions,
+    correlation_id: &str,
+    span: &Span,
+) -> Vec<(Cow<'static, str>, String)> {
+    let mut headers = vec![
+        (
+            Cow::Borrowed("Content-Type"),
+            options.payload_format().content_type().to_string(),
+        ),
+        (
+            Cow::Borrowed(CORRELATION_ID_HEADER),
+            correlation_id.to_string(),
+        ),
+    ];
+    headers.extend(options.credentials().and_then(I5Auth::header));
+    #[cfg(feature = "otel")]
+    headers.extend(
+        telemetry::forwarded_traceparent(span, options.parent_trace_context())
+            .map(|traceparent| (Cow::Borrowed(telemetry::TRACEPARENT_HEADER), traceparent)),
+    );
+    #[cfg(not(feature = "otel"))]
+    let _ = span;
+    headers
 }
@@ -0,0 +1,443 @@
+//! Windows integrated authentication against Interface5 servers behind IIS.
+//!
+//! With [`I5Auth::Negotiate`] or [`I5Auth::Ntlm`], the `Authorization` header is not
+//! fixed but negotiated with SSPI for the current Windows logon. Kerberos tickets are
+//! complete after one step and sent with the request itself. NTLM needs a challenge
+//! from the server, so the request is first sent without a body and with the
+//! initial token; the server answers `401` with the challenge in `WWW-Authenticate`,
+//! and the request is sent again with the final token. IIS binds NTLM authentication
+//! to the connection, so a client configured with these credentials only speaks
+//! HTTP/1.1 and keeps a single idle connection per host, and its negotiated requests
+//! are sent one at a time: every step of a handshake then reuses the connection of the
+//! previous one as long as the server keeps it alive. Credentials passed in
+//! [`I5SendOptions`](crate::request::options::I5SendOptions) instead of the
+//! configuration are sent one at a time as well, but over a client that may use
+//! HTTP/2 and several connections.
+//!
+//! SSPI is only available on Windows with the `sspi` feature; elsewhere the
+//! handshake fails with [`I5RequestError::CredentialError`].
+
+use base64::{Engine, engine::general_purpose};
+
+use crate::types::{i5_auth::I5Auth, i5_error::I5RequestError};
+
+/// The HTTP header carrying the challenge of the server.
+pub(crate) const WWW_AUTHENTICATE_HEADER: &str = "WWW-Authenticate";
+
+/// A handshake for one request, in progress.
+pub(crate) struct Handshake {
+    scheme: &'static str,
+    context: platform::Context,
+}
+
+/// The next `Authorization` header value of a handshake.
+pub(crate) struct Token {
+    /// The value of the `Authorization` header.
+    pub(crate) header: String,
+    /// Whether the token completes the handshake and is sent with the request itself.
+    pub(crate) complete: bool,
+}
+
+impl Handshake {
+    /// Starts a handshake for a request to `url` if `auth` is negotiated, and returns
+    /// its first token.
+    ///
+    /// Returns [`I5RequestError::CredentialError`] if SSPI is unavailable or refuses
+    /// the credentials of the current logon.
+    pub(crate) fn start(
+        auth: Option<&I5Auth>,
+        url: &str,
+    ) -> Result<Option<(Handshake, Token)>, I5RequestError> {
+        let (scheme, package, target) = match auth {
+            Some(I5Auth::Negotiate { service_principal }) => {
+                let target = match service_principal {
+                    Some(spn) => spn.clone(),
+                    None => format!("HTTP/{}", host(url)?),
+                };
+                ("Negotiate", "Negotiate", target)
+            }
+            Some(I5Auth::Ntlm) => ("NTLM", "NTLM", format!("HTTP/{}", host(url)?)),
+            _ => return Ok(None),
+        };
+        let mut handshake = Handshake {
+            scheme,
+            context: platform::Context::new(package, &target)?,
+        };
+        let token = handshake.step(None)?;
+        Ok(Some((handshake, token)))
+    }
+
+    /// Answers the challenge in the `WWW-Authenticate` values `challenges` of the
+    /// response with status `status` to the request without a body.
+    ///
+    /// Returns [`I5RequestError::CredentialError`] if the response is not a `401` with
+    /// a challenge for the scheme, e.g. because the server refused the credentials.
+    pub(crate) fn answer<'a>(
+        &mut self,
+        status: u16,
+        challenges: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Token, I5RequestError> {
+        if status != 401 {
+            return Err(I5RequestError::CredentialError(format!(
+                "Interface5 answered the {} handshake with status {} instead of a challenge",
+                self.scheme, status
+            )));
+        }
+        let challenge = challenge(self.scheme, challenges)?;
+        self.step(Some(&challenge))
+    }
+
+    fn step(&mut self, challenge: Option<&[u8]>) -> Result<Token, I5RequestError> {
+        let (token, complete) = self.context.step(challenge)?;
+        Ok(Token {
+            header: format!(
+                "{} {}",
+                self.scheme,
+                general_purpose::STANDARD.encode(token)
+            ),
+            complete,
+        })
+    }
+}
+
+/// The decoded token of the `scheme` challenge in the `WWW-Authenticate` values
+/// `challenges`, each of which may list several challenges separated by commas.
+/// Schemes are compared case-insensitively.
+///
+/// Returns [`I5RequestError::CredentialError`] if no challenge for the scheme carries
+/// a token, which is how the server refuses the credentials, or if the token is not
+/// base64.
+fn challenge<'a>(
+    scheme: &str,
+    challenges: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<u8>, I5RequestError> {
+    let token = challenges
+        .into_iter()
+        .flat_map(|value| value.split(','))
+        .find_map(|challenge| {
+            let (name, token) = challenge.trim().split_once(' ')?;
+            let token = token.trim();
+            (name.eq_ignore_ascii_case(scheme) && !token.is_empty()).then_some(token)
+        })
+        .ok_or_else(|| {
+            I5RequestError::CredentialError(format!(
+                "Interface5 refused the {} credentials of the current Windows logon",
+                scheme
+            ))
+        })?;
+    general_purpose::STANDARD.decode(token).map_err(|_| {
+        I5RequestError::CredentialError(format!(
+            "the {} challenge of the server is not base64",
+            scheme
+        ))
+    })
+}
+
+/// The host of `url`, which names the service principal `HTTP/<host>`.
+fn host(url: &str) -> Result<String, I5RequestError> {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(String::from))
+        .ok_or_else(|| I5RequestError::ConfigError(format!("'{}' has no host", url)))
+}
+
+#[cfg(all(windows, feature = "sspi"))]
+mod platform {
+    use std::{ffi::c_void, ptr};
+
+    use crate::types::i5_error::I5RequestError;
+
+    const SECPKG_CRED_OUTBOUND: u32 = 2;
+    const ISC_REQ_ALLOCATE_MEMORY: u32 = 0x100;
+    const ISC_REQ_CONNECTION: u32 = 0x800;
+    const SECURITY_NATIVE_DREP: u32 = 0x10;
+    const SECBUFFER_VERSION: u32 = 0;
+    const SECBUFFER_TOKEN: u32 = 2;
+    const SEC_E_OK: i32 = 0;
+    const SEC_I_CONTINUE_NEEDED: i32 = 0x0009_0312;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct SecHandle {
+        lower: usize,
+        upper: usize,
+    }
+
+    #[repr(C)]
+    struct SecBuffer {
+        size: u32,
+        buffer_type: u32,
+        buffer: *mut c_void,
+    }
+
+    #[repr(C)]
+    struct SecBufferDesc {
+        version: u32,
+        count: u32,
+        buffers: *mut SecBuffer,
+    }
+
+    #[link(name = "secur32")]
+    unsafe extern "system" {
+        fn AcquireCredentialsHandleW(
+            principal: *const u16,
+            package: *const u16,
+            credential_use: u32,
+            logon_id: *mut c_void,
+            auth_data: *mut c_void,
+            get_key_fn: *mut c_void,
+            get_key_argument: *mut c_void,
+            credential: *mut SecHandle,
+            expiry: *mut i64,
+        ) -> i32;
+        fn InitializeSecurityContextW(
+            credential: *mut SecHandle,
+            context: *mut SecHandle,
+            target_name: *const u16,
+            context_req: u32,
+            reserved1: u32,
+            target_data_rep: u32,
+            input: *mut SecBufferDesc,
+            reserved2: u32,
+            new_context: *mut SecHandle,
+            output: *mut SecBufferDesc,
+            context_attr: *mut u32,
+            expiry: *mut i64,
+        ) -> i32;
+        fn FreeContextBuffer(buffer: *mut c_void) -> i32;
+        fn DeleteSecurityContext(context: *mut SecHandle) -> i32;
+        fn FreeCredentialsHandle(credential: *mut SecHandle) -> i32;
+    }
+
+    /// An SSPI security context for the current logon.
+    pub(super) struct Context {
+        credential: SecHandle,
+        context: Option<SecHandle>,
+        target: Vec<u16>,
+    }
+
+    impl Context {
+        pub(super) fn new(package: &str, target: &str) -> Result<Context, I5RequestError> {
+            let package_wide: Vec<u16> = package.encode_utf16().chain(Some(0)).collect();
+            let mut credential = SecHandle::default();
+            let mut expiry = 0;
+
+            // SAFETY: `package_wide` is a NUL terminated UTF-16 string, the optional
+            // arguments are null and `credential` and `expiry` are valid out pointers.
+            // The handle is released with `FreeCredentialsHandle` on drop.
+            let status = unsafe {
+                AcquireCredentialsHandleW(
+                    ptr::null(),
+                    package_wide.as_ptr(),
+                    SECPKG_CRED_OUTBOUND,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    &mut credential,
+                    &mut expiry,
+                )
+            };
+            if status != SEC_E_OK {
+                return Err(I5RequestError::CredentialError(format!(
+                    "SSPI refused {} credentials for the current logon (0x{:08x})",
+                    package, status
+                )));
+            }
+            Ok(Context {
+                credential,
+                context: None,
+                target: target.encode_utf16().chain(Some(0)).collect(),
+            })
+        }
+
+        /// The next token and whether it completes the handshake.
+        pub(super) fn step(
+            &mut self,
+            challenge: Option<&[u8]>,
+        ) -> Result<(Vec<u8>, bool), I5RequestError> {
+            let mut input_buffer = SecBuffer {
+                size: challenge.map_or(0, |challenge| challenge.len() as u32),
+                buffer_type: SECBUFFER_TOKEN,
+                buffer: challenge.map_or(ptr::null_mut(), |challenge| {
+                    challenge.as_ptr() as *mut c_void
+                }),
+            };
+            let mut input = SecBufferDesc {
+                version: SECBUFFER_VERSION,
+                count: 1,
+                buffers: &mut input_buffer,
+            };
+            let mut output_buffer = SecBuffer {
+                size: 0,
+                buffer_type: SECBUFFER_TOKEN,
+                buffer: ptr::null_mut(),
+            };
+            let mut output = SecBufferDesc {
+                version: SECBUFFER_VERSION,
+                count: 1,
+                buffers: &mut output_buffer,
+            };
+            let (mut attributes, mut expiry) = (0, 0);
+            let first = self.context.is_none();
+            let context: *mut SecHandle = self.context.get_or_insert_with(SecHandle::default);
+
+            // SAFETY: all pointers refer to live locals or fields, the input buffer
+            // borrows `challenge` for the duration of the call, and the output token
+            // allocated by SSPI is copied and released with `FreeContextBuffer`. The
+            // context handle is only passed as input after SSPI initialized it.
+            let (status, token) = unsafe {
+                let status = InitializeSecurityContextW(
+                    &mut self.credential,
+                    if first { ptr::null_mut() } else { context },
+                    self.target.as_ptr(),
+                    ISC_REQ_ALLOCATE_MEMORY | ISC_REQ_CONNECTION,
+                    0,
+                    SECURITY_NATIVE_DREP,
+                    if challenge.is_some() {
+                        &mut input as *mut SecBufferDesc
+                    } else {
+                        ptr::null_mut()
+                    },
+                    0,
+                    context,
+                    &mut output,
+                    &mut attributes,
+                    &mut expiry,
+                );
+                let token = if output_buffer.buffer.is_null() {
+                    Vec::new()
+                } else {
+                    let token = std::slice::from_raw_parts(
+                        output_buffer.buffer as *const u8,
+                        output_buffer.size as usize,
+                    )
+                    .to_vec();
+                    FreeContextBuffer(output_buffer.buffer);
+                    token
+                };
+                (status, token)
+            };
+            if first && status != SEC_E_OK && status != SEC_I_CONTINUE_NEEDED {
+                self.context = None;
+            }
+            match status {
+                SEC_E_OK => Ok((token, true)),
+                SEC_I_CONTINUE_NEEDED => Ok((token, false)),
+                status => Err(I5RequestError::CredentialError(format!(
+                    "SSPI failed to create a token for {} (0x{:08x})",
+                    String::from_utf16_lossy(&self.target[..self.target.len() - 1]),
+                    status
+                ))),
+            }
+        }
+    }
+
+    impl Drop for Context {
+        fn drop(&mut self) {
+            // SAFETY: the handles were returned by SSPI and are released once.
+            unsafe {
+                if let Some(context) = self.context.as_mut() {
+                    DeleteSecurityContext(context);
+                }
+                FreeCredentialsHandle(&mut self.credential);
+            }
+        }
+    }
+}
+
+#[cfg(not(all(windows, feature = "sspi")))]
+mod platform {
+    use crate::types::i5_error::I5RequestError;
+
+    pub(super) struct Context;
+
+    impl Context {
+        pub(super) fn new(package: &str, _target: &str) -> Result<Context, I5RequestError> {
+            Err(I5RequestError::CredentialError(format!(
+                "{} authentication requires Windows and the `sspi` feature",
+                package
+            )))
+        }
+
+        pub(super) fn step(
+            &mut self,
+            _challenge: Option<&[u8]>,
+        ) -> Result<(Vec<u8>, bool), I5RequestError> {
+            unreachable!("a context cannot be created without SSPI")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_challenge_of_the_scheme() {
+        // The scheme, the `WWW-Authenticate` values and the decoded token, if any.
+        let cases: [(&str, &[&str], Option<&str>); 11] = [
+            ("NTLM", &["NTLM TlRMTQ=="], Some("NTLM")),
+            ("NTLM", &["ntlm TlRMTQ=="], Some("NTLM")),
+            ("Negotiate", &["NEGOTIATE  TlRMTQ== "], Some("NTLM")),
+            // Several headers and several challenges in one header.
+            ("NTLM", &["Negotiate", "NTLM TlRMTQ=="], Some("NTLM")),
+            ("NTLM", &["Basic realm=\"i5\", NTLM TlRMTQ=="], Some("NTLM")),
+            ("Negotiate", &["NTLM TlRMTQ==, Negotiate YWJj"], Some("abc")),
+            // A scheme without a token refuses the credentials.
+            ("NTLM", &["NTLM"], None),
+            ("NTLM", &["NTLM "], None),
+            ("NTLM", &["Negotiate TlRMTQ=="], None),
+            ("NTLM", &["NTLMv2 TlRMTQ=="], None),
+            ("NTLM", &[], None),
+        ];
+        for (scheme, challenges, expected) in cases {
+            let challenge = challenge(scheme, challenges.iter().copied());
+            match (challenge, expected) {
+                (Ok(token), Some(expected)) => {
+                    assert_eq!(token, expected.as_bytes(), "{:?}", challenges)
+                }
+                (Err(I5RequestError::CredentialError(message)), None) => {
+                    assert!(message.contains("refused"), "{:?}: {}", challenges, message)
+                }
+                (result, _) => panic!("{:?}: unexpected result {:?}", challenges, result),
+            }
+        }
+    }
+
+    #[test]
+    fn starts_handshakes_for_negotiated_credentials_only() {
+        let url = "https://i5.example.com:43001/api/v1/Input/Default/Scan";
+        for auth in [None, Some(I5Auth::bearer("TOKEN"))] {
+            assert!(matches!(Handshake::start(auth.as_ref(), url), Ok(None)));
+        }
+        assert!(matches!(
+            Handshake::start(Some(&I5Auth::Ntlm), "not a URL"),
+            Err(I5RequestError::ConfigError(_))
+        ));
+        #[cfg(not(all(windows, feature = "sspi")))]
+        for auth in [
+            I5Auth::Ntlm,
+            I5Auth::Negotiate {
+                service_principal: None,
+            },
+        ] {
+            assert!(matches!(
+                Handshake::start(Some(&auth), url),
+                Err(I5RequestError::CredentialError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_a_challenge_that_is_not_base64() {
+        for token in ["not base64!", "TlRMTQ", "TlRM TQ=="] {
+            match challenge("NTLM", [format!("NTLM {}", token).as_str()]) {
+                Err(I5RequestError::CredentialError(message)) => {
+                    assert!(message.contains("not base64"), "{}: {}", token, message)
+                }
+                result => panic!("{}: unexpected result {:?}", token, result),
+            }
+        }
+    }
+}
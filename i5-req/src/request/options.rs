@@ -0,0 +1,313 @@
+use std::{fs, path::PathBuf, sync::Arc};
+
+use crate::{
+    events::i5_event,
+    types::{
+        i5_audit::{I5AuditLog, I5AuditOperation},
+        i5_auth::I5Auth,
+        i5_content_format::I5ContentFormat,
+        i5_error::I5RequestError,
+        i5_redaction::I5Redaction,
+    },
+    util,
+};
+
+/// The HTTP header used to transmit the correlation ID of a submission.
+pub const CORRELATION_ID_HEADER: &str = "X-Correlation-ID";
+
+/// Per-submission options for sending a request to Interface5.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::request::options::I5SendOptions;
+///
+/// let options = I5SendOptions::new()
+///     .allow_untrusted_cert(true)
+///     .correlation_id("erp-export-4711");
+/// ```
+#[derive(Clone, Default)]
+pub struct I5SendOptions {
+    allow_untrusted_cert: bool,
+    correlation_id: Option<String>,
+    auth: Option<I5Auth>,
+    content_format: Option<I5ContentFormat>,
+    dump_dir: Option<PathBuf>,
+    dump_redaction: I5Redaction,
+    audit_log: Option<Arc<I5AuditLog>>,
+    raw_response_limit: Option<usize>,
+    check_response: bool,
+    retry_count: u32,
+    #[cfg(feature = "otel")]
+    trace_parent: Option<String>,
+}
+
+impl I5SendOptions {
+    /// Creates the default options: trusted certificates only and a generated
+    /// correlation ID.
+    pub fn new() -> I5SendOptions {
+        I5SendOptions::default()
+    }
+
+    /// Accepts invalid or self-signed server certificates.
+    pub fn allow_untrusted_cert(mut self, allow: bool) -> Self {
+        self.allow_untrusted_cert = allow;
+        self
+    }
+
+    /// Uses the given correlation ID instead of generating one.
+    ///
+    /// The ID is sent in the [`CORRELATION_ID_HEADER`] header, returned in the receipt
+    /// and recorded in the tracing span of the submission.
+    pub fn correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    /// Authenticates the submission with the given credentials instead of the ones
+    /// configured for the client, e.g. to submit on behalf of another service account
+    /// over the connections of a shared client.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::{
+    ///     request::{client::I5Client, options::I5SendOptions},
+    ///     types::i5_auth::I5Auth,
+    /// };
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let client = I5Client::from_env()?;
+    ///
+    /// let mut request = client.new_request()?;
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("No", "4711");
+    ///
+    /// let options = I5SendOptions::new().auth(I5Auth::basic("svc-branch-07", "hunter2"));
+    /// client.send_with_options(request.validate()?, &options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn auth(mut self, auth: I5Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Sends the body in `format` instead of JSON or the format configured for the
+    /// client.
+    pub fn content_format(mut self, format: I5ContentFormat) -> Self {
+        self.content_format = Some(format);
+        self
+    }
+
+    pub(crate) fn payload_format(&self) -> I5ContentFormat {
+        self.content_format.unwrap_or_default()
+    }
+
+    /// Returns the options falling back to `format` if no content format is set.
+    pub(crate) fn with_default_format(mut self, format: I5ContentFormat) -> I5SendOptions {
+        self.content_format.get_or_insert(format);
+        self
+    }
+
+    pub(crate) fn credentials(&self) -> Option<&I5Auth> {
+        self.auth.as_ref()
+    }
+
+    /// Returns a copy of the options that falls back to `auth` if no credentials are
+    /// set.
+    pub(crate) fn with_default_auth(&self, auth: Option<&I5Auth>) -> I5SendOptions {
+        let mut options = self.clone();
+        if options.auth.is_none() {
+            options.auth = auth.cloned();
+        }
+        options
+    }
+
+    /// Writes the serialized request to `dir` if the submission fails.
+    ///
+    /// The dump is named `{correlation_id}.json` and has `redaction` applied, so it can
+    /// be attached to support tickets without leaking sensitive values. Failing to
+    /// write the dump never hides the original error; it is only reported as a tracing
+    /// warning. The dump holds the JSON body even if the request is sent as XML.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::{request::options::I5SendOptions, types::i5_redaction::I5Redaction};
+    ///
+    /// let options = I5SendOptions::new().dump_on_failure(
+    ///     "/var/log/i5-req/dumps",
+    ///     I5Redaction::new().mask_field("*IBAN*").truncate_file_data(128),
+    /// );
+    /// ```
+    pub fn dump_on_failure(mut self, dir: impl Into<PathBuf>, redaction: I5Redaction) -> Self {
+        self.dump_dir = Some(dir.into());
+        self.dump_redaction = redaction;
+        self
+    }
+
+    /// Keeps the status, the headers and up to `max_body_bytes` of the body of the
+    /// response in the receipt, see
+    /// [`I5BatchReceipt::raw_response`](crate::types::i5_receipt::I5BatchReceipt::raw_response),
+    /// e.g. to investigate responses of an unknown server version.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::request::{client::I5Client, options::I5SendOptions};
+    ///
+    /// # async fn run() -> Result<(), i5_req::types::i5_error::I5RequestError> {
+    /// let client = I5Client::from_env()?;
+    /// let mut request = client.new_request()?;
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("No", "4711");
+    ///
+    /// let options = I5SendOptions::new().keep_raw_response(64 * 1024);
+    /// let receipt = client.send_with_options(request.validate()?, &options).await?;
+    /// if let Some(raw) = receipt.raw_response() {
+    ///     println!("{}: {}", raw.status(), String::from_utf8_lossy(raw.body()));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn keep_raw_response(mut self, max_body_bytes: usize) -> Self {
+        self.raw_response_limit = Some(max_body_bytes);
+        self
+    }
+
+    pub(crate) fn raw_response_limit(&self) -> Option<usize> {
+        self.raw_response_limit
+    }
+
+    /// Checks the response to an accepted request against the JSON Schema of the API
+    /// version of the endpoint, see
+    /// [`I5ApiVersion::response_schema`](crate::types::i5_api_version::I5ApiVersion::response_schema).
+    ///
+    /// A response that does not match, e.g. because a server update renamed a key,
+    /// fails the submission with [`I5RequestError::UnexpectedResponse`] listing the
+    /// violations, instead of a receipt missing the renamed values. Responses of
+    /// versions without a bundled schema are not checked.
+    ///
+    /// [`I5RequestError::UnexpectedResponse`]: crate::types::i5_error::I5RequestError::UnexpectedResponse
+    pub fn check_response(mut self, check: bool) -> Self {
+        self.check_response = check;
+        self
+    }
+
+    pub(crate) fn checks_response(&self) -> bool {
+        self.check_response
+    }
+
+    /// Marks the submission as the `retries`th retry of a failed one.
+    pub(crate) fn with_retry_count(mut self, retries: u32) -> I5SendOptions {
+        self.retry_count = retries;
+        self
+    }
+
+    /// How many submissions of the same request failed before this one.
+    pub(crate) fn retry_count(&self) -> u32 {
+        self.retry_count
+    }
+
+    /// Forwards the given W3C trace context (a `traceparent` header value) on the
+    /// outgoing call, e.g. the value injected by the application's OpenTelemetry
+    /// propagator for the current span.
+    ///
+    /// The value is sent unchanged if it is valid. Without it, or if it is invalid, no
+    /// `traceparent` header is sent: the crate does not propagate the context of its
+    /// own spans.
+    #[cfg(feature = "otel")]
+    pub fn trace_parent(mut self, traceparent: impl Into<String>) -> Self {
+        self.trace_parent = Some(traceparent.into());
+        self
+    }
+
+    #[cfg(feature = "otel")]
+    pub(crate) fn parent_trace_context(&self) -> Option<&str> {
+        self.trace_parent.as_deref()
+    }
+
+    /// Records every submission made with these options in `audit_log`.
+    pub fn audit_log(mut self, audit_log: Arc<I5AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Appends the outcome of a submission to the audit log, if configured.
+    ///
+    /// Audit failures are reported as tracing errors and do not change the result of
+    /// the submission.
+    pub(crate) fn audit_send(
+        &self,
+        batch_name: &str,
+        correlation_id: &str,
+        outcome: Result<(), &I5RequestError>,
+    ) {
+        if let Some(audit_log) = &self.audit_log
+            && let Err(err) = audit_log.append(
+                I5AuditOperation::Send,
+                batch_name,
+                Some(correlation_id),
+                outcome,
+            )
+        {
+            i5_event!(error, "failed to write audit log entry", error = err);
+        }
+    }
+
+    pub(crate) fn dumps_on_failure(&self) -> bool {
+        self.dump_dir.is_some()
+    }
+
+    /// Writes the redacted failure dump, if configured.
+    pub(crate) fn write_failure_dump(&self, correlation_id: &str, body: &str) {
+        let Some(dir) = &self.dump_dir else {
+            return;
+        };
+
+        let path = dir.join(format!("{}.json", correlation_id));
+        let result = fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&path, self.dump_redaction.apply_to_json(body)));
+        match result {
+            Ok(()) => i5_event!(info, "wrote failure dump", path = path.display()),
+            Err(err) => i5_event!(
+                warn,
+                "failed to write failure dump",
+                path = path.display(),
+                error = err
+            ),
+        }
+    }
+
+    pub(crate) fn accepts_untrusted_cert(&self) -> bool {
+        self.allow_untrusted_cert
+    }
+
+    /// Returns the caller supplied correlation ID or generates a new one.
+    pub(crate) fn resolve_correlation_id(&self) -> String {
+        self.correlation_id.clone().unwrap_or_else(util::uuid_v4)
+    }
+}
+
+impl std::fmt::Debug for I5SendOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("I5SendOptions");
+        debug
+            .field("allow_untrusted_cert", &self.allow_untrusted_cert)
+            .field("correlation_id", &self.correlation_id)
+            .field("auth", &self.auth)
+            .field("dump_dir", &self.dump_dir)
+            .field("dump_redaction", &self.dump_redaction)
+            .field(
+                "audit_log",
+                &self.audit_log.as_ref().map(|audit_log| audit_log.path()),
+            )
+            .field("raw_response_limit", &self.raw_response_limit)
+            .field("check_response", &self.check_response)
+            .field("retry_count", &self.retry_count);
+        #[cfg(feature = "otel")]
+        debug.field("trace_parent", &self.trace_parent);
+        debug.finish()
+    }
+}
@@ -0,0 +1,470 @@
+//! Pinning of the server certificate by the SHA-256 hash of its public key.
+//!
+//! A pin is the SHA-256 hash of the DER-encoded `SubjectPublicKeyInfo` of a
+//! certificate, the value HPKP called `pin-sha256`. A connection is accepted if the
+//! server certificate carries a pinned key, or if the chain sent by the server contains
+//! a certificate with a pinned key that the server certificate is validly issued under
+//! for the host name. The system trust store is not consulted, so a rogue CA in it
+//! cannot vouch for another server.
+//!
+//! Pinning is enforced by a custom rustls certificate verifier and requires the
+//! `rustls` feature.
+
+use base64::{Engine, engine::general_purpose};
+
+use crate::types::{i5_config::I5Config, i5_error::I5RequestError};
+
+/// Parses a pin given as `sha256/` followed by base64, as plain base64, or as
+/// hexadecimal with optional `:` separators.
+///
+/// Returns [`I5RequestError::ConfigError`] if the pin is not a SHA-256 hash.
+pub(crate) fn parse_pin(text: &str) -> Result<[u8; 32], I5RequestError> {
+    let trimmed = text.trim();
+    let value = trimmed.strip_prefix("sha256/").unwrap_or(trimmed);
+    let hex: String = value.chars().filter(|c| *c != ':').collect();
+    let bytes = if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        (0..32)
+            .map(|index| u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .ok()
+    } else {
+        general_purpose::STANDARD.decode(value).ok()
+    };
+    bytes
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| {
+            I5RequestError::ConfigError(format!(
+                "'{}' is not a SHA-256 public key pin, expected sha256/ followed by base64",
+                text
+            ))
+        })
+}
+
+/// The pins configured in `config`, parsed.
+pub(crate) fn pins(config: &I5Config) -> Result<Vec<[u8; 32]>, I5RequestError> {
+    config
+        .pinned_public_keys()
+        .iter()
+        .map(|pin| parse_pin(pin))
+        .collect()
+}
+
+/// The rustls configuration enforcing the pins of `config`, or `None` if no pins are
+/// configured.
+///
+/// Returns [`I5RequestError::ConfigError`] if a pin is malformed, or if the configured
+/// TLS versions leave no version rustls supports.
+#[cfg(feature = "rustls")]
+pub(crate) fn tls_config(
+    config: &I5Config,
+) -> Result<Option<rustls::ClientConfig>, I5RequestError> {
+    use std::sync::Arc;
+
+    use crate::types::i5_tls_version::I5TlsVersion;
+
+    let pins = pins(config)?;
+    if pins.is_empty() {
+        return Ok(None);
+    }
+
+    let (min, max) = config.tls_versions();
+    let versions: Vec<&'static rustls::SupportedProtocolVersion> = [
+        (I5TlsVersion::Tls1_2, &rustls::version::TLS12),
+        (I5TlsVersion::Tls1_3, &rustls::version::TLS13),
+    ]
+    .into_iter()
+    .filter(|(version, _)| min.is_none_or(|min| *version >= min))
+    .filter(|(version, _)| max.is_none_or(|max| *version <= max))
+    .map(|(_, supported)| supported)
+    .collect();
+    if versions.is_empty() {
+        return Err(I5RequestError::ConfigError(String::from(
+            "certificate pinning supports TLS 1.2 and 1.3 only, but the configured TLS versions exclude both",
+        )));
+    }
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut tls = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_protocol_versions(&versions)
+        .map_err(|err| I5RequestError::ConfigError(format!("invalid TLS settings: {}", err)))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier::PinVerifier { pins, provider }))
+        .with_no_client_auth();
+    tls.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Some(tls))
+}
+
+/// Returns [`I5RequestError::ConfigError`] if `config` pins certificates, which
+/// requires the `rustls` feature.
+#[cfg(not(feature = "rustls"))]
+pub(crate) fn tls_config(
+    config: &I5Config,
+) -> Result<Option<std::convert::Infallible>, I5RequestError> {
+    if config.pinned_public_keys().is_empty() {
+        Ok(None)
+    } else {
+        pins(config)?;
+        Err(I5RequestError::ConfigError(String::from(
+            "certificate pinning requires the `rustls` feature",
+        )))
+    }
+}
+
+/// The DER-encoded `SubjectPublicKeyInfo` of the DER-encoded certificate `cert`.
+#[cfg(feature = "rustls")]
+fn subject_public_key_info(cert: &[u8]) -> Option<&[u8]> {
+    /// The tag, the header length and the total length of the DER element at the
+    /// start of `data`.
+    fn element(data: &[u8]) -> Option<(u8, usize, usize)> {
+        let (&tag, rest) = data.split_first()?;
+        let (&first, rest) = rest.split_first()?;
+        let (header, length) = match first {
+            0..=0x7f => (2, usize::from(first)),
+            0x81..=0x84 => {
+                let count = usize::from(first & 0x7f);
+                let length = rest
+                    .get(..count)?
+                    .iter()
+                    .fold(0usize, |length, byte| (length << 8) | usize::from(*byte));
+                (2 + count, length)
+            }
+            _ => return None,
+        };
+        let total = header.checked_add(length)?;
+        (total <= data.len()).then_some((tag, header, total))
+    }
+    /// The content of the SEQUENCE at the start of `data`.
+    fn content(data: &[u8]) -> Option<&[u8]> {
+        let (tag, header, total) = element(data)?;
+        (tag == 0x30).then(|| &data[header..total])
+    }
+
+    // Certificate ::= SEQUENCE { tbsCertificate SEQUENCE { [0] version OPTIONAL,
+    // serialNumber, signature, issuer, validity, subject, subjectPublicKeyInfo, … } … }
+    let mut rest = content(content(cert)?)?;
+    if rest.first() == Some(&0xa0) {
+        rest = &rest[element(rest)?.2..];
+    }
+    for _ in 0..5 {
+        rest = &rest[element(rest)?.2..];
+    }
+    let (tag, _, total) = element(rest)?;
+    (tag == 0x30).then(|| &rest[..total])
+}
+
+/// Whether the public key of the DER-encoded certificate `cert` is one of `pins`.
+#[cfg(feature = "rustls")]
+fn is_pinned(cert: &[u8], pins: &[[u8; 32]]) -> bool {
+    subject_public_key_info(cert).is_some_and(|spki| {
+        let hash = ring::digest::digest(&ring::digest::SHA256, spki);
+        pins.iter().any(|pin| pin == hash.as_ref())
+    })
+}
+
+#[cfg(feature = "rustls")]
+mod verifier {
+    use std::sync::Arc;
+
+    use rustls::{
+        CertificateError, DigitallySignedStruct, Error, RootCertStore, SignatureScheme,
+        client::{
+            WebPkiServerVerifier,
+            danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        },
+        crypto::{CryptoProvider, verify_tls12_signature, verify_tls13_signature},
+        pki_types::{CertificateDer, ServerName, UnixTime},
+    };
+
+    use crate::events::i5_event;
+
+    /// Accepts server certificates carrying, or validly issued under, a pinned key.
+    #[derive(Debug)]
+    pub(super) struct PinVerifier {
+        pub(super) pins: Vec<[u8; 32]>,
+        pub(super) provider: Arc<CryptoProvider>,
+    }
+
+    impl ServerCertVerifier for PinVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &CertificateDer<'_>,
+            intermediates: &[CertificateDer<'_>],
+            server_name: &ServerName<'_>,
+            ocsp_response: &[u8],
+            now: UnixTime,
+        ) -> Result<ServerCertVerified, Error> {
+            // The handshake signature proves that the server holds the pinned key.
+            if super::is_pinned(end_entity, &self.pins) {
+                return Ok(ServerCertVerified::assertion());
+            }
+
+            // A pinned issuer only counts if the server certificate chains up to it.
+            let Some(anchor) = intermediates
+                .iter()
+                .find(|cert| super::is_pinned(cert, &self.pins))
+            else {
+                i5_event!(
+                    warn,
+                    "rejected Interface5 server certificate without a pinned key",
+                    server = format!("{:?}", server_name)
+                );
+                return Err(Error::InvalidCertificate(
+                    CertificateError::ApplicationVerificationFailure,
+                ));
+            };
+            let mut roots = RootCertStore::empty();
+            roots.add(anchor.clone().into_owned())?;
+            let verifier =
+                WebPkiServerVerifier::builder_with_provider(Arc::new(roots), self.provider.clone())
+                    .build()
+                    .map_err(|err| Error::General(err.to_string()))?;
+            verifier.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, Error> {
+            verify_tls12_signature(
+                message,
+                cert,
+                dss,
+                &self.provider.signature_verification_algorithms,
+            )
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, Error> {
+            verify_tls13_signature(
+                message,
+                cert,
+                dss,
+                &self.provider.signature_verification_algorithms,
+            )
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.provider
+                .signature_verification_algorithms
+                .supported_schemes()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rustls"))]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use rustls::{
+        CertificateError, Error,
+        client::danger::ServerCertVerifier,
+        pki_types::{CertificateDer, ServerName, UnixTime},
+    };
+
+    use super::*;
+
+    /// A self-signed P-256 CA, valid from 2026-10-14 for 100 years.
+    const CA_CERT: &str = "
+        MIIBmDCCAT2gAwIBAgIUdMPJ6ha/xtRbrUWvyt+i/nEnS40wCgYIKoZIzj0EAwIwGDEWMBQGA1UEAwwNaTVyZXEg
+        VGVzdCBDQTAgFw0yNjEwMTQxNzU2MDFaGA8yMTI2MDkyMDE3NTYwMVowGDEWMBQGA1UEAwwNaTVyZXEgVGVzdCBD
+        QTBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABPnFp27XLUjjnCx9qVy79GQknTJXBOI2DUTYVVsBG2s7luxcO+9C
+        Nfh/CBaQhhENLd/XnHBp8pn7tZeIoWdJblujYzBhMB0GA1UdDgQWBBTD70mKO4Riri3oYyXP6x+oHwasBDAfBgNV
+        HSMEGDAWgBTD70mKO4Riri3oYyXP6x+oHwasBDAPBgNVHRMBAf8EBTADAQH/MA4GA1UdDwEB/wQEAwICBDAKBggq
+        hkjOPQQDAgNJADBGAiEAjB3XDilHMByT0z5xv+Ym88LCPhpOOC745S0BJkBnaxICIQC8fiLcXDUNpC6Gi+OPrnTb
+        bxl31EktlbiFoyLDQxKH/Q==";
+
+    /// A server certificate for `i5.example.com` issued by [`CA_CERT`].
+    const LEAF_CERT: &str = "
+        MIIBoDCCAUWgAwIBAgIBAjAKBggqhkjOPQQDAjAYMRYwFAYDVQQDDA1pNXJlcSBUZXN0IENBMCAXDTI2MTAxNDE3
+        NTYwMVoYDzIxMjYwOTIwMTc1NjAxWjAZMRcwFQYDVQQDDA5pNS5leGFtcGxlLmNvbTBZMBMGByqGSM49AgEGCCqG
+        SM49AwEHA0IABOQpWojqsJPuyveZ8m+bDM7BKEjPuUHKb9U5iuhI+2/L88GF7O8IAg1Ce1KMo7eXX5abOhZNLuyt
+        kvkTJSeoBDKjfTB7MBkGA1UdEQQSMBCCDmk1LmV4YW1wbGUuY29tMAkGA1UdEwQCMAAwEwYDVR0lBAwwCgYIKwYB
+        BQUHAwEwHQYDVR0OBBYEFFcrqgSOb5WDdXbzrRzNEtal3wSiMB8GA1UdIwQYMBaAFMPvSYo7hGKuLehjJc/rH6gf
+        BqwEMAoGCCqGSM49BAMCA0kAMEYCIQCaCVRcR/sIVY69Uwf0DEA0XMptt9TFJLQ9hiuHtULamwIhAK1WN9FuOQXh
+        od718YUmSeliXjy+rD09IjYU3fVGJmHw";
+
+    /// The `SubjectPublicKeyInfo` of [`LEAF_CERT`], as exported by
+    /// `openssl pkey -pubin -outform der`.
+    const LEAF_SPKI: &str = "MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE5ClaiOqwk+7K95nyb5sMzsEoSM+5Qcpv1TmK6Ej7b8vzwYXs7wgCDUJ7Uoyjt5dflps6Fk0u7K2S+RMlJ6gEMg==";
+
+    const LEAF_PIN: &str = "sha256/DlKQyMt4VArzSwqwbnPHanf81e8HlGBseaOjAeqdL6s=";
+    const CA_PIN: &str = "sha256/d8HPqhfHau1deKvQJIZtGfY3/vLHcwWD0LGE8cvjSf4=";
+
+    fn decode(base64: &str) -> Vec<u8> {
+        let base64: String = base64.split_whitespace().collect();
+        general_purpose::STANDARD.decode(base64).unwrap()
+    }
+
+    /// A DER element with the given tag, in short or long length form.
+    fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut element = vec![tag];
+        match content.len() {
+            length @ 0..=0x7f => element.push(length as u8),
+            length @ 0x80..=0xff => element.extend([0x81, length as u8]),
+            length => element.extend([0x82, (length >> 8) as u8, length as u8]),
+        }
+        element.extend_from_slice(content);
+        element
+    }
+
+    /// A certificate with placeholder fields and the given `spki`, with or without the
+    /// `[0]` version field of v2 and v3 certificates.
+    fn certificate(version: bool, issuer: &[u8], spki: &[u8]) -> Vec<u8> {
+        let mut tbs = Vec::new();
+        if version {
+            tbs.extend(der(0xa0, &der(0x02, &[2])));
+        }
+        tbs.extend(der(0x02, &[1]));
+        tbs.extend(der(0x30, &der(0x06, &[0x2a])));
+        tbs.extend(der(0x30, issuer));
+        tbs.extend(der(0x30, &[]));
+        tbs.extend(der(0x30, &[]));
+        tbs.extend_from_slice(spki);
+        tbs.extend(der(0xa3, &[]));
+        let mut cert = der(0x30, &tbs);
+        cert.extend(der(0x30, &der(0x06, &[0x2a])));
+        cert.extend(der(0x03, &[0]));
+        der(0x30, &cert)
+    }
+
+    fn verifier(pins: &[&str]) -> verifier::PinVerifier {
+        verifier::PinVerifier {
+            pins: pins.iter().map(|pin| parse_pin(pin).unwrap()).collect(),
+            provider: Arc::new(rustls::crypto::ring::default_provider()),
+        }
+    }
+
+    fn verify(
+        verifier: &verifier::PinVerifier,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &str,
+    ) -> Result<(), Error> {
+        verifier
+            .verify_server_cert(
+                &CertificateDer::from(decode(LEAF_CERT)),
+                intermediates,
+                &ServerName::try_from(server_name).unwrap().to_owned(),
+                &[],
+                // 2030-01-01, within the validity of the test certificates.
+                UnixTime::since_unix_epoch(Duration::from_secs(1_893_456_000)),
+            )
+            .map(|_| ())
+    }
+
+    #[test]
+    fn extracts_the_public_key_of_v1_and_v3_certificates() {
+        let spki = der(0x30, &der(0x03, &[0, 1, 2, 3]));
+        assert_eq!(
+            subject_public_key_info(&certificate(true, &[], &spki)),
+            Some(spki.as_slice())
+        );
+        assert_eq!(
+            subject_public_key_info(&certificate(false, &[], &spki)),
+            Some(spki.as_slice())
+        );
+        // An issuer of 300 bytes has a two byte long form length.
+        let issuer = der(0x31, &[0; 296]);
+        assert_eq!(
+            subject_public_key_info(&certificate(true, &issuer, &spki)),
+            Some(spki.as_slice())
+        );
+
+        assert_eq!(
+            subject_public_key_info(&decode(LEAF_CERT)),
+            Some(decode(LEAF_SPKI).as_slice())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_lengths() {
+        let spki = der(0x30, &der(0x03, &[0, 1, 2, 3]));
+        let cert = certificate(true, &[], &spki);
+
+        // Truncated anywhere, the certificate is shorter than its lengths claim.
+        for length in 0..cert.len() {
+            assert_eq!(subject_public_key_info(&cert[..length]), None, "{}", length);
+        }
+        // An element longer than the certificate.
+        let mut overlong = cert.clone();
+        overlong[1] += 1;
+        assert_eq!(subject_public_key_info(&overlong), None);
+        // A public key that is not a SEQUENCE.
+        let bit_string = der(0x03, &[0, 1, 2, 3]);
+        assert_eq!(
+            subject_public_key_info(&certificate(true, &[], &bit_string)),
+            None
+        );
+        // Long form lengths of more than 4 bytes, and the indefinite form.
+        for header in [
+            &[0x30, 0x85, 0, 0, 0, 0, 1][..],
+            &[0x30, 0x80],
+            &[0x30, 0xff],
+        ] {
+            let mut cert = header.to_vec();
+            cert.extend([0; 8]);
+            assert_eq!(subject_public_key_info(&cert), None, "{:x?}", header);
+        }
+    }
+
+    #[test]
+    fn matches_the_known_pin_of_a_certificate() {
+        let cert = decode(LEAF_CERT);
+        assert!(is_pinned(&cert, &[parse_pin(LEAF_PIN).unwrap()]));
+        assert!(!is_pinned(&cert, &[parse_pin(CA_PIN).unwrap()]));
+        assert!(!is_pinned(
+            &cert[..cert.len() - 1],
+            &[parse_pin(LEAF_PIN).unwrap()]
+        ));
+    }
+
+    #[test]
+    fn accepts_a_pinned_server_certificate() {
+        assert_eq!(
+            verify(&verifier(&[LEAF_PIN]), &[], "i5.example.com"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn accepts_a_certificate_issued_under_a_pinned_intermediate() {
+        let ca = CertificateDer::from(decode(CA_CERT));
+        assert_eq!(
+            verify(
+                &verifier(&[CA_PIN]),
+                std::slice::from_ref(&ca),
+                "i5.example.com"
+            ),
+            Ok(())
+        );
+        // The pinned issuer does not vouch for another host.
+        assert_eq!(
+            verify(&verifier(&[CA_PIN]), &[ca], "other.example.com").map_err(|_| ()),
+            Err(())
+        );
+    }
+
+    #[test]
+    fn rejects_a_chain_without_a_pinned_key() {
+        let ca = CertificateDer::from(decode(CA_CERT));
+        // The pinned issuer is not part of the chain.
+        assert_eq!(
+            verify(&verifier(&[CA_PIN]), &[], "i5.example.com"),
+            Err(Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure
+            ))
+        );
+        let unrelated = "sha256/AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+        assert_eq!(
+            verify(&verifier(&[unrelated]), &[ca], "i5.example.com"),
+            Err(Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure
+            ))
+        );
+    }
+}
@@ -0,0 +1,365 @@
+//! A small framework for turning raw inputs into submissions.
+//!
+//! A pipeline takes its inputs from any iterator, e.g. the files of a folder, the rows
+//! of a CSV file or messages from a queue. Every input becomes one document: the
+//! [transform stages](I5Pipeline::transform) and [transformers](I5Pipeline::transformer)
+//! fill it in, then it is wrapped in a request, validated and posted by an
+//! [`I5Sender`]. Inputs that fail at any step are handed to the
+//! [dead-letter handler](I5Pipeline::dead_letter) together with the error, so one bad
+//! input never stops the run.
+
+use std::{fmt, sync::OnceLock};
+
+use crate::{
+    events::i5_event,
+    request::{
+        blocking::BlockingI5Client,
+        client::{self, I5Client},
+        transport::I5Transport,
+    },
+    types::{
+        i5_error::I5RequestError,
+        i5_receipt::I5BatchReceipt,
+        i5_request::{Document, I5Request, ValidatedI5Request},
+        i5_transformer::I5Transformer,
+    },
+};
+
+/// The final stage of an [`I5Pipeline`], which names, validates and posts the
+/// requests.
+///
+/// It is implemented by [`BlockingI5Client`] and [`I5Client`], which validate with the
+/// rules of their [target API version](crate::types::i5_config::I5Config::api_version);
+/// test doubles can implement it to run a pipeline without a server.
+pub trait I5Sender {
+    /// Creates a new empty request, see [`BlockingI5Client::new_request`].
+    fn new_request(&self) -> Result<I5Request, I5RequestError>;
+
+    /// Validates the request built for an input. The default uses the
+    /// [default rules](crate::types::i5_validation::I5ValidationOptions::new).
+    fn validate(&self, request: I5Request) -> Result<ValidatedI5Request, I5RequestError> {
+        request.validate()
+    }
+
+    /// Posts a validated request, see [`BlockingI5Client::send`].
+    fn send(&self, valid_body: &ValidatedI5Request) -> Result<I5BatchReceipt, I5RequestError>;
+}
+
+impl I5Sender for BlockingI5Client {
+    fn new_request(&self) -> Result<I5Request, I5RequestError> {
+        BlockingI5Client::new_request(self)
+    }
+
+    fn validate(&self, request: I5Request) -> Result<ValidatedI5Request, I5RequestError> {
+        client::validate_for_target(self.config(), request)
+    }
+
+    fn send(&self, valid_body: &ValidatedI5Request) -> Result<I5BatchReceipt, I5RequestError> {
+        BlockingI5Client::send(self, valid_body)
+    }
+}
+
+/// Runs the submissions of an [`I5Client`] in a pipeline on a runtime of their own,
+/// shared by every client so that pooled connections stay usable.
+///
+/// [`I5Pipeline::run`] blocks, so it must not be called within a Tokio runtime with an
+/// `I5Client`; [`send`](I5Sender::send) returns [`I5RequestError::ConfigError`] there.
+impl<T: I5Transport> I5Sender for I5Client<T> {
+    fn new_request(&self) -> Result<I5Request, I5RequestError> {
+        I5Client::new_request(self)
+    }
+
+    fn validate(&self, request: I5Request) -> Result<ValidatedI5Request, I5RequestError> {
+        client::validate_for_target(self.config(), request)
+    }
+
+    fn send(&self, valid_body: &ValidatedI5Request) -> Result<I5BatchReceipt, I5RequestError> {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return Err(I5RequestError::ConfigError(String::from(
+                "an I5Client cannot run a pipeline within a Tokio runtime, use a BlockingI5Client",
+            )));
+        }
+        static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+        let runtime = match RUNTIME.get() {
+            Some(runtime) => runtime,
+            None => {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(I5RequestError::IoError)?;
+                RUNTIME.get_or_init(|| runtime)
+            }
+        };
+        runtime.block_on(I5Client::send(self, valid_body))
+    }
+}
+
+/// The step of an [`I5Pipeline`] at which an input failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum I5PipelineStage {
+    /// The sender could not name the request.
+    Name,
+    /// A transform stage failed.
+    Transform,
+    /// The request built from the input is invalid.
+    Validate,
+    /// The request could not be posted.
+    Send,
+}
+
+impl fmt::Display for I5PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            I5PipelineStage::Name => "name",
+            I5PipelineStage::Transform => "transform",
+            I5PipelineStage::Validate => "validate",
+            I5PipelineStage::Send => "send",
+        })
+    }
+}
+
+/// An input that failed in an [`I5Pipeline`], handed to its dead-letter handler.
+#[derive(Debug)]
+pub struct I5PipelineFailure<T> {
+    input: T,
+    stage: I5PipelineStage,
+    error: I5RequestError,
+}
+
+impl<T> I5PipelineFailure<T> {
+    /// The input that failed.
+    pub fn input(&self) -> &T {
+        &self.input
+    }
+
+    /// Returns the input that failed, e.g. to store it for a later run.
+    pub fn into_input(self) -> T {
+        self.input
+    }
+
+    /// The step at which the input failed.
+    pub fn stage(&self) -> I5PipelineStage {
+        self.stage
+    }
+
+    /// The error.
+    pub fn error(&self) -> &I5RequestError {
+        &self.error
+    }
+}
+
+/// The outcome of [`I5Pipeline::run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5PipelineReport {
+    receipts: Vec<I5BatchReceipt>,
+    failed: usize,
+}
+
+impl I5PipelineReport {
+    /// The receipts of the posted requests, in input order.
+    pub fn receipts(&self) -> &[I5BatchReceipt] {
+        &self.receipts
+    }
+
+    /// The number of inputs that failed.
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+}
+
+/// The signature of a transform stage, see [`I5Pipeline::transform`].
+type TransformFn<'a, T> = dyn FnMut(&T, &mut Document) -> Result<(), I5RequestError> + 'a;
+
+/// The signature of a dead-letter handler, see [`I5Pipeline::dead_letter`].
+type DeadLetterFn<'a, T> = dyn FnMut(I5PipelineFailure<T>) + 'a;
+
+/// Turns inputs of type `T` into documents and posts them, one request per input.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::{
+///     request::pipeline::{I5Pipeline, I5PipelineStage, I5Sender},
+///     types::{
+///         i5_error::I5RequestError,
+///         i5_receipt::I5BatchReceipt,
+///         i5_request::{I5Request, ValidatedI5Request},
+///     },
+/// };
+///
+/// // A sender that accepts everything, standing in for a `BlockingI5Client`.
+/// struct Accepting;
+///
+/// impl I5Sender for Accepting {
+///     fn new_request(&self) -> Result<I5Request, I5RequestError> {
+///         Ok(I5Request::new("Batch"))
+///     }
+///
+///     fn send(&self, valid_body: &ValidatedI5Request) -> Result<I5BatchReceipt, I5RequestError> {
+///         Ok(I5BatchReceipt::new(200, valid_body.name(), Default::default()))
+///     }
+/// }
+///
+/// let rows = ["4711;100.00", "4712", "4713;7.50"];
+/// let mut rejected = Vec::new();
+/// let report = I5Pipeline::new("Invoice")
+///     .transform(|row: &&str, document| {
+///         let (number, total) = row.split_once(';').ok_or_else(|| {
+///             I5RequestError::ConversionError(format!("no total in '{}'", row))
+///         })?;
+///         document
+///             .add_header_field("InvoiceNo", number)
+///             .add_header_field("Total", total);
+///         Ok(())
+///     })
+///     .dead_letter(|failure| rejected.push((*failure.input(), failure.stage())))
+///     .run(rows, &Accepting);
+///
+/// assert_eq!(report.receipts().len(), 2);
+/// assert_eq!(report.failed(), 1);
+/// assert_eq!(rejected, [("4712", I5PipelineStage::Transform)]);
+/// ```
+pub struct I5Pipeline<'a, T> {
+    document_name: String,
+    stages: Vec<Box<TransformFn<'a, T>>>,
+    dead_letter: Option<Box<DeadLetterFn<'a, T>>>,
+}
+
+impl<'a, T> I5Pipeline<'a, T> {
+    /// Creates a pipeline whose documents are named `document_name`.
+    pub fn new(document_name: impl Into<String>) -> I5Pipeline<'a, T> {
+        I5Pipeline {
+            document_name: document_name.into(),
+            stages: Vec::new(),
+            dead_letter: None,
+        }
+    }
+
+    /// Adds a stage that fills in or enriches the document of an input. Stages run in
+    /// the order they were added, each on the document left by the previous one; the
+    /// first error sends the input to the dead-letter handler.
+    pub fn transform(
+        mut self,
+        stage: impl FnMut(&T, &mut Document) -> Result<(), I5RequestError> + 'a,
+    ) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Adds a stage that runs `transformer` on the document of every input, see
+    /// [`I5Pipeline::transform`].
+    pub fn transformer(self, transformer: impl I5Transformer + 'a) -> Self {
+        self.transform(move |_, document| transformer.transform(document))
+    }
+
+    /// Sets the handler for inputs that failed. Without one, failed inputs are only
+    /// logged and counted.
+    pub fn dead_letter(mut self, handler: impl FnMut(I5PipelineFailure<T>) + 'a) -> Self {
+        self.dead_letter = Some(Box::new(handler));
+        self
+    }
+
+    /// Runs every input of `source` through the stages and posts it with `sender`.
+    pub fn run(
+        mut self,
+        source: impl IntoIterator<Item = T>,
+        sender: &impl I5Sender,
+    ) -> I5PipelineReport {
+        let mut report = I5PipelineReport::default();
+        for input in source {
+            match self.process(&input, sender) {
+                Ok(receipt) => report.receipts.push(receipt),
+                Err((stage, error)) => {
+                    report.failed += 1;
+                    i5_event!(
+                        warn,
+                        "I5Pipeline input failed",
+                        stage = stage,
+                        error = error
+                    );
+                    if let Some(handler) = &mut self.dead_letter {
+                        handler(I5PipelineFailure {
+                            input,
+                            stage,
+                            error,
+                        });
+                    }
+                }
+            }
+        }
+        report
+    }
+
+    /// Builds, validates and posts the request for one input.
+    fn process(
+        &mut self,
+        input: &T,
+        sender: &impl I5Sender,
+    ) -> Result<I5BatchReceipt, (I5PipelineStage, I5RequestError)> {
+        let mut request = sender
+            .new_request()
+            .map_err(|error| (I5PipelineStage::Name, error))?;
+        let id = request.add_document(self.document_name.as_str());
+        let document = request
+            .get_document_mut(id)
+            .expect("document was just added");
+        for stage in &mut self.stages {
+            stage(input, document).map_err(|error| (I5PipelineStage::Transform, error))?;
+        }
+        let valid_body = sender
+            .validate(request)
+            .map_err(|error| (I5PipelineStage::Validate, error))?;
+        sender
+            .send(&valid_body)
+            .map_err(|error| (I5PipelineStage::Send, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{i5_api_version::I5ApiVersion, i5_config::I5Config};
+
+    fn client() -> I5Client {
+        I5Client::new(
+            I5Config::new("localhost", 43001, "Scan", "Default").api_version(I5ApiVersion::V1),
+        )
+        .unwrap()
+    }
+
+    /// Runs one input with a negative item number, or none if `negative` is unset.
+    fn run(client: &I5Client, negative: bool) -> Vec<(I5PipelineStage, String)> {
+        let mut failures = Vec::new();
+        I5Pipeline::new("Invoice")
+            .transform(|item: &i32, document| {
+                document.add_item_field("Article", "A-1", if negative { -*item } else { *item });
+                Ok(())
+            })
+            .dead_letter(|failure| {
+                failures.push((failure.stage(), failure.error().code().to_string()))
+            })
+            .run([1], client);
+        failures
+    }
+
+    #[test]
+    fn clients_validate_with_the_rules_of_their_api_version() {
+        assert_eq!(
+            run(&client(), true),
+            [(I5PipelineStage::Validate, String::from("I5_VALIDATION"))]
+        );
+    }
+
+    #[test]
+    fn clients_refuse_to_block_within_a_runtime() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let failures = runtime.block_on(async { run(&client(), false) });
+        assert_eq!(
+            failures,
+            [(I5PipelineStage::Send, String::from("I5_CONFIG"))]
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Tracing spans for submissions.
+//!
+//! With the `otel` feature the spans carry the OpenTelemetry semantic convention
+//! attributes understood by `tracing-opentelemetry`, so submissions are exported as
+//! OTLP client spans.
+//!
+//! The crate does not read the OpenTelemetry context of these spans, so it does not
+//! propagate it on its own. A W3C trace context supplied with
+//! [`I5SendOptions::trace_parent`](crate::request::options::I5SendOptions::trace_parent)
+//! is forwarded in a `traceparent` header on the outgoing HTTP call; without one, no
+//! header is sent.
+
+use tracing::Span;
+
+use crate::types::{
+    i5_error::I5RequestError, i5_request::ValidatedI5Request, i5_request_url::I5RequestUrl,
+};
+
+/// The HTTP header carrying the W3C trace context.
+#[cfg(feature = "otel")]
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+#[cfg(not(feature = "otel"))]
+pub(crate) fn submission_span(
+    url: &I5RequestUrl,
+    body: &ValidatedI5Request,
+    correlation_id: &str,
+) -> Span {
+    tracing::info_span!(
+        "i5_http_post",
+        correlation_id = %correlation_id,
+        url = %crate::util::redact_query(&url.to_url()),
+        documents = body.document_count()
+    )
+}
+
+#[cfg(feature = "otel")]
+pub(crate) fn submission_span(
+    url: &I5RequestUrl,
+    body: &ValidatedI5Request,
+    correlation_id: &str,
+) -> Span {
+    tracing::info_span!(
+        "i5_http_post",
+        correlation_id = %correlation_id,
+        url = %crate::util::redact_query(&url.to_url()),
+        documents = body.document_count(),
+        otel.name = %format!("POST {}", url.target_endpoint()),
+        otel.kind = "client",
+        otel.status_code = tracing::field::Empty,
+        http.request.method = "POST",
+        http.response.status_code = tracing::field::Empty,
+        url.full = %crate::util::redact_query(&url.to_url()),
+        server.address = %url.hostname(),
+        server.port = url.port(),
+        i5.tenant = %url.tenant(),
+        i5.scenario = %url.scenario(),
+        i5.batch.size = body.document_count(),
+        i5.retry_count = tracing::field::Empty,
+        i5.outcome = tracing::field::Empty,
+        trace_id = tracing::field::Empty,
+    )
+}
+
+/// Records on the span of a submission how many submissions of the same request
+/// failed before it.
+#[cfg(feature = "otel")]
+pub(crate) fn record_retry_count(span: &Span, retries: u32) {
+    span.record("i5.retry_count", retries);
+}
+
+#[cfg(not(feature = "otel"))]
+pub(crate) fn record_retry_count(_span: &Span, _retries: u32) {}
+
+/// Records the outcome of a submission on its span.
+///
+/// `result` carries the HTTP status of an accepted submission.
+#[cfg(feature = "otel")]
+pub(crate) fn record_outcome(span: &Span, result: Result<u16, &I5RequestError>) {
+    match result {
+        Ok(status) => {
+            span.record("http.response.status_code", status);
+            span.record("otel.status_code", "OK");
+            span.record("i5.outcome", "accepted");
+        }
+        Err(err) => {
+            if let I5RequestError::ServerError(server_error) = err {
+                span.record("http.response.status_code", server_error.status());
+            }
+            span.record("otel.status_code", "ERROR");
+            span.record("i5.outcome", err.code());
+        }
+    }
+}
+
+#[cfg(not(feature = "otel"))]
+pub(crate) fn record_outcome(_span: &Span, _result: Result<u16, &I5RequestError>) {}
+
+/// The `traceparent` header value forwarded on an outgoing call, if `parent` is a
+/// valid W3C `traceparent` value.
+///
+/// The value is forwarded unchanged, so the server span joins the caller's trace as a
+/// child of the caller's span, and its trace ID is recorded on `span` as `trace_id`.
+/// Invalid values are dropped.
+#[cfg(feature = "otel")]
+pub(crate) fn forwarded_traceparent(span: &Span, parent: Option<&str>) -> Option<String> {
+    let parent = parent?.trim();
+    let trace_id = parse_traceparent(parent)?;
+    span.record("trace_id", trace_id.as_str());
+    Some(parent.to_string())
+}
+
+/// The trace ID of a valid W3C `traceparent` value.
+///
+/// Values with an invalid version, malformed or all-zero IDs are rejected. Versions
+/// after `00` may append fields, which are ignored.
+#[cfg(feature = "otel")]
+fn parse_traceparent(traceparent: &str) -> Option<String> {
+    let parts: Vec<&str> = traceparent.trim().split('-').collect();
+    let [version, trace_id, parent_id, flags, rest @ ..] = parts.as_slice() else {
+        return None;
+    };
+    let is_hex = |part: &str, len: usize| {
+        part.len() == len
+            && part
+                .bytes()
+                .all(|b| b.is_ascii_digit() || matches!(b, b'a'..=b'f'))
+    };
+    let is_zero = |part: &str| part.bytes().all(|b| b == b'0');
+    let valid = is_hex(version, 2)
+        && *version != "ff"
+        && (rest.is_empty() || *version != "00")
+        && is_hex(trace_id, 32)
+        && !is_zero(trace_id)
+        && is_hex(parent_id, 16)
+        && !is_zero(parent_id)
+        && is_hex(flags, 2);
+    valid.then(|| trace_id.to_string())
+}
+
+#[cfg(all(test, feature = "otel"))]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use tracing::{Event, Metadata, span, subscriber::Subscriber};
+
+    use super::*;
+
+    /// A subscriber that only assigns span IDs.
+    struct Ids(AtomicU64);
+
+    impl Subscriber for Ids {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(self.0.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+        fn event(&self, _: &Event<'_>) {}
+        fn enter(&self, _: &span::Id) {}
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    const PARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn parses_valid_traceparents() {
+        assert_eq!(
+            parse_traceparent(PARENT),
+            Some(String::from("4bf92f3577b34da6a3ce929d0e0e4736"))
+        );
+        assert!(
+            parse_traceparent("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00-x")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_traceparents() {
+        for invalid in [
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+        ] {
+            assert_eq!(parse_traceparent(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn forwards_only_a_valid_caller_traceparent() {
+        tracing::subscriber::with_default(Ids(AtomicU64::new(0)), || {
+            let span = tracing::info_span!("submission", trace_id = tracing::field::Empty);
+            assert_eq!(
+                forwarded_traceparent(&span, Some(&format!(" {} ", PARENT))).as_deref(),
+                Some(PARENT)
+            );
+            assert_eq!(forwarded_traceparent(&span, None), None);
+            assert_eq!(forwarded_traceparent(&span, Some("00-invalid")), None);
+        });
+    }
+}
@@ -0,0 +1,87 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::{
+    future::Future,
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::types::{i5_config::I5Config, i5_ip_family::I5IpFamily};
+
+tokio::task_local! {
+    /// Where the resolver records the lookup of the request sent by the current task.
+    static LOOKUP: Arc<Mutex<Option<Duration>>>;
+}
+
+/// Runs `future`, which sends one request, and returns its output together with the
+/// time the [`TimingResolver`] spent resolving the hostname for it.
+///
+/// The duration is `None` if the request reused an open connection, as only lookups
+/// started while `future` is polled on the current task are counted. Concurrent
+/// requests therefore never see each other's lookups.
+pub(crate) async fn measure_lookup<F: Future>(future: F) -> (F::Output, Option<Duration>) {
+    let lookup = Arc::default();
+    let output = LOOKUP.scope(Arc::clone(&lookup), future).await;
+    let elapsed = *lookup
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    (output, elapsed)
+}
+
+/// A DNS resolver with the DNS timeout and IP family of the configuration, which
+/// measures the lookups of requests sent with [`measure_lookup`].
+#[derive(Clone, Default)]
+pub(crate) struct TimingResolver {
+    timeout: Option<Duration>,
+    family: I5IpFamily,
+}
+
+impl TimingResolver {
+    /// A resolver with the DNS timeout and the IP family of `config`.
+    pub(crate) fn new(config: &I5Config) -> TimingResolver {
+        TimingResolver {
+            timeout: config.connect_timeouts().0,
+            family: config.ip_family_policy(),
+        }
+    }
+}
+
+impl Resolve for TimingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let elapsed = LOOKUP.try_with(Arc::clone).ok();
+        let (timeout, family) = (self.timeout, self.family);
+        Box::pin(async move {
+            let started = Instant::now();
+            let lookup = tokio::net::lookup_host((name.as_str(), 0));
+            let addrs: Vec<SocketAddr> = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, lookup).await.map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!(
+                            "looking up {} took longer than {} ms",
+                            name.as_str(),
+                            timeout.as_millis()
+                        ),
+                    )
+                })??,
+                None => lookup.await?,
+            }
+            .collect();
+            if let Some(elapsed) = elapsed {
+                *elapsed
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(started.elapsed());
+            }
+            let addrs = family.arrange(addrs);
+            if addrs.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} has no address for IP family {}", name.as_str(), family),
+                )
+                .into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
@@ -0,0 +1,149 @@
+//! Bearer tokens obtained from a configured
+//! [`I5TokenEndpoint`](crate::types::i5_token_endpoint::I5TokenEndpoint).
+
+use bytes::Bytes;
+use serde_json::Value;
+use std::{
+    borrow::Cow,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    events::i5_event,
+    request::transport::{I5Body, I5Transport, I5TransportRequest},
+    types::{i5_error::I5RequestError, i5_secret::I5Secret, i5_token_endpoint::I5TokenEndpoint},
+};
+
+/// How long before its expiry a cached token is replaced, so that it does not expire
+/// while a submission is uploaded.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// The token of a client, shared by its submissions.
+#[derive(Default)]
+pub(crate) struct TokenCache(Mutex<Option<CachedToken>>);
+
+struct CachedToken {
+    token: I5Secret,
+    refresh_at: Option<Instant>,
+}
+
+impl TokenCache {
+    /// The cached token, unless it is about to expire.
+    pub(crate) fn current(&self) -> Option<I5Secret> {
+        let cached = self.lock();
+        cached
+            .as_ref()
+            .filter(|cached| cached.refresh_at.is_none_or(|at| Instant::now() < at))
+            .map(|cached| cached.token.clone())
+    }
+
+    /// Caches `token`, valid for `expires_in` if the endpoint said so.
+    pub(crate) fn store(&self, token: I5Secret, expires_in: Option<Duration>) {
+        let refresh_at =
+            expires_in.map(|valid| Instant::now() + valid.saturating_sub(EXPIRY_MARGIN));
+        *self.lock() = Some(CachedToken { token, refresh_at });
+    }
+
+    /// Drops `token` after the server rejected it, unless another submission already
+    /// replaced it.
+    pub(crate) fn invalidate(&self, token: &I5Secret) {
+        let mut cached = self.lock();
+        if cached.as_ref().is_some_and(|cached| cached.token == *token) {
+            *cached = None;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<CachedToken>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// The cached token of `cache`, or a new one requested from `endpoint` through
+/// `transport`.
+pub(crate) async fn token(
+    cache: &TokenCache,
+    transport: &impl I5Transport,
+    endpoint: &I5TokenEndpoint,
+) -> Result<I5Secret, I5RequestError> {
+    if let Some(token) = cache.current() {
+        return Ok(token);
+    }
+    let request = I5TransportRequest::new(
+        reqwest::Method::POST,
+        endpoint.url().to_string(),
+        request_headers(),
+        I5Body::buffered(Bytes::from(endpoint.form())),
+    );
+    let response = transport.send(request).await?;
+    let (token, expires_in) = parse_response(endpoint, response.status(), response.body())?;
+    cache.store(token.clone(), expires_in);
+    Ok(token)
+}
+
+/// The headers of a token request.
+pub(crate) fn request_headers() -> Vec<(Cow<'static, str>, String)> {
+    vec![
+        (
+            "Content-Type".into(),
+            String::from("application/x-www-form-urlencoded"),
+        ),
+        ("Accept".into(), String::from("application/json")),
+    ]
+}
+
+/// The access token and its lifetime in the response of `endpoint` with status
+/// `status` and body `body`.
+///
+/// Returns [`I5RequestError::CredentialError`] if the endpoint refused the client or
+/// answered without an access token.
+pub(crate) fn parse_response(
+    endpoint: &I5TokenEndpoint,
+    status: u16,
+    body: &[u8],
+) -> Result<(I5Secret, Option<Duration>), I5RequestError> {
+    let value: Option<Value> = serde_json::from_slice(body).ok();
+    if !(200..300).contains(&status) {
+        let reason = value
+            .as_ref()
+            .and_then(|value| value.get("error_description").or(value.get("error")))
+            .and_then(Value::as_str)
+            .unwrap_or("no error description");
+        return Err(I5RequestError::CredentialError(format!(
+            "the token endpoint {} answered {} for client '{}': {}",
+            endpoint.url(),
+            status,
+            endpoint.client_id(),
+            reason
+        )));
+    }
+
+    let token = value
+        .as_ref()
+        .and_then(|value| value.get("access_token"))
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            I5RequestError::CredentialError(format!(
+                "the response of the token endpoint {} carries no access_token",
+                endpoint.url()
+            ))
+        })?;
+    let expires_in = value
+        .as_ref()
+        .and_then(|value| value.get("expires_in"))
+        .and_then(|expires_in| {
+            expires_in
+                .as_u64()
+                .or_else(|| expires_in.as_str()?.parse().ok())
+        })
+        .map(Duration::from_secs);
+    i5_event!(
+        debug,
+        "obtained bearer token",
+        endpoint = crate::util::redact_query(endpoint.url()),
+        expires_in_s = expires_in.map_or(0, |valid| valid.as_secs())
+    );
+    Ok((I5Secret::new(token), expires_in))
+}
@@ -0,0 +1,582 @@
+//! The HTTP call behind [`I5Client`](crate::request::client::I5Client).
+//!
+//! A client hands every submission to an [`I5Transport`] as an
+//! [`I5TransportRequest`]: the URL, the headers and the encoded body. The default
+//! transport, [`I5HttpTransport`], posts it with `reqwest`. Applications can implement
+//! the trait to inject test doubles, wrap the default transport with custom logic or
+//! route submissions through a proprietary tunnel, and pass it to
+//! [`I5Client::with_transport`](crate::request::client::I5Client::with_transport).
+
+use bytes::{Bytes, BytesMut};
+use std::{
+    borrow::Cow,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    request::{
+        body::EncodedBody,
+        decompress::ResponseDecoding,
+        timing::{self, TimingResolver},
+        wire,
+    },
+    types::{
+        i5_config::I5Config, i5_error::I5RequestError, i5_request::ValidatedI5Request,
+        i5_tls_backend::I5TlsBackend,
+    },
+};
+
+/// Applies the connection, certificate and TLS settings of an [`I5Config`] to a
+/// `reqwest` client builder, e.g.
+/// `configure_http_client!(reqwest::Client::builder(), config)?`.
+///
+/// The async and the blocking builder have the same methods but no common trait, so
+/// both clients share this macro instead of a function. Evaluates to
+/// [`I5RequestError::ConfigError`] if a configured CA certificate cannot be loaded.
+macro_rules! configure_http_client {
+    ($builder:expr, $config:expr) => {
+        (|| -> Result<_, $crate::types::i5_error::I5RequestError> {
+            let config: &$crate::types::i5_config::I5Config = $config;
+            let mut builder = $builder
+                .danger_accept_invalid_certs(config.accepts_invalid_certs())
+                .dns_resolver(std::sync::Arc::new(
+                    $crate::request::timing::TimingResolver::new(config),
+                ));
+            for certificate in $crate::request::client::root_certificates(config)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+            for (hostname, ip) in config.resolve_overrides() {
+                builder = builder.resolve(hostname, std::net::SocketAddr::new(*ip, 0));
+            }
+            let (max_idle, idle_timeout) = config.idle_pool_limits();
+            if let Some(max) = max_idle {
+                builder = builder.pool_max_idle_per_host(max);
+            }
+            if let Some(timeout) = idle_timeout {
+                builder = builder.pool_idle_timeout(timeout);
+            }
+            if config
+                .credentials()
+                .is_some_and($crate::types::i5_auth::I5Auth::is_connection_bound)
+            {
+                // The handshake and the request must reuse one connection, see `negotiate`.
+                builder = builder.http1_only().pool_max_idle_per_host(1);
+            }
+            if let Some(timeout) = config.connect_timeouts().1 {
+                builder = builder.connect_timeout(timeout);
+            }
+            if let Some(address) = config.bind_address() {
+                builder = builder.local_address(address);
+            }
+            let (keepalive, keepalive_interval) = config.tcp_keepalive_times();
+            if let Some(time) = keepalive {
+                builder = builder.tcp_keepalive(time);
+            }
+            if let Some(interval) = keepalive_interval {
+                builder = builder.tcp_keepalive_interval(interval);
+            }
+            match $crate::request::pinning::tls_config(config)? {
+                #[cfg(feature = "rustls")]
+                Some(tls) => builder = builder.use_preconfigured_tls(tls),
+                _ => {
+                    let backend =
+                        $crate::request::client::tls_backend(config.effective_tls_backend())?;
+                    for certificate in $crate::request::client::backend_root_certificates(backend)?
+                    {
+                        builder = builder.add_root_certificate(certificate);
+                    }
+                    #[cfg(feature = "rustls")]
+                    if backend == $crate::types::i5_tls_backend::I5TlsBackend::Rustls {
+                        builder = builder.use_rustls_tls();
+                    }
+                    let (min_tls, max_tls) = $crate::request::client::tls_versions(config)?;
+                    if let Some(version) = min_tls {
+                        builder = builder.min_tls_version(version);
+                    }
+                    if let Some(version) = max_tls {
+                        builder = builder.max_tls_version(version);
+                    }
+                }
+            }
+            Ok(builder)
+        })()
+    };
+}
+
+pub(crate) use configure_http_client;
+
+/// Sends the HTTP requests of an [`I5Client`](crate::request::client::I5Client).
+///
+/// A transport only moves bytes: the client builds the body and the headers, and turns
+/// a non-success status into [`I5RequestError::ServerError`]. Errors returned by the
+/// transport are passed to the caller unchanged.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::{
+///     request::{
+///         client::I5Client,
+///         transport::{I5Transport, I5TransportRequest, I5TransportResponse},
+///     },
+///     types::{i5_config::I5Config, i5_error::I5RequestError},
+/// };
+/// use std::sync::Mutex;
+///
+/// // A transport that records the bodies instead of sending them.
+/// #[derive(Default)]
+/// struct Recording(Mutex<Vec<String>>);
+///
+/// impl I5Transport for Recording {
+///     async fn send(
+///         &self,
+///         request: I5TransportRequest,
+///     ) -> Result<I5TransportResponse, I5RequestError> {
+///         let body = request.into_body().into_bytes();
+///         self.0.lock().unwrap().push(String::from_utf8_lossy(&body).into_owned());
+///         Ok(I5TransportResponse::new(200, "OK"))
+///     }
+/// }
+///
+/// # tokio_test(async {
+/// let config = I5Config::new("i5.example.com", 43001, "Scan", "Default");
+/// let client = I5Client::with_transport(config, Recording::default());
+///
+/// let mut request = client.new_request()?;
+/// let id = request.add_document("Invoice");
+/// request.get_document_mut(id).unwrap().add_header_field("No", "4711");
+/// client.send(request.validate()?).await?;
+///
+/// assert!(client.transport().0.lock().unwrap()[0].contains(r#""Value":"4711""#));
+/// # Ok::<(), I5RequestError>(())
+/// # });
+/// # fn tokio_test<F: std::future::Future>(future: F) -> F::Output {
+/// #     tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+/// # }
+/// ```
+pub trait I5Transport: Send + Sync {
+    /// Sends `request` and returns the response of the server, whatever its status.
+    fn send(
+        &self,
+        request: I5TransportRequest,
+    ) -> impl Future<Output = Result<I5TransportResponse, I5RequestError>> + Send;
+}
+
+/// The body of a submission, either serialized or encoded while it is sent.
+pub struct I5Body(EncodedBody);
+
+impl I5Body {
+    /// The body of a request without content, e.g. a `GET`.
+    pub(crate) fn empty() -> I5Body {
+        I5Body(EncodedBody::Buffered(Bytes::new()))
+    }
+
+    /// A body sent from a buffer, e.g. a chunk of an attachment.
+    pub(crate) fn buffered(body: Bytes) -> I5Body {
+        I5Body(EncodedBody::Buffered(body))
+    }
+
+    /// The length of the body in bytes.
+    pub fn len(&self) -> u64 {
+        self.0.len()
+    }
+
+    /// Whether the body is empty, as for lookups but never for submissions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The body as a sequence of chunks, without encoding large bodies up front.
+    pub fn into_chunks(self) -> impl Iterator<Item = Bytes> + Send {
+        let (buffered, streamed) = match self.0 {
+            EncodedBody::Buffered(body) => (Some(body), None),
+            EncodedBody::Streamed(chunks) => (None, Some(chunks)),
+        };
+        buffered.into_iter().chain(streamed.into_iter().flatten())
+    }
+
+    /// The body if it is in memory rather than encoded while it is sent.
+    pub(crate) fn in_memory(&self) -> Option<&[u8]> {
+        self.0.in_memory()
+    }
+
+    /// The whole body in memory.
+    pub fn into_bytes(self) -> Bytes {
+        let len = usize::try_from(self.len()).unwrap_or(0);
+        match self.0 {
+            EncodedBody::Buffered(body) => body,
+            EncodedBody::Streamed(chunks) => {
+                let mut body = BytesMut::with_capacity(len);
+                chunks.for_each(|chunk| body.extend_from_slice(&chunk));
+                body.freeze()
+            }
+        }
+    }
+}
+
+impl From<EncodedBody> for I5Body {
+    fn from(body: EncodedBody) -> I5Body {
+        I5Body(body)
+    }
+}
+
+impl From<I5Body> for reqwest::Body {
+    fn from(body: I5Body) -> reqwest::Body {
+        reqwest::Body::from(body.0)
+    }
+}
+
+impl From<I5Body> for reqwest::blocking::Body {
+    fn from(body: I5Body) -> reqwest::blocking::Body {
+        reqwest::blocking::Body::from(body.0)
+    }
+}
+
+impl From<&ValidatedI5Request> for I5Body {
+    /// The JSON body of a request, e.g. for an HTTP stack other than `reqwest` through
+    /// [`I5Body::into_chunks`]. The body is sent from the cache if it is
+    /// [cached](ValidatedI5Request::body), and encoded chunk by chunk otherwise.
+    fn from(valid_body: &ValidatedI5Request) -> I5Body {
+        I5Body(EncodedBody::unbuffered(valid_body))
+    }
+}
+
+impl From<ValidatedI5Request> for I5Body {
+    fn from(valid_body: ValidatedI5Request) -> I5Body {
+        I5Body::from(&valid_body)
+    }
+}
+
+impl From<&ValidatedI5Request> for reqwest::Body {
+    /// The JSON body of a request, to send it with an existing `reqwest` client and
+    /// its middleware. The body carries its length; the caller sets the
+    /// `Content-Type` header and any credentials.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("No", "4711");
+    /// let validated = request.validate()?;
+    ///
+    /// let response = reqwest::Client::new()
+    ///     .post("https://i5.example.com:43001/api/v1/Input/Default/Scan")
+    ///     .header("Content-Type", "application/json")
+    ///     .body(&validated)
+    ///     .send()
+    ///     .await?;
+    /// println!("{}", response.status());
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn from(valid_body: &ValidatedI5Request) -> reqwest::Body {
+        reqwest::Body::from(I5Body::from(valid_body))
+    }
+}
+
+impl From<ValidatedI5Request> for reqwest::Body {
+    fn from(valid_body: ValidatedI5Request) -> reqwest::Body {
+        reqwest::Body::from(&valid_body)
+    }
+}
+
+impl From<&ValidatedI5Request> for reqwest::blocking::Body {
+    /// The JSON body of a request for a blocking `reqwest` client.
+    fn from(valid_body: &ValidatedI5Request) -> reqwest::blocking::Body {
+        reqwest::blocking::Body::from(I5Body::from(valid_body))
+    }
+}
+
+impl From<ValidatedI5Request> for reqwest::blocking::Body {
+    fn from(valid_body: ValidatedI5Request) -> reqwest::blocking::Body {
+        reqwest::blocking::Body::from(&valid_body)
+    }
+}
+
+/// A submission handed to an [`I5Transport`].
+pub struct I5TransportRequest {
+    method: reqwest::Method,
+    url: String,
+    headers: Vec<(Cow<'static, str>, String)>,
+    body: I5Body,
+}
+
+impl I5TransportRequest {
+    pub(crate) fn new(
+        method: reqwest::Method,
+        url: String,
+        headers: Vec<(Cow<'static, str>, String)>,
+        body: I5Body,
+    ) -> I5TransportRequest {
+        I5TransportRequest {
+            method,
+            url,
+            headers,
+            body,
+        }
+    }
+
+    /// The HTTP method, `POST` for submissions and `GET` for lookups such as
+    /// [`I5Client::tenants`](crate::request::client::I5Client::tenants).
+    pub fn method(&self) -> &reqwest::Method {
+        &self.method
+    }
+
+    /// The URL to send the request to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The HTTP headers to send, in order.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.as_ref(), value.as_str()))
+    }
+
+    /// The value of the header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// The body.
+    pub fn body(&self) -> &I5Body {
+        &self.body
+    }
+
+    /// Returns the body, e.g. to send it.
+    pub fn into_body(self) -> I5Body {
+        self.body
+    }
+
+    /// Adds the header `name`.
+    pub(crate) fn with_header(mut self, name: impl Into<Cow<'static, str>>, value: String) -> Self {
+        self.headers.push((name.into(), value));
+        self
+    }
+
+    /// The request with an empty body, e.g. to obtain an authentication challenge
+    /// without sending the payload twice.
+    pub(crate) fn without_body(&self) -> I5TransportRequest {
+        I5TransportRequest::new(
+            self.method.clone(),
+            self.url.clone(),
+            self.headers.clone(),
+            I5Body::empty(),
+        )
+    }
+}
+
+/// The response of the server to an [`I5TransportRequest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5TransportResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+    dns: Option<Duration>,
+    time_to_first_byte: Option<Duration>,
+}
+
+impl I5TransportResponse {
+    /// Creates a response with the given HTTP status and body.
+    pub fn new(status: u16, body: impl Into<Bytes>) -> I5TransportResponse {
+        I5TransportResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            dns: None,
+            time_to_first_byte: None,
+        }
+    }
+
+    /// Records the time until the response headers arrived, reported in the
+    /// [transfer stats](crate::types::i5_receipt::I5TransferStats) of the receipt.
+    /// Without it, the time until the transport returned is reported.
+    pub fn time_to_first_byte(mut self, time_to_first_byte: Duration) -> Self {
+        self.time_to_first_byte = Some(time_to_first_byte);
+        self
+    }
+
+    /// Records the time spent resolving the hostname.
+    pub fn dns(mut self, dns: Duration) -> Self {
+        self.dns = Some(dns);
+        self
+    }
+
+    /// Adds the response header `name`. Headers that occur several times, such as
+    /// `WWW-Authenticate`, are added once per value.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// The HTTP status code.
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// The response headers, in order.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The first value of the header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Every value of the header `name`, compared case-insensitively.
+    pub(crate) fn header_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.headers()
+            .filter(move |(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// The response body.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    pub(crate) fn measured_dns(&self) -> Option<Duration> {
+        self.dns
+    }
+
+    pub(crate) fn measured_time_to_first_byte(&self) -> Option<Duration> {
+        self.time_to_first_byte
+    }
+}
+
+/// The default [`I5Transport`], which posts submissions with `reqwest` and measures
+/// how long the hostname lookup of each request takes, if it needs one.
+#[derive(Clone)]
+pub struct I5HttpTransport {
+    http: reqwest::Client,
+    decoding: ResponseDecoding,
+}
+
+impl I5HttpTransport {
+    /// Creates a transport with the certificate options and the
+    /// [resolve overrides](I5Config::resolve) of `config`.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] if no host is configured or a
+    /// configured CA certificate cannot be loaded.
+    pub fn new(config: &I5Config) -> Result<I5HttpTransport, I5RequestError> {
+        crate::request::client::check_endpoint(config)?;
+        let builder = configure_http_client!(reqwest::Client::builder(), config)?;
+
+        let http = builder.build().map_err(I5RequestError::RequestError)?;
+        Ok(I5HttpTransport {
+            http,
+            decoding: ResponseDecoding::new(config),
+        })
+    }
+
+    /// Creates a transport that only trusts the system certificates, or every
+    /// certificate if `allow_untrusted_cert` is set.
+    pub(crate) fn untrusted(allow_untrusted_cert: bool) -> Result<I5HttpTransport, I5RequestError> {
+        let backend = crate::request::client::tls_backend(I5TlsBackend::compiled_default())?;
+        let mut builder = reqwest::Client::builder()
+            .danger_accept_invalid_certs(allow_untrusted_cert)
+            .dns_resolver(Arc::new(TimingResolver::default()));
+        for certificate in crate::request::client::backend_root_certificates(backend)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+        let http = builder.build().map_err(I5RequestError::RequestError)?;
+        Ok(I5HttpTransport {
+            http,
+            decoding: ResponseDecoding::default(),
+        })
+    }
+}
+
+impl From<reqwest::Client> for I5HttpTransport {
+    /// Uses an existing `reqwest` client, e.g. one built with a proxy. Hostname
+    /// lookups are not measured.
+    fn from(http: reqwest::Client) -> I5HttpTransport {
+        I5HttpTransport {
+            http,
+            decoding: ResponseDecoding::default(),
+        }
+    }
+}
+
+impl I5Transport for I5HttpTransport {
+    async fn send(
+        &self,
+        request: I5TransportRequest,
+    ) -> Result<I5TransportResponse, I5RequestError> {
+        let post = post(&self.http, self.decoding.prepare(request), &self.decoding);
+        let (response, dns) = timing::measure_lookup(post).await;
+        Ok(match dns {
+            Some(dns) => response?.dns(dns),
+            None => response?,
+        })
+    }
+}
+
+impl I5Transport for reqwest::Client {
+    async fn send(
+        &self,
+        request: I5TransportRequest,
+    ) -> Result<I5TransportResponse, I5RequestError> {
+        post(self, request, &ResponseDecoding::default()).await
+    }
+}
+
+async fn post(
+    http: &reqwest::Client,
+    request: I5TransportRequest,
+    decoding: &ResponseDecoding,
+) -> Result<I5TransportResponse, I5RequestError> {
+    wire::request(
+        request.method.as_str(),
+        &request.url,
+        request.headers(),
+        request.body.in_memory(),
+        request.body.len(),
+    );
+    let mut builder = http.request(request.method, request.url.as_str());
+    for (name, value) in &request.headers {
+        builder = builder.header(name.as_ref(), value);
+    }
+
+    let started = Instant::now();
+    let res = builder
+        .body(reqwest::Body::from(request.body))
+        .send()
+        .await
+        .map_err(I5RequestError::RequestError)?;
+    let time_to_first_byte = started.elapsed();
+
+    let status = res.status().as_u16();
+    let mut headers: Vec<(String, String)> = res
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let body = res.bytes().await.map_err(I5RequestError::RequestError)?;
+    let body = decoding.decode(status, &mut headers, body)?;
+    wire::response(
+        status,
+        headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str())),
+        &body,
+    );
+    let mut response =
+        I5TransportResponse::new(status, body).time_to_first_byte(time_to_first_byte);
+    response.headers = headers;
+    Ok(response)
+}
@@ -0,0 +1,249 @@
+//! Upload sessions for requests above the single-request limit of the server.
+//!
+//! Upload sessions are not part of the documented Interface5 WebServiceInput API. They
+//! are an extension assumed to be provided by a gateway or a customized server, so the
+//! client only opens them if [`I5Config::upload_sessions`] is enabled. The protocol
+//! the server is expected to follow:
+//!
+//! - A session is opened with `POST …/{tenant}/{scenario}/UploadSessions`, answered
+//!   with the `SessionId` of the session.
+//! - The content of every file is uploaded with
+//!   `PUT …/UploadSessions/{id}/Documents/{document}/Files/{file}`, documents and files
+//!   counted from zero, in chunks that each carry a `Content-Range` header.
+//! - The batch is posted to `…/UploadSessions/{id}/Batches` with the `Data` of the
+//!   uploaded files left empty, and the server is expected to take their content from
+//!   the session.
+//! - A session that fails is deleted.
+//!
+//! [`I5Config::upload_sessions`]: crate::types::i5_config::I5Config::upload_sessions
+
+use bytes::Bytes;
+use serde_json::{Value, json};
+use std::{borrow::Cow, time::Instant};
+use tracing::Span;
+
+use crate::{
+    events::i5_event,
+    request::{
+        self, Pooled,
+        options::I5SendOptions,
+        transport::{I5Body, I5Transport, I5TransportRequest},
+    },
+    types::{
+        i5_discovery,
+        i5_error::I5RequestError,
+        i5_receipt::{I5BatchReceipt, I5TransferStats},
+        i5_request::ValidatedI5Request,
+        i5_request_url::I5RequestUrl,
+    },
+};
+
+/// Sends a validated request through an upload session, uploading attachments in
+/// chunks of `chunk_size` bytes.
+///
+/// Every step carries the same correlation ID, which is returned in the receipt. The
+/// transfer stats cover the whole session.
+pub(crate) async fn send_in_session(
+    transport: &Pooled<impl I5Transport>,
+    valid_body: &ValidatedI5Request,
+    url: &I5RequestUrl,
+    options: I5SendOptions,
+    chunk_size: u64,
+) -> Result<I5BatchReceipt, I5RequestError> {
+    let correlation_id = options.resolve_correlation_id();
+    let options = options.correlation_id(correlation_id.clone());
+    let started = Instant::now();
+    let (batch, contents) = valid_body.detach_files();
+
+    let session = Session::open(transport, url, &options, &correlation_id, batch.name()).await?;
+    i5_event!(
+        info,
+        "opened Interface5 upload session",
+        correlation_id = correlation_id,
+        session = session.id,
+        files = contents.len()
+    );
+
+    let result = async {
+        let mut uploaded = 0;
+        for (document, file, content) in &contents {
+            uploaded += session
+                .upload(transport, *document, *file, content, chunk_size)
+                .await?;
+        }
+        let target = session.url(&["Batches"], reqwest::Method::POST)?;
+        let receipt = request::submit_to(transport, &batch, url, target, &options).await?;
+        Ok((receipt, uploaded))
+    }
+    .await;
+
+    match result {
+        Ok((receipt, uploaded)) => {
+            let stats = receipt.stats();
+            let stats = I5TransferStats::new(
+                stats.dns(),
+                stats.time_to_first_byte(),
+                started.elapsed(),
+                stats.bytes_sent() + uploaded,
+                stats.bytes_received(),
+            );
+            Ok(receipt.with_stats(stats))
+        }
+        Err(err) => {
+            session.abort(transport).await;
+            Err(err)
+        }
+    }
+}
+
+/// An open upload session.
+struct Session<'a> {
+    id: String,
+    url: &'a I5RequestUrl,
+    options: &'a I5SendOptions,
+    correlation_id: &'a str,
+}
+
+impl<'a> Session<'a> {
+    /// Opens a session for the batch `name`.
+    ///
+    /// Returns [`I5RequestError::ContractError`] if the response carries no session ID.
+    async fn open(
+        transport: &Pooled<impl I5Transport>,
+        url: &'a I5RequestUrl,
+        options: &'a I5SendOptions,
+        correlation_id: &'a str,
+        name: &str,
+    ) -> Result<Session<'a>, I5RequestError> {
+        let target = url
+            .custom_endpoint([url.scenario(), "UploadSessions"], reqwest::Method::POST)?
+            .url()
+            .to_string();
+        let body =
+            serde_json::to_vec(&json!({ "Name": name })).map_err(I5RequestError::SerializeError)?;
+        let mut headers = request::submission_headers(options, correlation_id, &Span::current());
+        headers.retain(|(name, _)| *name != "Content-Type");
+        headers.push(("Content-Type".into(), String::from("application/json")));
+        let request = I5TransportRequest::new(
+            reqwest::Method::POST,
+            target,
+            headers,
+            I5Body::buffered(Bytes::from(body)),
+        );
+
+        let response = request::send_checked(transport, request, options.credentials()).await?;
+        let value: Value =
+            serde_json::from_slice(response.body()).map_err(I5RequestError::SerializeError)?;
+        let id = value
+            .as_object()
+            .and_then(|object| i5_discovery::key(object, &["SessionId", "Id"]))
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                I5RequestError::ContractError(String::from(
+                    "the upload session response carries no SessionId",
+                ))
+            })?;
+        Ok(Session {
+            id: id.to_string(),
+            url,
+            options,
+            correlation_id,
+        })
+    }
+
+    /// Uploads the content of file `file` of document `document` and returns the number
+    /// of bytes sent.
+    async fn upload(
+        &self,
+        transport: &Pooled<impl I5Transport>,
+        document: usize,
+        file: usize,
+        content: &Bytes,
+        chunk_size: u64,
+    ) -> Result<u64, I5RequestError> {
+        let target = self.url(
+            &[
+                "Documents",
+                &document.to_string(),
+                "Files",
+                &file.to_string(),
+            ],
+            reqwest::Method::PUT,
+        )?;
+        let total = content.len();
+        let chunk_size = usize::try_from(chunk_size).unwrap_or(usize::MAX);
+        let mut start = 0;
+        while start < total {
+            let end = total.min(start.saturating_add(chunk_size));
+            let mut headers = self.headers();
+            headers.push((
+                "Content-Type".into(),
+                String::from("application/octet-stream"),
+            ));
+            headers.push((
+                "Content-Range".into(),
+                format!("bytes {}-{}/{}", start, end - 1, total),
+            ));
+            i5_event!(
+                debug,
+                "uploading attachment chunk to Interface5",
+                correlation_id = self.correlation_id,
+                session = self.id,
+                document = document,
+                file = file,
+                range_start = start,
+                range_end = end
+            );
+            let request = I5TransportRequest::new(
+                reqwest::Method::PUT,
+                target.clone(),
+                headers,
+                I5Body::buffered(content.slice(start..end)),
+            );
+            request::send_checked(transport, request, self.options.credentials()).await?;
+            start = end;
+        }
+        Ok(total as u64)
+    }
+
+    /// Deletes the session after a failure. Errors are logged, since the failure that
+    /// caused the abort is returned.
+    async fn abort(&self, transport: &Pooled<impl I5Transport>) {
+        let result = async {
+            let target = self.url(&[], reqwest::Method::DELETE)?;
+            let request = I5TransportRequest::new(
+                reqwest::Method::DELETE,
+                target,
+                self.headers(),
+                I5Body::empty(),
+            );
+            request::send_checked(transport, request, self.options.credentials()).await
+        }
+        .await;
+        if let Err(err) = result {
+            i5_event!(
+                warn,
+                "failed to delete Interface5 upload session",
+                correlation_id = self.correlation_id,
+                session = self.id,
+                error = err
+            );
+        }
+    }
+
+    /// The URL of a resource of the session.
+    fn url(&self, segments: &[&str], method: reqwest::Method) -> Result<String, I5RequestError> {
+        let path = [self.url.scenario(), "UploadSessions", &self.id]
+            .into_iter()
+            .chain(segments.iter().copied());
+        Ok(self.url.custom_endpoint(path, method)?.url().to_string())
+    }
+
+    /// The headers of a session request without a JSON body.
+    fn headers(&self) -> Vec<(Cow<'static, str>, String)> {
+        let mut headers =
+            request::submission_headers(self.options, self.correlation_id, &Span::current());
+        headers.retain(|(name, _)| *name != "Content-Type");
+        headers
+    }
+}
@@ -0,0 +1,207 @@
+//! Wire-level tracing of the HTTP calls, with the `wire-debug` feature.
+//!
+//! Every request is emitted as a `trace` event `wire request` with the request line,
+//! the headers and the first [`BODY_PREVIEW_LEN`] bytes of the body, and every
+//! response as `wire response` with the status, the headers and the start of the
+//! decoded body, so that a submission can be compared with one sent by another tool.
+//!
+//! Credentials are masked: the values of `Authorization`, `Proxy-Authorization`,
+//! `Cookie` and `Set-Cookie`, of headers whose name contains `key`, `token`,
+//! `secret` or `password` (e.g. `X-API-Key`), and of such parameters of form bodies
+//! and top-level members of JSON bodies, e.g. `client_secret` of token requests and
+//! `access_token` of token responses. The values of query parameters are masked as
+//! well. Field values of submissions are logged as they are.
+//!
+//! Only JSON bodies of up to [`MAX_PARSED_JSON_LEN`] bytes are parsed to mask their
+//! members, so that tracing a large submission does not parse it a second time. The
+//! credential bodies of token requests and responses are far smaller.
+
+/// The number of body bytes included in an event.
+#[cfg(feature = "wire-debug")]
+pub(crate) const BODY_PREVIEW_LEN: usize = 4096;
+
+/// The size up to which JSON bodies are parsed to mask their sensitive members.
+#[cfg(feature = "wire-debug")]
+pub(crate) const MAX_PARSED_JSON_LEN: usize = 64 * 1024;
+
+/// Emits the request to `url` with `method`, `headers` and a body of `len` bytes, of
+/// which `body` is the start if it is in memory.
+#[cfg(feature = "wire-debug")]
+pub(crate) fn request<'a>(
+    method: &str,
+    url: &str,
+    headers: impl Iterator<Item = (&'a str, &'a str)>,
+    body: Option<&[u8]>,
+    len: u64,
+) {
+    if !enabled() {
+        return;
+    }
+    let mut dump = format!("> {} {}\n", method, crate::util::redact_query(url));
+    let mut content_type = None;
+    for (name, value) in headers {
+        if name.eq_ignore_ascii_case("Content-Type") {
+            content_type = Some(value.to_ascii_lowercase());
+        }
+        dump.push_str(&format!("> {}: {}\n", name, header_value(name, value)));
+    }
+    dump.push_str(">\n");
+    match body {
+        Some(body) => dump.push_str(&preview(content_type.as_deref(), body)),
+        None => dump.push_str(&format!("<{} bytes, streamed>", len)),
+    }
+    crate::events::i5_event!(trace, "wire request", dump = dump);
+}
+
+/// Emits the response with `status`, `headers` and `body`.
+#[cfg(feature = "wire-debug")]
+pub(crate) fn response<'a>(
+    status: u16,
+    headers: impl Iterator<Item = (&'a str, &'a str)>,
+    body: &[u8],
+) {
+    if !enabled() {
+        return;
+    }
+    let mut dump = format!("< {}\n", status);
+    let mut content_type = None;
+    for (name, value) in headers {
+        if name.eq_ignore_ascii_case("Content-Type") {
+            content_type = Some(value.to_ascii_lowercase());
+        }
+        dump.push_str(&format!("< {}: {}\n", name, header_value(name, value)));
+    }
+    dump.push_str("<\n");
+    dump.push_str(&preview(content_type.as_deref(), body));
+    crate::events::i5_event!(trace, "wire response", dump = dump);
+}
+
+#[cfg(not(feature = "wire-debug"))]
+#[inline(always)]
+pub(crate) fn request<'a>(
+    _method: &str,
+    _url: &str,
+    _headers: impl Iterator<Item = (&'a str, &'a str)>,
+    _body: Option<&[u8]>,
+    _len: u64,
+) {
+}
+
+#[cfg(not(feature = "wire-debug"))]
+#[inline(always)]
+pub(crate) fn response<'a>(
+    _status: u16,
+    _headers: impl Iterator<Item = (&'a str, &'a str)>,
+    _body: &[u8],
+) {
+}
+
+/// Whether a `trace` event of the crate is recorded anywhere, so that the dumps are
+/// only formatted when they are.
+#[cfg(feature = "wire-debug")]
+fn enabled() -> bool {
+    #[cfg(feature = "log")]
+    if log::log_enabled!(target: "i5_req", log::Level::Trace) {
+        return true;
+    }
+    tracing::enabled!(tracing::Level::TRACE)
+}
+
+/// Whether the header or parameter `name` carries a credential.
+#[cfg(feature = "wire-debug")]
+fn is_sensitive(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    matches!(
+        lower.as_str(),
+        "authorization" | "proxy-authorization" | "cookie" | "set-cookie"
+    ) || ["key", "token", "secret", "password"]
+        .iter()
+        .any(|word| lower.contains(word))
+}
+
+#[cfg(feature = "wire-debug")]
+fn header_value<'a>(name: &str, value: &'a str) -> &'a str {
+    if is_sensitive(name) {
+        "<redacted>"
+    } else {
+        value
+    }
+}
+
+/// The start of `body` with content type `content_type`, with credentials masked.
+#[cfg(feature = "wire-debug")]
+fn preview(content_type: Option<&str>, body: &[u8]) -> String {
+    use serde_json::Value;
+
+    let redacted = match content_type {
+        Some(kind) if kind.starts_with("application/x-www-form-urlencoded") => {
+            Some(redact_form(&String::from_utf8_lossy(body)).into_bytes())
+        }
+        Some(kind) if kind.contains("json") && body.len() <= MAX_PARSED_JSON_LEN => {
+            match serde_json::from_slice(body) {
+                Ok(Value::Object(mut members)) => {
+                    let mut masked = false;
+                    for (name, value) in members.iter_mut() {
+                        if is_sensitive(name) {
+                            *value = Value::String(String::from("<redacted>"));
+                            masked = true;
+                        }
+                    }
+                    masked.then(|| Value::Object(members).to_string().into_bytes())
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    let body = redacted.as_deref().unwrap_or(body);
+    let shown = &body[..body.len().min(BODY_PREVIEW_LEN)];
+    let mut text = String::from_utf8_lossy(shown).into_owned();
+    if body.len() > shown.len() {
+        text.push_str(&format!(" … ({} more bytes)", body.len() - shown.len()));
+    }
+    text
+}
+
+/// `form` with the values of sensitive parameters masked.
+#[cfg(feature = "wire-debug")]
+fn redact_form(form: &str) -> String {
+    form.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_sensitive(name) => format!("{}=<redacted>", name),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+#[cfg(all(test, feature = "wire-debug"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_masks_credentials_of_small_bodies() {
+        let json = br#"{"access_token":"abc","expires_in":300}"#;
+        assert_eq!(
+            preview(Some("application/json; charset=utf-8"), json),
+            r#"{"access_token":"<redacted>","expires_in":300}"#
+        );
+        let form = b"grant_type=client_credentials&client_secret=s3cret";
+        assert_eq!(
+            preview(Some("application/x-www-form-urlencoded"), form),
+            "grant_type=client_credentials&client_secret=<redacted>"
+        );
+        assert_eq!(preview(None, b"plain"), "plain");
+    }
+
+    #[test]
+    fn preview_does_not_parse_large_json_bodies() {
+        let body = format!(r#"{{"Name":"{}"}}"#, "x".repeat(MAX_PARSED_JSON_LEN));
+        let text = preview(Some("application/json"), body.as_bytes());
+        assert!(text.starts_with(r#"{"Name":"xxx"#));
+        assert!(text.ends_with(&format!(
+            " … ({} more bytes)",
+            body.len() - BODY_PREVIEW_LEN
+        )));
+    }
+}
@@ -0,0 +1,270 @@
+//! A small JSON Schema checker for the bundled Interface5 contracts.
+//!
+//! Supports the subset of draft-07 used by the bundled schemas: `type`, `enum`,
+//! `required`, `properties`, `additionalProperties`, `items`, `minItems`, `maxItems`,
+//! `minLength`, `maxLength`, `minimum` and `maximum`. Other keywords are ignored.
+
+use serde_json::{Map, Value};
+
+/// Checks `value` against `schema` and returns every violation as
+/// `<path>: <message>`, with paths like `Documents[0].Fields[1].ItemNo` and `root`,
+/// e.g. `the request`, naming the value itself.
+pub(crate) fn violations(schema: &Value, value: &Value, root: &str) -> Vec<String> {
+    let mut violations = Vec::new();
+    check(schema, value, root, "", &mut violations);
+    violations
+}
+
+fn check(schema: &Value, value: &Value, root: &str, path: &str, violations: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+    let mut violation = |message: String| {
+        let location = if path.is_empty() { root } else { path };
+        violations.push(format!("{}: {}", location, message));
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|name| has_type(value, name)) {
+            violation(format!(
+                "expected {}, found {}",
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(Value::Array(allowed)) = schema.get("enum")
+        && !allowed.contains(value)
+    {
+        violation(format!("{} is not an allowed value", value));
+    }
+
+    match value {
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = keyword_u64(schema, "minLength")
+                && length < min
+            {
+                match min {
+                    1 => violation("must not be empty".to_string()),
+                    _ => violation(format!("shorter than {} characters", min)),
+                }
+            }
+            if let Some(max) = keyword_u64(schema, "maxLength")
+                && length > max
+            {
+                violation(format!("longer than {} characters", max));
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64)
+                && number < min
+            {
+                violation(format!("less than {}", min));
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64)
+                && number > max
+            {
+                violation(format!("greater than {}", max));
+            }
+        }
+        Value::Array(entries) => {
+            let length = entries.len() as u64;
+            if let Some(min) = keyword_u64(schema, "minItems")
+                && length < min
+            {
+                violation(format!("fewer than {} entries", min));
+            }
+            if let Some(max) = keyword_u64(schema, "maxItems")
+                && length > max
+            {
+                violation(format!("more than {} entries", max));
+            }
+            if let Some(items) = schema.get("items") {
+                for (index, entry) in entries.iter().enumerate() {
+                    check(
+                        items,
+                        entry,
+                        root,
+                        &format!("{}[{}]", path, index),
+                        violations,
+                    );
+                }
+            }
+        }
+        Value::Object(object) => check_object(schema, object, root, path, violations),
+        Value::Null | Value::Bool(_) => {}
+    }
+}
+
+fn check_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    root: &str,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    let location = if path.is_empty() { root } else { path };
+
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                violations.push(format!("{}: missing {}", location, key));
+            }
+        }
+    }
+
+    let empty = Map::new();
+    let properties = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    for (key, value) in object {
+        match (properties.get(key), schema.get("additionalProperties")) {
+            (Some(property), _) => check(property, value, root, &child(key), violations),
+            (None, Some(Value::Bool(false))) => {
+                violations.push(format!("{}: unexpected key {}", location, key));
+            }
+            (None, Some(additional @ Value::Object(_))) => {
+                check(additional, value, root, &child(key), violations);
+            }
+            (None, _) => {}
+        }
+    }
+}
+
+fn keyword_u64(schema: &Map<String, Value>, keyword: &str) -> Option<u64> {
+    schema.get(keyword).and_then(Value::as_u64)
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["Name", "Documents"],
+            "additionalProperties": false,
+            "properties": {
+                "Name": { "type": "string", "minLength": 1, "maxLength": 8 },
+                "Priority": { "type": ["integer", "null"], "minimum": 0, "maximum": 9 },
+                "Mode": { "enum": ["scan", "import"] },
+                "Tags": { "type": "object", "additionalProperties": { "type": "string" } },
+                "Documents": {
+                    "type": "array",
+                    "minItems": 1,
+                    "maxItems": 2,
+                    "items": {
+                        "type": "object",
+                        "required": ["Name"],
+                        "properties": { "Name": { "type": "string", "minLength": 2 } }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn accepts_matching_values() {
+        let value = json!({
+            "Name": "Batch",
+            "Priority": null,
+            "Mode": "scan",
+            "Tags": { "site": "A" },
+            "Documents": [{ "Name": "Invoice", "Extra": 1 }]
+        });
+        assert!(violations(&schema(), &value, "the request").is_empty());
+        assert!(violations(&json!(true), &value, "the request").is_empty());
+    }
+
+    #[test]
+    fn reports_every_violation_with_its_path() {
+        let value = json!({
+            "Name": "",
+            "Priority": 12.5,
+            "Mode": "fax",
+            "Tags": { "site": 1 },
+            "Documents": [{ "Name": "I" }, {}, { "Name": 4 }],
+            "Unknown": true
+        });
+        assert_eq!(
+            violations(&schema(), &value, "the request"),
+            [
+                "Name: must not be empty",
+                "Priority: expected integer or null, found number",
+                "Mode: \"fax\" is not an allowed value",
+                "Tags.site: expected string, found integer",
+                "Documents: more than 2 entries",
+                "Documents[0].Name: shorter than 2 characters",
+                "Documents[1]: missing Name",
+                "Documents[2].Name: expected string, found integer",
+                "the request: unexpected key Unknown",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_bounds_and_missing_keys_of_the_root() {
+        let value = json!({ "Name": "Batch-4711", "Priority": -1, "Documents": [] });
+        assert_eq!(
+            violations(&schema(), &value, "the request"),
+            [
+                "Name: longer than 8 characters",
+                "Priority: less than 0",
+                "Documents: fewer than 1 entries",
+            ]
+        );
+        assert_eq!(
+            violations(&schema(), &json!({}), "the request"),
+            [
+                "the request: missing Name",
+                "the request: missing Documents"
+            ]
+        );
+        assert_eq!(
+            violations(&schema(), &json!([]), "the batch"),
+            ["the batch: expected object, found array"]
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use std::ops::RangeInclusive;
+
+use crate::{
+    types::{
+        i5_amount::I5Amount,
+        i5_date::{I5Date, I5DateFormat},
+        i5_request::I5Request,
+    },
+    util,
+};
+
+/// The kind of value generated for a fake field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum I5FakeValue {
+    /// A number with exactly this many digits, e.g. an invoice number.
+    Digits(usize),
+    /// Between one and this many words of filler text.
+    Words(usize),
+    /// An integer within the range, e.g. a quantity.
+    Integer(RangeInclusive<i64>),
+    /// An amount with two decimals up to this many whole units, e.g. `1234.56`.
+    Amount(i64),
+    /// A day of the given year.
+    Date(i32),
+    /// One of the given values.
+    OneOf(Vec<String>),
+}
+
+const WORDS: &[&str] = &[
+    "office",
+    "paper",
+    "toner",
+    "cable",
+    "service",
+    "license",
+    "repair",
+    "freight",
+    "consulting",
+    "monitor",
+    "chair",
+    "desk",
+    "battery",
+    "adapter",
+    "maintenance",
+    "travel",
+    "catering",
+    "software",
+    "hardware",
+    "cleaning",
+];
+
+/// A generator of random but realistic requests, for load tests against staging
+/// systems and for fuzzing downstream field mappings.
+///
+/// The defaults produce invoices: one to three documents per request, each with an
+/// invoice number, date, vendor number and total, up to five items with article,
+/// quantity and price, and one attachment of 1 to 64 KiB. Every part can be
+/// configured. Generators with the same [seed](I5FakeRequests::seed) produce the same
+/// requests. Every generated request is valid; a document that would be empty gets an
+/// attachment.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::test_util::{I5FakeRequests, I5FakeValue};
+///
+/// let generator = I5FakeRequests::new()
+///     .seed(42)
+///     .documents(2..=2)
+///     .header_fields([("CostCenter", I5FakeValue::Digits(4))])
+///     .items(0..=0)
+///     .attachments(1..=1, 1024..=1024);
+///
+/// let requests: Vec<_> = generator.clone().take(10).collect();
+/// assert_eq!(requests, generator.take(10).collect::<Vec<_>>());
+/// for request in requests {
+///     assert_eq!(request.document_count(), 2);
+///     assert_eq!(request.total_attachment_bytes(), 2048);
+///     assert!(request.validate().is_ok());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct I5FakeRequests {
+    rng: SplitMix64,
+    seed: u64,
+    generated: u64,
+    documents: RangeInclusive<usize>,
+    document_names: Vec<String>,
+    header_fields: Vec<(String, I5FakeValue)>,
+    items: RangeInclusive<usize>,
+    item_fields: Vec<(String, I5FakeValue)>,
+    attachments: RangeInclusive<usize>,
+    attachment_size: RangeInclusive<usize>,
+}
+
+impl Default for I5FakeRequests {
+    fn default() -> I5FakeRequests {
+        I5FakeRequests::new()
+    }
+}
+
+impl I5FakeRequests {
+    /// Creates a generator of invoices with a random seed.
+    pub fn new() -> I5FakeRequests {
+        let seed = u64::from_le_bytes(util::random_bytes::<8>());
+        let fields = |fields: &[(&str, I5FakeValue)]| {
+            fields
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect()
+        };
+        I5FakeRequests {
+            rng: SplitMix64(seed),
+            seed,
+            generated: 0,
+            documents: 1..=3,
+            document_names: vec!["Invoice".to_string()],
+            header_fields: fields(&[
+                ("InvoiceNo", I5FakeValue::Digits(8)),
+                ("InvoiceDate", I5FakeValue::Date(2025)),
+                ("VendorNo", I5FakeValue::Digits(5)),
+                ("Total", I5FakeValue::Amount(10_000)),
+            ]),
+            items: 0..=5,
+            item_fields: fields(&[
+                ("Article", I5FakeValue::Words(3)),
+                ("Quantity", I5FakeValue::Integer(1..=100)),
+                ("Price", I5FakeValue::Amount(500)),
+            ]),
+            attachments: 1..=1,
+            attachment_size: 1024..=64 * 1024,
+        }
+    }
+
+    /// Sets the seed, restarting the sequence of generated requests.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = SplitMix64(seed);
+        self.seed = seed;
+        self.generated = 0;
+        self
+    }
+
+    /// The number of documents per request.
+    pub fn documents(mut self, documents: RangeInclusive<usize>) -> Self {
+        self.documents = documents;
+        self
+    }
+
+    /// The document names to choose from.
+    pub fn document_names<S: Into<String>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.document_names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Replaces the header fields of every document.
+    pub fn header_fields<S: Into<String>>(
+        mut self,
+        fields: impl IntoIterator<Item = (S, I5FakeValue)>,
+    ) -> Self {
+        self.header_fields = fields
+            .into_iter()
+            .map(|(name, value)| (name.into(), value))
+            .collect();
+        self
+    }
+
+    /// The number of items per document.
+    pub fn items(mut self, items: RangeInclusive<usize>) -> Self {
+        self.items = items;
+        self
+    }
+
+    /// Replaces the fields of every item.
+    pub fn item_fields<S: Into<String>>(
+        mut self,
+        fields: impl IntoIterator<Item = (S, I5FakeValue)>,
+    ) -> Self {
+        self.item_fields = fields
+            .into_iter()
+            .map(|(name, value)| (name.into(), value))
+            .collect();
+        self
+    }
+
+    /// The number of attachments per document and their size in bytes.
+    pub fn attachments(
+        mut self,
+        count: RangeInclusive<usize>,
+        size: RangeInclusive<usize>,
+    ) -> Self {
+        self.attachments = count;
+        self.attachment_size = size;
+        self
+    }
+
+    /// Generates the next request.
+    pub fn generate(&mut self) -> I5Request {
+        self.generated += 1;
+        let mut request = I5Request::new(format!("FAKE-{:016x}-{}", self.seed, self.generated));
+
+        for _ in 0..self.rng.within(&self.documents) {
+            let name = match self.document_names.len() {
+                0 => "Document".to_string(),
+                len => self.document_names[self.rng.below(len)].clone(),
+            };
+            let id = request.add_document(name);
+            let document = request
+                .get_document_mut(id)
+                .expect("document was just added");
+
+            for (name, value) in &self.header_fields {
+                document.add_header_field(name, self.rng.value(value));
+            }
+            for item in 1..=self.rng.within(&self.items) {
+                for (name, value) in &self.item_fields {
+                    document.add_item_field(name, self.rng.value(value), item as i32);
+                }
+            }
+
+            let mut attachments = self.rng.within(&self.attachments);
+            if attachments == 0 && document.field_count() == 0 {
+                attachments = 1;
+            }
+            for index in 1..=attachments {
+                let size = self.rng.within(&self.attachment_size);
+                let content = self.rng.bytes(size);
+                document.add_shared_file(format!("scan-{}.pdf", index), content);
+            }
+        }
+        request
+    }
+}
+
+impl Iterator for I5FakeRequests {
+    type Item = I5Request;
+
+    /// Generates the next request; the sequence never ends.
+    fn next(&mut self) -> Option<I5Request> {
+        Some(self.generate())
+    }
+}
+
+/// The SplitMix64 generator: small, fast and good enough for test data.
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`; `bound` must not be zero.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// A number within `range`, or its start if it is empty.
+    fn within(&mut self, range: &RangeInclusive<usize>) -> usize {
+        let (start, end) = (*range.start(), *range.end());
+        if end <= start {
+            return start;
+        }
+        start + (self.next_u64() % (end - start + 1) as u64) as usize
+    }
+
+    fn integer(&mut self, range: &RangeInclusive<i64>) -> i64 {
+        let (start, end) = (*range.start(), *range.end());
+        if end <= start {
+            return start;
+        }
+        let span = end.abs_diff(start).saturating_add(1);
+        start.wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(len + 8);
+        while bytes.len() < len {
+            bytes.extend_from_slice(&self.next_u64().to_le_bytes());
+        }
+        bytes.truncate(len);
+        bytes
+    }
+
+    fn value(&mut self, value: &I5FakeValue) -> String {
+        match value {
+            I5FakeValue::Digits(digits) => (0..*digits)
+                .map(|index| {
+                    let low = if index == 0 { 1 } else { 0 };
+                    char::from(b'0' + low + self.below(10 - low as usize) as u8)
+                })
+                .collect(),
+            I5FakeValue::Words(words) => {
+                let count = self.within(&(1..=(*words).max(1)));
+                let words: Vec<&str> = (0..count).map(|_| WORDS[self.below(WORDS.len())]).collect();
+                words.join(" ")
+            }
+            I5FakeValue::Integer(range) => self.integer(range).to_string(),
+            I5FakeValue::Amount(max) => {
+                let minor_units = self.integer(&(1..=max.saturating_mul(100)));
+                I5Amount::new(minor_units, 2).to_string()
+            }
+            I5FakeValue::Date(year) => {
+                let first = util::days_from_civil(i64::from(*year), 1, 1);
+                let last = util::days_from_civil(i64::from(*year), 12, 31);
+                let (year, month, day) = util::civil_from_days(self.integer(&(first..=last)));
+                I5Date::new(year as i32, month, day)
+                    .expect("civil_from_days returns valid dates")
+                    .format(I5DateFormat::Iso8601)
+            }
+            I5FakeValue::OneOf(values) => match values.len() {
+                0 => String::new(),
+                len => values[self.below(len)].clone(),
+            },
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Helpers for testing applications built on this crate, enabled with the `test-util`
+//! feature.
+
+mod fake;
+mod sender;
+mod snapshot;
+
+pub use fake::{I5FakeRequests, I5FakeValue};
+pub use sender::{I5ScriptedOutcome, I5ScriptedSender};
+pub use snapshot::{UPDATE_SNAPSHOTS_VAR, assert_snapshot, snapshot_json};
@@ -0,0 +1,183 @@
+use std::{collections::VecDeque, io, sync::Mutex, thread, time::Duration};
+
+use crate::{
+    request::pipeline::I5Sender,
+    types::{
+        i5_error::I5RequestError,
+        i5_receipt::{I5BatchReceipt, I5TransferStats},
+        i5_request::{I5Request, ValidatedI5Request},
+        i5_server_error::I5ServerError,
+    },
+};
+
+/// What an [`I5ScriptedSender`] does with one submission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum I5ScriptedOutcome {
+    /// Accepts the request after the given latency.
+    Accept(Duration),
+    /// Rejects the request with the given HTTP status and an empty body.
+    Reject(u16),
+    /// Waits for the given time, then fails with an [`io::ErrorKind::TimedOut`] error.
+    TimeOut(Duration),
+    /// Fails at once with an [`io::ErrorKind::ConnectionReset`] error.
+    Reset,
+}
+
+/// A sender that plays back a script of outcomes instead of contacting a server, to
+/// check how retries, dead-letter handling and spooling behave under failure.
+///
+/// Every submission takes the next step of the script; once the script is exhausted,
+/// all submissions are accepted at once. Simulated transport failures are reported as
+/// [`I5RequestError::IoError`] and rejections as [`I5RequestError::ServerError`].
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::{
+///     request::pipeline::I5Sender,
+///     test_util::I5ScriptedSender,
+///     types::i5_request::I5Request,
+/// };
+///
+/// let sender = I5ScriptedSender::new().reject(503, 2).reset();
+///
+/// let mut request = I5Request::new("Batch");
+/// let id = request.add_document("Invoice");
+/// request.get_document_mut(id).unwrap().add_header_field("No", "4711");
+/// let validated = request.validate()?;
+///
+/// let outcomes: Vec<bool> = (0..4).map(|_| sender.send(&validated).is_ok()).collect();
+/// assert_eq!(outcomes, [false, false, false, true]);
+/// assert_eq!(sender.submissions(), ["Batch"; 4]);
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct I5ScriptedSender {
+    script: Mutex<VecDeque<I5ScriptedOutcome>>,
+    submissions: Mutex<Vec<String>>,
+}
+
+impl I5ScriptedSender {
+    /// Creates a sender that accepts every submission.
+    pub fn new() -> I5ScriptedSender {
+        I5ScriptedSender::default()
+    }
+
+    /// Appends `outcome` to the script.
+    pub fn then(self, outcome: I5ScriptedOutcome) -> Self {
+        self.script
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push_back(outcome);
+        self
+    }
+
+    /// Accepts the next submission at once.
+    pub fn accept(self) -> Self {
+        self.then(I5ScriptedOutcome::Accept(Duration::ZERO))
+    }
+
+    /// Accepts the next submission after `latency`.
+    pub fn delay(self, latency: Duration) -> Self {
+        self.then(I5ScriptedOutcome::Accept(latency))
+    }
+
+    /// Rejects the next `times` submissions with `status`, e.g. a burst of `503`s.
+    pub fn reject(mut self, status: u16, times: usize) -> Self {
+        for _ in 0..times {
+            self = self.then(I5ScriptedOutcome::Reject(status));
+        }
+        self
+    }
+
+    /// Lets the next submission time out after `after`.
+    pub fn time_out(self, after: Duration) -> Self {
+        self.then(I5ScriptedOutcome::TimeOut(after))
+    }
+
+    /// Resets the connection of the next submission.
+    pub fn reset(self) -> Self {
+        self.then(I5ScriptedOutcome::Reset)
+    }
+
+    /// The names of all requests submitted so far, including failed submissions.
+    pub fn submissions(&self) -> Vec<String> {
+        self.submissions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// The number of script steps not played yet.
+    pub fn remaining(&self) -> usize {
+        self.script
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+}
+
+impl I5Sender for I5ScriptedSender {
+    /// Creates a request named `Batch-<n>`, numbered from 1.
+    fn new_request(&self) -> Result<I5Request, I5RequestError> {
+        let count = self
+            .submissions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len();
+        Ok(I5Request::new(format!("Batch-{}", count + 1)))
+    }
+
+    fn send(&self, valid_body: &ValidatedI5Request) -> Result<I5BatchReceipt, I5RequestError> {
+        let submission = {
+            let mut submissions = self
+                .submissions
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            submissions.push(valid_body.name().to_string());
+            submissions.len()
+        };
+        let outcome = self
+            .script
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .pop_front()
+            .unwrap_or(I5ScriptedOutcome::Accept(Duration::ZERO));
+
+        match outcome {
+            I5ScriptedOutcome::Accept(latency) => {
+                thread::sleep(latency);
+                let stats = I5TransferStats::new(
+                    None,
+                    latency,
+                    latency,
+                    valid_body.estimated_encoded_size() as u64,
+                    0,
+                );
+                let response = serde_json::json!({
+                    "BatchId": submission,
+                    "Name": valid_body.name(),
+                });
+                Ok(
+                    I5BatchReceipt::new(200, format!("scripted-{}", submission), stats)
+                        .with_response_body(response.to_string().as_bytes()),
+                )
+            }
+            I5ScriptedOutcome::Reject(status) => Err(I5RequestError::ServerError(
+                I5ServerError::from_response(status, ""),
+            )),
+            I5ScriptedOutcome::TimeOut(after) => {
+                thread::sleep(after);
+                Err(I5RequestError::IoError(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "scripted timeout",
+                )))
+            }
+            I5ScriptedOutcome::Reset => Err(I5RequestError::IoError(io::Error::new(
+                io::ErrorKind::ConnectionReset,
+                "scripted connection reset",
+            ))),
+        }
+    }
+}
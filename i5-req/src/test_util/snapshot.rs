@@ -0,0 +1,90 @@
+use std::{env, fs, path::Path};
+
+use crate::types::{i5_error::I5RequestError, i5_request::ValidatedI5Request};
+
+/// The environment variable that makes [`assert_snapshot`] rewrite snapshot files
+/// instead of comparing against them when set to `1`.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "I5REQ_UPDATE_SNAPSHOTS";
+
+/// The canonical JSON of a request: the body sent to Interface5, pretty-printed with
+/// two-space indentation and a trailing newline.
+///
+/// Keys keep the order in which they are serialized, so the text only changes when
+/// the payload does.
+pub fn snapshot_json(valid_body: &ValidatedI5Request) -> Result<String, I5RequestError> {
+    let body = valid_body.body()?;
+    let value: serde_json::Value =
+        serde_json::from_slice(&body).map_err(I5RequestError::SerializeError)?;
+    let mut json = serde_json::to_string_pretty(&value).map_err(I5RequestError::SerializeError)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Asserts that the [canonical JSON](snapshot_json) of a request matches the snapshot
+/// file at `path`, so serialization changes that would break the Interface5 contract
+/// fail the test suite.
+///
+/// A missing snapshot file is created, and every snapshot is rewritten when the
+/// [`UPDATE_SNAPSHOTS_VAR`] environment variable is `1`; review and commit the files
+/// like source code.
+///
+/// # Panics
+///
+/// Panics with the first differing line if the snapshot does not match, or if the
+/// snapshot cannot be read or written.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::{test_util::assert_snapshot, types::i5_request::I5Request};
+///
+/// let path = std::env::temp_dir().join(format!("i5-doc-snapshot-{}.json", std::process::id()));
+///
+/// let mut request = I5Request::new("Batch");
+/// let id = request.add_document("Invoice");
+/// request.get_document_mut(id).unwrap().add_header_field("InvoiceNo", "4711");
+/// let validated = request.validate()?;
+///
+/// assert_snapshot(&path, &validated); // written on the first run
+/// assert_snapshot(&path, &validated); // compared from then on
+/// assert!(std::fs::read_to_string(&path)?.contains(r#""Value": "4711""#));
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[track_caller]
+pub fn assert_snapshot(path: impl AsRef<Path>, valid_body: &ValidatedI5Request) {
+    let path = path.as_ref();
+    let actual = snapshot_json(valid_body)
+        .unwrap_or_else(|err| panic!("failed to serialize the request: {}", err));
+
+    let update = env::var(UPDATE_SNAPSHOTS_VAR).is_ok_and(|value| value == "1");
+    if update || !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .unwrap_or_else(|err| panic!("failed to create {}: {}", parent.display(), err));
+        }
+        fs::write(path, &actual)
+            .unwrap_or_else(|err| panic!("failed to write snapshot {}: {}", path.display(), err));
+        return;
+    }
+
+    let expected = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("failed to read snapshot {}: {}", path.display(), err));
+    if expected == actual {
+        return;
+    }
+    let mismatch = expected
+        .lines()
+        .zip(actual.lines())
+        .position(|(expected, actual)| expected != actual)
+        .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+    panic!(
+        "request does not match snapshot {} at line {}\n  expected: {}\n    actual: {}\n\
+         rerun with {}=1 to update the snapshot",
+        path.display(),
+        mismatch + 1,
+        expected.lines().nth(mismatch).unwrap_or("<end of file>"),
+        actual.lines().nth(mismatch).unwrap_or("<end of file>"),
+        UPDATE_SNAPSHOTS_VAR
+    );
+}
@@ -0,0 +1,540 @@
+//! A small TOML reader for configuration files.
+//!
+//! Supports the subset of TOML used by configuration files: tables, arrays of tables,
+//! dotted and quoted keys, basic and literal strings, integers, floats, booleans,
+//! arrays and inline tables. Dates and multi-line strings are not supported. The
+//! document is converted into a [`serde_json::Value`] so it can be deserialized with
+//! serde.
+
+use std::collections::HashSet;
+
+use serde_json::{Map, Number, Value};
+
+/// A TOML syntax error with the 1-based line and column where it was detected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TomlError {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) message: String,
+}
+
+impl std::fmt::Display for TomlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// Parses a TOML document into a JSON object.
+pub(crate) fn parse(input: &str) -> Result<Value, TomlError> {
+    Parser {
+        chars: input.chars().collect(),
+        position: 0,
+    }
+    .document()
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn document(&mut self) -> Result<Value, TomlError> {
+        let mut root = Value::Object(Map::new());
+        let mut current: Vec<String> = Vec::new();
+        // The `[table]` headers seen so far, as a table may only be defined once.
+        let mut defined: HashSet<Vec<String>> = HashSet::new();
+
+        loop {
+            self.skip_whitespace_and_comments(true);
+            match self.peek() {
+                None => break,
+                Some('[') => {
+                    self.position += 1;
+                    let array = self.peek() == Some('[');
+                    if array {
+                        self.position += 1;
+                    }
+                    self.skip_whitespace();
+                    let path = self.key()?;
+                    self.skip_whitespace();
+                    self.expect(']')?;
+                    if array {
+                        self.expect(']')?;
+                    }
+                    self.end_of_line()?;
+
+                    if array {
+                        let parent = self.table_at(&mut root, &path[..path.len() - 1])?;
+                        let last = &path[path.len() - 1];
+                        let entry = parent
+                            .entry(last.clone())
+                            .or_insert_with(|| Value::Array(Vec::new()));
+                        match entry {
+                            Value::Array(items) => items.push(Value::Object(Map::new())),
+                            _ => return Err(self.error(format!("'{}' is not an array", last))),
+                        }
+                        // The tables below the new entry are defined afresh.
+                        defined.retain(|table| !table.starts_with(&path));
+                    } else {
+                        if !defined.insert(path.clone()) {
+                            return Err(self.error(format!("duplicate table '{}'", path.join("."))));
+                        }
+                        self.table_at(&mut root, &path)?;
+                    }
+                    current = path;
+                }
+                Some(_) => {
+                    let path = self.key()?;
+                    self.skip_whitespace();
+                    self.expect('=')?;
+                    self.skip_whitespace();
+                    let value = self.value()?;
+                    self.end_of_line()?;
+
+                    let table = self.table_at(&mut root, &current)?;
+                    self.insert(table, &path, value)?;
+                }
+            }
+        }
+
+        Ok(root)
+    }
+
+    /// Returns the table at `path`, creating missing tables. If a path element is an
+    /// array of tables, its last element is used.
+    fn table_at<'a>(
+        &self,
+        root: &'a mut Value,
+        path: &[String],
+    ) -> Result<&'a mut Map<String, Value>, TomlError> {
+        let mut table = match root {
+            Value::Object(map) => map,
+            _ => unreachable!("the document root is always a table"),
+        };
+
+        for key in path {
+            let entry = table
+                .entry(key.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            table = match entry {
+                Value::Object(map) => map,
+                Value::Array(items) => match items.last_mut() {
+                    Some(Value::Object(map)) => map,
+                    _ => return Err(self.error(format!("'{}' is not a table", key))),
+                },
+                _ => return Err(self.error(format!("'{}' is not a table", key))),
+            };
+        }
+        Ok(table)
+    }
+
+    fn insert(
+        &self,
+        table: &mut Map<String, Value>,
+        path: &[String],
+        value: Value,
+    ) -> Result<(), TomlError> {
+        let (last, parents) = path.split_last().expect("keys have at least one part");
+        let mut table = table;
+        for key in parents {
+            let entry = table
+                .entry(key.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            table = match entry {
+                Value::Object(map) => map,
+                _ => return Err(self.error(format!("'{}' is not a table", key))),
+            };
+        }
+
+        if table.contains_key(last) {
+            return Err(self.error(format!("duplicate key '{}'", last)));
+        }
+        table.insert(last.clone(), value);
+        Ok(())
+    }
+
+    /// Parses a (possibly dotted) key.
+    fn key(&mut self) -> Result<Vec<String>, TomlError> {
+        let mut parts = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let part = match self.peek() {
+                Some('"') => self.basic_string()?,
+                Some('\'') => self.literal_string()?,
+                _ => {
+                    let start = self.position;
+                    while self
+                        .peek()
+                        .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                    {
+                        self.position += 1;
+                    }
+                    if start == self.position {
+                        return Err(self.error(String::from("expected a key")));
+                    }
+                    self.chars[start..self.position].iter().collect()
+                }
+            };
+            parts.push(part);
+
+            self.skip_whitespace();
+            if self.peek() == Some('.') {
+                self.position += 1;
+            } else {
+                return Ok(parts);
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, TomlError> {
+        match self.peek() {
+            Some('"') => {
+                if self.starts_with("\"\"\"") {
+                    return Err(self.error(String::from("multi-line strings are not supported")));
+                }
+                self.basic_string().map(Value::String)
+            }
+            Some('\'') => self.literal_string().map(Value::String),
+            Some('[') => self.array(),
+            Some('{') => self.inline_table(),
+            Some('t') if self.starts_with("true") => {
+                self.position += 4;
+                Ok(Value::Bool(true))
+            }
+            Some('f') if self.starts_with("false") => {
+                self.position += 5;
+                Ok(Value::Bool(false))
+            }
+            Some(c) if c.is_ascii_digit() || c == '+' || c == '-' => self.number(),
+            Some(c) => Err(self.error(format!("unexpected character '{}'", c))),
+            None => Err(self.error(String::from("expected a value"))),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, TomlError> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.' | '_'))
+        {
+            self.position += 1;
+        }
+        let text: String = self.chars[start..self.position]
+            .iter()
+            .filter(|c| **c != '_')
+            .collect();
+
+        if let Ok(integer) = text.parse::<i64>() {
+            return Ok(Value::Number(integer.into()));
+        }
+        if let Some(hex) = text.strip_prefix("0x")
+            && let Ok(integer) = i64::from_str_radix(hex, 16)
+        {
+            return Ok(Value::Number(integer.into()));
+        }
+        text.parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| {
+                self.position = start;
+                self.error(format!("invalid number '{}'", text))
+            })
+    }
+
+    fn array(&mut self) -> Result<Value, TomlError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace_and_comments(true);
+            if self.peek() == Some(']') {
+                self.position += 1;
+                return Ok(Value::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_whitespace_and_comments(true);
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {}
+                _ => return Err(self.error(String::from("expected ',' or ']' in array"))),
+            }
+        }
+    }
+
+    fn inline_table(&mut self) -> Result<Value, TomlError> {
+        self.expect('{')?;
+        let mut table = Map::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Value::Object(table));
+        }
+        loop {
+            let path = self.key()?;
+            self.skip_whitespace();
+            self.expect('=')?;
+            self.skip_whitespace();
+            let value = self.value()?;
+            self.insert(&mut table, &path, value)?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Value::Object(table));
+                }
+                _ => return Err(self.error(String::from("expected ',' or '}' in inline table"))),
+            }
+        }
+    }
+
+    fn basic_string(&mut self) -> Result<String, TomlError> {
+        self.expect('"')?;
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => {
+                    let escaped = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => self.unicode_escape(4)?,
+                        Some('U') => self.unicode_escape(8)?,
+                        _ => return Err(self.error(String::from("invalid escape sequence"))),
+                    };
+                    text.push(escaped);
+                }
+                Some('\n') | None => return Err(self.unterminated()),
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self, digits: usize) -> Result<char, TomlError> {
+        let end = (self.position + digits).min(self.chars.len());
+        let hex: String = self.chars[self.position..end].iter().collect();
+        self.position = end;
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error(format!("invalid unicode escape '{}'", hex)))
+    }
+
+    fn literal_string(&mut self) -> Result<String, TomlError> {
+        self.expect('\'')?;
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                Some('\'') => return Ok(text),
+                Some('\n') | None => return Err(self.unterminated()),
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    /// The error for a string missing its closing quote, at the end of its line.
+    fn unterminated(&mut self) -> TomlError {
+        if self.chars.get(self.position.wrapping_sub(1)) == Some(&'\n') {
+            self.position -= 1;
+        }
+        self.error(String::from("unterminated string"))
+    }
+
+    fn end_of_line(&mut self) -> Result<(), TomlError> {
+        self.skip_whitespace_and_comments(false);
+        match self.peek() {
+            None | Some('\n') => Ok(()),
+            Some('\r') if self.chars.get(self.position + 1) == Some(&'\n') => Ok(()),
+            Some(c) => Err(self.error(format!("unexpected '{}' after value", c))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.position += 1;
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self, newlines: bool) {
+        loop {
+            match self.peek() {
+                Some(' ' | '\t') => self.position += 1,
+                Some('\r' | '\n') if newlines => self.position += 1,
+                Some('#') => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.position += 1;
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), TomlError> {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", expected)))
+        }
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(offset, c)| self.chars.get(self.position + offset) == Some(&c))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.position += 1;
+        }
+        c
+    }
+
+    fn error(&self, message: String) -> TomlError {
+        let consumed = &self.chars[..self.position.min(self.chars.len())];
+        let line = consumed.iter().filter(|c| **c == '\n').count() + 1;
+        let column = consumed.iter().rev().take_while(|c| **c != '\n').count() + 1;
+        TomlError {
+            line,
+            column,
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn error(input: &str) -> String {
+        parse(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn reads_scalars_and_keys() {
+        let input = "\
+# settings
+title = \"Scan \\\"A\\\"\\t\\u00e4\" # trailing comment
+path = 'C:\\Scans'
+count = 1_000
+mask = 0xff
+negative = -5
+ratio = 2.5
+on = true
+off = false
+site.\"host name\" = 'scan-1'\r
+";
+        assert_eq!(
+            parse(input).unwrap(),
+            json!({
+                "title": "Scan \"A\"\tä",
+                "path": "C:\\Scans",
+                "count": 1000,
+                "mask": 255,
+                "negative": -5,
+                "ratio": 2.5,
+                "on": true,
+                "off": false,
+                "site": { "host name": "scan-1" }
+            })
+        );
+    }
+
+    #[test]
+    fn reads_tables_arrays_and_inline_tables() {
+        let input = "\
+ports = [ 80, 443, # https
+  8443 ]
+empty = []
+auth = { kind = 'basic', user.name = \"scan\" }
+
+[client.retry]
+attempts = 3
+
+[[profiles]]
+name = 'test'
+[profiles.auth]
+kind = 'none'
+
+[[profiles]]
+name = 'prod'
+[profiles.auth]
+kind = 'basic'
+";
+        assert_eq!(
+            parse(input).unwrap(),
+            json!({
+                "ports": [80, 443, 8443],
+                "empty": [],
+                "auth": { "kind": "basic", "user": { "name": "scan" } },
+                "client": { "retry": { "attempts": 3 } },
+                "profiles": [
+                    { "name": "test", "auth": { "kind": "none" } },
+                    { "name": "prod", "auth": { "kind": "basic" } }
+                ]
+            })
+        );
+        assert_eq!(parse("").unwrap(), json!({}));
+    }
+
+    #[test]
+    fn reports_the_position_of_errors() {
+        assert_eq!(
+            error("a = 1\nb = \"open\n"),
+            "line 2, column 10: unterminated string"
+        );
+        assert_eq!(error("a = 1\na = 2"), "line 2, column 6: duplicate key 'a'");
+        assert_eq!(
+            error("a = 1\n[a]\n"),
+            "line 2, column 4: 'a' is not a table"
+        );
+        assert_eq!(
+            error("a = \"\"\"text\"\"\""),
+            "line 1, column 5: multi-line strings are not supported"
+        );
+        assert_eq!(
+            error("a = \"\\q\""),
+            "line 1, column 8: invalid escape sequence"
+        );
+        assert_eq!(
+            error("a = 1 2"),
+            "line 1, column 7: unexpected '2' after value"
+        );
+        assert_eq!(
+            error("a = 1.2.3"),
+            "line 1, column 5: invalid number '1.2.3'"
+        );
+        assert_eq!(
+            error("a = nope"),
+            "line 1, column 5: unexpected character 'n'"
+        );
+        assert_eq!(error("a ="), "line 1, column 4: expected a value");
+        assert_eq!(
+            error("a = [1 2]"),
+            "line 1, column 8: expected ',' or ']' in array"
+        );
+        assert_eq!(error("= 1"), "line 1, column 1: expected a key");
+        assert_eq!(
+            error("[[a]]\n[a.b]\n[a.b]\n"),
+            "line 3, column 6: duplicate table 'a.b'"
+        );
+    }
+}
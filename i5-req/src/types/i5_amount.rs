@@ -0,0 +1,318 @@
+use std::fmt;
+
+/// The largest supported number of decimal places.
+const MAX_SCALE: u32 = 18;
+
+/// A monetary amount stored as an integer number of minor units, e.g. cents.
+///
+/// The value of an amount is `minor_units / 10^scale`, so no precision is lost on the
+/// way to the field value. Amounts are turned into field values with an
+/// [`I5AmountFormat`].
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_amount::{I5Amount, I5AmountFormat};
+///
+/// let amount = I5Amount::new(123456, 2);
+/// assert_eq!(amount.format(&I5AmountFormat::german()), "1.234,56");
+/// assert_eq!(amount.format(&I5AmountFormat::plain()), "1234.56");
+///
+/// let parsed = I5Amount::parse("1.234,5", &I5AmountFormat::german()).unwrap();
+/// assert_eq!(parsed.format(&I5AmountFormat::plain()), "1234.50");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct I5Amount {
+    minor_units: i64,
+    scale: u32,
+}
+
+impl I5Amount {
+    /// Creates an amount of `minor_units / 10^scale`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is greater than 18.
+    pub fn new(minor_units: i64, scale: u32) -> I5Amount {
+        assert!(scale <= MAX_SCALE, "scale must be at most {}", MAX_SCALE);
+        I5Amount { minor_units, scale }
+    }
+
+    /// Rounds `value` to `scale` decimal places, half away from zero.
+    ///
+    /// Returns `None` if `value` is not finite, does not fit or `scale` is greater
+    /// than 18.
+    pub fn from_f64(value: f64, scale: u32) -> Option<I5Amount> {
+        if !value.is_finite() || scale > MAX_SCALE {
+            return None;
+        }
+        let scaled = (value * 10f64.powi(scale as i32)).round();
+        if scaled.abs() >= i64::MAX as f64 {
+            return None;
+        }
+        Some(I5Amount::new(scaled as i64, scale))
+    }
+
+    /// Creates an amount from a decimal `mantissa` and `scale`, e.g. the
+    /// `mantissa()` and `scale()` of a `rust_decimal::Decimal`.
+    ///
+    /// Returns `None` if the mantissa does not fit into 64 bits or `scale` is greater
+    /// than 18.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_amount::I5Amount;
+    ///
+    /// // rust_decimal::Decimal::new(-1999, 2) has the mantissa -1999 and the scale 2.
+    /// let amount = I5Amount::from_mantissa(-1999, 2).unwrap();
+    /// assert_eq!(amount.to_string(), "-19.99");
+    /// assert!(I5Amount::from_mantissa(1, 28).is_none());
+    /// ```
+    pub fn from_mantissa(mantissa: i128, scale: u32) -> Option<I5Amount> {
+        if scale > MAX_SCALE {
+            return None;
+        }
+        Some(I5Amount::new(i64::try_from(mantissa).ok()?, scale))
+    }
+
+    /// Parses an amount written in `format`, e.g. `-1.234,56` for
+    /// [`I5AmountFormat::german`].
+    ///
+    /// Group separators are optional. The number of decimals may differ from the
+    /// format. Returns `None` if `text` is not a valid amount.
+    pub fn parse(text: &str, format: &I5AmountFormat) -> Option<I5Amount> {
+        let text = text.trim();
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (integer, fraction) = match digits.split_once(format.decimal_separator) {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (digits, ""),
+        };
+
+        let integer: String = integer
+            .chars()
+            .filter(|c| Some(*c) != format.group_separator)
+            .collect();
+        if integer.is_empty() && fraction.is_empty()
+            || !integer.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+            || fraction.len() > MAX_SCALE as usize
+        {
+            return None;
+        }
+
+        let minor_units: i64 = format!("{}{}", integer, fraction).parse().ok()?;
+        Some(I5Amount::new(
+            if negative { -minor_units } else { minor_units },
+            fraction.len() as u32,
+        ))
+    }
+
+    /// The amount in minor units.
+    pub fn minor_units(&self) -> i64 {
+        self.minor_units
+    }
+
+    /// The number of decimal places of [`I5Amount::minor_units`].
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Formats the amount, rounding half away from zero to the decimals of `format`.
+    pub fn format(&self, format: &I5AmountFormat) -> String {
+        let value = i128::from(self.minor_units);
+        let scaled = if format.decimals >= self.scale {
+            value * 10i128.pow(format.decimals - self.scale)
+        } else {
+            let divisor = 10i128.pow(self.scale - format.decimals);
+            let rounded = (value.abs() + divisor / 2) / divisor;
+            rounded * value.signum()
+        };
+
+        let divisor = 10i128.pow(format.decimals);
+        let integer = (scaled.abs() / divisor).to_string();
+        let fraction = scaled.abs() % divisor;
+
+        let mut output = String::new();
+        if scaled < 0 {
+            output.push('-');
+        }
+        for (index, digit) in integer.chars().enumerate() {
+            if index > 0
+                && (integer.len() - index).is_multiple_of(3)
+                && let Some(separator) = format.group_separator
+            {
+                output.push(separator);
+            }
+            output.push(digit);
+        }
+        if format.decimals > 0 {
+            output.push(format.decimal_separator);
+            output.push_str(&format!(
+                "{:0width$}",
+                fraction,
+                width = format.decimals as usize
+            ));
+        }
+        output
+    }
+}
+
+impl fmt::Display for I5Amount {
+    /// Formats the amount with [`I5AmountFormat::plain`] and its own scale.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(&I5AmountFormat::plain().decimals(self.scale)))
+    }
+}
+
+/// An [`I5Amount`] in a currency, identified by its ISO 4217 code.
+///
+/// A monetary value is added with [`Document::add_money_field`], optionally with the
+/// currency code in a companion field.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_amount::{I5Amount, I5AmountFormat, I5Money};
+///
+/// let money = I5Money::new(I5Amount::new(123456, 2), "eur").unwrap();
+/// assert_eq!(money.currency(), "EUR");
+/// assert_eq!(money.format(&I5AmountFormat::german()), "1.234,56");
+/// assert_eq!(money.to_string(), "1234.56 EUR");
+///
+/// assert!(I5Money::new(I5Amount::new(1, 0), "EURO").is_none());
+/// ```
+///
+/// [`Document::add_money_field`]: crate::types::i5_request::Document::add_money_field
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct I5Money {
+    amount: I5Amount,
+    currency: String,
+}
+
+impl I5Money {
+    /// Creates `amount` in `currency`, a three-letter ISO 4217 code such as `EUR`,
+    /// which is stored in upper case.
+    ///
+    /// Returns `None` if `currency` is not three ASCII letters.
+    pub fn new(amount: I5Amount, currency: &str) -> Option<I5Money> {
+        let currency = currency.trim();
+        (currency.len() == 3 && currency.bytes().all(|b| b.is_ascii_alphabetic())).then(|| {
+            I5Money {
+                amount,
+                currency: currency.to_ascii_uppercase(),
+            }
+        })
+    }
+
+    /// The amount.
+    pub fn amount(&self) -> I5Amount {
+        self.amount
+    }
+
+    /// The ISO 4217 code of the currency, e.g. `EUR`.
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    /// Formats the amount without the currency, see [`I5Amount::format`].
+    pub fn format(&self, format: &I5AmountFormat) -> String {
+        self.amount.format(format)
+    }
+}
+
+impl fmt::Display for I5Money {
+    /// Formats the amount like [`I5Amount`] followed by the currency code.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+/// How an [`I5Amount`] is written into a field value.
+///
+/// | Preset                         | Example     |
+/// |--------------------------------|-------------|
+/// | [`I5AmountFormat::plain`]      | `1234.56`   |
+/// | [`I5AmountFormat::german`]     | `1.234,56`  |
+/// | [`I5AmountFormat::english`]    | `1,234.56`  |
+/// | [`I5AmountFormat::swiss`]      | `1'234.56`  |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I5AmountFormat {
+    decimal_separator: char,
+    group_separator: Option<char>,
+    decimals: u32,
+}
+
+impl I5AmountFormat {
+    /// Two decimals with `.` as decimal separator and no grouping: `1234.56`.
+    pub fn plain() -> I5AmountFormat {
+        I5AmountFormat {
+            decimal_separator: '.',
+            group_separator: None,
+            decimals: 2,
+        }
+    }
+
+    /// Two decimals as used in Germany: `1.234,56`.
+    pub fn german() -> I5AmountFormat {
+        I5AmountFormat {
+            decimal_separator: ',',
+            group_separator: Some('.'),
+            decimals: 2,
+        }
+    }
+
+    /// Two decimals as used in English-speaking countries: `1,234.56`.
+    pub fn english() -> I5AmountFormat {
+        I5AmountFormat {
+            decimal_separator: '.',
+            group_separator: Some(','),
+            decimals: 2,
+        }
+    }
+
+    /// Two decimals as used in Switzerland: `1'234.56`.
+    pub fn swiss() -> I5AmountFormat {
+        I5AmountFormat {
+            decimal_separator: '.',
+            group_separator: Some('\''),
+            decimals: 2,
+        }
+    }
+
+    /// Sets the number of decimals.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `decimals` is greater than 18.
+    pub fn decimals(mut self, decimals: u32) -> Self {
+        assert!(
+            decimals <= MAX_SCALE,
+            "decimals must be at most {}",
+            MAX_SCALE
+        );
+        self.decimals = decimals;
+        self
+    }
+
+    /// Sets the decimal separator.
+    pub fn decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Sets the separator between groups of thousands, or `None` for no grouping.
+    pub fn group_separator(mut self, separator: Option<char>) -> Self {
+        self.group_separator = separator;
+        self
+    }
+}
+
+impl Default for I5AmountFormat {
+    fn default() -> Self {
+        I5AmountFormat::plain()
+    }
+}
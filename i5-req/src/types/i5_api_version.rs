@@ -0,0 +1,70 @@
+use std::{fmt, str::FromStr};
+
+use crate::types::i5_error::I5RequestError;
+
+/// A version of the Interface5 WebServiceInput API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum I5ApiVersion {
+    /// The `v1` API.
+    #[default]
+    V1,
+    /// The `v2` API of newer Interface5 releases.
+    V2,
+}
+
+impl I5ApiVersion {
+    /// The version as written in URL paths, e.g. `v1`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            I5ApiVersion::V1 => "v1",
+            I5ApiVersion::V2 => "v2",
+        }
+    }
+
+    /// The JSON Schema of the request body accepted by this version, bundled with the
+    /// crate, or `None` if no schema is bundled for it yet.
+    pub fn schema(&self) -> Option<&'static str> {
+        match self {
+            I5ApiVersion::V1 => Some(include_str!(
+                "../../schemas/webservice-input-v1.schema.json"
+            )),
+            I5ApiVersion::V2 => None,
+        }
+    }
+
+    /// The JSON Schema of the response to an accepted request in this version, bundled
+    /// with the crate, or `None` if no schema is bundled for it yet.
+    pub fn response_schema(&self) -> Option<&'static str> {
+        match self {
+            I5ApiVersion::V1 => Some(include_str!(
+                "../../schemas/webservice-input-v1.response.schema.json"
+            )),
+            I5ApiVersion::V2 => None,
+        }
+    }
+}
+
+impl FromStr for I5ApiVersion {
+    type Err = I5RequestError;
+
+    /// Parses a version as written in URL paths, ignoring case.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] for unknown versions.
+    fn from_str(text: &str) -> Result<I5ApiVersion, I5RequestError> {
+        match text.to_ascii_lowercase().as_str() {
+            "v1" => Ok(I5ApiVersion::V1),
+            "v2" => Ok(I5ApiVersion::V2),
+            _ => Err(I5RequestError::ConfigError(format!(
+                "unknown Interface5 API version '{}', expected v1 or v2",
+                text
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for I5ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
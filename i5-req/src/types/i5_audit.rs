@@ -0,0 +1,295 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use crate::{types::i5_error::I5RequestError, util};
+
+/// The hash the first entry of an audit log is chained to.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// The kind of operation recorded in the audit log.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum I5AuditOperation {
+    /// The body of a validated request was built and staged in a file.
+    Build,
+    /// A request was validated.
+    Validate,
+    /// A request was sent to Interface5.
+    Send,
+    /// A staged request was deleted.
+    Delete,
+}
+
+/// A single audit log line, without its own hash.
+#[derive(Serialize)]
+struct AuditRecord<'a> {
+    sequence: u64,
+    timestamp: String,
+    actor: &'a str,
+    operation: I5AuditOperation,
+    subject: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    correlation_id: Option<&'a str>,
+    outcome: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    prev_hash: &'a str,
+}
+
+struct AuditState {
+    file: File,
+    sequence: u64,
+    last_hash: String,
+}
+
+/// An append-only audit log written as JSON lines with hash chaining.
+///
+/// Every line records one operation with its timestamp, actor, subject (usually the
+/// batch name) and outcome. Each line carries the SHA-256 hash of its content combined
+/// with the hash of the previous line, so removing or editing an entry breaks the chain
+/// and is detected by [`I5AuditLog::verify`].
+///
+/// Sends are recorded automatically when the log is set via
+/// [`I5SendOptions::audit_log`](crate::request::options::I5SendOptions::audit_log),
+/// validations, staging and deleting staged requests when it is set via
+/// [`I5ValidationOptions::audit_log`](crate::types::i5_validation::I5ValidationOptions::audit_log).
+/// Other operations can be recorded with [`I5AuditLog::record`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use i5_req::types::{
+///     i5_audit::I5AuditLog, i5_request::I5Request, i5_validation::I5ValidationOptions,
+/// };
+/// use std::sync::Arc;
+///
+/// let audit = Arc::new(I5AuditLog::open("audit.jsonl", "scan-station-01")?);
+/// let options = I5ValidationOptions::new().audit_log(audit);
+///
+/// let mut request = I5Request::new("Batch-4711");
+/// let id = request.add_document("Invoice");
+/// request.get_document_mut(id).unwrap().add_header_field("InvoiceNo", "4711");
+/// let validated = request.validate_with(&options)?; // recorded as "validate"
+/// validated.save("/var/spool/i5-req/4711.i5")?; // recorded as "build"
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+pub struct I5AuditLog {
+    path: PathBuf,
+    actor: String,
+    state: Mutex<AuditState>,
+}
+
+impl I5AuditLog {
+    /// Opens (or creates) the audit log at `path` and continues its hash chain.
+    ///
+    /// `actor` identifies the user, service account or host recorded with each entry.
+    pub fn open(
+        path: impl AsRef<Path>,
+        actor: impl Into<String>,
+    ) -> Result<I5AuditLog, I5RequestError> {
+        let path = path.as_ref().to_path_buf();
+        let (sequence, last_hash) = match fs::read_to_string(&path) {
+            Ok(content) => match content.lines().rev().find(|line| !line.trim().is_empty()) {
+                Some(line) => {
+                    let entry: Value = serde_json::from_str(line).map_err(|err| {
+                        I5RequestError::IoError(io::Error::new(io::ErrorKind::InvalidData, err))
+                    })?;
+                    let sequence = entry["sequence"].as_u64().unwrap_or_default();
+                    let hash = entry["hash"].as_str().unwrap_or(GENESIS_HASH).to_string();
+                    (sequence + 1, hash)
+                }
+                None => (0, GENESIS_HASH.to_string()),
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (0, GENESIS_HASH.to_string()),
+            Err(err) => return Err(I5RequestError::IoError(err)),
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(I5RequestError::IoError)?;
+
+        Ok(I5AuditLog {
+            path,
+            actor: actor.into(),
+            state: Mutex::new(AuditState {
+                file,
+                sequence,
+                last_hash,
+            }),
+        })
+    }
+
+    /// The path of the audit log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends an entry for `operation` on `subject`.
+    ///
+    /// An `Err` outcome is recorded with its stable error code and message.
+    pub fn record(
+        &self,
+        operation: I5AuditOperation,
+        subject: &str,
+        outcome: Result<(), &I5RequestError>,
+    ) -> Result<(), I5RequestError> {
+        self.append(operation, subject, None, outcome)
+    }
+
+    pub(crate) fn append(
+        &self,
+        operation: I5AuditOperation,
+        subject: &str,
+        correlation_id: Option<&str>,
+        outcome: Result<(), &I5RequestError>,
+    ) -> Result<(), I5RequestError> {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let record = AuditRecord {
+            sequence: state.sequence,
+            timestamp: util::format_rfc3339(SystemTime::now()),
+            actor: &self.actor,
+            operation,
+            subject,
+            correlation_id,
+            outcome: match outcome {
+                Ok(()) => "ok",
+                Err(err) => err.code(),
+            },
+            error: outcome.err().map(|err| err.to_string()),
+            prev_hash: &state.last_hash,
+        };
+
+        let mut entry = serde_json::to_value(&record).map_err(I5RequestError::SerializeError)?;
+        let hash = chain_hash(&entry)?;
+        entry["hash"] = Value::String(hash.clone());
+
+        let mut line = entry.to_string();
+        line.push('\n');
+        state
+            .file
+            .write_all(line.as_bytes())
+            .and_then(|_| state.file.flush())
+            .map_err(I5RequestError::IoError)?;
+
+        state.sequence += 1;
+        state.last_hash = hash;
+        Ok(())
+    }
+
+    /// Verifies the hash chain of the audit log at `path`.
+    ///
+    /// Returns the number of valid entries, or an [`I5RequestError::IoError`] of kind
+    /// [`io::ErrorKind::InvalidData`] naming the first line that was tampered with.
+    pub fn verify(path: impl AsRef<Path>) -> Result<usize, I5RequestError> {
+        let content = fs::read_to_string(path).map_err(I5RequestError::IoError)?;
+        let invalid = |line: usize, reason: &str| {
+            I5RequestError::IoError(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("audit log line {}: {}", line, reason),
+            ))
+        };
+
+        let mut expected_prev = GENESIS_HASH.to_string();
+        let mut count = 0;
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut entry: Value =
+                serde_json::from_str(line).map_err(|_| invalid(index + 1, "not valid JSON"))?;
+            let hash = entry
+                .as_object_mut()
+                .and_then(|object| object.shift_remove("hash"))
+                .and_then(|hash| hash.as_str().map(str::to_string))
+                .ok_or_else(|| invalid(index + 1, "missing hash"))?;
+
+            if entry["prev_hash"].as_str() != Some(expected_prev.as_str()) {
+                return Err(invalid(index + 1, "previous hash does not match"));
+            }
+            if chain_hash(&entry)? != hash {
+                return Err(invalid(index + 1, "hash does not match content"));
+            }
+
+            expected_prev = hash;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Hashes an entry (without its `hash` key) together with its `prev_hash`.
+fn chain_hash(entry: &Value) -> Result<String, I5RequestError> {
+    let content = serde_json::to_string(entry).map_err(I5RequestError::SerializeError)?;
+    Ok(util::sha256_hex(content.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{i5_request::I5Request, i5_validation::I5ValidationOptions};
+    use std::sync::Arc;
+
+    #[test]
+    fn validation_options_record_validate_build_and_delete() {
+        let dir = std::env::temp_dir().join(format!("i5-audit-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (log, staged) = (dir.join("audit.jsonl"), dir.join("4711.i5"));
+        let _ = fs::remove_file(&log);
+        let audit = Arc::new(I5AuditLog::open(&log, "tests").unwrap());
+        let options = I5ValidationOptions::new().audit_log(audit);
+
+        assert!(I5Request::new("Empty").validate_with(&options).is_err());
+        let mut request = I5Request::new("Batch-4711");
+        let id = request.add_document("Invoice");
+        request
+            .get_document_mut(id)
+            .unwrap()
+            .add_header_field("InvoiceNo", "4711");
+        let validated = request.validate_with(&options).unwrap();
+        validated.save(&staged).unwrap();
+        validated.remove_staged(&staged).unwrap();
+        assert!(validated.remove_staged(&staged).is_err());
+
+        let entries: Vec<Value> = fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let recorded: Vec<(&str, &str, &str)> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry["operation"].as_str().unwrap(),
+                    entry["subject"].as_str().unwrap(),
+                    entry["outcome"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            recorded,
+            [
+                ("validate", "Empty", "I5_VALIDATION"),
+                ("validate", "Batch-4711", "ok"),
+                ("build", "Batch-4711", "ok"),
+                ("delete", "Batch-4711", "ok"),
+                ("delete", "Batch-4711", "I5_IO"),
+            ]
+        );
+        assert_eq!(I5AuditLog::verify(&log).unwrap(), 5);
+    }
+}
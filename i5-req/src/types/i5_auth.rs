@@ -0,0 +1,164 @@
+use base64::{Engine, engine::general_purpose};
+use std::borrow::Cow;
+
+#[cfg(feature = "keyring")]
+use crate::types::{i5_error::I5RequestError, i5_keyring};
+use crate::{request::AUTHORIZATION_HEADER, types::i5_secret::I5Secret};
+
+/// Credentials used to authenticate against Interface5.
+///
+/// Secrets are stored as [`I5Secret`], so `Debug` output never contains them.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_auth::I5Auth;
+///
+/// let auth = I5Auth::basic("svc-scanner", "hunter2");
+/// assert!(!format!("{:?}", auth).contains("hunter2"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum I5Auth {
+    /// HTTP Basic authentication.
+    Basic {
+        username: String,
+        password: I5Secret,
+    },
+    /// A bearer token sent in the `Authorization` header.
+    Bearer { token: I5Secret },
+    /// A static API key sent as the value of the header `header_name`, e.g.
+    /// `X-API-Key`, as required by API gateways in front of Interface5.
+    ApiKey {
+        header_name: String,
+        value: I5Secret,
+    },
+    /// Windows integrated authentication with the current logon, Kerberos or NTLM as
+    /// negotiated by SSPI, for servers behind IIS with Windows authentication.
+    ///
+    /// The ticket is requested for the service principal `HTTP/<host>` unless
+    /// `service_principal` names another one. Requires Windows and the `sspi`
+    /// feature, otherwise sending fails with
+    /// [`I5RequestError::CredentialError`](crate::types::i5_error::I5RequestError::CredentialError).
+    Negotiate { service_principal: Option<String> },
+    /// Windows integrated authentication with the current logon over NTLM, for
+    /// servers that only offer `WWW-Authenticate: NTLM`. Requires Windows and the
+    /// `sspi` feature.
+    Ntlm,
+}
+
+impl I5Auth {
+    /// Creates HTTP Basic credentials.
+    pub fn basic(username: impl Into<String>, password: impl Into<I5Secret>) -> I5Auth {
+        I5Auth::Basic {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+
+    /// Creates bearer token credentials.
+    pub fn bearer(token: impl Into<I5Secret>) -> I5Auth {
+        I5Auth::Bearer {
+            token: token.into(),
+        }
+    }
+
+    /// Creates API key credentials sent in the header `header_name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_auth::I5Auth;
+    ///
+    /// let auth = I5Auth::api_key("X-API-Key", "k-42");
+    /// assert!(!format!("{:?}", auth).contains("k-42"));
+    /// ```
+    pub fn api_key(header_name: impl Into<String>, value: impl Into<I5Secret>) -> I5Auth {
+        I5Auth::ApiKey {
+            header_name: header_name.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Authenticates with the current Windows logon, see [`I5Auth::Negotiate`].
+    pub fn negotiate() -> I5Auth {
+        I5Auth::Negotiate {
+            service_principal: None,
+        }
+    }
+
+    /// Whether the server binds the authentication to the connection, so that the
+    /// handshake must run on a single HTTP/1.1 connection.
+    pub(crate) fn is_connection_bound(&self) -> bool {
+        matches!(self, I5Auth::Negotiate { .. } | I5Auth::Ntlm)
+    }
+
+    /// Creates HTTP Basic credentials with the password read from the OS credential
+    /// store entry identified by `service` and `username`.
+    ///
+    /// See [`i5_keyring::read_secret`] for the supported stores.
+    #[cfg(feature = "keyring")]
+    pub fn basic_from_keyring(service: &str, username: &str) -> Result<I5Auth, I5RequestError> {
+        let password = i5_keyring::read_secret(service, username)?;
+        Ok(I5Auth::basic(username, password))
+    }
+
+    /// Creates bearer token credentials with the token read from the OS credential
+    /// store entry identified by `service` and `account`.
+    #[cfg(feature = "keyring")]
+    pub fn bearer_from_keyring(service: &str, account: &str) -> Result<I5Auth, I5RequestError> {
+        Ok(I5Auth::bearer(i5_keyring::read_secret(service, account)?))
+    }
+
+    /// Creates API key credentials sent in the header `header_name`, with the key read
+    /// from the OS credential store entry identified by `service` and `account`.
+    #[cfg(feature = "keyring")]
+    pub fn api_key_from_keyring(
+        header_name: &str,
+        service: &str,
+        account: &str,
+    ) -> Result<I5Auth, I5RequestError> {
+        Ok(I5Auth::api_key(
+            header_name,
+            i5_keyring::read_secret(service, account)?,
+        ))
+    }
+
+    /// The name of the header carrying the credentials.
+    pub(crate) fn header_name(&self) -> &str {
+        match self {
+            I5Auth::ApiKey { header_name, .. } => header_name,
+            _ => AUTHORIZATION_HEADER,
+        }
+    }
+
+    /// The header carrying the credentials, or `None` if it is negotiated for every
+    /// request.
+    pub(crate) fn header(&self) -> Option<(Cow<'static, str>, String)> {
+        let value = match self {
+            I5Auth::Basic { username, password } => format!(
+                "Basic {}",
+                general_purpose::STANDARD.encode(format!("{}:{}", username, password.expose()))
+            ),
+            I5Auth::Bearer { token } => format!("Bearer {}", token.expose()),
+            I5Auth::ApiKey { header_name, value } => {
+                return Some((Cow::Owned(header_name.clone()), value.expose().to_string()));
+            }
+            I5Auth::Negotiate { .. } | I5Auth::Ntlm => return None,
+        };
+        Some((Cow::Borrowed(AUTHORIZATION_HEADER), value))
+    }
+
+    /// The value of the credentials header with the secret replaced by a placeholder.
+    pub(crate) fn redacted_header_value(&self) -> String {
+        match self {
+            I5Auth::Basic { username, .. } => {
+                format!("Basic <base64 of '{}:<password>'>", username)
+            }
+            I5Auth::Bearer { .. } => String::from("Bearer <token>"),
+            I5Auth::ApiKey { .. } => String::from("<api key>"),
+            I5Auth::Negotiate { .. } => String::from("Negotiate <token>"),
+            I5Auth::Ntlm => String::from("NTLM <token>"),
+        }
+    }
+}
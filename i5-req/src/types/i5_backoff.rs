@@ -0,0 +1,224 @@
+use std::{fmt::Debug, time::Duration};
+
+use crate::util;
+
+/// Computes the wait before a retry.
+///
+/// The built-in strategies are [`I5ConstantBackoff`], [`I5ExponentialBackoff`],
+/// [`I5FibonacciBackoff`] and [`I5DecorrelatedJitter`]; a strategy is selected with
+/// [`I5RetryPolicy::backoff`](crate::types::i5_retry::I5RetryPolicy::backoff).
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_backoff::{I5Backoff, I5ExponentialBackoff};
+/// use std::time::Duration;
+///
+/// let backoff = I5ExponentialBackoff::new(Duration::from_millis(100))
+///     .max_delay(Duration::from_millis(500));
+///
+/// let delays: Vec<_> = (1..=5)
+///     .map(|retry| backoff.delay(retry, Duration::ZERO).as_millis())
+///     .collect();
+/// assert_eq!(delays, [100, 200, 400, 500, 500]);
+/// ```
+pub trait I5Backoff: Debug + Send + Sync {
+    /// Returns the wait before retry number `retry`, counted from 1. `previous` is the
+    /// wait before the previous retry, or zero before the first one.
+    fn delay(&self, retry: u32, previous: Duration) -> Duration;
+}
+
+/// Waits the same time before every retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I5ConstantBackoff {
+    delay: Duration,
+}
+
+impl I5ConstantBackoff {
+    /// Creates a backoff that always waits `delay`.
+    pub fn new(delay: Duration) -> I5ConstantBackoff {
+        I5ConstantBackoff { delay }
+    }
+}
+
+impl I5Backoff for I5ConstantBackoff {
+    fn delay(&self, _retry: u32, _previous: Duration) -> Duration {
+        self.delay
+    }
+}
+
+/// Multiplies the wait by a factor after every retry, up to a maximum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct I5ExponentialBackoff {
+    base: Duration,
+    factor: f64,
+    max_delay: Duration,
+}
+
+impl I5ExponentialBackoff {
+    /// Creates a backoff that waits `base` before the first retry and doubles the wait
+    /// after every retry, up to one minute.
+    pub fn new(base: Duration) -> I5ExponentialBackoff {
+        I5ExponentialBackoff {
+            base,
+            factor: 2.0,
+            max_delay: Duration::from_secs(60),
+        }
+    }
+
+    /// The factor applied to the wait after every retry.
+    pub fn factor(mut self, factor: f64) -> Self {
+        self.factor = factor.max(1.0);
+        self
+    }
+
+    /// The longest wait.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+}
+
+impl I5Backoff for I5ExponentialBackoff {
+    fn delay(&self, retry: u32, _previous: Duration) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let seconds = self.base.as_secs_f64() * self.factor.powi(exponent);
+        capped(seconds, self.max_delay)
+    }
+}
+
+/// Grows the wait along the Fibonacci sequence, 1, 1, 2, 3, 5, 8 times the base, up
+/// to a maximum.
+///
+/// The wait grows more slowly than with [`I5ExponentialBackoff`], which suits servers
+/// that take a while to restart but should be polled regularly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I5FibonacciBackoff {
+    base: Duration,
+    max_delay: Duration,
+}
+
+impl I5FibonacciBackoff {
+    /// Creates a backoff that waits `base` before the first two retries, up to one
+    /// minute.
+    pub fn new(base: Duration) -> I5FibonacciBackoff {
+        I5FibonacciBackoff {
+            base,
+            max_delay: Duration::from_secs(60),
+        }
+    }
+
+    /// The longest wait.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+}
+
+impl I5Backoff for I5FibonacciBackoff {
+    fn delay(&self, retry: u32, _previous: Duration) -> Duration {
+        let (mut current, mut next) = (1u32, 1u32);
+        for _ in 1..retry {
+            (current, next) = (next, current.saturating_add(next));
+            if self.base.saturating_mul(current) >= self.max_delay {
+                return self.max_delay;
+            }
+        }
+        self.base.saturating_mul(current).min(self.max_delay)
+    }
+}
+
+/// Waits a random time between the base and three times the previous wait, up to a
+/// maximum.
+///
+/// The "decorrelated jitter" strategy spreads the retries of many clients that failed
+/// at the same moment while still backing off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I5DecorrelatedJitter {
+    base: Duration,
+    max_delay: Duration,
+}
+
+impl I5DecorrelatedJitter {
+    /// Creates a backoff that waits at least `base` and at most one minute.
+    pub fn new(base: Duration) -> I5DecorrelatedJitter {
+        I5DecorrelatedJitter {
+            base,
+            max_delay: Duration::from_secs(60),
+        }
+    }
+
+    /// The longest wait.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+}
+
+impl I5Backoff for I5DecorrelatedJitter {
+    fn delay(&self, _retry: u32, previous: Duration) -> Duration {
+        let high = previous.max(self.base).saturating_mul(3);
+        random_between(self.base, high).min(self.max_delay)
+    }
+}
+
+/// Randomizes the waits computed by an [`I5Backoff`], so clients that failed at the
+/// same moment do not retry in lockstep.
+///
+/// Selected with [`I5RetryPolicy::jitter`](crate::types::i5_retry::I5RetryPolicy::jitter).
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_backoff::I5Jitter;
+/// use std::time::Duration;
+///
+/// let delay = Duration::from_secs(4);
+/// assert_eq!(I5Jitter::None.apply(delay), delay);
+/// assert!(I5Jitter::Full.apply(delay) <= delay);
+/// assert!(I5Jitter::Equal.apply(delay) >= delay / 2);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum I5Jitter {
+    /// Waits exactly as computed.
+    #[default]
+    None,
+    /// Waits a random time between zero and the computed wait.
+    Full,
+    /// Waits half the computed wait plus a random time up to the other half.
+    Equal,
+}
+
+impl I5Jitter {
+    /// Applies the jitter to `delay`.
+    pub fn apply(&self, delay: Duration) -> Duration {
+        match self {
+            I5Jitter::None => delay,
+            I5Jitter::Full => random_between(Duration::ZERO, delay),
+            I5Jitter::Equal => {
+                let half = delay / 2;
+                half + random_between(Duration::ZERO, delay - half)
+            }
+        }
+    }
+}
+
+/// `seconds` as a duration, at most `max`.
+fn capped(seconds: f64, max: Duration) -> Duration {
+    if seconds.is_finite() && seconds < max.as_secs_f64() {
+        Duration::from_secs_f64(seconds.max(0.0))
+    } else {
+        max
+    }
+}
+
+/// A uniformly distributed duration in `low..=high`.
+fn random_between(low: Duration, high: Duration) -> Duration {
+    if high <= low {
+        return low;
+    }
+    let span = (high - low).as_nanos().min(u64::MAX as u128) as u64;
+    let random = u64::from_le_bytes(util::random_bytes::<8>());
+    low + Duration::from_nanos(random % span.saturating_add(1))
+}
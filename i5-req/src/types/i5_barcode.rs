@@ -0,0 +1,171 @@
+use std::{fmt, str::FromStr};
+
+use crate::types::i5_error::I5RequestError;
+
+/// The most characters a QR code holds in numeric mode, at version 40 and error
+/// correction level L.
+const QR_MAX_NUMERIC: usize = 7089;
+
+/// The most characters a QR code holds in alphanumeric mode.
+const QR_MAX_ALPHANUMERIC: usize = 4296;
+
+/// The most bytes a QR code holds in byte mode.
+const QR_MAX_BYTES: usize = 2953;
+
+/// The barcode symbology a value was read from, checked by
+/// [`Document::add_barcode_field`].
+///
+/// Every symbology restricts the characters it can encode, and EAN codes carry a
+/// check digit, so that a value a scanner misread or a user mistyped is rejected
+/// before it is sent.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_barcode::I5BarcodeSymbology;
+///
+/// assert!(I5BarcodeSymbology::Ean13.validate("4006381333931").is_ok());
+/// assert!(I5BarcodeSymbology::Ean13.validate("4006381333932").is_err());
+/// assert!(I5BarcodeSymbology::Code39.validate("INV-4711").is_ok());
+/// assert!(I5BarcodeSymbology::Code39.validate("inv-4711").is_err());
+/// ```
+///
+/// [`Document::add_barcode_field`]: crate::types::i5_request::Document::add_barcode_field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum I5BarcodeSymbology {
+    /// Code 39: digits, upper case letters, space and `-.$/+%`.
+    Code39,
+    /// Code 128: any ASCII character.
+    Code128,
+    /// EAN-8: 8 digits including the check digit.
+    Ean8,
+    /// EAN-13: 13 digits including the check digit.
+    Ean13,
+    /// QR code: any text within the capacity of the largest QR code, 7089 digits,
+    /// 4296 characters of the alphanumeric set or 2953 bytes of UTF-8.
+    QrCode,
+}
+
+impl I5BarcodeSymbology {
+    /// The symbology as written in configuration, e.g. `ean13`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            I5BarcodeSymbology::Code39 => "code39",
+            I5BarcodeSymbology::Code128 => "code128",
+            I5BarcodeSymbology::Ean8 => "ean8",
+            I5BarcodeSymbology::Ean13 => "ean13",
+            I5BarcodeSymbology::QrCode => "qr",
+        }
+    }
+
+    /// Checks that `value` can be encoded in the symbology.
+    ///
+    /// Returns [`I5RequestError::ConversionError`] naming the problem otherwise.
+    pub fn validate(&self, value: &str) -> Result<(), I5RequestError> {
+        match self.problem(value) {
+            Some(problem) => Err(I5RequestError::ConversionError(format!(
+                "'{}' is not a valid {} barcode: {}",
+                value, self, problem
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// What keeps `value` from being encoded in the symbology.
+    pub(crate) fn problem(&self, value: &str) -> Option<String> {
+        if value.is_empty() {
+            return Some(String::from("the value is empty"));
+        }
+        match self {
+            I5BarcodeSymbology::Code39 => value
+                .chars()
+                .find(|c| !is_code39(*c))
+                .map(|c| format!("'{}' cannot be encoded", c.escape_default())),
+            I5BarcodeSymbology::Code128 => value
+                .chars()
+                .find(|c| !c.is_ascii())
+                .map(|c| format!("'{}' is not an ASCII character", c)),
+            I5BarcodeSymbology::Ean8 => ean_problem(value, 8),
+            I5BarcodeSymbology::Ean13 => ean_problem(value, 13),
+            I5BarcodeSymbology::QrCode => {
+                let (limit, unit) = if value.bytes().all(|b| b.is_ascii_digit()) {
+                    (QR_MAX_NUMERIC, "digits")
+                } else if value.chars().all(is_qr_alphanumeric) {
+                    (QR_MAX_ALPHANUMERIC, "characters")
+                } else {
+                    (QR_MAX_BYTES, "bytes")
+                };
+                (value.len() > limit)
+                    .then(|| format!("{} {} exceed the capacity of {}", value.len(), unit, limit))
+            }
+        }
+    }
+}
+
+impl FromStr for I5BarcodeSymbology {
+    type Err = I5RequestError;
+
+    /// Parses `code39`, `code128`, `ean8`, `ean13` or `qr`, ignoring case and `-`.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] for unknown symbologies.
+    fn from_str(text: &str) -> Result<I5BarcodeSymbology, I5RequestError> {
+        match text.trim().to_ascii_lowercase().replace('-', "").as_str() {
+            "code39" => Ok(I5BarcodeSymbology::Code39),
+            "code128" => Ok(I5BarcodeSymbology::Code128),
+            "ean8" => Ok(I5BarcodeSymbology::Ean8),
+            "ean13" => Ok(I5BarcodeSymbology::Ean13),
+            "qr" | "qrcode" => Ok(I5BarcodeSymbology::QrCode),
+            _ => Err(I5RequestError::ConfigError(format!(
+                "unknown barcode symbology '{}', expected code39, code128, ean8, ean13 or qr",
+                text
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for I5BarcodeSymbology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            I5BarcodeSymbology::Code39 => "Code 39",
+            I5BarcodeSymbology::Code128 => "Code 128",
+            I5BarcodeSymbology::Ean8 => "EAN-8",
+            I5BarcodeSymbology::Ean13 => "EAN-13",
+            I5BarcodeSymbology::QrCode => "QR code",
+        })
+    }
+}
+
+fn is_code39(c: char) -> bool {
+    c.is_ascii_digit() || c.is_ascii_uppercase() || " -.$/+%".contains(c)
+}
+
+fn is_qr_alphanumeric(c: char) -> bool {
+    c.is_ascii_digit() || c.is_ascii_uppercase() || " $%*+-./:".contains(c)
+}
+
+/// What keeps `value` from being an EAN code of `len` digits.
+fn ean_problem(value: &str, len: usize) -> Option<String> {
+    if !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Some(String::from("only digits can be encoded"));
+    }
+    if value.len() != len {
+        return Some(format!("expected {} digits, got {}", len, value.len()));
+    }
+    let digits: Vec<u32> = value.bytes().map(|b| u32::from(b - b'0')).collect();
+    // Weighted 3 and 1 alternately, starting with 3 next to the check digit.
+    let sum: u32 = digits[..len - 1]
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| if index % 2 == 0 { digit * 3 } else { *digit })
+        .sum();
+    let expected = (10 - sum % 10) % 10;
+    (digits[len - 1] != expected).then(|| {
+        format!(
+            "the check digit is {}, expected {}",
+            digits[len - 1],
+            expected
+        )
+    })
+}
@@ -0,0 +1,303 @@
+use std::{
+    fmt::Debug,
+    fs::{self, File, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::SystemTime,
+};
+
+use crate::{types::i5_error::I5RequestError, util};
+
+/// Generates batch names.
+///
+/// Implementations must return a new name on every call, or an error if no name can
+/// be generated. The built-in strategies are [`I5TimestampNamer`], [`I5UuidNamer`],
+/// [`I5SequenceNamer`] and [`I5FileSequenceNamer`]; a namer is used
+/// with [`I5Request::named_by`] or configured on a client with
+/// [`I5Config::batch_namer`](crate::types::i5_config::I5Config::batch_namer).
+///
+/// [`I5Request::named_by`]: crate::types::i5_request::I5Request::named_by
+pub trait I5BatchNamer: Debug + Send + Sync {
+    /// Returns the next batch name.
+    fn next_name(&self) -> Result<String, I5RequestError>;
+}
+
+/// Names batches after the current UTC time, e.g. `SCAN-20250627-081500-123`.
+///
+/// The pattern supports `%Y`, `%y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%f` (milliseconds),
+/// `%s` (Unix seconds) and `%%`.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_batch_name::{I5BatchNamer, I5TimestampNamer};
+///
+/// let namer = I5TimestampNamer::new("SCAN-%Y%m%d-%H%M%S-%f")?;
+/// assert!(namer.next_name()?.starts_with("SCAN-"));
+///
+/// assert!(I5TimestampNamer::new("%Q").is_err());
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct I5TimestampNamer {
+    pattern: String,
+}
+
+impl I5TimestampNamer {
+    /// Creates a namer for `pattern`.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] if the pattern contains an unsupported
+    /// specifier.
+    pub fn new(pattern: impl Into<String>) -> Result<I5TimestampNamer, I5RequestError> {
+        let pattern = pattern.into();
+        util::format_strftime(SystemTime::now(), &pattern).map_err(|specifier| {
+            I5RequestError::ConfigError(format!(
+                "batch name pattern '{}': unsupported specifier {}",
+                pattern, specifier
+            ))
+        })?;
+        Ok(I5TimestampNamer { pattern })
+    }
+}
+
+impl I5BatchNamer for I5TimestampNamer {
+    fn next_name(&self) -> Result<String, I5RequestError> {
+        Ok(util::format_strftime(SystemTime::now(), &self.pattern)
+            .expect("the pattern was validated on creation"))
+    }
+}
+
+/// Names batches with a random UUID and an optional prefix, e.g.
+/// `SCAN-6728fdad-afec-419f-aa63-9b0c5a56a3c8`.
+#[derive(Debug, Clone, Default)]
+pub struct I5UuidNamer {
+    prefix: String,
+}
+
+impl I5UuidNamer {
+    /// Creates a namer that prepends `prefix` to every UUID.
+    pub fn new(prefix: impl Into<String>) -> I5UuidNamer {
+        I5UuidNamer {
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl I5BatchNamer for I5UuidNamer {
+    fn next_name(&self) -> Result<String, I5RequestError> {
+        Ok(format!("{}{}", self.prefix, util::uuid_v4()))
+    }
+}
+
+/// Names batches with a prefix and a zero-padded in-memory sequence number, e.g.
+/// `SCAN-000042`.
+///
+/// The sequence restarts with every process; use [`I5FileSequenceNamer`] for numbers
+/// that never repeat.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_batch_name::{I5BatchNamer, I5SequenceNamer};
+///
+/// let namer = I5SequenceNamer::new("SCAN-").width(6).start_at(41);
+/// assert_eq!(namer.next_name()?, "SCAN-000041");
+/// assert_eq!(namer.next_name()?, "SCAN-000042");
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+#[derive(Debug)]
+pub struct I5SequenceNamer {
+    prefix: String,
+    width: usize,
+    next: AtomicU64,
+}
+
+impl I5SequenceNamer {
+    /// Creates a namer starting at 1 without padding.
+    pub fn new(prefix: impl Into<String>) -> I5SequenceNamer {
+        I5SequenceNamer {
+            prefix: prefix.into(),
+            width: 0,
+            next: AtomicU64::new(1),
+        }
+    }
+
+    /// Pads the sequence number with zeros to `width` digits.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the first sequence number.
+    pub fn start_at(self, first: u64) -> Self {
+        self.next.store(first, Ordering::Relaxed);
+        self
+    }
+}
+
+impl I5BatchNamer for I5SequenceNamer {
+    fn next_name(&self) -> Result<String, I5RequestError> {
+        let number = self.next.fetch_add(1, Ordering::Relaxed);
+        Ok(format!(
+            "{}{:0width$}",
+            self.prefix,
+            number,
+            width = self.width
+        ))
+    }
+}
+
+/// Names batches with a prefix and a zero-padded sequence number that is persisted in
+/// a file, so numbers never repeat across restarts, e.g. `SCAN-000042`.
+///
+/// The file holds the last issued number as decimal text. Every call locks the
+/// companion file `<path>.lock`, so several threads and processes can share one
+/// counter. The new number is written to a temporary file, synced and renamed over
+/// the counter file before it is returned: a crash can skip a number but never
+/// issue one twice.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_batch_name::{I5BatchNamer, I5FileSequenceNamer};
+///
+/// let directory = std::env::temp_dir().join(format!("i5-doc-seq-{}", std::process::id()));
+/// std::fs::create_dir_all(&directory)?;
+/// let path = directory.join("batches.seq");
+///
+/// let namer = I5FileSequenceNamer::open(&path, "SCAN-")?.width(6);
+/// assert_eq!(namer.next_name()?, "SCAN-000001");
+///
+/// // A new namer, e.g. after a restart, continues the sequence.
+/// let namer = I5FileSequenceNamer::open(&path, "SCAN-")?.width(6);
+/// assert_eq!(namer.next_name()?, "SCAN-000002");
+/// # std::fs::remove_dir_all(&directory)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct I5FileSequenceNamer {
+    path: PathBuf,
+    prefix: String,
+    width: usize,
+    first: u64,
+    guard: Mutex<()>,
+}
+
+impl I5FileSequenceNamer {
+    /// Creates a namer backed by the counter file at `path`.
+    ///
+    /// The file is created on first use. Returns [`I5RequestError::IoError`] if the
+    /// directory of `path` does not exist.
+    pub fn open(
+        path: impl Into<PathBuf>,
+        prefix: impl Into<String>,
+    ) -> Result<I5FileSequenceNamer, I5RequestError> {
+        let path = path.into();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+            && !parent.is_dir()
+        {
+            return Err(I5RequestError::IoError(std::io::Error::new(
+                ErrorKind::NotFound,
+                format!("directory {} does not exist", parent.display()),
+            )));
+        }
+
+        Ok(I5FileSequenceNamer {
+            path,
+            prefix: prefix.into(),
+            width: 0,
+            first: 1,
+            guard: Mutex::new(()),
+        })
+    }
+
+    /// Pads the sequence number with zeros to `width` digits.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the first sequence number, used while the counter file does not exist.
+    pub fn start_at(mut self, first: u64) -> Self {
+        self.first = first;
+        self
+    }
+
+    /// The path of the counter file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reserves and persists the next sequence number.
+    fn next_number(&self) -> Result<u64, I5RequestError> {
+        let _guard = self.guard.lock().unwrap_or_else(|e| e.into_inner());
+
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.sibling("lock"))
+            .map_err(I5RequestError::IoError)?;
+        lock.lock().map_err(I5RequestError::IoError)?;
+
+        let next = match fs::read_to_string(&self.path) {
+            Ok(text) => text
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .and_then(|last| last.checked_add(1))
+                .ok_or_else(|| {
+                    I5RequestError::ConfigError(format!(
+                        "sequence file {} does not contain a valid number",
+                        self.path.display()
+                    ))
+                })?,
+            Err(err) if err.kind() == ErrorKind::NotFound => self.first,
+            Err(err) => return Err(I5RequestError::IoError(err)),
+        };
+
+        let temporary = self.sibling("tmp");
+        let mut file = File::create(&temporary).map_err(I5RequestError::IoError)?;
+        file.write_all(format!("{}\n", next).as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(I5RequestError::IoError)?;
+        fs::rename(&temporary, &self.path).map_err(I5RequestError::IoError)?;
+        #[cfg(unix)]
+        if let Some(parent) = self.path.parent()
+            && let Ok(directory) = File::open(if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            })
+        {
+            let _ = directory.sync_all();
+        }
+
+        Ok(next)
+    }
+
+    /// A file next to the counter file with `extension` appended to its name.
+    fn sibling(&self, extension: &str) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(".");
+        name.push(extension);
+        PathBuf::from(name)
+    }
+}
+
+impl I5BatchNamer for I5FileSequenceNamer {
+    fn next_name(&self) -> Result<String, I5RequestError> {
+        let number = self.next_number()?;
+        Ok(format!(
+            "{}{:0width$}",
+            self.prefix,
+            number,
+            width = self.width
+        ))
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+
+use crate::{
+    events::i5_event,
+    types::{
+        i5_error::I5RequestError,
+        i5_receipt::I5BatchReceipt,
+        i5_request::{I5Request, ValidatedI5Request},
+        i5_validation::{I5ValidationIssue, I5ValidationOptions, I5ValidationReport},
+    },
+};
+
+/// Several requests forming one logical job, e.g. an ERP export producing a batch per
+/// company code, validated together and submitted with
+/// [`I5Client::send_batch_set`](crate::request::client::I5Client::send_batch_set).
+///
+/// The requests of a set need distinct names, so that the outcome of each can be told
+/// apart.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{i5_batch_set::I5BatchSet, i5_request::I5Request};
+///
+/// let mut set = I5BatchSet::new("export-2025-06-27");
+/// for company in ["1000", "2000"] {
+///     let mut request = I5Request::new(format!("export-{}", company));
+///     let id = request.add_document("Invoice");
+///     request.get_document_mut(id).unwrap().add_header_field("CompanyCode", company);
+///     set.push(request);
+/// }
+///
+/// let validated = set.validate()?;
+/// assert_eq!(validated.len(), 2);
+/// assert_eq!(validated.requests()[1].request().name(), "export-2000");
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5BatchSet {
+    name: String,
+    requests: Vec<I5Request>,
+}
+
+impl I5BatchSet {
+    /// Creates an empty set named `name`, the name of the job it belongs to.
+    pub fn new(name: impl Into<String>) -> I5BatchSet {
+        I5BatchSet {
+            name: name.into(),
+            requests: Vec::new(),
+        }
+    }
+
+    /// The name of the set.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Appends `request` to the set.
+    ///
+    /// Returns the index of the request.
+    pub fn push(&mut self, request: I5Request) -> usize {
+        self.requests.push(request);
+        self.requests.len() - 1
+    }
+
+    /// The requests of the set, in the order they were added.
+    pub fn requests(&self) -> &[I5Request] {
+        &self.requests
+    }
+
+    /// Gets a mutable reference to a request by index.
+    pub fn get_request_mut(&mut self, index: usize) -> Option<&mut I5Request> {
+        self.requests.get_mut(index)
+    }
+
+    /// The number of requests.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Returns `true` if the set has no requests.
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Checks every request like [`I5Request::validation_report`] and reports a
+    /// request named like an earlier one with
+    /// [`I5ValidationIssue::DuplicateBatchName`].
+    ///
+    /// Returns one report per request, in the order of [`I5BatchSet::requests`].
+    pub fn validation_reports(&self) -> Vec<I5ValidationReport> {
+        self.validation_reports_with(&I5ValidationOptions::default())
+    }
+
+    /// Checks every request with the rules of `options`, see
+    /// [`I5BatchSet::validation_reports`].
+    pub fn validation_reports_with(
+        &self,
+        options: &I5ValidationOptions,
+    ) -> Vec<I5ValidationReport> {
+        let mut names = HashSet::new();
+        self.requests
+            .iter()
+            .map(|request| {
+                let mut report = request.validation_report_with(options);
+                if !names.insert(request.name()) {
+                    report.push(I5ValidationIssue::DuplicateBatchName {
+                        name: request.name().to_string(),
+                    });
+                }
+                report
+            })
+            .collect()
+    }
+
+    /// Consumes and validates all requests of the set.
+    ///
+    /// Returns [`I5RequestError::ValidationError`] if the set is empty or any request
+    /// is invalid, so that no part of a job is sent while another part is rejected.
+    pub fn validate(self) -> Result<ValidatedI5BatchSet, I5RequestError> {
+        self.validate_with(&I5ValidationOptions::default())
+    }
+
+    /// Consumes and validates all requests of the set with the rules of `options`, see
+    /// [`I5BatchSet::validate`].
+    pub fn validate_with(
+        self,
+        options: &I5ValidationOptions,
+    ) -> Result<ValidatedI5BatchSet, I5RequestError> {
+        let reports = self.validation_reports_with(options);
+        if self.requests.is_empty() {
+            i5_event!(warn, "I5BatchSet has no requests", set = self.name);
+            return Err(I5RequestError::ValidationError);
+        }
+        if reports.iter().any(|report| !report.is_valid()) {
+            for (request, report) in self.requests.iter().zip(&reports) {
+                for issue in report.issues() {
+                    i5_event!(
+                        warn,
+                        "I5BatchSet validation issue",
+                        set = self.name,
+                        request = request.name(),
+                        reason = issue
+                    );
+                }
+            }
+            return Err(I5RequestError::ValidationError);
+        }
+        let requests = self
+            .requests
+            .into_iter()
+            .map(|request| request.validate_with(options))
+            .collect::<Result<_, _>>()?;
+        Ok(ValidatedI5BatchSet {
+            name: self.name,
+            requests,
+        })
+    }
+}
+
+/// An [`I5BatchSet`] whose requests all passed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatedI5BatchSet {
+    name: String,
+    requests: Vec<ValidatedI5Request>,
+}
+
+impl ValidatedI5BatchSet {
+    /// The name of the set.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The validated requests, in the order they were added.
+    pub fn requests(&self) -> &[ValidatedI5Request] {
+        &self.requests
+    }
+
+    /// The number of requests.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Always `false`, as an empty set does not pass validation.
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+}
+
+/// The outcome of submitting a [`ValidatedI5BatchSet`]: a receipt or an error for
+/// each request, in the order of the set.
+#[derive(Debug)]
+pub struct I5BatchSetOutcome {
+    name: String,
+    outcomes: Vec<(String, Result<I5BatchReceipt, I5RequestError>)>,
+}
+
+impl I5BatchSetOutcome {
+    pub(crate) fn new(
+        name: String,
+        outcomes: Vec<(String, Result<I5BatchReceipt, I5RequestError>)>,
+    ) -> I5BatchSetOutcome {
+        I5BatchSetOutcome { name, outcomes }
+    }
+
+    /// The name of the set.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The name of every request with its outcome, in the order of the set.
+    pub fn outcomes(&self) -> &[(String, Result<I5BatchReceipt, I5RequestError>)] {
+        &self.outcomes
+    }
+
+    /// Returns `true` if every request was accepted.
+    pub fn is_success(&self) -> bool {
+        self.outcomes.iter().all(|(_, outcome)| outcome.is_ok())
+    }
+
+    /// The receipts of the accepted requests, with their names.
+    pub fn receipts(&self) -> impl Iterator<Item = (&str, &I5BatchReceipt)> {
+        self.outcomes
+            .iter()
+            .filter_map(|(name, outcome)| Some((name.as_str(), outcome.as_ref().ok()?)))
+    }
+
+    /// The errors of the failed requests, with their names.
+    pub fn failures(&self) -> impl Iterator<Item = (&str, &I5RequestError)> {
+        self.outcomes
+            .iter()
+            .filter_map(|(name, outcome)| Some((name.as_str(), outcome.as_ref().err()?)))
+    }
+
+    /// The outcome of every request, with its name.
+    pub fn into_outcomes(self) -> Vec<(String, Result<I5BatchReceipt, I5RequestError>)> {
+        self.outcomes
+    }
+}
@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+use crate::types::i5_error::I5RequestError;
+
+/// How a `bool` is written into a checkbox field.
+///
+/// Scenarios differ in the tokens they expect for checkboxes. The format of a client
+/// is set with [`I5Config::bool_format`] and used with [`Document::add_bool_field`],
+/// so that every checkbox of the client is written alike.
+///
+/// | Preset                       | `true` | `false` |
+/// |------------------------------|--------|---------|
+/// | [`I5BoolFormat::true_false`] | `true` | `false` |
+/// | [`I5BoolFormat::one_zero`]   | `1`    | `0`     |
+/// | [`I5BoolFormat::cross`]      | `X`    | empty   |
+///
+/// In configuration, a format is written as the two tokens separated by `/`, e.g.
+/// `1/0` or `X/`.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_bool_format::I5BoolFormat;
+///
+/// assert_eq!(I5BoolFormat::cross().format(true), "X");
+/// assert_eq!(I5BoolFormat::cross().format(false), "");
+///
+/// let format: I5BoolFormat = "J/N".parse()?;
+/// assert_eq!(format.format(true), "J");
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+///
+/// [`I5Config::bool_format`]: crate::types::i5_config::I5Config::bool_format
+/// [`Document::add_bool_field`]: crate::types::i5_request::Document::add_bool_field
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct I5BoolFormat {
+    true_token: String,
+    false_token: String,
+}
+
+impl I5BoolFormat {
+    /// Writes `true_token` for `true` and `false_token` for `false`.
+    pub fn new(true_token: impl Into<String>, false_token: impl Into<String>) -> I5BoolFormat {
+        I5BoolFormat {
+            true_token: true_token.into(),
+            false_token: false_token.into(),
+        }
+    }
+
+    /// `true` and `false`, as written for a `bool` field value.
+    pub fn true_false() -> I5BoolFormat {
+        I5BoolFormat::new("true", "false")
+    }
+
+    /// `1` and `0`.
+    pub fn one_zero() -> I5BoolFormat {
+        I5BoolFormat::new("1", "0")
+    }
+
+    /// `X` and an empty value, as for SAP checkboxes.
+    pub fn cross() -> I5BoolFormat {
+        I5BoolFormat::new("X", "")
+    }
+
+    /// The token for `value`.
+    pub fn format(&self, value: bool) -> &str {
+        if value {
+            &self.true_token
+        } else {
+            &self.false_token
+        }
+    }
+}
+
+impl Default for I5BoolFormat {
+    fn default() -> Self {
+        I5BoolFormat::true_false()
+    }
+}
+
+impl FromStr for I5BoolFormat {
+    type Err = I5RequestError;
+
+    /// Parses the `true` and `false` tokens separated by `/`, e.g. `1/0` or `X/`.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] if there is no `/` or both tokens are
+    /// equal.
+    fn from_str(text: &str) -> Result<I5BoolFormat, I5RequestError> {
+        match text.split_once('/') {
+            Some((true_token, false_token)) if true_token != false_token => {
+                Ok(I5BoolFormat::new(true_token, false_token))
+            }
+            _ => Err(I5RequestError::ConfigError(format!(
+                "invalid boolean format '{}', expected two different tokens such as 1/0",
+                text
+            ))),
+        }
+    }
+}
@@ -0,0 +1,1933 @@
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    toml,
+    types::{
+        i5_api_version::I5ApiVersion,
+        i5_auth::I5Auth,
+        i5_batch_name::{
+            I5BatchNamer, I5FileSequenceNamer, I5SequenceNamer, I5TimestampNamer, I5UuidNamer,
+        },
+        i5_bool_format::I5BoolFormat,
+        i5_content_format::I5ContentFormat,
+        i5_error::I5RequestError,
+        i5_field_mapping::I5FieldMapping,
+        i5_field_rules::{I5FieldRules, RuleFile},
+        i5_ip_family::I5IpFamily,
+        i5_request_url::I5RequestUrl,
+        i5_retry::I5RetryPolicy,
+        i5_sanitizer::I5Sanitizer,
+        i5_tls_backend::I5TlsBackend,
+        i5_tls_version::I5TlsVersion,
+        i5_token_endpoint::I5TokenEndpoint,
+        i5_transform::{I5Transform, I5Transforms},
+    },
+    yaml,
+};
+
+/// The default port of an Interface5 WebServiceInput.
+pub const DEFAULT_PORT: i32 = 43001;
+
+/// The request size in bytes above which
+/// [`I5Client::send_large`](crate::request::client::I5Client::send_large) opens an
+/// [upload session](I5Config::upload_sessions) unless [`I5Config::max_request_size`]
+/// is set.
+pub const DEFAULT_MAX_REQUEST_SIZE: u64 = 64 << 20;
+
+/// The size in bytes of the attachment chunks uploaded in an upload session, unless
+/// [`I5Config::upload_chunk_size`] is set.
+pub const DEFAULT_UPLOAD_CHUNK_SIZE: u64 = 8 << 20;
+
+/// The configuration of an [`I5Client`](crate::request::client::I5Client).
+///
+/// Holds the target endpoint, the credentials and the TLS options. It can be built
+/// programmatically, loaded from the environment with [`I5Config::from_env`] or read
+/// from a configuration file with [`I5Config::from_file`].
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{i5_auth::I5Auth, i5_config::I5Config};
+///
+/// let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default")
+///     .auth(I5Auth::basic("svc-scanner", "hunter2"))
+///     .add_root_certificate("/etc/ssl/company-ca.pem");
+/// ```
+#[derive(Clone)]
+pub struct I5Config {
+    hostname: String,
+    port: i32,
+    scenario: String,
+    tenant: String,
+    api_version: I5ApiVersion,
+    content_format: I5ContentFormat,
+    auth: Option<I5Auth>,
+    token_endpoint: Option<I5TokenEndpoint>,
+    accept_invalid_certs: bool,
+    root_certificates: Vec<PathBuf>,
+    tls_backend: Option<I5TlsBackend>,
+    min_tls_version: Option<I5TlsVersion>,
+    max_tls_version: Option<I5TlsVersion>,
+    pinned_keys: Vec<String>,
+    resolve: BTreeMap<String, IpAddr>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    tcp_keepalive_interval: Option<Duration>,
+    dns_timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    ip_family: I5IpFamily,
+    local_address: Option<IpAddr>,
+    accept_encoding: Option<String>,
+    decompress_responses: bool,
+    query: Vec<(String, String)>,
+    default_fields: Vec<(String, String)>,
+    field_mapping: I5FieldMapping,
+    transforms: I5Transforms,
+    bool_format: I5BoolFormat,
+    sanitizer: Option<I5Sanitizer>,
+    field_rules: I5FieldRules,
+    batch_namer: Option<Arc<dyn I5BatchNamer>>,
+    retry: Option<I5RetryPolicy>,
+    field_definitions_ttl: Duration,
+    max_request_size: Option<u64>,
+    split_oversized: bool,
+    upload_sessions: bool,
+    upload_chunk_size: u64,
+    max_in_flight_bytes: Option<u64>,
+    profile: Option<String>,
+    source: Option<Arc<ConfigSource>>,
+}
+
+impl I5Config {
+    /// Creates a configuration for the given endpoint without credentials.
+    pub fn new(
+        hostname: impl Into<String>,
+        port: i32,
+        scenario: impl Into<String>,
+        tenant: impl Into<String>,
+    ) -> I5Config {
+        I5Config {
+            hostname: hostname.into(),
+            port,
+            scenario: scenario.into(),
+            tenant: tenant.into(),
+            api_version: I5ApiVersion::V1,
+            content_format: I5ContentFormat::Json,
+            auth: None,
+            token_endpoint: None,
+            accept_invalid_certs: false,
+            root_certificates: Vec::new(),
+            tls_backend: None,
+            min_tls_version: None,
+            max_tls_version: None,
+            pinned_keys: Vec::new(),
+            resolve: BTreeMap::new(),
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            tcp_keepalive: None,
+            tcp_keepalive_interval: None,
+            dns_timeout: None,
+            connect_timeout: None,
+            ip_family: I5IpFamily::Any,
+            local_address: None,
+            accept_encoding: None,
+            decompress_responses: true,
+            query: Vec::new(),
+            default_fields: Vec::new(),
+            field_mapping: I5FieldMapping::new(),
+            transforms: I5Transforms::new(),
+            bool_format: I5BoolFormat::true_false(),
+            sanitizer: None,
+            field_rules: I5FieldRules::new(),
+            batch_namer: None,
+            retry: None,
+            field_definitions_ttl: Duration::from_secs(300),
+            max_request_size: None,
+            split_oversized: false,
+            upload_sessions: false,
+            upload_chunk_size: DEFAULT_UPLOAD_CHUNK_SIZE,
+            max_in_flight_bytes: None,
+            profile: None,
+            source: None,
+        }
+    }
+
+    /// Reads the configuration from `I5REQ_*` environment variables.
+    ///
+    /// | Variable                            | Meaning                                              |
+    /// |-------------------------------------|------------------------------------------------------|
+    /// | `I5REQ_HOST`                        | Hostname or IP address (required)                    |
+    /// | `I5REQ_PORT`                        | Port, defaults to [`DEFAULT_PORT`]                   |
+    /// | `I5REQ_SCENARIO`                    | Scenario name (required)                             |
+    /// | `I5REQ_TENANT`                      | Tenant identifier (required)                         |
+    /// | `I5REQ_API_VERSION`                 | API version, `v1` (default) or `v2`                  |
+    /// | `I5REQ_CONTENT_FORMAT`              | Body format, `json` (default) or `xml`               |
+    /// | `I5REQ_USERNAME`                    | Username for HTTP Basic authentication               |
+    /// | `I5REQ_PASSWORD`                    | Password for HTTP Basic authentication               |
+    /// | `I5REQ_TOKEN`                       | Bearer token, used instead of username and password  |
+    /// | `I5REQ_API_KEY`                     | API key, used instead of username and password       |
+    /// | `I5REQ_API_KEY_HEADER`              | Header carrying `I5REQ_API_KEY` (default `X-API-Key`) |
+    /// | `I5REQ_TOKEN_URL`                   | OAuth 2.0 token endpoint, with `I5REQ_CLIENT_ID` and `I5REQ_CLIENT_SECRET` |
+    /// | `I5REQ_TOKEN_SCOPE`                 | Scope requested from `I5REQ_TOKEN_URL`               |
+    /// | `I5REQ_KEYRING_SERVICE`             | Load the password for `I5REQ_USERNAME` from the OS credential store (`keyring` feature) |
+    /// | `I5REQ_AUTH_SCHEME`                 | `negotiate` or `ntlm` for the current Windows logon (`sspi` feature) |
+    /// | `I5REQ_ACCEPT_INVALID_CERTS`        | `true`/`1` to accept invalid server certificates     |
+    /// | `I5REQ_CA_CERT`                     | Path to a PEM file with additional trusted CAs       |
+    /// | `I5REQ_TLS_BACKEND`                 | `native` (`native-tls` feature) or `rustls` (`rustls` feature) |
+    /// | `I5REQ_MIN_TLS_VERSION`             | Lowest accepted TLS version, e.g. `1.2`              |
+    /// | `I5REQ_MAX_TLS_VERSION`             | Highest accepted TLS version, e.g. `1.3`             |
+    /// | `I5REQ_PINNED_KEYS`                 | Comma-separated public key pins (`rustls` feature)   |
+    /// | `I5REQ_POOL_MAX_IDLE_PER_HOST`      | Idle connections kept per host                       |
+    /// | `I5REQ_POOL_IDLE_TIMEOUT_SECS`      | Seconds an idle connection is kept                   |
+    /// | `I5REQ_TCP_KEEPALIVE_SECS`          | Idle seconds before the first TCP keepalive probe    |
+    /// | `I5REQ_TCP_KEEPALIVE_INTERVAL_SECS` | Seconds between TCP keepalive probes                 |
+    /// | `I5REQ_DNS_TIMEOUT_SECS`            | Seconds a hostname lookup may take                   |
+    /// | `I5REQ_CONNECT_TIMEOUT_SECS`        | Seconds the TCP connect and TLS handshake may take   |
+    /// | `I5REQ_IP_FAMILY`                   | `any` (default), `prefer-ipv4`, `prefer-ipv6`, `ipv4` or `ipv6` |
+    /// | `I5REQ_LOCAL_ADDRESS`               | Local IP address connections are sent from           |
+    /// | `I5REQ_ACCEPT_ENCODING`             | `Accept-Encoding` header, e.g. `gzip, deflate`       |
+    /// | `I5REQ_DECOMPRESS_RESPONSES`        | `false`/`0` to keep compressed responses as they are |
+    ///
+    /// Returns [`I5RequestError::ConfigError`] naming the variable if a required
+    /// variable is missing or a value cannot be parsed.
+    pub fn from_env() -> Result<I5Config, I5RequestError> {
+        Self::from_lookup(|name| env::var(name).ok())
+    }
+
+    /// Reads the configuration through `lookup`, which maps variable names to values.
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<I5Config, I5RequestError> {
+        let required = |name: &str| {
+            lookup(name)
+                .filter(|value| !value.trim().is_empty())
+                .ok_or_else(|| I5RequestError::ConfigError(format!("{} is not set", name)))
+        };
+
+        let port = match lookup("I5REQ_PORT") {
+            Some(port) => port.trim().parse().map_err(|_| {
+                I5RequestError::ConfigError(format!("I5REQ_PORT is not a valid port: {}", port))
+            })?,
+            None => DEFAULT_PORT,
+        };
+
+        let mut config = I5Config::new(
+            required("I5REQ_HOST")?,
+            port,
+            required("I5REQ_SCENARIO")?,
+            required("I5REQ_TENANT")?,
+        );
+
+        if let Some(version) = lookup("I5REQ_API_VERSION") {
+            config.api_version = version.parse().map_err(|_| {
+                I5RequestError::ConfigError(format!(
+                    "I5REQ_API_VERSION is not a valid API version: {}",
+                    version
+                ))
+            })?;
+        }
+        if let Some(format) = lookup("I5REQ_CONTENT_FORMAT") {
+            config.content_format = format.parse().map_err(|_| {
+                I5RequestError::ConfigError(format!(
+                    "I5REQ_CONTENT_FORMAT is not a valid content format: {}",
+                    format
+                ))
+            })?;
+        }
+        if let Some(url) = lookup("I5REQ_TOKEN_URL") {
+            let client = |name| {
+                lookup(name).ok_or_else(|| {
+                    I5RequestError::ConfigError(format!(
+                        "{} is not set but I5REQ_TOKEN_URL is",
+                        name
+                    ))
+                })
+            };
+            let endpoint = I5TokenEndpoint::new(
+                url,
+                client("I5REQ_CLIENT_ID")?,
+                client("I5REQ_CLIENT_SECRET")?,
+            );
+            config.token_endpoint = Some(match lookup("I5REQ_TOKEN_SCOPE") {
+                Some(scope) => endpoint.scope(scope),
+                None => endpoint,
+            });
+        }
+        if let Some(scheme) = lookup("I5REQ_AUTH_SCHEME") {
+            config.auth = Some(windows_auth(&scheme, None).ok_or_else(|| {
+                I5RequestError::ConfigError(format!(
+                    "I5REQ_AUTH_SCHEME must be negotiate or ntlm: {}",
+                    scheme
+                ))
+            })?);
+        } else if let Some(token) = lookup("I5REQ_TOKEN") {
+            config.auth = Some(I5Auth::bearer(token));
+        } else if let Some(key) = lookup("I5REQ_API_KEY") {
+            let header = lookup("I5REQ_API_KEY_HEADER");
+            config.auth = Some(I5Auth::api_key(
+                header.as_deref().unwrap_or(DEFAULT_API_KEY_HEADER),
+                key,
+            ));
+        } else if let Some(username) = lookup("I5REQ_USERNAME") {
+            config.auth = Some(Self::basic_auth_from_lookup(&lookup, username)?);
+        }
+
+        if let Some(value) = lookup("I5REQ_ACCEPT_INVALID_CERTS") {
+            config.accept_invalid_certs = parse_bool("I5REQ_ACCEPT_INVALID_CERTS", &value)?;
+        }
+        if let Some(encoding) = lookup("I5REQ_ACCEPT_ENCODING") {
+            config.accept_encoding = Some(encoding);
+        }
+        if let Some(value) = lookup("I5REQ_DECOMPRESS_RESPONSES") {
+            config.decompress_responses = parse_bool("I5REQ_DECOMPRESS_RESPONSES", &value)?;
+        }
+        if let Some(path) = lookup("I5REQ_CA_CERT") {
+            config.root_certificates.push(PathBuf::from(path));
+        }
+        if let Some(backend) = lookup("I5REQ_TLS_BACKEND") {
+            config.tls_backend = Some(backend.parse().map_err(|_| {
+                I5RequestError::ConfigError(format!(
+                    "I5REQ_TLS_BACKEND is not a valid TLS backend: {}",
+                    backend
+                ))
+            })?);
+        }
+        if let Some(pins) = lookup("I5REQ_PINNED_KEYS") {
+            config.pinned_keys = pins
+                .split(',')
+                .map(str::trim)
+                .filter(|pin| !pin.is_empty())
+                .map(String::from)
+                .collect();
+        }
+        if let Some(max) = lookup("I5REQ_POOL_MAX_IDLE_PER_HOST") {
+            config.pool_max_idle_per_host = Some(max.trim().parse().map_err(|_| {
+                I5RequestError::ConfigError(format!(
+                    "I5REQ_POOL_MAX_IDLE_PER_HOST is not a valid number: {}",
+                    max
+                ))
+            })?);
+        }
+        for (name, duration) in [
+            (
+                "I5REQ_POOL_IDLE_TIMEOUT_SECS",
+                &mut config.pool_idle_timeout,
+            ),
+            ("I5REQ_TCP_KEEPALIVE_SECS", &mut config.tcp_keepalive),
+            (
+                "I5REQ_TCP_KEEPALIVE_INTERVAL_SECS",
+                &mut config.tcp_keepalive_interval,
+            ),
+            ("I5REQ_DNS_TIMEOUT_SECS", &mut config.dns_timeout),
+            ("I5REQ_CONNECT_TIMEOUT_SECS", &mut config.connect_timeout),
+        ] {
+            if let Some(seconds) = lookup(name) {
+                *duration = Some(Duration::from_secs(seconds.trim().parse().map_err(
+                    |_| {
+                        I5RequestError::ConfigError(format!(
+                            "{} is not a valid number of seconds: {}",
+                            name, seconds
+                        ))
+                    },
+                )?));
+            }
+        }
+        if let Some(family) = lookup("I5REQ_IP_FAMILY") {
+            config.ip_family = family.parse().map_err(|_| {
+                I5RequestError::ConfigError(format!(
+                    "I5REQ_IP_FAMILY is not a valid IP family: {}",
+                    family
+                ))
+            })?;
+        }
+        if let Some(address) = lookup("I5REQ_LOCAL_ADDRESS") {
+            config.local_address = Some(address.trim().parse().map_err(|_| {
+                I5RequestError::ConfigError(format!(
+                    "I5REQ_LOCAL_ADDRESS is not a valid IP address: {}",
+                    address
+                ))
+            })?);
+        }
+        for (name, bound) in [
+            ("I5REQ_MIN_TLS_VERSION", &mut config.min_tls_version),
+            ("I5REQ_MAX_TLS_VERSION", &mut config.max_tls_version),
+        ] {
+            if let Some(version) = lookup(name) {
+                *bound = Some(version.parse().map_err(|_| {
+                    I5RequestError::ConfigError(format!(
+                        "{} is not a valid TLS version: {}",
+                        name, version
+                    ))
+                })?);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Reads the configuration from a TOML (`.toml`), JSON (`.json`) or YAML (`.yaml`,
+    /// `.yml`) file.
+    ///
+    /// The file uses the same settings as [`I5Config::from_env`], shown here in TOML:
+    ///
+    /// ```toml
+    /// host = "i5.company.local"
+    /// port = 43001                  # optional, defaults to DEFAULT_PORT
+    /// scenario = "Invoices"
+    /// tenant = "Default"
+    /// api_version = "v1"            # optional, or "v2"
+    /// content_format = "json"       # optional, or "xml"
+    /// accept_invalid_certs = false  # optional
+    /// ca_certs = ["/etc/ssl/company-ca.pem"]
+    /// tls_backend = "rustls"        # optional, see I5Config::tls_backend
+    /// min_tls_version = "1.2"       # optional, see I5Config::min_tls_version
+    /// max_tls_version = "1.3"       # optional
+    /// pinned_keys = ["sha256/..."]  # optional, see I5Config::pin_public_key
+    /// pool_max_idle_per_host = 4    # optional, see I5Config::pool_max_idle_per_host
+    /// pool_idle_timeout_secs = 50   # optional
+    /// tcp_keepalive_secs = 30       # optional, see I5Config::tcp_keepalive
+    /// tcp_keepalive_interval_secs = 10 # optional
+    /// dns_timeout_secs = 2          # optional, see I5Config::dns_timeout
+    /// connect_timeout_secs = 5      # optional, see I5Config::connect_timeout
+    /// ip_family = "prefer-ipv4"     # optional, see I5Config::ip_family
+    /// local_address = "10.20.0.7"   # optional, see I5Config::local_address
+    /// accept_encoding = "gzip"      # optional, see I5Config::accept_encoding
+    /// decompress_responses = true   # optional
+    /// bool_format = "X/"            # optional, see I5Config::bool_format
+    ///
+    /// [resolve]                     # optional, see I5Config::resolve
+    /// "i5.company.local" = "10.20.0.15"
+    ///
+    /// [query]                       # optional, see I5RequestUrl::query
+    /// async = true
+    ///
+    /// [fields]                      # optional, see I5Config::default_field
+    /// SOURCE = "ScannerA"
+    ///
+    /// [field_mapping]               # optional, see I5FieldMapping
+    /// KUNNR = "CustomerNo"
+    ///
+    /// [transforms]                  # optional, see I5Transforms
+    /// "*IBAN*" = ["trim", "uppercase"]
+    /// CostCenter = [{ left_pad = { width = 6, fill = "0" } }]
+    ///
+    /// [sanitize]                    # optional, see I5Sanitizer
+    /// max_length = 255
+    ///
+    /// [[rules]]                     # optional, see I5FieldRules
+    /// field = "Discount*"
+    /// omit_if_empty = true
+    ///
+    /// [[rules]]
+    /// field = "VatId"
+    /// when = { field = "Country", matches = "DE" }
+    ///
+    /// [batch_name]                  # optional, see I5Config::batch_namer
+    /// strategy = "timestamp"        # or "uuid", or "sequence" with prefix, width and
+    ///                               # an optional counter file
+    /// pattern = "SCAN-%Y%m%d-%H%M%S-%f"
+    ///
+    /// [auth]                        # optional
+    /// username = "svc-scanner"
+    /// password = "hunter2"          # or keyring_service = "i5" (`keyring` feature)
+    /// # token = "..."               # bearer token instead of username and password
+    /// # token_url = "https://..."  # OAuth 2.0 token endpoint, with client_id,
+    /// # client_id = "..."           # client_secret and an optional scope
+    /// # client_secret = "..."
+    /// # api_key = "..."             # API key instead of username and password,
+    /// # api_key_header = "X-API-Key" # sent in this header (the default)
+    /// # scheme = "negotiate"        # or "ntlm": the Windows logon (`sspi` feature),
+    /// # service_principal = "..."   # with an optional SPN instead of HTTP/<host>
+    /// ```
+    ///
+    /// Named profiles such as `[profiles.prod]` override these settings and are
+    /// selected with [`I5Config::profile`]. If every profile sets `host`, the
+    /// top-level settings may leave it out; the configuration read from the file then
+    /// only serves to select a profile, and clients created from it are rejected with
+    /// [`I5RequestError::ConfigError`].
+    ///
+    /// YAML files set the same keys with block mappings and sequences; plain scalars
+    /// such as `43001` or `true` are read as numbers and booleans, quoted ones as
+    /// strings. Flow mappings such as `{ width: 6 }` are not supported in YAML.
+    ///
+    /// Relative `ca_certs` paths are resolved against the directory of the file.
+    /// Returns [`I5RequestError::ConfigError`] naming the file, and the line and column
+    /// for syntax errors, if the file cannot be read or parsed, or if a TOML file
+    /// defines a table twice.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<I5Config, I5RequestError> {
+        let path = path.as_ref();
+        let error = |message: String| {
+            I5RequestError::ConfigError(format!("{}: {}", path.display(), message))
+        };
+
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let parse: fn(&str) -> Result<Value, String> = match extension.as_deref() {
+            Some("toml") => |text| toml::parse(text).map_err(|e| e.to_string()),
+            Some("json") => |text| serde_json::from_str(text).map_err(|e| e.to_string()),
+            Some("yaml" | "yml") => |text| yaml::parse_typed(text).map_err(|e| e.to_string()),
+            _ => {
+                return Err(error(String::from(
+                    "unknown configuration format, expected a .toml, .json, .yaml or .yml file",
+                )));
+            }
+        };
+
+        let text = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+        let value = parse(&text).map_err(error)?;
+
+        ConfigSource::new(value, path.parent().map(Path::to_path_buf))
+            .and_then(|source| Arc::new(source).config(None))
+            .map_err(error)
+    }
+
+    /// Parses a configuration in the TOML format described in [`I5Config::from_file`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_config::I5Config;
+    ///
+    /// let config = I5Config::from_toml_str(
+    ///     r#"
+    ///     host = "i5.company.local"
+    ///     scenario = "Invoices"
+    ///     tenant = "Default"
+    ///
+    ///     [auth]
+    ///     token = "secret"
+    ///     "#,
+    /// )?;
+    /// assert_eq!(config.url().port(), 43001);
+    /// assert!(config.credentials().is_some());
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn from_toml_str(text: &str) -> Result<I5Config, I5RequestError> {
+        toml::parse(text)
+            .map_err(|e| e.to_string())
+            .and_then(|value| ConfigSource::new(value, None))
+            .and_then(|source| Arc::new(source).config(None))
+            .map_err(I5RequestError::ConfigError)
+    }
+
+    /// Selects the named profile of the configuration file this configuration was
+    /// read from.
+    ///
+    /// Profiles are tables under `profiles` whose settings override the top-level
+    /// settings. Tables such as `[profiles.prod.fields]` are merged key by key with the
+    /// top-level tables, other values replace them. The `auth` and `batch_name` tables
+    /// describe a single choice, e.g. a bearer token or a username and password, so
+    /// they replace the top-level table as a whole. Selecting a profile always starts
+    /// from the top-level settings, so profiles do not stack.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] if the profile does not exist or the
+    /// configuration was not read from a file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_config::I5Config;
+    ///
+    /// let config = I5Config::from_toml_str(
+    ///     r#"
+    ///     host = "i5-dev.company.local"
+    ///     scenario = "Invoices"
+    ///     tenant = "Default"
+    ///
+    ///     [profiles.prod]
+    ///     host = "i5.company.local"
+    ///     auth = { username = "svc-scanner", password = "hunter2" }
+    ///     "#,
+    /// )?;
+    /// let prod = config.profile("prod")?;
+    /// assert_eq!(prod.url().hostname(), "i5.company.local");
+    /// assert_eq!(prod.url().scenario(), "Invoices");
+    /// assert_eq!(prod.profile_name(), Some("prod"));
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn profile(&self, name: &str) -> Result<I5Config, I5RequestError> {
+        let source = self.source.as_ref().ok_or_else(|| {
+            I5RequestError::ConfigError(String::from(
+                "profiles are only available for configurations read from a file",
+            ))
+        })?;
+        source
+            .config(Some(name))
+            .map_err(I5RequestError::ConfigError)
+    }
+
+    /// The names of the profiles defined in the configuration file, in file order.
+    pub fn profile_names(&self) -> Vec<&str> {
+        self.source
+            .as_ref()
+            .map(|source| source.profiles.keys().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// The name of the selected profile, if any.
+    pub fn profile_name(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    fn basic_auth_from_lookup(
+        lookup: &impl Fn(&str) -> Option<String>,
+        username: String,
+    ) -> Result<I5Auth, I5RequestError> {
+        #[cfg(feature = "keyring")]
+        if let Some(service) = lookup("I5REQ_KEYRING_SERVICE") {
+            return I5Auth::basic_from_keyring(&service, &username);
+        }
+
+        let password = lookup("I5REQ_PASSWORD").ok_or_else(|| {
+            I5RequestError::ConfigError(String::from(
+                "I5REQ_PASSWORD is not set but I5REQ_USERNAME is",
+            ))
+        })?;
+        Ok(I5Auth::basic(username, password))
+    }
+
+    /// Sets the credentials used for every submission.
+    pub fn auth(mut self, auth: I5Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Authenticates submissions and lookups with bearer tokens obtained from
+    /// `endpoint`, refreshed as they expire or are rejected, see [`I5TokenEndpoint`].
+    /// The endpoint takes precedence over the [credentials](I5Config::auth).
+    pub fn token_endpoint(mut self, endpoint: I5TokenEndpoint) -> Self {
+        self.token_endpoint = Some(endpoint);
+        self
+    }
+
+    /// Accepts invalid or self-signed server certificates.
+    pub fn accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// Connects with the TLS implementation `backend` instead of the default one, see
+    /// [`I5TlsBackend`]. Creating a client fails with [`I5RequestError::ConfigError`]
+    /// if the backend is not compiled in.
+    ///
+    /// Selecting rustls installs its `ring` crypto provider as the process-wide
+    /// default, unless another provider is installed already.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{i5_config::I5Config, i5_tls_backend::I5TlsBackend};
+    ///
+    /// let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default")
+    ///     .tls_backend(I5TlsBackend::Rustls);
+    /// assert_eq!(config.effective_tls_backend(), I5TlsBackend::Rustls);
+    /// ```
+    pub fn tls_backend(mut self, backend: I5TlsBackend) -> Self {
+        self.tls_backend = Some(backend);
+        self
+    }
+
+    /// Trusts the CA certificates in the PEM file at `path` in addition to the system
+    /// trust store.
+    pub fn add_root_certificate(mut self, path: impl Into<PathBuf>) -> Self {
+        self.root_certificates.push(path.into());
+        self
+    }
+
+    /// Refuses connections negotiating a TLS version below `version`, e.g. to enforce
+    /// a TLS 1.2 baseline. Applies to the async and the blocking client.
+    ///
+    /// The native [TLS backend](I5Config::tls_backend) uses the TLS library of the
+    /// platform (OpenSSL, SChannel or Secure Transport), which cannot require TLS 1.3
+    /// as the minimum; creating a client with that minimum fails with
+    /// [`I5RequestError::ConfigError`] unless rustls is used. Cipher suites are not
+    /// configured per client: the native backend offers the suites allowed by the
+    /// system-wide policy of the library, e.g. `CipherString` in `openssl.cnf` or the
+    /// SChannel cipher suite order set by group policy, so a cipher baseline is
+    /// enforced there.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{i5_config::I5Config, i5_tls_version::I5TlsVersion};
+    ///
+    /// let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default")
+    ///     .min_tls_version(I5TlsVersion::Tls1_2);
+    /// assert_eq!(config.tls_versions(), (Some(I5TlsVersion::Tls1_2), None));
+    /// ```
+    pub fn min_tls_version(mut self, version: I5TlsVersion) -> Self {
+        self.min_tls_version = Some(version);
+        self
+    }
+
+    /// Accepts only servers whose certificate carries the public key with the SHA-256
+    /// hash `pin`, or is issued for the host under a certificate in the chain sent by
+    /// the server that carries it. Can be called repeatedly, e.g. to add the key of
+    /// the next certificate before a renewal.
+    ///
+    /// The pin is the SHA-256 hash of the DER-encoded `SubjectPublicKeyInfo`, written
+    /// as `sha256/` followed by base64, as plain base64 or as hexadecimal. It is
+    /// printed for the certificate in `cert.pem` by
+    ///
+    /// ```text
+    /// openssl x509 -in cert.pem -pubkey -noout | openssl pkey -pubin -outform der \
+    ///     | openssl dgst -sha256 -binary | base64
+    /// ```
+    ///
+    /// With pins, the system trust store, the configured
+    /// [root certificates](I5Config::add_root_certificate) and
+    /// [`I5Config::accept_invalid_certs`] are ignored, so a rogue CA in the trust store
+    /// cannot vouch for another server. Pinning requires the `rustls` feature and TLS
+    /// 1.2 or 1.3; creating a client fails with [`I5RequestError::ConfigError`]
+    /// without the feature or if a pin is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_config::I5Config;
+    ///
+    /// let config = I5Config::new("scanner-gw.local", 43001, "Invoices", "Default")
+    ///     .pin_public_key("sha256/47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=")
+    ///     .pin_public_key("sha256/9JAhk0r3jE1B3NHJ7wqfEyfA2l0IgbHk2tKosWxzd7A=");
+    /// assert_eq!(config.pinned_public_keys().len(), 2);
+    /// ```
+    pub fn pin_public_key(mut self, pin: impl Into<String>) -> Self {
+        self.pinned_keys.push(pin.into());
+        self
+    }
+
+    /// Refuses connections negotiating a TLS version above `version`. Creating a
+    /// client fails if it is below the [minimum](I5Config::min_tls_version).
+    pub fn max_tls_version(mut self, version: I5TlsVersion) -> Self {
+        self.max_tls_version = Some(version);
+        self
+    }
+
+    /// Connects to `ip` whenever a submission is sent to `hostname`, without asking
+    /// the system DNS, e.g. to point a client at a disaster recovery node.
+    ///
+    /// The port is always taken from the URL, and TLS certificates are still verified
+    /// against `hostname`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_config::I5Config;
+    ///
+    /// let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default")
+    ///     .resolve("i5.company.local", [10, 20, 0, 15].into());
+    /// assert_eq!(config.resolve_overrides().len(), 1);
+    /// ```
+    pub fn resolve(mut self, hostname: impl Into<String>, ip: IpAddr) -> Self {
+        self.resolve.insert(hostname.into(), ip);
+        self
+    }
+
+    /// Keeps at most `max` idle connections per host for reuse. Applies to the async
+    /// and the blocking client; by default, the number is not limited.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Closes connections that were idle for `timeout`, by default 90 seconds. Set it
+    /// below the idle timeout of firewalls and load balancers on the way, so that a
+    /// submission never picks a connection they already dropped.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Sends TCP keepalive probes on connections that were idle for `time`, which keeps
+    /// the state of firewalls on the way alive and detects connections they dropped.
+    /// By default, no probes are sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_config::I5Config;
+    /// use std::time::Duration;
+    ///
+    /// let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default")
+    ///     .pool_idle_timeout(Duration::from_secs(240))
+    ///     .tcp_keepalive(Duration::from_secs(60))
+    ///     .tcp_keepalive_interval(Duration::from_secs(15));
+    /// assert_eq!(
+    ///     config.tcp_keepalive_times(),
+    ///     (Some(Duration::from_secs(60)), Some(Duration::from_secs(15)))
+    /// );
+    /// ```
+    pub fn tcp_keepalive(mut self, time: Duration) -> Self {
+        self.tcp_keepalive = Some(time);
+        self
+    }
+
+    /// Repeats unanswered TCP keepalive probes after `interval` instead of the system
+    /// default, see [`I5Config::tcp_keepalive`].
+    pub fn tcp_keepalive_interval(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive_interval = Some(interval);
+        self
+    }
+
+    /// Gives up the lookup of a hostname after `timeout` instead of waiting for the
+    /// system resolver, which may retry unreachable DNS servers for half a minute.
+    /// Hostnames set with [`I5Config::resolve`] are not looked up.
+    pub fn dns_timeout(mut self, timeout: Duration) -> Self {
+        self.dns_timeout = Some(timeout);
+        self
+    }
+
+    /// Gives up establishing a connection after `timeout`, from the first TCP connect
+    /// attempt to the end of the TLS handshake. Each resolved address gets an equal
+    /// share of `timeout` for its TCP connect, so an unreachable address does not use
+    /// up the time of the next one. By default, only the operating system limits a
+    /// connect attempt.
+    ///
+    /// `reqwest` bounds the TCP connect and the TLS handshake together, so there is no
+    /// separate handshake timeout.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{i5_config::I5Config, i5_ip_family::I5IpFamily};
+    /// use std::time::Duration;
+    ///
+    /// let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default")
+    ///     .dns_timeout(Duration::from_secs(2))
+    ///     .connect_timeout(Duration::from_secs(5))
+    ///     .ip_family(I5IpFamily::PreferIpv4);
+    /// assert_eq!(
+    ///     config.connect_timeouts(),
+    ///     (Some(Duration::from_secs(2)), Some(Duration::from_secs(5)))
+    /// );
+    /// assert_eq!(config.ip_family_policy(), I5IpFamily::PreferIpv4);
+    /// ```
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Connects to the addresses of `family` first or only, e.g. to stop trying IPv6
+    /// first on sites where the IPv6 route to Interface5 is black-holed. See
+    /// [`I5IpFamily`] for how the families are tried.
+    pub fn ip_family(mut self, family: I5IpFamily) -> Self {
+        self.ip_family = family;
+        self
+    }
+
+    /// Sends connections from the local IP address `address`, e.g. so that the traffic
+    /// of a multi-homed server leaves through the interface the firewall rules for
+    /// Interface5 expect. By default, the operating system picks the address from the
+    /// routing table.
+    ///
+    /// The address must belong to the host and match the family of the server
+    /// address; combine it with [`I5Config::ip_family`] on dual-stack sites.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_config::I5Config;
+    ///
+    /// let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default")
+    ///     .local_address([10, 20, 0, 7].into());
+    /// assert_eq!(config.bind_address(), Some([10, 20, 0, 7].into()));
+    /// ```
+    pub fn local_address(mut self, address: IpAddr) -> Self {
+        self.local_address = Some(address);
+        self
+    }
+
+    /// Sends `Accept-Encoding: value` with every request, e.g. `gzip, deflate` to ask
+    /// a gateway to compress its replies, or `identity` to ask it not to. By default,
+    /// no `Accept-Encoding` header is sent.
+    ///
+    /// Responses compressed with `gzip` or `deflate` are decoded before they are
+    /// parsed, whether or not they were asked for, unless
+    /// [decompression](I5Config::decompress_responses) is turned off. Bodies in other
+    /// encodings, e.g. `br`, are kept as they are.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_config::I5Config;
+    ///
+    /// let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default")
+    ///     .accept_encoding("gzip, deflate");
+    /// assert_eq!(config.accepted_encoding(), Some("gzip, deflate"));
+    /// assert!(config.decompresses_responses());
+    /// ```
+    pub fn accept_encoding(mut self, value: impl Into<String>) -> Self {
+        self.accept_encoding = Some(value.into());
+        self
+    }
+
+    /// Whether responses compressed with `gzip` or `deflate` are decoded, by default
+    /// `true`. Without decoding, the receipts and
+    /// [raw responses](crate::request::options::I5SendOptions::keep_raw_response)
+    /// carry the compressed body.
+    pub fn decompress_responses(mut self, decompress: bool) -> Self {
+        self.decompress_responses = decompress;
+        self
+    }
+
+    /// Adds a header field that is injected into every document sent through an
+    /// [`I5Client`](crate::request::client::I5Client) with this configuration.
+    ///
+    /// Documents that already have a header field with the same name keep their
+    /// value. The value may contain [template placeholders] such as
+    /// `{{now:%Y-%m-%d}}` or `{{filename}}`, which are resolved for each document. See
+    /// also [`I5Request::add_default_header_field`].
+    ///
+    /// [template placeholders]: crate::types::i5_template::I5TemplateContext
+    /// [`I5Request::add_default_header_field`]: crate::types::i5_request::I5Request::add_default_header_field
+    pub fn default_field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        let value = value.into();
+        match self
+            .default_fields
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            Some(field) => field.1 = value,
+            None => self.default_fields.push((name, value)),
+        }
+        self
+    }
+
+    /// Sets the mapping from source field names to Interface5 field names applied to
+    /// every document sent through an [`I5Client`](crate::request::client::I5Client)
+    /// with this configuration.
+    pub fn field_mapping(mut self, mapping: I5FieldMapping) -> Self {
+        self.field_mapping = mapping;
+        self
+    }
+
+    /// Sets the transformations applied to the field values of every document sent
+    /// through an [`I5Client`](crate::request::client::I5Client) with this
+    /// configuration, after the default fields were added.
+    pub fn transforms(mut self, transforms: I5Transforms) -> Self {
+        self.transforms = transforms;
+        self
+    }
+
+    /// Sanitizes the field values of every document sent through an
+    /// [`I5Client`](crate::request::client::I5Client) with this configuration, after
+    /// the transformations. Truncated values are logged as warnings.
+    pub fn sanitizer(mut self, sanitizer: I5Sanitizer) -> Self {
+        self.sanitizer = Some(sanitizer);
+        self
+    }
+
+    /// Sets the rules deciding which fields are included in every document sent
+    /// through an [`I5Client`](crate::request::client::I5Client) with this
+    /// configuration, evaluated after the transformations.
+    pub fn field_rules(mut self, rules: I5FieldRules) -> Self {
+        self.field_rules = rules;
+        self
+    }
+
+    /// Sets how checkboxes added with
+    /// [`Document::add_bool_field`](crate::types::i5_request::Document::add_bool_field)
+    /// are written for the scenario, by default `true` and `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{
+    ///     i5_bool_format::I5BoolFormat, i5_config::I5Config, i5_request::I5Request,
+    /// };
+    ///
+    /// let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default")
+    ///     .bool_format(I5BoolFormat::one_zero());
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document.add_bool_field("Paid", true, config.checkbox_format());
+    /// assert_eq!(document.fields()[0].value(), "1");
+    /// ```
+    pub fn bool_format(mut self, format: I5BoolFormat) -> Self {
+        self.bool_format = format;
+        self
+    }
+
+    /// Sets the strategy naming the requests created with
+    /// [`I5Client::new_request`](crate::request::client::I5Client::new_request).
+    pub fn batch_namer(mut self, namer: impl I5BatchNamer + 'static) -> Self {
+        self.batch_namer = Some(Arc::new(namer));
+        self
+    }
+
+    /// How long [field definitions](crate::request::client::I5Client::get_field_definitions)
+    /// fetched from the server are reused before they are fetched again. Defaults to
+    /// five minutes.
+    pub fn field_definitions_ttl(mut self, ttl: Duration) -> Self {
+        self.field_definitions_ttl = ttl;
+        self
+    }
+
+    /// The largest request in bytes the server accepts in a single submission.
+    /// [`I5Client::send_large`](crate::request::client::I5Client::send_large) uploads
+    /// larger requests in an [upload session](I5Config::upload_sessions) if enabled.
+    /// Defaults to [`DEFAULT_MAX_REQUEST_SIZE`].
+    ///
+    /// Once set, [`I5Client::send`](crate::request::client::I5Client::send) rejects
+    /// larger requests with [`I5RequestError::PayloadTooLarge`] before anything is
+    /// uploaded, and [`I5Client::validate`](crate::request::client::I5Client::validate)
+    /// rejects or [splits](I5Config::split_oversized_requests) them.
+    pub fn max_request_size(mut self, bytes: u64) -> Self {
+        self.max_request_size = Some(bytes);
+        self
+    }
+
+    /// Whether [`I5Client::validate`](crate::request::client::I5Client::validate)
+    /// splits requests above the [maximum size](I5Config::max_request_size) by
+    /// documents instead of rejecting them, see [`I5Request::split_by_size`].
+    /// Defaults to `false`.
+    ///
+    /// [`I5Request::split_by_size`]: crate::types::i5_request::I5Request::split_by_size
+    pub fn split_oversized_requests(mut self, split: bool) -> Self {
+        self.split_oversized = split;
+        self
+    }
+
+    /// Whether [`I5Client::send_large`](crate::request::client::I5Client::send_large)
+    /// sends requests above the [maximum size](I5Config::max_request_size) in an upload
+    /// session. Defaults to `false`, so that they are rejected with
+    /// [`I5RequestError::PayloadTooLarge`].
+    ///
+    /// Upload sessions are not part of the documented Interface5 API. They are an
+    /// extension assumed to be provided by a gateway or a customized server, so only
+    /// enable them for one that follows this protocol:
+    ///
+    /// - `POST …/{tenant}/{scenario}/UploadSessions` opens a session and answers with
+    ///   its `SessionId`.
+    /// - `PUT …/UploadSessions/{id}/Documents/{document}/Files/{file}` takes the content
+    ///   of a file in chunks with a `Content-Range` header, documents and files
+    ///   counted from zero.
+    /// - `POST …/UploadSessions/{id}/Batches` takes the batch with the `Data` of the
+    ///   uploaded files left empty.
+    /// - `DELETE …/UploadSessions/{id}` discards a session that failed.
+    pub fn upload_sessions(mut self, enabled: bool) -> Self {
+        self.upload_sessions = enabled;
+        self
+    }
+
+    /// The size in bytes of the attachment chunks uploaded in an upload session, at
+    /// least one byte. Defaults to [`DEFAULT_UPLOAD_CHUNK_SIZE`].
+    pub fn upload_chunk_size(mut self, bytes: u64) -> Self {
+        self.upload_chunk_size = bytes.max(1);
+        self
+    }
+
+    /// The most bytes, by [estimated size](crate::types::i5_request::I5Request::estimated_encoded_size),
+    /// that [`I5Client::send_concurrent`] and [`I5Client::send_batch_set`] keep in
+    /// flight across all their calls on a client. Unlimited by default.
+    ///
+    /// A submission waits until the requests in flight leave room for it, so that
+    /// sending many large requests cannot exhaust the memory of the host. The size of
+    /// a request is estimated as it is sent, after the configured field steps ran. A
+    /// request larger than the budget is sent once nothing else is in flight. The
+    /// budget is tracked in KiB.
+    ///
+    /// Only these two methods take from the budget. [`I5Client::send`],
+    /// [`I5Client::send_large`], the
+    /// [blocking client](crate::request::blocking::BlockingI5Client) and the
+    /// [pipeline](crate::request::pipeline) send without waiting for it, so limit
+    /// their concurrency by other means.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_config::I5Config;
+    ///
+    /// let config = I5Config::new("i5.example.com", 43001, "Scan", "Default")
+    ///     .max_in_flight_bytes(256 << 20);
+    /// assert_eq!(config.in_flight_limit(), Some(256 << 20));
+    /// ```
+    ///
+    /// [`I5Client::send_concurrent`]: crate::request::client::I5Client::send_concurrent
+    /// [`I5Client::send_batch_set`]: crate::request::client::I5Client::send_batch_set
+    /// [`I5Client::send`]: crate::request::client::I5Client::send
+    /// [`I5Client::send_large`]: crate::request::client::I5Client::send_large
+    pub fn max_in_flight_bytes(mut self, bytes: u64) -> Self {
+        self.max_in_flight_bytes = Some(bytes);
+        self
+    }
+
+    /// Retries submissions that failed transiently as described by `policy`.
+    pub fn retry(mut self, policy: I5RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Targets `version` of the Interface5 API, see [`I5RequestUrl::version`].
+    pub fn api_version(mut self, version: I5ApiVersion) -> Self {
+        self.api_version = version;
+        self
+    }
+
+    /// Sends request bodies in `format`, e.g. XML for older Interface5 web inputs.
+    /// Defaults to JSON.
+    pub fn content_format(mut self, format: I5ContentFormat) -> Self {
+        self.content_format = format;
+        self
+    }
+
+    /// Appends a query parameter to the URL of every submission, see
+    /// [`I5RequestUrl::query`].
+    pub fn query_param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((name.into(), value.into()));
+        self
+    }
+
+    /// The target URL of the configured endpoint.
+    pub fn url(&self) -> I5RequestUrl {
+        let url = I5RequestUrl::new(
+            self.hostname.clone(),
+            self.port,
+            self.scenario.clone(),
+            self.tenant.clone(),
+        )
+        .version(self.api_version);
+        self.query
+            .iter()
+            .fold(url, |url, (name, value)| url.query(name, value))
+    }
+
+    /// The version of the Interface5 API the requests are sent to.
+    pub fn target_api_version(&self) -> I5ApiVersion {
+        self.api_version
+    }
+
+    /// The format of request bodies.
+    pub fn payload_format(&self) -> I5ContentFormat {
+        self.content_format
+    }
+
+    /// The configured credentials.
+    pub fn credentials(&self) -> Option<&I5Auth> {
+        self.auth.as_ref()
+    }
+
+    /// The configured token endpoint, see [`I5Config::token_endpoint`].
+    pub fn token_source(&self) -> Option<&I5TokenEndpoint> {
+        self.token_endpoint.as_ref()
+    }
+
+    /// Whether invalid server certificates are accepted.
+    pub fn accepts_invalid_certs(&self) -> bool {
+        self.accept_invalid_certs
+    }
+
+    /// The TLS backend clients connect with: the [configured](I5Config::tls_backend)
+    /// one, or the [default](I5TlsBackend::compiled_default).
+    pub fn effective_tls_backend(&self) -> I5TlsBackend {
+        self.tls_backend
+            .unwrap_or_else(I5TlsBackend::compiled_default)
+    }
+
+    /// The lowest and the highest accepted TLS version, if restricted.
+    pub fn tls_versions(&self) -> (Option<I5TlsVersion>, Option<I5TlsVersion>) {
+        (self.min_tls_version, self.max_tls_version)
+    }
+
+    /// The public key pins, see [`I5Config::pin_public_key`].
+    pub fn pinned_public_keys(&self) -> &[String] {
+        &self.pinned_keys
+    }
+
+    /// The PEM files with additional trusted CA certificates.
+    pub fn root_certificates(&self) -> &[PathBuf] {
+        &self.root_certificates
+    }
+
+    /// The IP addresses that hostnames resolve to instead of asking the system DNS.
+    pub fn resolve_overrides(&self) -> &BTreeMap<String, IpAddr> {
+        &self.resolve
+    }
+
+    /// The maximum number of idle connections per host and their idle timeout, if
+    /// configured.
+    pub fn idle_pool_limits(&self) -> (Option<usize>, Option<Duration>) {
+        (self.pool_max_idle_per_host, self.pool_idle_timeout)
+    }
+
+    /// The idle time before the first TCP keepalive probe and the interval between
+    /// probes, if configured.
+    pub fn tcp_keepalive_times(&self) -> (Option<Duration>, Option<Duration>) {
+        (self.tcp_keepalive, self.tcp_keepalive_interval)
+    }
+
+    /// The timeout of hostname lookups and of establishing a connection, if
+    /// configured.
+    pub fn connect_timeouts(&self) -> (Option<Duration>, Option<Duration>) {
+        (self.dns_timeout, self.connect_timeout)
+    }
+
+    /// The IP family connections prefer or are restricted to.
+    pub fn ip_family_policy(&self) -> I5IpFamily {
+        self.ip_family
+    }
+
+    /// The local IP address connections are sent from, if configured.
+    pub fn bind_address(&self) -> Option<IpAddr> {
+        self.local_address
+    }
+
+    /// The `Accept-Encoding` header sent with every request, if configured.
+    pub fn accepted_encoding(&self) -> Option<&str> {
+        self.accept_encoding.as_deref()
+    }
+
+    /// Whether compressed responses are decoded.
+    pub fn decompresses_responses(&self) -> bool {
+        self.decompress_responses
+    }
+
+    /// The header fields injected into every document, as name and value.
+    pub fn default_fields(&self) -> &[(String, String)] {
+        &self.default_fields
+    }
+
+    /// The mapping from source field names to Interface5 field names.
+    pub fn field_mappings(&self) -> &I5FieldMapping {
+        &self.field_mapping
+    }
+
+    /// The transformations applied to field values.
+    pub fn field_transforms(&self) -> &I5Transforms {
+        &self.transforms
+    }
+
+    /// How checkboxes are written.
+    pub fn checkbox_format(&self) -> &I5BoolFormat {
+        &self.bool_format
+    }
+
+    /// The sanitizer applied to field values, if any.
+    pub fn value_sanitizer(&self) -> Option<&I5Sanitizer> {
+        self.sanitizer.as_ref()
+    }
+
+    /// Generates the next batch name with the configured batch namer, if any.
+    pub fn next_batch_name(&self) -> Result<Option<String>, I5RequestError> {
+        self.batch_namer
+            .as_ref()
+            .map(|namer| namer.next_name())
+            .transpose()
+    }
+
+    /// The rules deciding which fields are included.
+    pub fn field_inclusion_rules(&self) -> &I5FieldRules {
+        &self.field_rules
+    }
+
+    /// How long fetched field definitions are reused.
+    pub fn field_definitions_max_age(&self) -> Duration {
+        self.field_definitions_ttl
+    }
+
+    /// The largest request sent in a single submission, if configured.
+    pub fn request_size_limit(&self) -> Option<u64> {
+        self.max_request_size
+    }
+
+    /// Whether oversized requests are split when validated by the client.
+    pub fn splits_oversized_requests(&self) -> bool {
+        self.split_oversized
+    }
+
+    /// Whether large requests are sent in upload sessions.
+    pub fn uses_upload_sessions(&self) -> bool {
+        self.upload_sessions
+    }
+
+    /// The most bytes concurrent submissions keep in flight, if configured.
+    pub fn in_flight_limit(&self) -> Option<u64> {
+        self.max_in_flight_bytes
+    }
+
+    /// The size of the attachment chunks uploaded in an upload session.
+    pub fn upload_chunk_bytes(&self) -> u64 {
+        self.upload_chunk_size
+    }
+
+    /// The policy for retrying failed submissions, if any.
+    pub fn retry_policy(&self) -> Option<&I5RetryPolicy> {
+        self.retry.as_ref()
+    }
+}
+
+/// The parsed settings of a configuration file, kept to select profiles.
+struct ConfigSource {
+    base: Value,
+    profiles: Map<String, Value>,
+    directory: Option<PathBuf>,
+}
+
+impl ConfigSource {
+    fn new(value: Value, directory: Option<PathBuf>) -> Result<ConfigSource, String> {
+        let Value::Object(mut base) = value else {
+            return Err(String::from("the configuration must be a table"));
+        };
+        let profiles = match base.remove("profiles") {
+            None => Map::new(),
+            Some(Value::Object(profiles)) => profiles,
+            Some(_) => return Err(String::from("profiles must be a table")),
+        };
+        for (name, profile) in &profiles {
+            match profile {
+                Value::Object(settings) if settings.contains_key("profiles") => {
+                    return Err(format!("profile '{}': profiles cannot be nested", name));
+                }
+                Value::Object(_) => {}
+                _ => return Err(format!("profile '{}' must be a table", name)),
+            }
+        }
+
+        Ok(ConfigSource {
+            base: Value::Object(base),
+            profiles,
+            directory,
+        })
+    }
+
+    /// Builds the configuration of the top-level settings or the named profile.
+    ///
+    /// Top-level settings without a host, in a file with profiles, yield a
+    /// configuration that only selects profiles.
+    fn config(self: &Arc<Self>, profile: Option<&str>) -> Result<I5Config, String> {
+        let settings = match profile {
+            None if !self.profiles.is_empty() && self.base.get("host").is_none() => {
+                let mut config = I5Config::new("", DEFAULT_PORT, "", "");
+                config.source = Some(Arc::clone(self));
+                return Ok(config);
+            }
+            None => self.base.clone(),
+            Some(name) => {
+                let overrides = self.profiles.get(name).ok_or_else(|| {
+                    let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                    format!(
+                        "unknown profile '{}', available profiles: {}",
+                        name,
+                        if available.is_empty() {
+                            String::from("none")
+                        } else {
+                            available.join(", ")
+                        }
+                    )
+                })?;
+                let mut settings = self.base.clone();
+                merge(&mut settings, overrides, REPLACED_TABLES);
+                settings
+            }
+        };
+
+        let file: ConfigFile = serde_json::from_value(settings).map_err(|e| match profile {
+            Some(name) => format!("profile '{}': {}", name, e),
+            None => e.to_string(),
+        })?;
+        let mut config = file.into_config(self.directory.as_deref())?;
+        config.profile = profile.map(String::from);
+        config.source = Some(Arc::clone(self));
+        Ok(config)
+    }
+}
+
+impl fmt::Debug for I5Config {
+    // Query parameter values may carry API keys, so only their names are shown.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("I5Config")
+            .field("hostname", &self.hostname)
+            .field("port", &self.port)
+            .field("scenario", &self.scenario)
+            .field("tenant", &self.tenant)
+            .field("api_version", &self.api_version)
+            .field("content_format", &self.content_format)
+            .field("auth", &self.auth)
+            .field("token_endpoint", &self.token_endpoint)
+            .field("accept_invalid_certs", &self.accept_invalid_certs)
+            .field("root_certificates", &self.root_certificates)
+            .field("tls_backend", &self.tls_backend)
+            .field("min_tls_version", &self.min_tls_version)
+            .field("max_tls_version", &self.max_tls_version)
+            .field("pinned_keys", &self.pinned_keys)
+            .field("resolve", &self.resolve)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("tcp_keepalive", &self.tcp_keepalive)
+            .field("tcp_keepalive_interval", &self.tcp_keepalive_interval)
+            .field("dns_timeout", &self.dns_timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("ip_family", &self.ip_family)
+            .field("local_address", &self.local_address)
+            .field("accept_encoding", &self.accept_encoding)
+            .field("decompress_responses", &self.decompress_responses)
+            .field(
+                "query",
+                &self
+                    .query
+                    .iter()
+                    .map(|(name, _)| format!("{}=<redacted>", name))
+                    .collect::<Vec<_>>(),
+            )
+            .field("default_fields", &self.default_fields)
+            .field("field_mapping", &self.field_mapping)
+            .field("transforms", &self.transforms)
+            .field("bool_format", &self.bool_format)
+            .field("sanitizer", &self.sanitizer)
+            .field("field_rules", &self.field_rules)
+            .field("batch_namer", &self.batch_namer)
+            .field("retry", &self.retry)
+            .field("field_definitions_ttl", &self.field_definitions_ttl)
+            .field("max_request_size", &self.max_request_size)
+            .field("split_oversized", &self.split_oversized)
+            .field("upload_sessions", &self.upload_sessions)
+            .field("upload_chunk_size", &self.upload_chunk_size)
+            .field("max_in_flight_bytes", &self.max_in_flight_bytes)
+            .field("profile", &self.profile)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl fmt::Debug for ConfigSource {
+    // The settings may contain secrets, so only the profile names are shown.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigSource")
+            .field("profiles", &self.profiles.keys().collect::<Vec<_>>())
+            .field("directory", &self.directory)
+            .finish()
+    }
+}
+
+/// The tables a profile replaces as a whole instead of merging them into the
+/// top-level tables, as their keys only make sense together.
+const REPLACED_TABLES: &[&str] = &["auth", "batch_name"];
+
+/// Merges `overrides` into `target`, recursing into tables present in both except
+/// for the `replaced` ones, which are overwritten as a whole.
+fn merge(target: &mut Value, overrides: &Value, replaced: &[&str]) {
+    match (target, overrides) {
+        (Value::Object(target), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match target.get_mut(key) {
+                    Some(existing) if !replaced.contains(&key.as_str()) => {
+                        merge(existing, value, &[])
+                    }
+                    _ => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (target, overrides) => *target = overrides.clone(),
+    }
+}
+
+/// The contents of a configuration file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    host: String,
+    port: Option<i32>,
+    scenario: String,
+    tenant: String,
+    api_version: Option<String>,
+    content_format: Option<String>,
+    #[serde(default)]
+    accept_invalid_certs: bool,
+    #[serde(default)]
+    ca_certs: Vec<PathBuf>,
+    tls_backend: Option<String>,
+    min_tls_version: Option<String>,
+    max_tls_version: Option<String>,
+    #[serde(default)]
+    pinned_keys: Vec<String>,
+    #[serde(default)]
+    resolve: BTreeMap<String, IpAddr>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout_secs: Option<u64>,
+    tcp_keepalive_secs: Option<u64>,
+    tcp_keepalive_interval_secs: Option<u64>,
+    dns_timeout_secs: Option<u64>,
+    connect_timeout_secs: Option<u64>,
+    ip_family: Option<String>,
+    local_address: Option<IpAddr>,
+    accept_encoding: Option<String>,
+    decompress_responses: Option<bool>,
+    bool_format: Option<String>,
+    #[serde(default)]
+    query: Map<String, Value>,
+    #[serde(default)]
+    fields: Map<String, Value>,
+    #[serde(default)]
+    field_mapping: BTreeMap<String, String>,
+    #[serde(default)]
+    transforms: Map<String, Value>,
+    sanitize: Option<SanitizeFile>,
+    #[serde(default)]
+    rules: Vec<RuleFile>,
+    batch_name: Option<BatchNameFile>,
+    auth: Option<AuthFile>,
+}
+
+/// The `[sanitize]` table of a configuration file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SanitizeFile {
+    normalize: Option<bool>,
+    replace_spaces: Option<bool>,
+    remove_invisible: Option<bool>,
+    remove_control: Option<bool>,
+    max_length: Option<usize>,
+}
+
+impl SanitizeFile {
+    fn into_sanitizer(self) -> I5Sanitizer {
+        let mut sanitizer = I5Sanitizer::new()
+            .normalize(self.normalize.unwrap_or(true))
+            .replace_spaces(self.replace_spaces.unwrap_or(true))
+            .remove_invisible(self.remove_invisible.unwrap_or(true))
+            .remove_control(self.remove_control.unwrap_or(true));
+        if let Some(max_length) = self.max_length {
+            sanitizer = sanitizer.max_length(max_length);
+        }
+        sanitizer
+    }
+}
+
+/// The `[batch_name]` table of a configuration file.
+#[derive(Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case", deny_unknown_fields)]
+enum BatchNameFile {
+    Timestamp {
+        pattern: String,
+    },
+    Uuid {
+        #[serde(default)]
+        prefix: String,
+    },
+    Sequence {
+        #[serde(default)]
+        prefix: String,
+        #[serde(default)]
+        width: usize,
+        #[serde(default = "first_sequence_number")]
+        start: u64,
+        file: Option<PathBuf>,
+    },
+}
+
+fn first_sequence_number() -> u64 {
+    1
+}
+
+/// The `[auth]` table of a configuration file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AuthFile {
+    username: Option<String>,
+    password: Option<String>,
+    token: Option<String>,
+    token_url: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    scope: Option<String>,
+    api_key: Option<String>,
+    api_key_header: Option<String>,
+    keyring_service: Option<String>,
+    scheme: Option<String>,
+    service_principal: Option<String>,
+}
+
+impl ConfigFile {
+    /// Builds the configuration, resolving relative paths against `directory`.
+    fn into_config(self, directory: Option<&Path>) -> Result<I5Config, String> {
+        let resolve = |path: PathBuf| match directory {
+            Some(directory) => directory.join(path),
+            None => path,
+        };
+
+        let mut config = I5Config::new(
+            self.host,
+            self.port.unwrap_or(DEFAULT_PORT),
+            self.scenario,
+            self.tenant,
+        );
+        if let Some(version) = self.api_version {
+            config.api_version = version
+                .parse()
+                .map_err(|_| format!("api_version: unknown version '{}'", version))?;
+        }
+        if let Some(format) = self.content_format {
+            config.content_format = format
+                .parse()
+                .map_err(|_| format!("content_format: unknown format '{}'", format))?;
+        }
+        config.accept_invalid_certs = self.accept_invalid_certs;
+        config.root_certificates = self.ca_certs.into_iter().map(resolve).collect();
+        config.pinned_keys = self.pinned_keys;
+        if let Some(backend) = self.tls_backend {
+            config.tls_backend = Some(
+                backend
+                    .parse()
+                    .map_err(|_| format!("tls_backend: unknown TLS backend '{}'", backend))?,
+            );
+        }
+        for (key, version, bound) in [
+            (
+                "min_tls_version",
+                self.min_tls_version,
+                &mut config.min_tls_version,
+            ),
+            (
+                "max_tls_version",
+                self.max_tls_version,
+                &mut config.max_tls_version,
+            ),
+        ] {
+            if let Some(version) = version {
+                *bound = Some(
+                    version
+                        .parse()
+                        .map_err(|_| format!("{}: unknown TLS version '{}'", key, version))?,
+                );
+            }
+        }
+        config.resolve = self.resolve;
+        config.pool_max_idle_per_host = self.pool_max_idle_per_host;
+        config.pool_idle_timeout = self.pool_idle_timeout_secs.map(Duration::from_secs);
+        config.tcp_keepalive = self.tcp_keepalive_secs.map(Duration::from_secs);
+        config.tcp_keepalive_interval = self.tcp_keepalive_interval_secs.map(Duration::from_secs);
+        config.dns_timeout = self.dns_timeout_secs.map(Duration::from_secs);
+        config.connect_timeout = self.connect_timeout_secs.map(Duration::from_secs);
+        if let Some(family) = self.ip_family {
+            config.ip_family = family
+                .parse()
+                .map_err(|_| format!("ip_family: unknown IP family '{}'", family))?;
+        }
+        config.local_address = self.local_address;
+        config.accept_encoding = self.accept_encoding;
+        if let Some(decompress) = self.decompress_responses {
+            config.decompress_responses = decompress;
+        }
+        if let Some(format) = self.bool_format {
+            config.bool_format = format
+                .parse()
+                .map_err(|_| format!("bool_format: invalid boolean format '{}'", format))?;
+        }
+        for (name, value) in self.query {
+            let value = match value {
+                Value::String(value) => value,
+                Value::Number(_) | Value::Bool(_) => value.to_string(),
+                _ => return Err(format!("query.{}: expected a string", name)),
+            };
+            config = config.query_param(name, value);
+        }
+        for (name, value) in self.fields {
+            let value = match value {
+                Value::String(value) => value,
+                Value::Number(_) | Value::Bool(_) => value.to_string(),
+                _ => return Err(format!("fields.{}: expected a string", name)),
+            };
+            config = config.default_field(name, value);
+        }
+        config.field_mapping = self.field_mapping.into_iter().collect();
+        for (pattern, chain) in self.transforms {
+            let chain: Vec<I5Transform> = serde_json::from_value(chain)
+                .map_err(|e| format!("transforms.{}: {}", pattern, e))?;
+            config.transforms = config.transforms.field(pattern, chain);
+        }
+        if let Some(sanitize) = self.sanitize {
+            config.sanitizer = Some(sanitize.into_sanitizer());
+        }
+        for rule in self.rules {
+            config.field_rules = rule.add_to(config.field_rules)?;
+        }
+        config.batch_namer = match self.batch_name {
+            None => None,
+            Some(BatchNameFile::Timestamp { pattern }) => Some(Arc::new(
+                I5TimestampNamer::new(pattern).map_err(|e| match e {
+                    I5RequestError::ConfigError(message) => message,
+                    other => other.to_string(),
+                })?,
+            )),
+            Some(BatchNameFile::Uuid { prefix }) => Some(Arc::new(I5UuidNamer::new(prefix))),
+            Some(BatchNameFile::Sequence {
+                prefix,
+                width,
+                start,
+                file: None,
+            }) => Some(Arc::new(
+                I5SequenceNamer::new(prefix).width(width).start_at(start),
+            )),
+            Some(BatchNameFile::Sequence {
+                prefix,
+                width,
+                start,
+                file: Some(file),
+            }) => Some(Arc::new(
+                I5FileSequenceNamer::open(resolve(file), prefix)
+                    .map_err(|e| e.to_string())?
+                    .width(width)
+                    .start_at(start),
+            )),
+        };
+        if let Some(mut auth) = self.auth {
+            config.token_endpoint = auth.token_endpoint()?;
+            let credentials = [&auth.token, &auth.api_key, &auth.username, &auth.scheme];
+            if config.token_endpoint.is_none() || credentials.iter().any(|value| value.is_some()) {
+                config.auth = Some(auth.into_auth()?);
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl AuthFile {
+    /// Takes the token endpoint, if `token_url` is set.
+    fn token_endpoint(&mut self) -> Result<Option<I5TokenEndpoint>, String> {
+        let Some(url) = self.token_url.take() else {
+            return Ok(None);
+        };
+        let client_id = self
+            .client_id
+            .take()
+            .ok_or_else(|| String::from("auth: client_id is not set but token_url is"))?;
+        let client_secret = self
+            .client_secret
+            .take()
+            .ok_or_else(|| String::from("auth: client_secret is not set but token_url is"))?;
+        let endpoint = I5TokenEndpoint::new(url, client_id, client_secret);
+        Ok(Some(match self.scope.take() {
+            Some(scope) => endpoint.scope(scope),
+            None => endpoint,
+        }))
+    }
+
+    fn into_auth(self) -> Result<I5Auth, String> {
+        if let Some(scheme) = &self.scheme {
+            return windows_auth(scheme, self.service_principal).ok_or_else(|| {
+                format!(
+                    "auth: unknown scheme '{}', expected negotiate or ntlm",
+                    scheme
+                )
+            });
+        }
+        if let Some(token) = self.token {
+            return Ok(I5Auth::bearer(token));
+        }
+        if let Some(key) = self.api_key {
+            let header = self.api_key_header.as_deref();
+            return Ok(I5Auth::api_key(
+                header.unwrap_or(DEFAULT_API_KEY_HEADER),
+                key,
+            ));
+        }
+        let username = self
+            .username
+            .ok_or_else(|| String::from("auth: either token, api_key or username must be set"))?;
+
+        #[cfg(feature = "keyring")]
+        if let Some(service) = &self.keyring_service {
+            return I5Auth::basic_from_keyring(service, &username).map_err(|e| e.to_string());
+        }
+        #[cfg(not(feature = "keyring"))]
+        if self.keyring_service.is_some() {
+            return Err(String::from(
+                "auth: keyring_service requires the `keyring` feature",
+            ));
+        }
+
+        let password = self
+            .password
+            .ok_or_else(|| String::from("auth: password is not set but username is"))?;
+        Ok(I5Auth::basic(username, password))
+    }
+}
+
+/// The header carrying an API key unless another one is configured.
+const DEFAULT_API_KEY_HEADER: &str = "X-API-Key";
+
+/// The Windows integrated authentication named by `scheme`, if it names one.
+fn windows_auth(scheme: &str, service_principal: Option<String>) -> Option<I5Auth> {
+    match scheme.trim().to_ascii_lowercase().as_str() {
+        "negotiate" | "kerberos" => Some(I5Auth::Negotiate { service_principal }),
+        "ntlm" => Some(I5Auth::Ntlm),
+        _ => None,
+    }
+}
+
+/// Parses a boolean configuration value.
+fn parse_bool(name: &str, value: &str) -> Result<bool, I5RequestError> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" | "" => Ok(false),
+        _ => Err(I5RequestError::ConfigError(format!(
+            "{} is not a valid boolean: {}",
+            name, value
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_redacts_query_values() {
+        let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default")
+            .query_param("api_key", "S3CR3T-KEY");
+        let debug = format!("{:?}", config);
+
+        assert!(!debug.contains("S3CR3T-KEY"), "{}", debug);
+        assert!(debug.contains("api_key=<redacted>"), "{}", debug);
+    }
+
+    #[test]
+    fn profile_replaces_the_auth_table() {
+        let config = I5Config::from_toml_str(
+            r#"
+            host = "i5-dev.company.local"
+            scenario = "Invoices"
+            tenant = "Default"
+
+            [auth]
+            token = "DEV-TOKEN"
+
+            [profiles.prod.auth]
+            username = "svc-scanner"
+            password = "hunter2"
+            "#,
+        )
+        .unwrap();
+        let prod = config.profile("prod").unwrap();
+        assert!(matches!(prod.credentials(), Some(I5Auth::Basic { .. })));
+    }
+
+    #[test]
+    fn profile_merges_other_tables() {
+        let config = I5Config::from_toml_str(
+            r#"
+            host = "i5-dev.company.local"
+            scenario = "Invoices"
+            tenant = "Default"
+
+            [fields]
+            SOURCE = "ScannerA"
+
+            [profiles.prod.fields]
+            SITE = "Berlin"
+            "#,
+        )
+        .unwrap();
+        let prod = config.profile("prod").unwrap();
+        assert_eq!(prod.default_fields().len(), 2);
+    }
+
+    #[test]
+    fn file_with_only_profiles_selects_them() {
+        let config = I5Config::from_toml_str(
+            r#"
+            scenario = "Invoices"
+
+            [profiles.dev]
+            host = "i5-dev.company.local"
+            tenant = "Default"
+
+            [profiles.prod]
+            host = "i5.company.local"
+            tenant = "Default"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.profile_names(), ["dev", "prod"]);
+        assert_eq!(
+            config.profile("prod").unwrap().url().hostname(),
+            "i5.company.local"
+        );
+        assert!(matches!(
+            crate::request::client::I5Client::new(config),
+            Err(I5RequestError::ConfigError(_))
+        ));
+    }
+
+    /// Writes `content` to a file named `name` in a folder of its own.
+    fn file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("i5-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn yaml_file_reads_typed_settings() {
+        let path = file(
+            "typed.yaml",
+            "host: i5.company.local\n\
+             port: 43002\n\
+             scenario: Invoices\n\
+             tenant: '0042'\n\
+             accept_invalid_certs: true\n\
+             fields:\n  \
+               SOURCE: ScannerA\n\
+             profiles:\n  \
+               prod:\n    \
+                 host: i5-prod.company.local\n",
+        );
+        let config = I5Config::from_file(&path).unwrap();
+        assert_eq!(config.url().port(), 43002);
+        assert_eq!(config.url().tenant(), "0042");
+        assert!(config.accepts_invalid_certs());
+        assert_eq!(config.default_fields().len(), 1);
+        assert_eq!(
+            config.profile("prod").unwrap().url().hostname(),
+            "i5-prod.company.local"
+        );
+    }
+
+    #[test]
+    fn yaml_syntax_errors_name_file_and_line() {
+        let path = file("broken.yml", "host: i5.company.local\n\tport: 43002\n");
+        let message = I5Config::from_file(&path).unwrap_err().to_string();
+        assert!(message.contains("broken.yml"), "{}", message);
+        assert!(message.contains("line 2"), "{}", message);
+    }
+
+    #[test]
+    fn toml_rejects_duplicate_tables() {
+        let error = I5Config::from_toml_str(
+            r#"
+            host = "i5.company.local"
+            scenario = "Invoices"
+            tenant = "Default"
+
+            [auth]
+            token = "secret"
+
+            [auth]
+            username = "svc-scanner"
+            "#,
+        )
+        .unwrap_err();
+        assert!(
+            error.to_string().contains("duplicate table 'auth'"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::{fmt, str::FromStr};
+
+use crate::types::i5_error::I5RequestError;
+
+/// The format of the body of a submission.
+///
+/// Newer Interface5 web inputs accept JSON; some older ones only accept the equivalent
+/// XML, see [`ValidatedI5Request::to_xml_string`].
+///
+/// [`ValidatedI5Request::to_xml_string`]: crate::types::i5_request::ValidatedI5Request::to_xml_string
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum I5ContentFormat {
+    /// A JSON body.
+    #[default]
+    Json,
+    /// An XML body.
+    Xml,
+}
+
+impl I5ContentFormat {
+    /// The format as written in configuration, e.g. `json`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            I5ContentFormat::Json => "json",
+            I5ContentFormat::Xml => "xml",
+        }
+    }
+
+    /// The value of the `Content-Type` header of a body in this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            I5ContentFormat::Json => "application/json",
+            I5ContentFormat::Xml => "application/xml",
+        }
+    }
+}
+
+impl FromStr for I5ContentFormat {
+    type Err = I5RequestError;
+
+    /// Parses a format as written in configuration, ignoring case.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] for unknown formats.
+    fn from_str(text: &str) -> Result<I5ContentFormat, I5RequestError> {
+        match text.to_ascii_lowercase().as_str() {
+            "json" => Ok(I5ContentFormat::Json),
+            "xml" => Ok(I5ContentFormat::Xml),
+            _ => Err(I5RequestError::ConfigError(format!(
+                "unknown content format '{}', expected json or xml",
+                text
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for I5ContentFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
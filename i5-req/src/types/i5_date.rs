@@ -0,0 +1,213 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::util::{self, UtcDateTime};
+
+/// A calendar date without a time zone.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_date::{I5Date, I5DateFormat};
+///
+/// let date = I5Date::new(2025, 6, 27).unwrap();
+/// assert_eq!(date.format(I5DateFormat::Iso8601), "2025-06-27");
+/// assert_eq!(date.format(I5DateFormat::German), "27.06.2025");
+/// assert_eq!(date.format(I5DateFormat::UnixEpoch), "1750982400");
+///
+/// assert!(I5Date::new(2025, 2, 29).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct I5Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl I5Date {
+    /// Creates a date, or returns `None` if it does not exist.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<I5Date> {
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days_in_month)
+            .contains(&day)
+            .then_some(I5Date { year, month, day })
+    }
+
+    /// The UTC date of a point in time.
+    pub fn from_system_time(time: SystemTime) -> I5Date {
+        let utc = UtcDateTime::new(time);
+        I5Date {
+            year: utc.year as i32,
+            month: utc.month,
+            day: utc.day,
+        }
+    }
+
+    /// The current UTC date.
+    pub fn today() -> I5Date {
+        I5Date::from_system_time(SystemTime::now())
+    }
+
+    /// The year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// The month, from 1 to 12.
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    /// The day of the month, from 1 to 31.
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Midnight UTC at the start of this date.
+    pub fn to_system_time(&self) -> SystemTime {
+        let days = util::days_from_civil(i64::from(self.year), self.month, self.day);
+        let seconds = Duration::from_secs(days.unsigned_abs() * 86_400);
+        if days >= 0 {
+            UNIX_EPOCH + seconds
+        } else {
+            UNIX_EPOCH - seconds
+        }
+    }
+
+    /// Formats the date in `format`.
+    pub fn format(&self, format: I5DateFormat) -> String {
+        match format {
+            I5DateFormat::Iso8601 => {
+                format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+            }
+            I5DateFormat::German => {
+                format!("{:02}.{:02}.{:04}", self.day, self.month, self.year)
+            }
+            I5DateFormat::UnixEpoch => {
+                (util::days_from_civil(i64::from(self.year), self.month, self.day) * 86_400)
+                    .to_string()
+            }
+        }
+    }
+}
+
+impl From<SystemTime> for I5Date {
+    fn from(time: SystemTime) -> Self {
+        I5Date::from_system_time(time)
+    }
+}
+
+/// The format of date and date-time field values.
+///
+/// | Format                      | Date         | Date-time (UTC)          |
+/// |-----------------------------|--------------|--------------------------|
+/// | [`I5DateFormat::Iso8601`]   | `2025-06-27` | `2025-06-27T08:15:00Z`   |
+/// | [`I5DateFormat::German`]    | `27.06.2025` | `27.06.2025 08:15:00`    |
+/// | [`I5DateFormat::UnixEpoch`] | `1750982400` | `1751012100`             |
+///
+/// The German format has no time zone marker, so a date-time written with
+/// [`format_datetime`](I5DateFormat::format_datetime) is UTC and one written with
+/// [`format_datetime_at`](I5DateFormat::format_datetime_at) is the wall-clock time at
+/// the given offset; the reader has to know which.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum I5DateFormat {
+    /// ISO 8601, e.g. `2025-06-27` or `2025-06-27T08:15:00Z`.
+    Iso8601,
+    /// `dd.MM.yyyy`, e.g. `27.06.2025` or `27.06.2025 08:15:00`, without a time zone.
+    German,
+    /// Seconds since 1970-01-01 00:00:00 UTC.
+    UnixEpoch,
+}
+
+impl I5DateFormat {
+    /// Formats a point in time in UTC.
+    ///
+    /// [`I5DateFormat::German`] does not mark the time as UTC; use
+    /// [`format_datetime_at`](I5DateFormat::format_datetime_at) for the local time of the
+    /// people reading it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// use i5_req::types::i5_date::I5DateFormat;
+    ///
+    /// let time = UNIX_EPOCH + Duration::from_secs(1_751_012_100);
+    /// assert_eq!(I5DateFormat::Iso8601.format_datetime(time), "2025-06-27T08:15:00Z");
+    /// assert_eq!(I5DateFormat::German.format_datetime(time), "27.06.2025 08:15:00");
+    /// ```
+    pub fn format_datetime(self, time: SystemTime) -> String {
+        let utc = UtcDateTime::new(time);
+        match self {
+            I5DateFormat::Iso8601 => format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                utc.year, utc.month, utc.day, utc.hour, utc.minute, utc.second
+            ),
+            I5DateFormat::German => format!(
+                "{:02}.{:02}.{:04} {:02}:{:02}:{:02}",
+                utc.day, utc.month, utc.year, utc.hour, utc.minute, utc.second
+            ),
+            I5DateFormat::UnixEpoch => utc.unix_seconds.to_string(),
+        }
+    }
+
+    /// Formats a point in time at `utc_offset_minutes` east of UTC, e.g. `120` for
+    /// Central European Summer Time.
+    ///
+    /// [`I5DateFormat::Iso8601`] appends the offset, [`I5DateFormat::German`] writes the
+    /// wall-clock time at the offset and [`I5DateFormat::UnixEpoch`] is the same at
+    /// every offset.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// use i5_req::types::i5_date::I5DateFormat;
+    ///
+    /// let time = UNIX_EPOCH + Duration::from_secs(1_751_012_100);
+    /// assert_eq!(
+    ///     I5DateFormat::Iso8601.format_datetime_at(time, 120),
+    ///     "2025-06-27T10:15:00+02:00"
+    /// );
+    /// assert_eq!(I5DateFormat::German.format_datetime_at(time, 120), "27.06.2025 10:15:00");
+    /// assert_eq!(I5DateFormat::UnixEpoch.format_datetime_at(time, 120), "1751012100");
+    /// ```
+    pub fn format_datetime_at(self, time: SystemTime, utc_offset_minutes: i32) -> String {
+        let offset = Duration::from_secs(u64::from(utc_offset_minutes.unsigned_abs()) * 60);
+        let local = if utc_offset_minutes >= 0 {
+            time + offset
+        } else {
+            time - offset
+        };
+        let local = UtcDateTime::new(local);
+        match self {
+            I5DateFormat::Iso8601 if utc_offset_minutes == 0 => self.format_datetime(time),
+            I5DateFormat::Iso8601 => format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
+                local.year,
+                local.month,
+                local.day,
+                local.hour,
+                local.minute,
+                local.second,
+                if utc_offset_minutes < 0 { '-' } else { '+' },
+                utc_offset_minutes.unsigned_abs() / 60,
+                utc_offset_minutes.unsigned_abs() % 60
+            ),
+            I5DateFormat::German => format!(
+                "{:02}.{:02}.{:04} {:02}:{:02}:{:02}",
+                local.day, local.month, local.year, local.hour, local.minute, local.second
+            ),
+            I5DateFormat::UnixEpoch => self.format_datetime(time),
+        }
+    }
+}
@@ -0,0 +1,609 @@
+use std::{collections::BTreeMap, fmt, fmt::Write};
+
+use crate::{
+    types::i5_request::{Document, Field, File, FileData, I5Request},
+    util,
+};
+
+/// Compares the request `old` with `new`, e.g. a batch recorded in production with the
+/// batch regenerated from the same input after a change to the mapping. Equivalent to
+/// [`I5Request::diff`].
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{i5_diff, i5_request::I5Request};
+///
+/// let mut recorded = I5Request::new("Batch");
+/// let id = recorded.add_document("Invoice");
+/// recorded
+///     .get_document_mut(id)
+///     .unwrap()
+///     .add_header_field("Amount", "100.00")
+///     .add_item_field("Article", "A-1", 1)
+///     .add_bytes_file("scan.pdf", b"%PDF-1.7");
+///
+/// let mut regenerated = I5Request::new("Batch");
+/// let id = regenerated.add_document("Invoice");
+/// regenerated
+///     .get_document_mut(id)
+///     .unwrap()
+///     .add_header_field("Amount", "100.0")
+///     .add_item_field("Article", "A-1", 1)
+///     .add_item_field("Article", "A-2", 2)
+///     .add_bytes_file("scan.pdf", b"%PDF-1.7");
+///
+/// let diff = i5_diff::compare(&recorded, &regenerated);
+/// assert_eq!(
+///     diff.report(),
+///     "document 0 'Invoice':\n  \
+///        header:\n    \
+///          ~ Amount: '100.00' -> '100.0'\n  \
+///        item 2 (added):\n    \
+///          + Article: 'A-2'\n"
+/// );
+/// ```
+pub fn compare(old: &I5Request, new: &I5Request) -> I5RequestDiff {
+    I5RequestDiff::between(old, new)
+}
+
+/// A single difference found by [`I5Request::diff`].
+///
+/// Documents are compared by index. Fields are matched by name and item number, and
+/// files by name; repeated names are matched in order of appearance. Files are
+/// compared by the SHA-256 hash of their content, and a file that only exists under
+/// different names with the same content is reported as renamed.
+///
+/// [`I5Request::diff`]: crate::types::i5_request::I5Request::diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum I5Change {
+    /// The batch name differs.
+    RequestRenamed { from: String, to: String },
+
+    /// The document only exists in the other request.
+    DocumentAdded { document: usize, name: String },
+
+    /// The document only exists in this request.
+    DocumentRemoved { document: usize, name: String },
+
+    /// The document name differs.
+    DocumentRenamed {
+        document: usize,
+        from: String,
+        to: String,
+    },
+
+    /// The item only exists in the other request. Its fields are reported as added.
+    ItemAdded { document: usize, item_number: i32 },
+
+    /// The item only exists in this request. Its fields are reported as removed.
+    ItemRemoved { document: usize, item_number: i32 },
+
+    /// The field only exists in the other request.
+    FieldAdded {
+        document: usize,
+        name: String,
+        item_number: i32,
+        value: String,
+    },
+
+    /// The field only exists in this request.
+    FieldRemoved {
+        document: usize,
+        name: String,
+        item_number: i32,
+        value: String,
+    },
+
+    /// The field value differs.
+    FieldChanged {
+        document: usize,
+        name: String,
+        item_number: i32,
+        from: String,
+        to: String,
+    },
+
+    /// The file only exists in the other request.
+    FileAdded { document: usize, name: String },
+
+    /// The file only exists in this request.
+    FileRemoved { document: usize, name: String },
+
+    /// The file content or page number differs. Carries the lowercase hexadecimal
+    /// SHA-256 hashes of the contents, which are equal if only the page number differs.
+    FileChanged {
+        document: usize,
+        name: String,
+        from_sha256: String,
+        to_sha256: String,
+    },
+
+    /// The file exists with the same content under another name.
+    FileRenamed {
+        document: usize,
+        from: String,
+        to: String,
+    },
+}
+
+impl fmt::Display for I5Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            I5Change::RequestRenamed { from, to } => {
+                write!(f, "request renamed from '{}' to '{}'", from, to)
+            }
+            I5Change::DocumentAdded { document, name } => {
+                write!(f, "document {} '{}' added", document, name)
+            }
+            I5Change::DocumentRemoved { document, name } => {
+                write!(f, "document {} '{}' removed", document, name)
+            }
+            I5Change::DocumentRenamed { document, from, to } => {
+                write!(
+                    f,
+                    "document {} renamed from '{}' to '{}'",
+                    document, from, to
+                )
+            }
+            I5Change::ItemAdded {
+                document,
+                item_number,
+            } => write!(f, "document {}: item {} added", document, item_number),
+            I5Change::ItemRemoved {
+                document,
+                item_number,
+            } => write!(f, "document {}: item {} removed", document, item_number),
+            I5Change::FieldAdded {
+                document,
+                name,
+                item_number,
+                ..
+            } => write!(
+                f,
+                "document {}: field {}[{}] added",
+                document, name, item_number
+            ),
+            I5Change::FieldRemoved {
+                document,
+                name,
+                item_number,
+                ..
+            } => write!(
+                f,
+                "document {}: field {}[{}] removed",
+                document, name, item_number
+            ),
+            I5Change::FieldChanged {
+                document,
+                name,
+                item_number,
+                ..
+            } => write!(
+                f,
+                "document {}: field {}[{}] changed",
+                document, name, item_number
+            ),
+            I5Change::FileAdded { document, name } => {
+                write!(f, "document {}: file '{}' added", document, name)
+            }
+            I5Change::FileRemoved { document, name } => {
+                write!(f, "document {}: file '{}' removed", document, name)
+            }
+            I5Change::FileChanged { document, name, .. } => {
+                write!(f, "document {}: file '{}' changed", document, name)
+            }
+            I5Change::FileRenamed { document, from, to } => write!(
+                f,
+                "document {}: file renamed from '{}' to '{}'",
+                document, from, to
+            ),
+        }
+    }
+}
+
+/// The differences between two requests, see [`I5Request::diff`].
+///
+/// The [`Display`](fmt::Display) output names the changed fields and files without
+/// their values, so it is safe for logs. The [report](I5RequestDiff::report) shows the
+/// values, grouped by document and item, for reviewing a regression test.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{i5_diff::I5Change, i5_request::I5Request};
+///
+/// let mut sent = I5Request::new("Batch");
+/// let id = sent.add_document("Invoice");
+/// sent.get_document_mut(id)
+///     .unwrap()
+///     .add_header_field("InvoiceNo", "4711")
+///     .add_header_field("Amount", "100.00");
+///
+/// let mut regenerated = sent.clone();
+/// regenerated
+///     .get_document_mut(id)
+///     .unwrap()
+///     .add_bytes_file("scan.pdf", b"%PDF-1.7");
+///
+/// let diff = sent.diff(&regenerated);
+/// assert_eq!(
+///     diff.changes(),
+///     [I5Change::FileAdded { document: 0, name: "scan.pdf".to_string() }]
+/// );
+/// assert!(sent.diff(&sent.clone()).is_empty());
+/// ```
+///
+/// [`I5Request::diff`]: crate::types::i5_request::I5Request::diff
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5RequestDiff {
+    changes: Vec<I5Change>,
+    /// The names of the documents of the old request, for the report.
+    documents: Vec<String>,
+}
+
+impl I5RequestDiff {
+    /// Compares `old` with `new`.
+    pub(crate) fn between(old: &I5Request, new: &I5Request) -> I5RequestDiff {
+        let mut diff = I5RequestDiff {
+            documents: old
+                .documents()
+                .iter()
+                .map(|d| d.name().to_string())
+                .collect(),
+            ..I5RequestDiff::default()
+        };
+        if old.name() != new.name() {
+            diff.changes.push(I5Change::RequestRenamed {
+                from: old.name().to_string(),
+                to: new.name().to_string(),
+            });
+        }
+
+        let (old_documents, new_documents) = (old.documents(), new.documents());
+        for index in 0..old_documents.len().max(new_documents.len()) {
+            match (old_documents.get(index), new_documents.get(index)) {
+                (Some(old), Some(new)) => diff.compare_documents(index, old, new),
+                (Some(old), None) => diff.changes.push(I5Change::DocumentRemoved {
+                    document: index,
+                    name: old.name().to_string(),
+                }),
+                (None, Some(new)) => diff.changes.push(I5Change::DocumentAdded {
+                    document: index,
+                    name: new.name().to_string(),
+                }),
+                (None, None) => {}
+            }
+        }
+        diff
+    }
+
+    fn compare_documents(&mut self, document: usize, old: &Document, new: &Document) {
+        if old.name() != new.name() {
+            self.changes.push(I5Change::DocumentRenamed {
+                document,
+                from: old.name().to_string(),
+                to: new.name().to_string(),
+            });
+        }
+
+        let items = |document: &Document| {
+            let mut items: Vec<i32> = document
+                .fields()
+                .iter()
+                .map(Field::item_number)
+                .filter(|item_number| *item_number > 0)
+                .collect();
+            items.sort_unstable();
+            items.dedup();
+            items
+        };
+        let (old_items, new_items) = (items(old), items(new));
+        for item_number in old_items.iter().filter(|item| !new_items.contains(item)) {
+            self.changes.push(I5Change::ItemRemoved {
+                document,
+                item_number: *item_number,
+            });
+        }
+        for item_number in new_items.iter().filter(|item| !old_items.contains(item)) {
+            self.changes.push(I5Change::ItemAdded {
+                document,
+                item_number: *item_number,
+            });
+        }
+
+        let field_key = |field: &Field| (field.name().to_string(), field.item_number());
+        let (matched, removed, added) = match_by_key(old.fields(), new.fields(), field_key);
+        for (old, new) in matched {
+            if old.value() != new.value() {
+                self.changes.push(I5Change::FieldChanged {
+                    document,
+                    name: old.name().to_string(),
+                    item_number: old.item_number(),
+                    from: old.value().to_string(),
+                    to: new.value().to_string(),
+                });
+            }
+        }
+        for field in removed {
+            self.changes.push(I5Change::FieldRemoved {
+                document,
+                name: field.name().to_string(),
+                item_number: field.item_number(),
+                value: field.value().to_string(),
+            });
+        }
+        for field in added {
+            self.changes.push(I5Change::FieldAdded {
+                document,
+                name: field.name().to_string(),
+                item_number: field.item_number(),
+                value: field.value().to_string(),
+            });
+        }
+
+        let file_key = |file: &File| file.name().to_string();
+        let (matched, removed, added) = match_by_key(old.files(), new.files(), file_key);
+        for (old, new) in matched {
+            let (from_sha256, to_sha256) = (content_hash(old), content_hash(new));
+            if from_sha256 != to_sha256 || old.page() != new.page() {
+                self.changes.push(I5Change::FileChanged {
+                    document,
+                    name: old.name().to_string(),
+                    from_sha256,
+                    to_sha256,
+                });
+            }
+        }
+        let (renamed, removed, added) = match_by_key(&removed, &added, |file| content_hash(file));
+        for (old, new) in renamed {
+            self.changes.push(I5Change::FileRenamed {
+                document,
+                from: old.name().to_string(),
+                to: new.name().to_string(),
+            });
+        }
+        for file in removed {
+            self.changes.push(I5Change::FileRemoved {
+                document,
+                name: file.name().to_string(),
+            });
+        }
+        for file in added {
+            self.changes.push(I5Change::FileAdded {
+                document,
+                name: file.name().to_string(),
+            });
+        }
+    }
+
+    /// All changes, in document order.
+    pub fn changes(&self) -> &[I5Change] {
+        &self.changes
+    }
+
+    /// Returns `true` if both requests are equal.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// A readable report of the changes with their values, one block per document.
+    ///
+    /// Within a document, changed header fields come first, then the changed fields
+    /// of every item in item order, marked `(added)` or `(removed)` if the item only
+    /// exists in one of the requests, then the changed files with the first twelve
+    /// digits of their SHA-256 hashes. Lines start with `+` for added, `-` for removed
+    /// and `~` for changed entries. The report is empty if the requests are equal.
+    ///
+    /// Unlike the [`Display`](fmt::Display) output, the report contains field values,
+    /// so it should not be logged for batches with personal data.
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        let mut documents: BTreeMap<usize, DocumentReport> = BTreeMap::new();
+        for change in &self.changes {
+            let (document, line) = match change {
+                I5Change::RequestRenamed { from, to } => {
+                    let _ = writeln!(report, "request renamed from '{}' to '{}'", from, to);
+                    continue;
+                }
+                I5Change::DocumentAdded { document, name } => {
+                    documents.entry(*document).or_default().heading =
+                        format!("document {} '{}' (added)", document, name);
+                    continue;
+                }
+                I5Change::DocumentRemoved { document, name } => {
+                    documents.entry(*document).or_default().heading =
+                        format!("document {} '{}' (removed)", document, name);
+                    continue;
+                }
+                I5Change::DocumentRenamed { document, from, to } => {
+                    documents.entry(*document).or_default().heading =
+                        format!("document {} '{}' (renamed to '{}')", document, from, to);
+                    continue;
+                }
+                I5Change::ItemAdded {
+                    document,
+                    item_number,
+                } => (document, Line::Item(*item_number, "added")),
+                I5Change::ItemRemoved {
+                    document,
+                    item_number,
+                } => (document, Line::Item(*item_number, "removed")),
+                I5Change::FieldAdded {
+                    document,
+                    name,
+                    item_number,
+                    value,
+                } => (
+                    document,
+                    Line::Field(*item_number, '+', format!("{}: '{}'", name, value)),
+                ),
+                I5Change::FieldRemoved {
+                    document,
+                    name,
+                    item_number,
+                    value,
+                } => (
+                    document,
+                    Line::Field(*item_number, '-', format!("{}: '{}'", name, value)),
+                ),
+                I5Change::FieldChanged {
+                    document,
+                    name,
+                    item_number,
+                    from,
+                    to,
+                } => (
+                    document,
+                    Line::Field(
+                        *item_number,
+                        '~',
+                        format!("{}: '{}' -> '{}'", name, from, to),
+                    ),
+                ),
+                I5Change::FileAdded { document, name } => (document, Line::File('+', name.clone())),
+                I5Change::FileRemoved { document, name } => {
+                    (document, Line::File('-', name.clone()))
+                }
+                I5Change::FileChanged {
+                    document,
+                    name,
+                    from_sha256,
+                    to_sha256,
+                } => {
+                    let line = if from_sha256 == to_sha256 {
+                        format!("{}: page number changed", name)
+                    } else {
+                        format!(
+                            "{}: sha256 {} -> {}",
+                            name,
+                            short_hash(from_sha256),
+                            short_hash(to_sha256)
+                        )
+                    };
+                    (document, Line::File('~', line))
+                }
+                I5Change::FileRenamed { document, from, to } => (
+                    document,
+                    Line::File('~', format!("{} renamed to {}", from, to)),
+                ),
+            };
+            let entry = documents.entry(*document).or_default();
+            match line {
+                Line::Item(item_number, label) => {
+                    entry.item_labels.insert(item_number, label);
+                }
+                Line::Field(item_number, sign, line) => entry
+                    .items
+                    .entry(item_number)
+                    .or_default()
+                    .push((sign, line)),
+                Line::File(sign, line) => entry.files.push((sign, line)),
+            }
+        }
+
+        for (index, document) in documents {
+            let heading = match document.heading.is_empty() {
+                true => format!("document {} '{}'", index, self.document_name(index)),
+                false => document.heading,
+            };
+            let _ = writeln!(report, "{}:", heading);
+            for (item_number, lines) in document.items {
+                let label = match (item_number, document.item_labels.get(&item_number)) {
+                    (0, _) => String::from("header"),
+                    (number, Some(label)) => format!("item {} ({})", number, label),
+                    (number, None) => format!("item {}", number),
+                };
+                let _ = writeln!(report, "  {}:", label);
+                for (sign, line) in lines {
+                    let _ = writeln!(report, "    {} {}", sign, line);
+                }
+            }
+            if !document.files.is_empty() {
+                let _ = writeln!(report, "  files:");
+                for (sign, line) in document.files {
+                    let _ = writeln!(report, "    {} {}", sign, line);
+                }
+            }
+        }
+        report
+    }
+
+    /// The name of document `index`, for documents that were neither added, removed
+    /// nor renamed.
+    fn document_name(&self, index: usize) -> &str {
+        self.documents
+            .get(index)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+}
+
+/// The lines of a document in [`I5RequestDiff::report`].
+#[derive(Default)]
+struct DocumentReport {
+    heading: String,
+    items: BTreeMap<i32, Vec<(char, String)>>,
+    item_labels: BTreeMap<i32, &'static str>,
+    files: Vec<(char, String)>,
+}
+
+/// A line of [`I5RequestDiff::report`].
+enum Line {
+    Item(i32, &'static str),
+    Field(i32, char, String),
+    File(char, String),
+}
+
+/// The lowercase hexadecimal SHA-256 hash of the decoded content of `file`, or of its
+/// base64 text if it is not valid base64.
+fn content_hash(file: &File) -> String {
+    match file.data().decoded() {
+        Ok(content) => util::sha256_hex(&content),
+        Err(_) => match file.data() {
+            FileData::Base64(data) => util::sha256_hex(data.as_bytes()),
+            FileData::Bytes(bytes) => util::sha256_hex(bytes),
+        },
+    }
+}
+
+fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(12)]
+}
+
+impl fmt::Display for I5RequestDiff {
+    /// Lists one change per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of [`match_by_key`]: the pairs, the unmatched entries of `old` and the
+/// unmatched entries of `new`.
+type Matched<'a, T> = (Vec<(&'a T, &'a T)>, Vec<&'a T>, Vec<&'a T>);
+
+/// Pairs the entries of `old` and `new` with equal keys in order of appearance.
+fn match_by_key<'a, T, K: PartialEq>(
+    old: &'a [T],
+    new: &'a [T],
+    key: impl Fn(&T) -> K,
+) -> Matched<'a, T> {
+    let mut unmatched: Vec<Option<&T>> = new.iter().map(Some).collect();
+    let mut matched = Vec::new();
+    let mut removed = Vec::new();
+    for entry in old {
+        let entry_key = key(entry);
+        match unmatched
+            .iter_mut()
+            .find(|candidate| candidate.is_some_and(|candidate| key(candidate) == entry_key))
+        {
+            Some(candidate) => matched.push((entry, candidate.take().unwrap())),
+            None => removed.push(entry),
+        }
+    }
+    (matched, removed, unmatched.into_iter().flatten().collect())
+}
@@ -0,0 +1,126 @@
+use serde_json::{Map, Value};
+
+/// A tenant offered by an Interface5 server, see
+/// [`I5Client::tenants`](crate::request::client::I5Client::tenants).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5Tenant {
+    name: String,
+    description: Option<String>,
+}
+
+impl I5Tenant {
+    /// The tenant identifier used in request URLs.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The description shown by the server, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// A scenario of a tenant, see
+/// [`I5Client::scenarios`](crate::request::client::I5Client::scenarios).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5Scenario {
+    name: String,
+    description: Option<String>,
+}
+
+impl I5Scenario {
+    /// The scenario name used in request URLs.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The description shown by the server, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// A document class of a scenario, see
+/// [`I5Client::document_classes`](crate::request::client::I5Client::document_classes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5DocumentClass {
+    name: String,
+    description: Option<String>,
+}
+
+impl I5DocumentClass {
+    /// The class name to pass to
+    /// [`Document::set_class`](crate::types::i5_request::Document::set_class).
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The description shown by the server, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// Reads the tenants from a discovery response.
+pub(crate) fn tenants(value: &Value) -> Vec<I5Tenant> {
+    entries(value)
+        .map(|(name, description)| I5Tenant { name, description })
+        .collect()
+}
+
+/// Reads the scenarios from a discovery response.
+pub(crate) fn scenarios(value: &Value) -> Vec<I5Scenario> {
+    entries(value)
+        .map(|(name, description)| I5Scenario { name, description })
+        .collect()
+}
+
+/// Reads the document classes from a discovery response.
+pub(crate) fn document_classes(value: &Value) -> Vec<I5DocumentClass> {
+    entries(value)
+        .map(|(name, description)| I5DocumentClass { name, description })
+        .collect()
+}
+
+/// The names and descriptions in a discovery response.
+///
+/// The response is read leniently: a list, or an object whose first list value is
+/// taken, of names or of objects with a `Name` or `Id` and an optional
+/// `Description`, with keys matched ignoring case. Entries without a name are
+/// skipped.
+fn entries(value: &Value) -> impl Iterator<Item = (String, Option<String>)> + '_ {
+    list(value).filter_map(|entry| match entry {
+        Value::String(name) => Some((name.clone(), None)),
+        Value::Object(object) => {
+            let text = |keys: &[&str]| {
+                key(object, keys)
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            };
+            let name = text(&["Name", "Id"])?;
+            Some((name, text(&["Description", "DisplayName"])))
+        }
+        _ => None,
+    })
+}
+
+/// The entries of a lookup response: the response itself if it is a list, or the
+/// first list value of an object.
+pub(crate) fn list(value: &Value) -> impl Iterator<Item = &Value> {
+    let list = match value {
+        Value::Array(list) => Some(list),
+        Value::Object(object) => object.values().find_map(Value::as_array),
+        _ => None,
+    };
+    list.into_iter().flatten()
+}
+
+/// The value of the first of `keys` present in `object`, matched ignoring case.
+pub(crate) fn key<'a>(object: &'a Map<String, Value>, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter().find_map(|wanted| {
+        object
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(wanted))
+            .map(|(_, value)| value)
+    })
+}
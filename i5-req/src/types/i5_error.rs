@@ -1,5 +1,9 @@
 use std::{error::Error, fmt::Display};
 
+#[cfg(feature = "l10n")]
+use crate::types::i5_locale::I5MessageCatalog;
+use crate::types::i5_server_error::I5ServerError;
+
 /// An error type representing possible failures when handling Interface5 requests.
 ///
 /// This enum encapsulates typical error scenarios that can occur when:
@@ -7,13 +11,38 @@ use std::{error::Error, fmt::Display};
 /// - Serializing the request body to a JSON String
 /// - Sending the HTTP request to the target interface
 ///
+/// The enum is `#[non_exhaustive]`: new failure cases will be added over time.
+/// Code that needs to react to a specific failure in a way that survives such
+/// changes should match on [`I5RequestError::code`] or
+/// [`I5RequestError::numeric_code`] instead of the variant shape.
+///
 /// # Variants
 ///
 /// - [`ValidationError`]: The i5Request failed validation checks.
 /// - [`SerializeError`]: JSON serialization failed (typically from `serde_json::to_string`).
 /// - [`RequestError`]: Sending the HTTP request via `reqwest` failed.
+/// - [`ServerError`]: Interface5 answered with an error payload.
+/// - [`IoError`]: Reading or writing a local file failed.
+/// - [`CredentialError`]: Credentials could not be loaded.
+/// - [`ConfigError`]: The client configuration is missing or invalid.
+/// - [`ConversionError`]: Request data could not be converted into an i5Request.
+/// - [`ContractError`]: The serialized request violates the Interface5 schema.
+/// - [`PayloadTooLarge`]: The request exceeds the size the server accepts.
+/// - [`UnexpectedResponse`]: The response of Interface5 does not have the expected shape.
 ///
+/// [`ValidationError`]: I5RequestError::ValidationError
+/// [`SerializeError`]: I5RequestError::SerializeError
+/// [`RequestError`]: I5RequestError::RequestError
+/// [`ServerError`]: I5RequestError::ServerError
+/// [`IoError`]: I5RequestError::IoError
+/// [`CredentialError`]: I5RequestError::CredentialError
+/// [`ConfigError`]: I5RequestError::ConfigError
+/// [`ConversionError`]: I5RequestError::ConversionError
+/// [`ContractError`]: I5RequestError::ContractError
+/// [`PayloadTooLarge`]: I5RequestError::PayloadTooLarge
+/// [`UnexpectedResponse`]: I5RequestError::UnexpectedResponse
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum I5RequestError {
     /// The i5Request Object validation.
     ValidationError,
@@ -27,6 +56,124 @@ pub enum I5RequestError {
     ///
     /// Contains the original [`reqwest::Error`].
     RequestError(reqwest::Error),
+
+    /// Interface5 rejected the request with a non-success status.
+    ///
+    /// Contains the parsed [`I5ServerError`].
+    ServerError(I5ServerError),
+
+    /// Reading or writing a local file (e.g. the audit log) failed.
+    ///
+    /// Contains the original [`std::io::Error`].
+    IoError(std::io::Error),
+
+    /// Loading credentials (e.g. from the OS credential store) failed.
+    ///
+    /// Contains a description of the failure.
+    CredentialError(String),
+
+    /// The client configuration is missing or invalid.
+    ///
+    /// Contains a description naming the offending setting.
+    ConfigError(String),
+
+    /// Request data (e.g. JSON) could not be converted into an i5Request.
+    ///
+    /// Contains a description naming the path of the offending value.
+    ConversionError(String),
+
+    /// The serialized request does not match the Interface5 contract.
+    ///
+    /// Contains the violations, each naming the path of the offending value.
+    ContractError(String),
+
+    /// The request is larger than the configured
+    /// [maximum request size](crate::types::i5_config::I5Config::max_request_size).
+    ///
+    /// Contains the estimated size of the encoded request and the limit, in bytes.
+    PayloadTooLarge { size: u64, limit: u64 },
+
+    /// Interface5 accepted the request, but its response does not have the shape the
+    /// API version defines, see
+    /// [`I5SendOptions::check_response`](crate::request::options::I5SendOptions::check_response).
+    ///
+    /// Contains the status of the response and the violations, each naming the path
+    /// of the offending value.
+    UnexpectedResponse { status: u16, details: String },
+}
+
+impl I5RequestError {
+    /// Returns the stable string code of this error.
+    ///
+    /// Codes are never reused or renamed, so they can be stored in tickets,
+    /// dashboards or log indexes.
+    ///
+    /// | Code                     | Numeric | Variant                                |
+    /// |--------------------------|---------|----------------------------------------|
+    /// | `I5_VALIDATION`          | 1000    | [`I5RequestError::ValidationError`]    |
+    /// | `I5_SERIALIZE`           | 2000    | [`I5RequestError::SerializeError`]     |
+    /// | `I5_REQUEST`             | 3000    | [`I5RequestError::RequestError`]       |
+    /// | `I5_SERVER`              | 4000    | [`I5RequestError::ServerError`]        |
+    /// | `I5_IO`                  | 5000    | [`I5RequestError::IoError`]            |
+    /// | `I5_CREDENTIAL`          | 6000    | [`I5RequestError::CredentialError`]    |
+    /// | `I5_CONFIG`              | 7000    | [`I5RequestError::ConfigError`]        |
+    /// | `I5_CONVERSION`          | 8000    | [`I5RequestError::ConversionError`]    |
+    /// | `I5_CONTRACT`            | 9000    | [`I5RequestError::ContractError`]      |
+    /// | `I5_PAYLOAD_TOO_LARGE`   | 10000   | [`I5RequestError::PayloadTooLarge`]    |
+    /// | `I5_UNEXPECTED_RESPONSE` | 11000   | [`I5RequestError::UnexpectedResponse`] |
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_error::I5RequestError;
+    ///
+    /// let err = I5RequestError::ValidationError;
+    /// assert_eq!(err.code(), "I5_VALIDATION");
+    /// assert_eq!(err.numeric_code(), 1000);
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ValidationError => "I5_VALIDATION",
+            Self::SerializeError(_) => "I5_SERIALIZE",
+            Self::RequestError(_) => "I5_REQUEST",
+            Self::ServerError(_) => "I5_SERVER",
+            Self::IoError(_) => "I5_IO",
+            Self::CredentialError(_) => "I5_CREDENTIAL",
+            Self::ConfigError(_) => "I5_CONFIG",
+            Self::ConversionError(_) => "I5_CONVERSION",
+            Self::ContractError(_) => "I5_CONTRACT",
+            Self::PayloadTooLarge { .. } => "I5_PAYLOAD_TOO_LARGE",
+            Self::UnexpectedResponse { .. } => "I5_UNEXPECTED_RESPONSE",
+        }
+    }
+
+    /// Returns the stable numeric code of this error.
+    ///
+    /// See [`I5RequestError::code`] for the full code table.
+    pub fn numeric_code(&self) -> u16 {
+        match self {
+            Self::ValidationError => 1000,
+            Self::SerializeError(_) => 2000,
+            Self::RequestError(_) => 3000,
+            Self::ServerError(_) => 4000,
+            Self::IoError(_) => 5000,
+            Self::CredentialError(_) => 6000,
+            Self::ConfigError(_) => 7000,
+            Self::ConversionError(_) => 8000,
+            Self::ContractError(_) => 9000,
+            Self::PayloadTooLarge { .. } => 10000,
+            Self::UnexpectedResponse { .. } => 11000,
+        }
+    }
+
+    /// The message of this error from `catalog`, or its English text if the catalog
+    /// has none, see [`i5_locale`](crate::types::i5_locale).
+    ///
+    /// The [codes](I5RequestError::code) are the same in every language.
+    #[cfg(feature = "l10n")]
+    pub fn localized(&self, catalog: &dyn I5MessageCatalog) -> String {
+        catalog.error(self).unwrap_or_else(|| self.to_string())
+    }
 }
 
 impl Display for I5RequestError {
@@ -37,6 +184,24 @@ impl Display for I5RequestError {
             Self::RequestError(err) => {
                 write!(f, "Failed posting Body to Interface5: {}", err)
             }
+            Self::ServerError(err) => write!(f, "{}", err),
+            Self::IoError(err) => write!(f, "Failed accessing local file: {}", err),
+            Self::CredentialError(err) => write!(f, "Failed loading credentials: {}", err),
+            Self::ConfigError(err) => write!(f, "Invalid configuration: {}", err),
+            Self::ConversionError(err) => write!(f, "Invalid request data: {}", err),
+            Self::ContractError(err) => {
+                write!(f, "Request violates the Interface5 contract: {}", err)
+            }
+            Self::PayloadTooLarge { size, limit } => write!(
+                f,
+                "Request of about {} bytes exceeds the limit of {} bytes",
+                size, limit
+            ),
+            Self::UnexpectedResponse { status, details } => write!(
+                f,
+                "Interface5 answered {} with an unexpected response: {}",
+                status, details
+            ),
         }
     }
 }
@@ -0,0 +1,238 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::types::{
+    i5_discovery,
+    i5_request::I5Request,
+    i5_validation::{I5ValidationIssue, I5ValidationReport},
+};
+
+/// The definition of an index field of a scenario.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5FieldDefinition {
+    name: String,
+    required: bool,
+    max_length: Option<usize>,
+    item: bool,
+    data_type: Option<String>,
+}
+
+impl I5FieldDefinition {
+    /// Defines an optional header field without a length limit.
+    pub fn new(name: impl Into<String>) -> I5FieldDefinition {
+        I5FieldDefinition {
+            name: name.into(),
+            required: false,
+            max_length: None,
+            item: false,
+            data_type: None,
+        }
+    }
+
+    /// Whether every document must have the field; for item fields, every item.
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// The maximum length of a value in characters.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Whether the field belongs to the items rather than the header.
+    pub fn item(mut self, item: bool) -> Self {
+        self.item = item;
+        self
+    }
+
+    /// The data type reported by the server, e.g. `String` or `Date`.
+    pub fn data_type(mut self, data_type: impl Into<String>) -> Self {
+        self.data_type = Some(data_type.into());
+        self
+    }
+
+    /// The field name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether every document must have the field.
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// The maximum length of a value in characters, if limited.
+    pub fn max_len(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    /// Whether the field belongs to the items.
+    pub fn is_item(&self) -> bool {
+        self.item
+    }
+
+    /// The data type reported by the server, if any.
+    pub fn kind(&self) -> Option<&str> {
+        self.data_type.as_deref()
+    }
+}
+
+/// The index fields of a scenario, to check requests locally before they are sent.
+///
+/// Fetched from the server with
+/// [`I5Client::get_field_definitions`](crate::request::client::I5Client::get_field_definitions)
+/// or built from a list of [`I5FieldDefinition`]s.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{
+///     i5_field_definition::{I5FieldDefinition, I5FieldDefinitions},
+///     i5_request::I5Request,
+///     i5_validation::I5ValidationIssue,
+/// };
+///
+/// let definitions: I5FieldDefinitions = [
+///     I5FieldDefinition::new("InvoiceNo").required(true).max_length(10),
+///     I5FieldDefinition::new("Article").item(true),
+/// ]
+/// .into_iter()
+/// .collect();
+///
+/// let mut request = I5Request::new("Batch");
+/// let id = request.add_document("Invoice");
+/// request
+///     .get_document_mut(id)
+///     .unwrap()
+///     .add_header_field("Vendor", "ACME")
+///     .add_item_field("Article", "A-1", 1);
+///
+/// let report = definitions.validate(&request);
+/// assert_eq!(
+///     report.issues(),
+///     [
+///         I5ValidationIssue::UnknownField { document: 0, field: "Vendor".into() },
+///         I5ValidationIssue::MissingField { document: 0, field: "InvoiceNo".into() },
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5FieldDefinitions {
+    definitions: Vec<I5FieldDefinition>,
+    index: HashMap<String, usize>,
+}
+
+impl I5FieldDefinitions {
+    /// The definitions, in server order.
+    pub fn definitions(&self) -> &[I5FieldDefinition] {
+        &self.definitions
+    }
+
+    /// The definition of the field `name`, compared ignoring case.
+    pub fn get(&self, name: &str) -> Option<&I5FieldDefinition> {
+        self.index
+            .get(&name.to_lowercase())
+            .map(|&index| &self.definitions[index])
+    }
+
+    /// Checks `request` against the definitions, in addition to
+    /// [`I5Request::validation_report`].
+    ///
+    /// Reports fields the scenario does not define, required fields that are missing
+    /// and values that are too long.
+    pub fn validate(&self, request: &I5Request) -> I5ValidationReport {
+        let mut report = request.validation_report();
+        for (index, document) in request.documents().iter().enumerate() {
+            for field in document.fields() {
+                match self.get(field.name()) {
+                    None => report.push(I5ValidationIssue::UnknownField {
+                        document: index,
+                        field: field.name().to_string(),
+                    }),
+                    Some(definition) => {
+                        if let Some(max_length) = definition.max_length
+                            && field.value().chars().count() > max_length
+                        {
+                            report.push(I5ValidationIssue::ValueTooLong {
+                                document: index,
+                                field: field.name().to_string(),
+                                max_length,
+                            });
+                        }
+                    }
+                }
+            }
+
+            for definition in self.definitions.iter().filter(|d| d.required) {
+                let present = |item: i32| {
+                    document.fields().iter().any(|field| {
+                        field.item_number() == item
+                            && field.name().eq_ignore_ascii_case(&definition.name)
+                    })
+                };
+                let missing = if definition.item {
+                    (1..=document.max_item_number()).any(|item| !present(item))
+                } else {
+                    !present(0)
+                };
+                if missing {
+                    report.push(I5ValidationIssue::MissingField {
+                        document: index,
+                        field: definition.name.clone(),
+                    });
+                }
+            }
+        }
+        report
+    }
+
+    /// Reads the definitions from a lookup response.
+    ///
+    /// The response is read leniently: a list, or an object whose first list value is
+    /// taken, of names or of objects with a `Name` and optionally `Required` (or
+    /// `Mandatory`), `MaxLength`, `Item` (or `IsItem`) and `Type` (or `DataType`), with
+    /// keys matched ignoring case.
+    pub(crate) fn from_json(value: &Value) -> I5FieldDefinitions {
+        i5_discovery::list(value)
+            .filter_map(|entry| match entry {
+                Value::String(name) => Some(I5FieldDefinition::new(name.as_str())),
+                Value::Object(object) => {
+                    let key = |keys: &[&str]| i5_discovery::key(object, keys);
+                    let flag = |keys: &[&str]| key(keys).and_then(Value::as_bool).unwrap_or(false);
+                    let name = key(&["Name", "Id"]).and_then(Value::as_str)?;
+                    Some(I5FieldDefinition {
+                        name: name.to_string(),
+                        required: flag(&["Required", "Mandatory", "IsRequired", "IsMandatory"]),
+                        max_length: key(&["MaxLength", "Length"])
+                            .and_then(Value::as_u64)
+                            .and_then(|length| usize::try_from(length).ok()),
+                        item: flag(&["Item", "IsItem", "ItemField", "IsPosition"]),
+                        data_type: key(&["Type", "DataType"])
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl FromIterator<I5FieldDefinition> for I5FieldDefinitions {
+    fn from_iter<I: IntoIterator<Item = I5FieldDefinition>>(iter: I) -> I5FieldDefinitions {
+        let mut definitions = I5FieldDefinitions::default();
+        for definition in iter {
+            let key = definition.name.to_lowercase();
+            match definitions.index.get(&key) {
+                Some(&index) => definitions.definitions[index] = definition,
+                None => {
+                    definitions.index.insert(key, definitions.definitions.len());
+                    definitions.definitions.push(definition);
+                }
+            }
+        }
+        definitions
+    }
+}
@@ -0,0 +1,77 @@
+/// A table translating source field names to Interface5 field names.
+///
+/// Source names are matched ignoring ASCII case, and fields without an entry keep
+/// their name. The mapping is applied to every document sent through an
+/// [`I5Client`](crate::request::client::I5Client) configured with
+/// [`I5Config::field_mapping`](crate::types::i5_config::I5Config::field_mapping), before
+/// default fields and transformations, or explicitly with
+/// [`I5Request::apply_field_mapping`].
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_field_mapping::I5FieldMapping;
+///
+/// let mapping = I5FieldMapping::new()
+///     .map("KUNNR", "CustomerNo")
+///     .map("BELNR", "InvoiceNo");
+///
+/// assert_eq!(mapping.rename("kunnr"), "CustomerNo");
+/// assert_eq!(mapping.rename("Amount"), "Amount");
+/// ```
+///
+/// [`I5Request::apply_field_mapping`]: crate::types::i5_request::I5Request::apply_field_mapping
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5FieldMapping {
+    entries: Vec<(String, String)>,
+}
+
+impl I5FieldMapping {
+    /// Creates an empty mapping.
+    pub fn new() -> I5FieldMapping {
+        I5FieldMapping::default()
+    }
+
+    /// Maps the source field `source` to the Interface5 field `target`, replacing an
+    /// earlier entry for the same source name.
+    pub fn map(mut self, source: impl Into<String>, target: impl Into<String>) -> Self {
+        let source = source.into();
+        let target = target.into();
+        match self
+            .entries
+            .iter_mut()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(&source))
+        {
+            Some(entry) => entry.1 = target,
+            None => self.entries.push((source, target)),
+        }
+        self
+    }
+
+    /// Returns `true` if the mapping has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The Interface5 name for the source field `name`, if it is mapped.
+    pub fn target(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(source, _)| source.eq_ignore_ascii_case(name))
+            .map(|(_, target)| target.as_str())
+    }
+
+    /// The Interface5 name for the source field `name`, or `name` if it is not mapped.
+    pub fn rename<'a>(&'a self, name: &'a str) -> &'a str {
+        self.target(name).unwrap_or(name)
+    }
+}
+
+impl<S: Into<String>, T: Into<String>> FromIterator<(S, T)> for I5FieldMapping {
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(I5FieldMapping::new(), |mapping, (source, target)| {
+                mapping.map(source, target)
+            })
+    }
+}
@@ -0,0 +1,42 @@
+/// The attributes of a field added with [`Document::add_field`], besides its name and
+/// value.
+///
+/// The options start out as a header field; attributes are set with the chained
+/// methods. The struct cannot be built literally, so attributes that Interface5 field
+/// entries gain later can be added here without changing the signature of
+/// [`Document::add_field`] or of code calling it.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{i5_field_options::I5FieldOptions, i5_request::I5Request};
+///
+/// let mut request = I5Request::new("Batch");
+/// let id = request.add_document("Invoice");
+/// let document = request.get_document_mut(id).unwrap();
+/// document.add_field("InvoiceNo", "4711", I5FieldOptions::new());
+/// document.add_field("Quantity", 120, I5FieldOptions::new().item_number(1));
+///
+/// assert_eq!(document.fields()[0].item_number(), 0);
+/// assert_eq!(document.fields()[1].item_number(), 1);
+/// ```
+///
+/// [`Document::add_field`]: crate::types::i5_request::Document::add_field
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct I5FieldOptions {
+    pub(crate) item_number: i32,
+}
+
+impl I5FieldOptions {
+    /// Creates the options of a header field.
+    pub fn new() -> I5FieldOptions {
+        I5FieldOptions::default()
+    }
+
+    /// Adds the field to item `item_number`, `0` for a header field.
+    pub fn item_number(mut self, item_number: i32) -> Self {
+        self.item_number = item_number;
+        self
+    }
+}
@@ -0,0 +1,158 @@
+use serde::Deserialize;
+
+use crate::util;
+
+/// Rules deciding whether a field is included in a document.
+///
+/// Field names and expected values are matched by pattern (`*` and `?` wildcards,
+/// ignoring case). A field is dropped if any rule matching its name rejects it:
+///
+/// - [`omit_if_empty`](I5FieldRules::omit_if_empty) drops fields whose value is empty
+///   or only whitespace.
+/// - [`include_only_if`](I5FieldRules::include_only_if) drops fields unless another
+///   field of the same document matches a value. For item fields the other field is
+///   looked up in the same item first and in the header fields second.
+///
+/// The rules are applied to every document sent through an
+/// [`I5Client`](crate::request::client::I5Client) configured with
+/// [`I5Config::field_rules`](crate::types::i5_config::I5Config::field_rules), after
+/// transformations, or explicitly with [`I5Request::apply_field_rules`].
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{i5_field_rules::I5FieldRules, i5_request::I5Request};
+///
+/// let rules = I5FieldRules::new()
+///     .omit_if_empty("*")
+///     .include_only_if("VatId", "Country", "DE");
+///
+/// let mut request = I5Request::new("Batch");
+/// let id = request.add_document("Invoice");
+/// request
+///     .get_document_mut(id)
+///     .unwrap()
+///     .add_header_field("Country", "AT")
+///     .add_header_field("VatId", "ATU12345678")
+///     .add_header_field("Discount", " ");
+/// request.apply_field_rules(&rules);
+///
+/// let json = request.validate()?.to_json_string()?;
+/// assert!(!json.contains("VatId"));
+/// assert!(!json.contains("Discount"));
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+///
+/// [`I5Request::apply_field_rules`]: crate::types::i5_request::I5Request::apply_field_rules
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5FieldRules {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rule {
+    OmitIfEmpty {
+        field: String,
+    },
+    IncludeOnlyIf {
+        field: String,
+        other: String,
+        matches: String,
+    },
+}
+
+impl I5FieldRules {
+    /// Creates an empty set of rules that keeps every field.
+    pub fn new() -> I5FieldRules {
+        I5FieldRules::default()
+    }
+
+    /// Drops fields matching `field` whose value is empty or only whitespace.
+    pub fn omit_if_empty(mut self, field: impl Into<String>) -> Self {
+        self.rules.push(Rule::OmitIfEmpty {
+            field: field.into(),
+        });
+        self
+    }
+
+    /// Keeps fields matching `field` only if the field `other` of the same document
+    /// has a value matching `matches`.
+    pub fn include_only_if(
+        mut self,
+        field: impl Into<String>,
+        other: impl Into<String>,
+        matches: impl Into<String>,
+    ) -> Self {
+        self.rules.push(Rule::IncludeOnlyIf {
+            field: field.into(),
+            other: other.into(),
+            matches: matches.into(),
+        });
+        self
+    }
+
+    /// Returns `true` if no rules are configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Decides whether the field `name` with `value` is kept.
+    ///
+    /// `lookup` returns the value of another field of the same document by name.
+    pub(crate) fn keeps<'a>(
+        &self,
+        name: &str,
+        value: &str,
+        lookup: impl Fn(&str) -> Option<&'a str>,
+    ) -> bool {
+        self.rules.iter().all(|rule| match rule {
+            Rule::OmitIfEmpty { field } => {
+                !util::wildcard_match(field, name) || !value.trim().is_empty()
+            }
+            Rule::IncludeOnlyIf {
+                field,
+                other,
+                matches,
+            } => {
+                !util::wildcard_match(field, name)
+                    || lookup(other).is_some_and(|other| util::wildcard_match(matches, other))
+            }
+        })
+    }
+}
+
+/// A rule as written in the `rules` array of a configuration file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RuleFile {
+    field: String,
+    #[serde(default)]
+    omit_if_empty: bool,
+    when: Option<ConditionFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConditionFile {
+    field: String,
+    matches: String,
+}
+
+impl RuleFile {
+    /// Adds the rules described by this entry to `rules`.
+    pub(crate) fn add_to(self, mut rules: I5FieldRules) -> Result<I5FieldRules, String> {
+        if !self.omit_if_empty && self.when.is_none() {
+            return Err(format!(
+                "rules: the rule for '{}' needs omit_if_empty or when",
+                self.field
+            ));
+        }
+        if self.omit_if_empty {
+            rules = rules.omit_if_empty(self.field.clone());
+        }
+        if let Some(when) = self.when {
+            rules = rules.include_only_if(self.field, when.field, when.matches);
+        }
+        Ok(rules)
+    }
+}
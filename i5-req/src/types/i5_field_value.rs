@@ -0,0 +1,106 @@
+use std::borrow::Cow;
+
+use crate::types::{
+    i5_amount::I5Amount,
+    i5_date::{I5Date, I5DateFormat},
+};
+
+/// A value that can be written into a field.
+///
+/// Implemented for strings, `char`, integers, floats, `bool`, [`I5Amount`] (written
+/// like its `Display` output), [`I5Date`] (written as ISO 8601) and `Option<T>`.
+/// `None` yields no value, so the field is skipped.
+///
+/// Floats are written in their shortest exact form, e.g. `0.1 + 0.2` as
+/// `0.30000000000000004`; use [`I5Amount`] for monetary values.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{i5_amount::I5Amount, i5_date::I5Date, i5_request::I5Request};
+///
+/// let discount: Option<f64> = None;
+///
+/// let mut request = I5Request::new("Batch");
+/// let id = request.add_document("Invoice");
+/// let document = request.get_document_mut(id).unwrap();
+/// document
+///     .add_header_field("InvoiceNo", 4711)
+///     .add_header_field("Amount", 1234.56)
+///     .add_header_field("Net", I5Amount::new(103700, 2))
+///     .add_header_field("Paid", true)
+///     .add_header_field("Date", I5Date::new(2025, 6, 27).unwrap())
+///     .add_header_field("Discount", discount);
+///
+/// let values: Vec<&str> = document.fields().iter().map(|field| field.value()).collect();
+/// assert_eq!(values, ["4711", "1234.56", "1037.00", "true", "2025-06-27"]);
+/// ```
+pub trait I5FieldValue {
+    /// Converts the value into its field representation, or `None` to skip the field.
+    fn into_field_value(self) -> Option<String>;
+}
+
+impl I5FieldValue for String {
+    fn into_field_value(self) -> Option<String> {
+        Some(self)
+    }
+}
+
+impl I5FieldValue for &str {
+    fn into_field_value(self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+impl I5FieldValue for &String {
+    fn into_field_value(self) -> Option<String> {
+        Some(self.clone())
+    }
+}
+
+impl I5FieldValue for Cow<'_, str> {
+    fn into_field_value(self) -> Option<String> {
+        Some(self.into_owned())
+    }
+}
+
+impl I5FieldValue for char {
+    fn into_field_value(self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+impl I5FieldValue for I5Amount {
+    fn into_field_value(self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+impl I5FieldValue for I5Date {
+    fn into_field_value(self) -> Option<String> {
+        Some(self.format(I5DateFormat::Iso8601))
+    }
+}
+
+impl<T: I5FieldValue> I5FieldValue for Option<T> {
+    fn into_field_value(self) -> Option<String> {
+        self.and_then(I5FieldValue::into_field_value)
+    }
+}
+
+/// Implements [`I5FieldValue`] through `Display`.
+macro_rules! display_field_value {
+    ($($type:ty),*) => {
+        $(
+            impl I5FieldValue for $type {
+                fn into_field_value(self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+display_field_value!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool
+);
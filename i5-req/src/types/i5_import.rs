@@ -0,0 +1,364 @@
+//! Importers for the index formats of legacy capture systems.
+//!
+//! Migration projects can read the batches released by an older capture system with
+//! [`I5CsvImport`], for a CSV index with one document per row, or [`I5KofaxImport`],
+//! for the XML written by the Kofax Capture XML release script. Both construct
+//! unvalidated [`I5Request`]s, so mapping and validation work as for any other batch.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    types::{
+        i5_error::I5RequestError,
+        i5_request::{Document, I5Request},
+    },
+    xml,
+};
+
+/// Reads a CSV index with a header row and one document per row into an
+/// [`I5Request`].
+///
+/// The header names the fields; every other row becomes a document with a header
+/// field per non-empty cell. The cells of the [file column](I5CsvImport::file_column)
+/// name the image of the document, e.g. the TIFF next to the index, instead. Quoted
+/// cells may contain the delimiter, doubled quotes and line breaks.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_import::I5CsvImport;
+///
+/// let csv = "InvoiceNo;Amount;Image\n4711;\"1.234,50\";\n4712;99,00;\n";
+/// let request = I5CsvImport::new("Invoice")
+///     .delimiter(';')
+///     .file_column("Image")
+///     .read_str("Batch", csv, ".")?;
+///
+/// assert_eq!(request.document_count(), 2);
+/// let fields = request.documents()[0].fields();
+/// assert_eq!((fields[1].name(), fields[1].value()), ("Amount", "1.234,50"));
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5CsvImport {
+    document_name: String,
+    delimiter: char,
+    file_column: Option<String>,
+}
+
+impl I5CsvImport {
+    /// Creates an importer naming every document `document_name`, for comma-separated
+    /// files without a file column.
+    pub fn new(document_name: impl Into<String>) -> I5CsvImport {
+        I5CsvImport {
+            document_name: document_name.into(),
+            delimiter: ',',
+            file_column: None,
+        }
+    }
+
+    /// The character separating the cells, e.g. `;` for exports of German systems.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// The column, matched ignoring case, holding the path of the file of each
+    /// document. Relative paths are resolved against the directory of the index.
+    pub fn file_column(mut self, column: impl Into<String>) -> Self {
+        self.file_column = Some(column.into());
+        self
+    }
+
+    /// Reads the index at `path`, naming the request after the file without its
+    /// extension.
+    ///
+    /// Returns [`I5RequestError::IoError`] if the index or a file cannot be read.
+    pub fn read(&self, path: impl AsRef<Path>) -> Result<I5Request, I5RequestError> {
+        let path = path.as_ref();
+        let csv = fs::read_to_string(path).map_err(I5RequestError::IoError)?;
+        self.read_str(&batch_name(path), &csv, base_directory(path))
+    }
+
+    /// Reads the index `csv` into a request named `name`, resolving relative file
+    /// paths against `base`.
+    ///
+    /// Returns [`I5RequestError::ConversionError`] naming the line of a row with more
+    /// cells than the header or of an unterminated quote, and
+    /// [`I5RequestError::IoError`] if a file cannot be read.
+    pub fn read_str(
+        &self,
+        name: &str,
+        csv: &str,
+        base: impl AsRef<Path>,
+    ) -> Result<I5Request, I5RequestError> {
+        let mut rows = csv::rows(csv.trim_start_matches('\u{feff}'), self.delimiter)?.into_iter();
+        let Some((_, header)) = rows.next() else {
+            return Err(I5RequestError::ConversionError(String::from(
+                "the CSV index has no header row",
+            )));
+        };
+        let file_column = self.file_column.as_deref().and_then(|column| {
+            header
+                .iter()
+                .position(|name| name.trim().eq_ignore_ascii_case(column))
+        });
+
+        let mut request = I5Request::new(name);
+        for (line, row) in rows {
+            if row.iter().all(|cell| cell.is_empty()) {
+                continue;
+            }
+            if row.len() > header.len() {
+                return Err(I5RequestError::ConversionError(format!(
+                    "line {}: {} cells, but the header has {} columns",
+                    line,
+                    row.len(),
+                    header.len()
+                )));
+            }
+            let id = request.add_document(self.document_name.as_str());
+            let document = request
+                .get_document_mut(id)
+                .expect("document was just added");
+            for (column, cell) in row.iter().enumerate() {
+                if cell.is_empty() {
+                    continue;
+                }
+                if Some(column) == file_column {
+                    document.add_path_file(resolve(base.as_ref(), cell))?;
+                } else {
+                    document.add_header_field(header[column].trim(), cell.as_str());
+                }
+            }
+        }
+        Ok(request)
+    }
+}
+
+/// Reads the batches released by the Kofax Capture XML release script.
+///
+/// Every `Batch` element becomes a request named after its `Name` attribute, every
+/// `Document` a document named after its `FormTypeName`. The `IndexField`s of a
+/// document become header fields, the rows of its `Table`s item fields numbered from
+/// one, and the `ImportFileName` of every `Page` is added as a file. Element and
+/// attribute names are matched ignoring case; unknown elements are skipped.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_import::I5KofaxImport;
+///
+/// let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <ImportSession>
+///   <Batches>
+///     <Batch Name="Scan 2024-05-02" BatchClassName="AP">
+///       <Documents>
+///         <Document FormTypeName="Invoice">
+///           <IndexFields>
+///             <IndexField Name="Vendor" Value="Smith &amp; Sons" />
+///           </IndexFields>
+///           <Tables>
+///             <Table Name="Lines">
+///               <TableRows>
+///                 <TableRow>
+///                   <IndexFields><IndexField Name="Article" Value="A-1" /></IndexFields>
+///                 </TableRow>
+///               </TableRows>
+///             </Table>
+///           </Tables>
+///         </Document>
+///       </Documents>
+///     </Batch>
+///   </Batches>
+/// </ImportSession>"#;
+///
+/// let requests = I5KofaxImport::new().read_str(xml, ".")?;
+/// assert_eq!(requests[0].name(), "Scan 2024-05-02");
+/// let document = &requests[0].documents()[0];
+/// assert_eq!(document.name(), "Invoice");
+/// assert_eq!(document.fields()[0].value(), "Smith & Sons");
+/// assert_eq!(document.fields()[1].item_number(), 1);
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5KofaxImport {
+    skip_empty: bool,
+}
+
+impl I5KofaxImport {
+    /// Creates an importer that keeps index fields with empty values.
+    pub fn new() -> I5KofaxImport {
+        I5KofaxImport::default()
+    }
+
+    /// Skips index fields with empty values, which Kofax writes for every field of the
+    /// document class.
+    pub fn skip_empty_fields(mut self, skip: bool) -> Self {
+        self.skip_empty = skip;
+        self
+    }
+
+    /// Reads the release file at `path`, resolving relative page paths against its
+    /// directory.
+    ///
+    /// Returns [`I5RequestError::IoError`] if the release file or a page cannot be
+    /// read.
+    pub fn read(&self, path: impl AsRef<Path>) -> Result<Vec<I5Request>, I5RequestError> {
+        let path = path.as_ref();
+        let xml = fs::read_to_string(path).map_err(I5RequestError::IoError)?;
+        self.read_str(&xml, base_directory(path))
+    }
+
+    /// Reads the release XML `xml`, resolving relative page paths against `base`.
+    ///
+    /// Returns [`I5RequestError::ConversionError`] if the XML is malformed or a batch
+    /// has no name, and [`I5RequestError::IoError`] if a page cannot be read.
+    pub fn read_str(
+        &self,
+        xml: &str,
+        base: impl AsRef<Path>,
+    ) -> Result<Vec<I5Request>, I5RequestError> {
+        let root = xml::parse(xml)?;
+        let mut requests = Vec::new();
+        for batch in root.descendants("Batch") {
+            let name = batch.attribute("Name").ok_or_else(|| {
+                I5RequestError::ConversionError(String::from("a Batch has no Name attribute"))
+            })?;
+            let mut request = I5Request::new(name);
+            for source in batch.descendants("Document") {
+                let id =
+                    request.add_document(source.attribute("FormTypeName").unwrap_or("Document"));
+                let document = request
+                    .get_document_mut(id)
+                    .expect("document was just added");
+                self.read_document(source, document, base.as_ref())?;
+            }
+            requests.push(request);
+        }
+        Ok(requests)
+    }
+
+    fn read_document(
+        &self,
+        source: &xml::Element,
+        document: &mut Document,
+        base: &Path,
+    ) -> Result<(), I5RequestError> {
+        for fields in source.children("IndexFields") {
+            self.read_fields(fields, document, 0);
+        }
+        let mut item_number = 0;
+        for tables in source.children("Tables") {
+            for table in tables.children("Table") {
+                for row in table.descendants("TableRow") {
+                    item_number += 1;
+                    for fields in row.children("IndexFields") {
+                        self.read_fields(fields, document, item_number);
+                    }
+                }
+            }
+        }
+        for page in source.descendants("Page") {
+            if let Some(file) = page.attribute("ImportFileName") {
+                document.add_path_file(resolve(base, file))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_fields(&self, fields: &xml::Element, document: &mut Document, item_number: i32) {
+        for field in fields.children("IndexField") {
+            let (Some(name), value) = (field.attribute("Name"), field.attribute("Value")) else {
+                continue;
+            };
+            let value = value.unwrap_or_default();
+            if !(self.skip_empty && value.is_empty()) {
+                document.add_item_field(name, value, item_number);
+            }
+        }
+    }
+}
+
+/// The file name of `path` without its extension.
+fn batch_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// The directory of `path`, against which relative file paths are resolved.
+fn base_directory(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new("."))
+}
+
+/// `file` resolved against `base` if it is relative.
+fn resolve(base: &Path, file: &str) -> PathBuf {
+    base.join(file.trim())
+}
+
+/// A CSV reader following RFC 4180.
+mod csv {
+    use crate::types::i5_error::I5RequestError;
+
+    /// The rows of `text` with the line each starts on, counted from one.
+    pub(super) fn rows(
+        text: &str,
+        delimiter: char,
+    ) -> Result<Vec<(usize, Vec<String>)>, I5RequestError> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut cell = String::new();
+        let (mut line, mut start) = (1, 1);
+        let (mut quoted, mut was_quoted) = (false, false);
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        cell.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                }
+                '"' if cell.is_empty() && !was_quoted => (quoted, was_quoted) = (true, true),
+                '\n' | '\r' if !quoted => {
+                    if c == '\r' && chars.peek() == Some(&'\n') {
+                        chars.next();
+                    }
+                    row.push(std::mem::take(&mut cell));
+                    rows.push((start, std::mem::take(&mut row)));
+                    was_quoted = false;
+                    line += 1;
+                    start = line;
+                }
+                c if c == delimiter && !quoted => {
+                    row.push(std::mem::take(&mut cell));
+                    was_quoted = false;
+                }
+                c => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    cell.push(c);
+                }
+            }
+        }
+        if quoted {
+            return Err(I5RequestError::ConversionError(format!(
+                "line {}: unterminated quoted cell",
+                start
+            )));
+        }
+        if !cell.is_empty() || !row.is_empty() || was_quoted {
+            row.push(cell);
+            rows.push((start, row));
+        }
+        Ok(rows)
+    }
+}
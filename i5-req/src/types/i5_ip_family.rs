@@ -0,0 +1,82 @@
+use std::{fmt, net::SocketAddr, str::FromStr};
+
+use crate::types::i5_error::I5RequestError;
+
+/// Which IP addresses of a dual-stack host the clients connect to, set with
+/// [`I5Config::ip_family`].
+///
+/// Connections are attempted in the order of the resolved addresses, and the other
+/// family is tried in parallel if the first one did not connect within 300
+/// milliseconds ("happy eyeballs"). Preferring a family moves its addresses to the
+/// front; restricting to a family drops the others, so a black-holed route is never
+/// tried at all.
+///
+/// [`I5Config::ip_family`]: crate::types::i5_config::I5Config::ip_family
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum I5IpFamily {
+    /// The addresses in the order of the system resolver.
+    #[default]
+    Any,
+    /// IPv4 addresses first, IPv6 as the fallback.
+    PreferIpv4,
+    /// IPv6 addresses first, IPv4 as the fallback.
+    PreferIpv6,
+    /// IPv4 addresses only.
+    Ipv4Only,
+    /// IPv6 addresses only.
+    Ipv6Only,
+}
+
+impl I5IpFamily {
+    /// The family as written in configuration, e.g. `prefer-ipv4`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            I5IpFamily::Any => "any",
+            I5IpFamily::PreferIpv4 => "prefer-ipv4",
+            I5IpFamily::PreferIpv6 => "prefer-ipv6",
+            I5IpFamily::Ipv4Only => "ipv4",
+            I5IpFamily::Ipv6Only => "ipv6",
+        }
+    }
+
+    /// Reorders or filters the resolved `addrs` for this family.
+    pub(crate) fn arrange(self, mut addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+        match self {
+            I5IpFamily::Any => {}
+            I5IpFamily::PreferIpv4 => addrs.sort_by_key(SocketAddr::is_ipv6),
+            I5IpFamily::PreferIpv6 => addrs.sort_by_key(SocketAddr::is_ipv4),
+            I5IpFamily::Ipv4Only => addrs.retain(SocketAddr::is_ipv4),
+            I5IpFamily::Ipv6Only => addrs.retain(SocketAddr::is_ipv6),
+        }
+        addrs
+    }
+}
+
+impl FromStr for I5IpFamily {
+    type Err = I5RequestError;
+
+    /// Parses `any`, `prefer-ipv4`, `prefer-ipv6`, `ipv4` or `ipv6`, ignoring case and
+    /// accepting `_` for `-`.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] for unknown families.
+    fn from_str(text: &str) -> Result<I5IpFamily, I5RequestError> {
+        match text.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "any" => Ok(I5IpFamily::Any),
+            "prefer-ipv4" => Ok(I5IpFamily::PreferIpv4),
+            "prefer-ipv6" => Ok(I5IpFamily::PreferIpv6),
+            "ipv4" | "ipv4-only" => Ok(I5IpFamily::Ipv4Only),
+            "ipv6" | "ipv6-only" => Ok(I5IpFamily::Ipv6Only),
+            _ => Err(I5RequestError::ConfigError(format!(
+                "unknown IP family '{}', expected any, prefer-ipv4, prefer-ipv6, ipv4 or ipv6",
+                text
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for I5IpFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,235 @@
+//! Access to the operating system credential store.
+//!
+//! Entries are looked up by service name and account, using the same conventions as
+//! the `keyring` crate, so credentials stored by other tools can be shared:
+//!
+//! - **macOS**: the login Keychain, a generic password with the given service and
+//!   account (`security add-generic-password -s <service> -a <account> -w`).
+//! - **Windows**: the Credential Manager, a generic credential with the target name
+//!   `<account>.<service>`.
+//! - **Linux and other Unix systems**: the Secret Service (GNOME Keyring, KWallet),
+//!   an item with the attributes `service` and `username`
+//!   (`secret-tool store --label=... service <service> username <account>`).
+
+use crate::types::{i5_error::I5RequestError, i5_secret::I5Secret};
+
+/// Reads the secret stored for `service` and `account`.
+///
+/// Returns [`I5RequestError::CredentialError`] if the entry does not exist or the
+/// credential store cannot be accessed, and [`I5RequestError::ConfigError`] if the
+/// stored secret is not text.
+pub fn read_secret(service: &str, account: &str) -> Result<I5Secret, I5RequestError> {
+    platform::read_secret(service, account).map(I5Secret::new)
+}
+
+/// The secret a command line tool printed for `entry`, e.g. "Keychain entry for
+/// service 'i5' and account 'scan'", without the trailing line break.
+///
+/// Returns [`I5RequestError::CredentialError`] if the tool failed or printed nothing,
+/// as it does for a missing entry, and [`I5RequestError::ConfigError`] if the secret
+/// is not UTF-8 text.
+#[cfg(unix)]
+fn secret_from_output(output: std::process::Output, entry: &str) -> Result<String, I5RequestError> {
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(I5RequestError::CredentialError(format!("no {}", entry)));
+    }
+    let secret = String::from_utf8(output.stdout)
+        .map_err(|_| I5RequestError::ConfigError(format!("the {} is not UTF-8 text", entry)))?;
+    Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    use crate::types::i5_error::I5RequestError;
+
+    pub(super) fn read_secret(service: &str, account: &str) -> Result<String, I5RequestError> {
+        let output = Command::new("security")
+            .args(["find-generic-password", "-s", service, "-a", account, "-w"])
+            .output()
+            .map_err(|err| {
+                I5RequestError::CredentialError(format!("failed to run security: {}", err))
+            })?;
+
+        super::secret_from_output(
+            output,
+            &format!(
+                "Keychain entry for service '{}' and account '{}'",
+                service, account
+            ),
+        )
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::{ffi::c_void, ptr};
+
+    use crate::types::i5_error::I5RequestError;
+
+    const CRED_TYPE_GENERIC: u32 = 1;
+
+    #[repr(C)]
+    struct FileTime {
+        low_date_time: u32,
+        high_date_time: u32,
+    }
+
+    #[repr(C)]
+    struct CredentialW {
+        flags: u32,
+        cred_type: u32,
+        target_name: *mut u16,
+        comment: *mut u16,
+        last_written: FileTime,
+        credential_blob_size: u32,
+        credential_blob: *mut u8,
+        persist: u32,
+        attribute_count: u32,
+        attributes: *mut c_void,
+        target_alias: *mut u16,
+        user_name: *mut u16,
+    }
+
+    #[link(name = "advapi32")]
+    unsafe extern "system" {
+        fn CredReadW(
+            target_name: *const u16,
+            cred_type: u32,
+            flags: u32,
+            credential: *mut *mut CredentialW,
+        ) -> i32;
+        fn CredFree(buffer: *mut c_void);
+    }
+
+    pub(super) fn read_secret(service: &str, account: &str) -> Result<String, I5RequestError> {
+        let target = format!("{}.{}", account, service);
+        let target_wide: Vec<u16> = target.encode_utf16().chain(Some(0)).collect();
+        let mut credential: *mut CredentialW = ptr::null_mut();
+
+        // SAFETY: `target_wide` is a NUL terminated UTF-16 string and `credential` is a
+        // valid out pointer. On success the returned buffer is read and then released
+        // with `CredFree` exactly once.
+        unsafe {
+            if CredReadW(target_wide.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) == 0 {
+                return Err(I5RequestError::CredentialError(format!(
+                    "no Credential Manager entry for target '{}'",
+                    target
+                )));
+            }
+
+            let blob = std::slice::from_raw_parts(
+                (*credential).credential_blob,
+                (*credential).credential_blob_size as usize,
+            );
+            let wide: Vec<u16> = blob
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect();
+            let secret = String::from_utf16(&wide);
+            CredFree(credential as *mut c_void);
+
+            secret.map_err(|_| {
+                I5RequestError::ConfigError(format!(
+                    "Credential Manager entry for target '{}' is not UTF-16 text",
+                    target
+                ))
+            })
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::process::Command;
+
+    use crate::types::i5_error::I5RequestError;
+
+    pub(super) fn read_secret(service: &str, account: &str) -> Result<String, I5RequestError> {
+        let output = Command::new("secret-tool")
+            .args(["lookup", "service", service, "username", account])
+            .output()
+            .map_err(|err| {
+                I5RequestError::CredentialError(format!("failed to run secret-tool: {}", err))
+            })?;
+
+        super::secret_from_output(
+            output,
+            &format!(
+                "Secret Service entry for service '{}' and username '{}'",
+                service, account
+            ),
+        )
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use crate::types::i5_error::I5RequestError;
+
+    pub(super) fn read_secret(_service: &str, _account: &str) -> Result<String, I5RequestError> {
+        Err(I5RequestError::CredentialError(String::from(
+            "no credential store is supported on this platform",
+        )))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{
+        os::unix::process::ExitStatusExt,
+        process::{ExitStatus, Output},
+    };
+
+    use super::*;
+
+    const ENTRY: &str = "Keychain entry for service 'i5' and account 'scan'";
+
+    fn output(code: i32, stdout: &[u8]) -> Output {
+        Output {
+            // A wait status carries the exit code in its second byte.
+            status: ExitStatus::from_raw(code << 8),
+            stdout: stdout.to_vec(),
+            stderr: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn strips_the_trailing_line_break() {
+        for stdout in [&b"s3cret"[..], b"s3cret\n", b"s3cret\r\n"] {
+            assert_eq!(
+                secret_from_output(output(0, stdout), ENTRY).unwrap(),
+                "s3cret"
+            );
+        }
+        assert_eq!(
+            secret_from_output(output(0, b" two words \n"), ENTRY).unwrap(),
+            " two words "
+        );
+    }
+
+    #[test]
+    fn rejects_a_secret_that_is_not_utf8() {
+        match secret_from_output(output(0, b"s3\xffcret\n"), ENTRY) {
+            Err(I5RequestError::ConfigError(message)) => assert_eq!(
+                message,
+                "the Keychain entry for service 'i5' and account 'scan' is not UTF-8 text"
+            ),
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn reports_a_missing_entry() {
+        // `security` fails with status 44, `secret-tool` succeeds without output.
+        for output in [output(44, b""), output(1, b"s3cret\n"), output(0, b"")] {
+            match secret_from_output(output, ENTRY) {
+                Err(I5RequestError::CredentialError(message)) => {
+                    assert_eq!(message, format!("no {}", ENTRY))
+                }
+                other => panic!("unexpected result: {:?}", other.map(|_| ())),
+            }
+        }
+    }
+}
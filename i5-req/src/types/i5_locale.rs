@@ -0,0 +1,330 @@
+//! Error and validation messages in the language of the people reading them.
+//!
+//! The [`Display`](std::fmt::Display) texts of [`I5RequestError`] and
+//! [`I5ValidationIssue`] are English and meant for logs. Applications showing them to
+//! operators render them with an [`I5MessageCatalog`] instead, either a built-in
+//! [`I5Locale`] or a catalog of their own, while [`I5RequestError::code`] and
+//! [`I5ValidationIssue::code`] stay the same in every language.
+//!
+//! Details that come from elsewhere, such as the message of the server, an I/O error
+//! or the reason given by a validator, are kept as reported.
+//!
+//! # Example
+//!
+//! ```rust
+//! use i5_req::types::{i5_locale::I5Locale, i5_request::I5Request};
+//!
+//! let request = I5Request::new("Batch");
+//! let report = request.validation_report();
+//! assert_eq!(
+//!     report.issues()[0].localized(&I5Locale::German),
+//!     "Der Stapel enthält keine Dokumente"
+//! );
+//! assert_eq!(report.issues()[0].code(), "I5_NO_DOCUMENTS");
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use crate::types::{
+    i5_error::I5RequestError,
+    i5_validation::{I5NameProblem, I5PropertyProblem, I5ValidationIssue},
+};
+
+/// Texts for errors and validation issues, see the [module documentation](self).
+///
+/// A catalog returns `None` for messages it has no text for, which are then rendered
+/// with their English [`Display`](std::fmt::Display) text. A catalog usually matches
+/// on [`I5RequestError::code`] or [`I5ValidationIssue::code`] and only reads the
+/// variant fields it needs.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{
+///     i5_error::I5RequestError,
+///     i5_locale::{I5Locale, I5MessageCatalog},
+///     i5_validation::I5ValidationIssue,
+/// };
+///
+/// /// The German texts, with the wording of our scan stations.
+/// struct ScanStation;
+///
+/// impl I5MessageCatalog for ScanStation {
+///     fn error(&self, error: &I5RequestError) -> Option<String> {
+///         match error.code() {
+///             "I5_REQUEST" => Some(String::from("Keine Verbindung zum Archiv")),
+///             _ => I5Locale::German.error(error),
+///         }
+///     }
+///
+///     fn issue(&self, issue: &I5ValidationIssue) -> Option<String> {
+///         I5Locale::German.issue(issue)
+///     }
+/// }
+///
+/// let error = I5RequestError::ValidationError;
+/// assert_eq!(error.localized(&ScanStation), "Der Stapel ist ungültig");
+/// ```
+pub trait I5MessageCatalog {
+    /// The text of `error`, or `None` to use its English text.
+    fn error(&self, error: &I5RequestError) -> Option<String> {
+        let _ = error;
+        None
+    }
+
+    /// The text of `issue`, or `None` to use its English text.
+    fn issue(&self, issue: &I5ValidationIssue) -> Option<String> {
+        let _ = issue;
+        None
+    }
+}
+
+/// The languages the crate has messages for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum I5Locale {
+    /// The English [`Display`](std::fmt::Display) texts.
+    #[default]
+    English,
+    /// German.
+    German,
+}
+
+impl I5Locale {
+    /// The language tag of the locale, e.g. `de`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            I5Locale::English => "en",
+            I5Locale::German => "de",
+        }
+    }
+}
+
+impl I5MessageCatalog for I5Locale {
+    fn error(&self, error: &I5RequestError) -> Option<String> {
+        match self {
+            I5Locale::English => None,
+            I5Locale::German => Some(german_error(error)),
+        }
+    }
+
+    fn issue(&self, issue: &I5ValidationIssue) -> Option<String> {
+        match self {
+            I5Locale::English => None,
+            I5Locale::German => Some(german_issue(issue)),
+        }
+    }
+}
+
+impl FromStr for I5Locale {
+    type Err = I5RequestError;
+
+    /// Parses a language tag such as `de`, `de-AT` or `en_US`, ignoring case and the
+    /// region.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] for languages without messages.
+    fn from_str(text: &str) -> Result<I5Locale, I5RequestError> {
+        let language = text.trim().split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Ok(I5Locale::English),
+            "de" => Ok(I5Locale::German),
+            _ => Err(I5RequestError::ConfigError(format!(
+                "unsupported locale '{}', expected en or de",
+                text
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for I5Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn german_error(error: &I5RequestError) -> String {
+    match error {
+        I5RequestError::ValidationError => String::from("Der Stapel ist ungültig"),
+        I5RequestError::SerializeError(err) => {
+            format!("Der Stapel konnte nicht umgewandelt werden: {}", err)
+        }
+        I5RequestError::RequestError(err) => {
+            format!("Senden an Interface5 fehlgeschlagen: {}", err)
+        }
+        I5RequestError::ServerError(err) => {
+            let mut text = format!(
+                "Interface5 hat den Stapel abgelehnt ({}): {}",
+                err.status(),
+                err.message()
+            );
+            if let Some(field) = err.field() {
+                text.push_str(&format!(" [Feld: {}]", field));
+            }
+            text
+        }
+        I5RequestError::IoError(err) => format!("Zugriff auf lokale Datei fehlgeschlagen: {}", err),
+        I5RequestError::CredentialError(err) => {
+            format!("Zugangsdaten konnten nicht geladen werden: {}", err)
+        }
+        I5RequestError::ConfigError(err) => format!("Ungültige Konfiguration: {}", err),
+        I5RequestError::ConversionError(err) => format!("Ungültige Stapeldaten: {}", err),
+        I5RequestError::ContractError(err) => {
+            format!("Der Stapel verletzt den Interface5-Vertrag: {}", err)
+        }
+        I5RequestError::PayloadTooLarge { size, limit } => format!(
+            "Der Stapel ist etwa {} Bytes groß, erlaubt sind höchstens {} Bytes",
+            size, limit
+        ),
+        I5RequestError::UnexpectedResponse { status, details } => format!(
+            "Interface5 hat mit {} und einer unerwarteten Antwort geantwortet: {}",
+            status, details
+        ),
+    }
+}
+
+fn german_issue(issue: &I5ValidationIssue) -> String {
+    match issue {
+        I5ValidationIssue::NoDocuments => String::from("Der Stapel enthält keine Dokumente"),
+        I5ValidationIssue::DuplicateBatchName { name } => {
+            format!("Ein anderer Stapel des Auftrags heißt ebenfalls '{}'", name)
+        }
+        I5ValidationIssue::TooManyDocuments { count, limit } => format!(
+            "Der Stapel enthält {} Dokumente, erlaubt sind höchstens {}",
+            count, limit
+        ),
+        I5ValidationIssue::EmptyDocument { document } => {
+            format!("Dokument {} hat weder Felder noch Dateien", document)
+        }
+        I5ValidationIssue::MissingItemNumbers { document, missing } => {
+            let missing: Vec<String> = missing.iter().map(i32::to_string).collect();
+            format!(
+                "In Dokument {} fehlen die Positionsnummern {}",
+                document,
+                missing.join(", ")
+            )
+        }
+        I5ValidationIssue::NegativeItemNumber {
+            document,
+            item_number,
+        } => format!(
+            "Dokument {} hat die negative Positionsnummer {}",
+            document, item_number
+        ),
+        I5ValidationIssue::InvalidProperty {
+            document,
+            field,
+            reason,
+        } => format!(
+            "Dokument {}: {} {}",
+            document,
+            field,
+            german_property_problem(reason)
+        ),
+        I5ValidationIssue::InvalidFieldName {
+            document,
+            field,
+            reason,
+        } => format!(
+            "Dokument {}: Der Feldname '{}' {}",
+            document,
+            field,
+            german_name_problem(reason)
+        ),
+        I5ValidationIssue::InvalidName {
+            document: None,
+            name,
+            reason,
+        } => format!("Der Stapelname '{}' {}", name, german_name_problem(reason)),
+        I5ValidationIssue::InvalidName {
+            document: Some(document),
+            name,
+            reason,
+        } => format!(
+            "Dokument {}: Der Name '{}' {}",
+            document,
+            name,
+            german_name_problem(reason)
+        ),
+        I5ValidationIssue::UnresolvedReference {
+            document,
+            field,
+            value,
+        } => format!(
+            "Dokument {}: {} verweist auf '{}', das nicht im Stapel enthalten ist",
+            document, field, value
+        ),
+        I5ValidationIssue::ValidatorFailed { document, reason } => {
+            format!("Dokument {}: {}", document, reason)
+        }
+        I5ValidationIssue::UnknownField { document, field } => {
+            format!("Dokument {} hat das unbekannte Feld {}", document, field)
+        }
+        I5ValidationIssue::MissingField { document, field } => {
+            format!("In Dokument {} fehlt das Feld {}", document, field)
+        }
+        I5ValidationIssue::ValueTooLong {
+            document,
+            field,
+            max_length,
+        } => format!(
+            "Dokument {}: {} ist länger als {} Zeichen",
+            document, field, max_length
+        ),
+    }
+}
+
+fn german_name_problem(problem: &I5NameProblem) -> String {
+    match problem {
+        I5NameProblem::Empty => String::from("ist leer"),
+        I5NameProblem::LeadingDigit => String::from("beginnt mit einer Ziffer"),
+        I5NameProblem::SurroundingWhitespace => String::from("beginnt oder endet mit Leerzeichen"),
+        I5NameProblem::InvalidCharacter(c) => format!("enthält '{}'", c.escape_default()),
+        I5NameProblem::TooLong { length, max } => format!(
+            "ist {} Zeichen lang, erlaubt sind höchstens {}",
+            length, max
+        ),
+    }
+}
+
+fn german_property_problem(problem: &I5PropertyProblem) -> String {
+    match problem {
+        I5PropertyProblem::Repeated { count } => format!("ist {} Mal gesetzt", count),
+        I5PropertyProblem::Blank => String::from("ist leer"),
+        I5PropertyProblem::NotANumber { value } => format!("ist keine Zahl: '{}'", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{i5_request::I5Request, i5_validation::I5ValidationOptions};
+
+    #[test]
+    fn german_issues_translate_the_reason() {
+        let mut request = I5Request::new(" Batch");
+        let id = request.add_document("Invoice");
+        let document = request.get_document_mut(id).unwrap();
+        document.add_header_field("1st Reminder", "yes");
+        document.add_header_field("Priority", "high");
+
+        let report = request.validation_report_with(&I5ValidationOptions::strict());
+        let texts: Vec<String> = report
+            .issues()
+            .iter()
+            .map(|issue| issue.localized(&I5Locale::German))
+            .collect();
+        assert!(texts.contains(&String::from(
+            "Der Stapelname ' Batch' beginnt oder endet mit Leerzeichen"
+        )));
+        assert!(texts.contains(&String::from(
+            "Dokument 0: Der Feldname '1st Reminder' beginnt mit einer Ziffer"
+        )));
+        assert!(report.issues().iter().any(|issue| matches!(
+            issue,
+            I5ValidationIssue::InvalidFieldName {
+                reason: I5NameProblem::LeadingDigit,
+                ..
+            }
+        )));
+    }
+}
@@ -0,0 +1,179 @@
+//! Memory-mapped file attachments, enabled with the `mmap` feature.
+//!
+//! A mapped file is read by the operating system page by page while the request is
+//! serialized, so large scans never have to be loaded into memory as a whole. Pages
+//! that have been encoded can be dropped again by the operating system under memory
+//! pressure, keeping resident memory nearly flat.
+//!
+//! Mapping is implemented for Unix systems. On other platforms the file is read
+//! into memory instead.
+
+use std::{fs, io, path::Path};
+
+/// A read-only memory map of a file.
+///
+/// The file must not be modified or truncated while it is mapped, see
+/// [`I5MappedFile::open`].
+///
+/// # Example
+///
+/// ```rust
+/// use bytes::Bytes;
+/// use i5_req::types::{i5_mapped_file::I5MappedFile, i5_request::I5Request};
+///
+/// let path = std::env::temp_dir().join(format!("i5-doc-mmap-{}.tif", std::process::id()));
+/// std::fs::write(&path, [0u8; 4096])?;
+///
+/// let mut request = I5Request::new("Batch");
+/// let id = request.add_document("Invoice");
+/// let document = request.get_document_mut(id).unwrap();
+/// // SAFETY: The file was written above and is not changed until it is removed at
+/// // the end, after the request was dropped.
+/// unsafe { document.add_mapped_file(&path)? };
+/// assert_eq!(document.total_attachment_bytes(), 4096);
+///
+/// // A mapping can be shared between documents without copying.
+/// // SAFETY: As above.
+/// let scan = Bytes::from_owner(unsafe { I5MappedFile::open(&path)? });
+/// document.add_shared_file("copy.tif", scan);
+/// # drop(request);
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct I5MappedFile {
+    map: platform::Map,
+}
+
+impl I5MappedFile {
+    /// Maps the file at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other process,
+    /// until the map is dropped. The bytes of the map would change while they are
+    /// borrowed, and reading pages of a truncated file terminates the process with
+    /// `SIGBUS`. Only map files the application controls, e.g. scans it has finished
+    /// writing.
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<I5MappedFile> {
+        let file = fs::File::open(path)?;
+        Ok(I5MappedFile {
+            map: platform::Map::new(&file)?,
+        })
+    }
+
+    /// The length of the file in bytes.
+    pub fn len(&self) -> usize {
+        self.as_ref().len()
+    }
+
+    /// Returns `true` if the file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl AsRef<[u8]> for I5MappedFile {
+    fn as_ref(&self) -> &[u8] {
+        self.map.as_slice()
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::{fs::File, io, os::fd::AsRawFd, ptr};
+
+    /// A `PROT_READ`, `MAP_PRIVATE` mapping of a whole file.
+    #[derive(Debug)]
+    pub(super) struct Map {
+        address: *mut libc::c_void,
+        len: usize,
+    }
+
+    // The mapping is read-only and owned by this value, so it can be shared and sent
+    // between threads like a `Box<[u8]>`.
+    unsafe impl Send for Map {}
+    unsafe impl Sync for Map {}
+
+    impl Map {
+        pub(super) fn new(file: &File) -> io::Result<Map> {
+            let len = usize::try_from(file.metadata()?.len())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file too large"))?;
+            if len == 0 {
+                // Empty mappings are not allowed.
+                return Ok(Map {
+                    address: ptr::null_mut(),
+                    len: 0,
+                });
+            }
+
+            // SAFETY: The arguments describe a new read-only mapping of `len` bytes of
+            // an open file; the result is checked before it is used.
+            let address = unsafe {
+                libc::mmap(
+                    ptr::null_mut(),
+                    len,
+                    libc::PROT_READ,
+                    libc::MAP_PRIVATE,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            if address == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: `address` and `len` describe the mapping created above. The
+            // advice only affects read-ahead, so a failure is ignored.
+            unsafe {
+                libc::madvise(address, len, libc::MADV_SEQUENTIAL);
+            }
+            Ok(Map { address, len })
+        }
+
+        pub(super) fn as_slice(&self) -> &[u8] {
+            if self.len == 0 {
+                return &[];
+            }
+            // SAFETY: The mapping is readable, `len` bytes long and lives as long as
+            // `self`. The caller of `I5MappedFile::open` guarantees that the file is
+            // not changed while it is mapped.
+            unsafe { std::slice::from_raw_parts(self.address.cast::<u8>(), self.len) }
+        }
+    }
+
+    impl Drop for Map {
+        fn drop(&mut self) {
+            if self.len > 0 {
+                // SAFETY: The mapping was created in `Map::new` and is not used after
+                // this point.
+                unsafe {
+                    libc::munmap(self.address, self.len);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use std::{
+        fs::File,
+        io::{self, Read},
+    };
+
+    /// The content of a file read into memory.
+    #[derive(Debug)]
+    pub(super) struct Map(Vec<u8>);
+
+    impl Map {
+        pub(super) fn new(mut file: &File) -> io::Result<Map> {
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)?;
+            Ok(Map(content))
+        }
+
+        pub(super) fn as_slice(&self) -> &[u8] {
+            &self.0
+        }
+    }
+}
@@ -0,0 +1,424 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Measured timings and transfer sizes of a submission.
+///
+/// The TCP connect, the TLS handshake and the upload of the body are not reported as
+/// phases of their own, as the HTTP stack does not expose them; they are part of
+/// [`I5TransferStats::time_to_first_byte`].
+///
+/// # Fields
+///
+/// - `dns`: Time spent resolving the hostname for this submission. `None` if no
+///   lookup was made for it, because the URL used an IP literal or a
+///   [resolve override](crate::types::i5_config::I5Config::resolve) or an open
+///   connection was reused, and always `None` for the
+///   [blocking client](crate::request::blocking::BlockingI5Client) and transports
+///   other than [`I5HttpTransport`](crate::request::transport::I5HttpTransport).
+/// - `time_to_first_byte`: Time from starting the request until the response headers
+///   arrived, including connecting, the TLS handshake and uploading the body.
+/// - `total`: Time from starting the request until the response body was read.
+/// - `bytes_sent`: Size of the request body.
+/// - `bytes_received`: Size of the response body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct I5TransferStats {
+    dns: Option<Duration>,
+    time_to_first_byte: Duration,
+    total: Duration,
+    bytes_sent: u64,
+    bytes_received: u64,
+}
+
+impl I5TransferStats {
+    pub(crate) fn new(
+        dns: Option<Duration>,
+        time_to_first_byte: Duration,
+        total: Duration,
+        bytes_sent: u64,
+        bytes_received: u64,
+    ) -> I5TransferStats {
+        I5TransferStats {
+            dns,
+            time_to_first_byte,
+            total,
+            bytes_sent,
+            bytes_received,
+        }
+    }
+
+    /// Time spent resolving the hostname for this submission, `None` if no lookup was
+    /// made for it.
+    pub fn dns(&self) -> Option<Duration> {
+        self.dns
+    }
+
+    /// Time until the response headers arrived.
+    pub fn time_to_first_byte(&self) -> Duration {
+        self.time_to_first_byte
+    }
+
+    /// Time until the response body was read.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Size of the request body in bytes.
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// Size of the response body in bytes.
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+}
+
+/// The request quota reported by Interface5 or a gateway in front of it in the
+/// `X-RateLimit-Limit`, `X-RateLimit-Remaining` and `X-RateLimit-Reset` headers, or
+/// their unprefixed `RateLimit-*` counterparts.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_receipt::I5RateLimit;
+/// use std::time::Duration;
+///
+/// let headers = [("X-RateLimit-Limit", "600"), ("X-RateLimit-Remaining", "12"), ("X-RateLimit-Reset", "30")];
+/// let limit = I5RateLimit::from_headers(headers).unwrap();
+/// assert_eq!(limit.remaining(), Some(12));
+/// assert_eq!(limit.reset(), Some(Duration::from_secs(30)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct I5RateLimit {
+    limit: Option<u64>,
+    remaining: Option<u64>,
+    reset: Option<Duration>,
+}
+
+impl I5RateLimit {
+    /// Reads the rate limit headers among `headers`, with names compared
+    /// case-insensitively. Returns `None` if there are none.
+    ///
+    /// A reset value above one billion is read as a Unix timestamp, as sent by some
+    /// gateways, and any other value as the number of seconds until the reset.
+    pub fn from_headers<'a>(
+        headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Option<I5RateLimit> {
+        let mut rate_limit = I5RateLimit::default();
+        let mut found = false;
+        for (name, value) in headers {
+            let name = name.to_ascii_lowercase();
+            let Some(counter) = name
+                .strip_prefix("x-ratelimit-")
+                .or_else(|| name.strip_prefix("ratelimit-"))
+            else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<u64>() else {
+                continue;
+            };
+            match counter {
+                "limit" => rate_limit.limit = Some(value),
+                "remaining" => rate_limit.remaining = Some(value),
+                "reset" => rate_limit.reset = Some(reset_delay(value)),
+                _ => continue,
+            }
+            found = true;
+        }
+        found.then_some(rate_limit)
+    }
+
+    /// The number of requests allowed in the current window.
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// The number of requests left in the current window.
+    pub fn remaining(&self) -> Option<u64> {
+        self.remaining
+    }
+
+    /// The time until the window resets, as of the response.
+    pub fn reset(&self) -> Option<Duration> {
+        self.reset
+    }
+}
+
+/// The delay until a rate limit reset given as seconds or as a Unix timestamp.
+fn reset_delay(value: u64) -> Duration {
+    if value < 1_000_000_000 {
+        return Duration::from_secs(value);
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Duration::from_secs(value).saturating_sub(now)
+}
+
+/// The response to a submission as received, kept in the receipt if
+/// [requested](crate::request::options::I5SendOptions::keep_raw_response).
+///
+/// The body is cut off after the requested number of bytes; [`I5RawResponse::body_len`]
+/// is the length of the whole body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5RawResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+    body_len: u64,
+}
+
+impl I5RawResponse {
+    /// Captures a response, keeping at most `limit` bytes of `body`.
+    pub(crate) fn capture<'a>(
+        status: u16,
+        headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+        body: &Bytes,
+        limit: usize,
+    ) -> I5RawResponse {
+        I5RawResponse {
+            status,
+            headers: headers
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: body.slice(..body.len().min(limit)),
+            body_len: body.len() as u64,
+        }
+    }
+
+    /// The HTTP status code.
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// The response headers, in order.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The first value of the header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// The kept part of the body.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// The length of the whole body in bytes.
+    pub fn body_len(&self) -> u64 {
+        self.body_len
+    }
+
+    /// Whether the body was cut off.
+    pub fn is_truncated(&self) -> bool {
+        (self.body.len() as u64) < self.body_len
+    }
+}
+
+/// The result of a successful submission to Interface5.
+///
+/// Receipts are serializable, so that they can be stored and the batches reconciled
+/// with Interface5 later; the raw response is diagnostic and not serialized.
+///
+/// # Fields
+///
+/// - `status`: The HTTP status code returned by Interface5.
+/// - `correlation_id`: The correlation ID sent with the request, either supplied by the
+///   caller or generated for this submission.
+/// - `batch_id`: The ID Interface5 assigned to the batch, from the `BatchId` of the
+///   response.
+/// - `batch_name`: The name of the batch as echoed in the `Name` of the response.
+/// - `created_at`: When Interface5 created the batch, from the `Created` of the
+///   response, as sent.
+/// - `warnings`: The `Warnings` of the response, for batches accepted with remarks.
+/// - `stats`: Timings and transfer sizes measured while sending.
+/// - `location`: The URL of the created batch from the `Location` header, if sent.
+/// - `rate_limit`: The remaining request quota, if reported.
+/// - `raw_response`: The response as received, if requested.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_receipt::I5BatchReceipt;
+///
+/// let receipt = I5BatchReceipt::new(201, "7f8e", Default::default())
+///     .with_response_body(br#"{"BatchId": 4711, "Name": "Scan", "Warnings": ["Field Cost ignored"]}"#);
+/// assert_eq!(receipt.batch_id(), Some("4711"));
+/// assert_eq!(receipt.warnings(), ["Field Cost ignored"]);
+///
+/// let stored = serde_json::to_string(&receipt).unwrap();
+/// let restored: I5BatchReceipt = serde_json::from_str(&stored).unwrap();
+/// assert_eq!(restored, receipt);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct I5BatchReceipt {
+    status: u16,
+    correlation_id: String,
+    #[serde(default)]
+    batch_id: Option<String>,
+    #[serde(default)]
+    batch_name: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    warnings: Vec<String>,
+    #[serde(default)]
+    stats: I5TransferStats,
+    #[serde(default)]
+    location: Option<String>,
+    #[serde(default)]
+    rate_limit: Option<I5RateLimit>,
+    #[serde(skip)]
+    raw_response: Option<I5RawResponse>,
+}
+
+impl I5BatchReceipt {
+    /// Creates a receipt, e.g. in a test double of a sender.
+    pub fn new(
+        status: u16,
+        correlation_id: impl Into<String>,
+        stats: I5TransferStats,
+    ) -> I5BatchReceipt {
+        I5BatchReceipt {
+            status,
+            correlation_id: correlation_id.into(),
+            batch_id: None,
+            batch_name: None,
+            created_at: None,
+            warnings: Vec::new(),
+            stats,
+            location: None,
+            rate_limit: None,
+            raw_response: None,
+        }
+    }
+
+    /// Records what the JSON body of the response says about the batch, keeping the
+    /// receipt as it is for empty or unreadable bodies.
+    ///
+    /// Keys are matched ignoring case. Warnings given as objects are recorded by their
+    /// `Message`, or as JSON if they have none.
+    pub fn with_response_body(mut self, body: &[u8]) -> Self {
+        let Ok(Value::Object(response)) = serde_json::from_slice::<Value>(body) else {
+            return self;
+        };
+        let entry = |key: &str| {
+            response
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(_, value)| value)
+        };
+        let text = |value: &Value| match value {
+            Value::String(text) => Some(text.clone()),
+            Value::Number(number) => Some(number.to_string()),
+            _ => None,
+        };
+
+        self.batch_id = entry("BatchId").and_then(text);
+        self.batch_name = entry("Name").and_then(text);
+        self.created_at = entry("Created").and_then(text);
+        self.warnings = match entry("Warnings") {
+            Some(Value::Array(warnings)) => warnings
+                .iter()
+                .map(|warning| match warning {
+                    Value::String(message) => message.clone(),
+                    Value::Object(warning) => warning
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case("Message"))
+                        .and_then(|(_, message)| message.as_str())
+                        .map_or_else(|| Value::Object(warning.clone()).to_string(), String::from),
+                    other => other.to_string(),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        self
+    }
+
+    /// Records the headers of the response that carry information about the batch.
+    pub(crate) fn with_response_headers<'a>(
+        mut self,
+        headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Self {
+        let headers: Vec<(&str, &str)> = headers.into_iter().collect();
+        self.location = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Location"))
+            .map(|(_, value)| value.to_string());
+        self.rate_limit = I5RateLimit::from_headers(headers);
+        self
+    }
+
+    /// Keeps the response as received.
+    pub(crate) fn with_raw_response(mut self, raw_response: Option<I5RawResponse>) -> Self {
+        self.raw_response = raw_response;
+        self
+    }
+
+    /// Replaces the transfer stats, e.g. with those of a whole upload session.
+    pub(crate) fn with_stats(mut self, stats: I5TransferStats) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// The HTTP status code returned by Interface5.
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// The correlation ID the request was sent with.
+    pub fn correlation_id(&self) -> &str {
+        &self.correlation_id
+    }
+
+    /// The ID Interface5 assigned to the batch, if the response named it.
+    pub fn batch_id(&self) -> Option<&str> {
+        self.batch_id.as_deref()
+    }
+
+    /// The name of the batch as echoed by Interface5.
+    pub fn batch_name(&self) -> Option<&str> {
+        self.batch_name.as_deref()
+    }
+
+    /// When Interface5 created the batch, as sent, typically in RFC 3339 format.
+    pub fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    /// The warnings Interface5 accepted the batch with.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Timings and transfer sizes of the submission.
+    pub fn stats(&self) -> &I5TransferStats {
+        &self.stats
+    }
+
+    /// The URL of the created batch, from the `Location` header of the response.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// The request quota left after the submission, if Interface5 or a gateway
+    /// reported it.
+    pub fn rate_limit(&self) -> Option<&I5RateLimit> {
+        self.rate_limit.as_ref()
+    }
+
+    /// The response as received, if
+    /// [requested](crate::request::options::I5SendOptions::keep_raw_response).
+    pub fn raw_response(&self) -> Option<&I5RawResponse> {
+        self.raw_response.as_ref()
+    }
+}
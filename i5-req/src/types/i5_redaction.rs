@@ -0,0 +1,162 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::{Debug, Display};
+
+use crate::util;
+
+/// Describes which parts of a serialized request are masked before it leaves the
+/// process, e.g. when it is written to a debug dump.
+///
+/// Field values are masked by field name pattern (`*` and `?` wildcards, ignoring case).
+/// File data can be truncated to a maximum number of base64 characters.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_redaction::I5Redaction;
+///
+/// let redaction = I5Redaction::new()
+///     .mask_field("*IBAN*")
+///     .mask_field("Password")
+///     .truncate_file_data(64);
+/// ```
+#[derive(Debug, Clone)]
+pub struct I5Redaction {
+    masked_fields: Vec<String>,
+    mask: String,
+    max_file_data: Option<usize>,
+}
+
+impl Default for I5Redaction {
+    fn default() -> Self {
+        I5Redaction {
+            masked_fields: Vec::new(),
+            mask: String::from("***"),
+            max_file_data: None,
+        }
+    }
+}
+
+impl I5Redaction {
+    /// Creates a redaction that keeps everything as is.
+    pub fn new() -> I5Redaction {
+        I5Redaction::default()
+    }
+
+    /// Creates a redaction that masks every field value and removes all file data.
+    pub fn all() -> I5Redaction {
+        I5Redaction::new().mask_field("*").truncate_file_data(0)
+    }
+
+    /// Masks the values of all fields whose name matches `pattern`.
+    pub fn mask_field(mut self, pattern: impl Into<String>) -> Self {
+        self.masked_fields.push(pattern.into());
+        self
+    }
+
+    /// Sets the replacement text for masked values. Defaults to `***`.
+    pub fn mask(mut self, mask: impl Into<String>) -> Self {
+        self.mask = mask.into();
+        self
+    }
+
+    /// Truncates the base64 data of every file to at most `max_chars` characters.
+    pub fn truncate_file_data(mut self, max_chars: usize) -> Self {
+        self.max_file_data = Some(max_chars);
+        self
+    }
+
+    /// Returns `true` if values of the field `name` are masked.
+    pub fn is_masked(&self, name: &str) -> bool {
+        self.masked_fields
+            .iter()
+            .any(|pattern| util::wildcard_match(pattern, name))
+    }
+
+    /// Applies the redaction to a serialized request body.
+    ///
+    /// Bodies that are not valid JSON are returned unchanged.
+    pub fn apply_to_json(&self, body: &str) -> String {
+        match serde_json::from_str::<Value>(body) {
+            Ok(mut value) => {
+                self.apply(&mut value);
+                value.to_string()
+            }
+            Err(_) => body.to_string(),
+        }
+    }
+
+    pub(crate) fn apply(&self, request: &mut Value) {
+        let Some(documents) = request.get_mut("Documents").and_then(Value::as_array_mut) else {
+            return;
+        };
+
+        for document in documents {
+            if let Some(fields) = document.get_mut("Fields").and_then(Value::as_array_mut) {
+                for field in fields {
+                    let masked = field
+                        .get("Name")
+                        .and_then(Value::as_str)
+                        .is_some_and(|name| self.is_masked(name));
+                    if masked {
+                        field["Value"] = Value::String(self.mask.clone());
+                    }
+                }
+            }
+
+            if let (Some(max), Some(files)) = (
+                self.max_file_data,
+                document.get_mut("Files").and_then(Value::as_array_mut),
+            ) {
+                for file in files {
+                    if let Some(Value::String(data)) = file.get_mut("Data")
+                        && data.len() > max
+                    {
+                        let original = data.len();
+                        data.truncate(max);
+                        data.push_str(&format!("...[{} chars truncated]", original - max));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A view of a request that formats it with an [`I5Redaction`] applied.
+///
+/// Created by `redacted()` / `redacted_with()` on
+/// [`I5Request`](crate::types::i5_request::I5Request) and
+/// [`ValidatedI5Request`](crate::types::i5_request::ValidatedI5Request). Both `Debug`
+/// and `Display` print the redacted JSON payload.
+pub struct I5Redacted<'a, T: Serialize> {
+    payload: &'a T,
+    redaction: I5Redaction,
+}
+
+impl<'a, T: Serialize> I5Redacted<'a, T> {
+    pub(crate) fn new(payload: &'a T, redaction: I5Redaction) -> I5Redacted<'a, T> {
+        I5Redacted { payload, redaction }
+    }
+
+    fn redacted_value(&self) -> Value {
+        let mut value = serde_json::to_value(self.payload).unwrap_or(Value::Null);
+        self.redaction.apply(&mut value);
+        value
+    }
+}
+
+impl<T: Serialize> Debug for I5Redacted<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            write!(f, "{:#}", self.redacted_value())
+        } else {
+            write!(f, "{}", self.redacted_value())
+        }
+    }
+}
+
+impl<T: Serialize> Display for I5Redacted<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.redacted_value())
+    }
+}
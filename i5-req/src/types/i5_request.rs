@@ -1,8 +1,67 @@
-use base64::{Engine, engine::general_purpose};
+use base64::{Engine, display::Base64Display, engine::general_purpose};
+use bytes::Bytes;
 use serde::Serialize;
-use std::collections::HashSet;
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+    time::SystemTime,
+};
 
-use crate::types::i5_error::I5RequestError;
+use crate::{
+    events::i5_event,
+    schema,
+    types::{
+        i5_amount::{I5Amount, I5AmountFormat, I5Money},
+        i5_api_version::I5ApiVersion,
+        i5_audit::I5AuditOperation,
+        i5_barcode::I5BarcodeSymbology,
+        i5_batch_name::I5BatchNamer,
+        i5_bool_format::I5BoolFormat,
+        i5_date::{I5Date, I5DateFormat},
+        i5_diff::I5RequestDiff,
+        i5_error::I5RequestError,
+        i5_field_mapping::I5FieldMapping,
+        i5_field_options::I5FieldOptions,
+        i5_field_rules::I5FieldRules,
+        i5_field_value::I5FieldValue,
+        i5_redaction::{I5Redacted, I5Redaction},
+        i5_sanitizer::{I5SanitizeReport, I5SanitizedField, I5Sanitizer},
+        i5_summary::I5RequestSummary,
+        i5_template::I5TemplateContext,
+        i5_transform::I5Transforms,
+        i5_transformer::I5Transformer,
+        i5_validation::{
+            self, I5PropertyProblem, I5ValidationIssue, I5ValidationOptions, I5ValidationReport,
+        },
+    },
+    util, xml,
+};
+
+/// The header field that carries the class of a document, see [`Document::set_class`].
+pub const DOCUMENT_CLASS_FIELD: &str = "DocumentClass";
+
+/// The header field that carries the external reference of a document, see
+/// [`Document::set_external_reference`].
+pub const DOCUMENT_REFERENCE_FIELD: &str = "ExternalReference";
+
+/// The header field that carries the priority of a document, see
+/// [`Document::set_priority`].
+pub const DOCUMENT_PRIORITY_FIELD: &str = "Priority";
+
+/// The header field that carries the external reference of the batch a document
+/// belongs to, see [`I5Request::set_external_reference`].
+pub const BATCH_REFERENCE_FIELD: &str = "BatchReference";
+
+/// The header fields holding document properties, which may appear at most once.
+const PROPERTY_FIELDS: [&str; 4] = [
+    DOCUMENT_CLASS_FIELD,
+    DOCUMENT_REFERENCE_FIELD,
+    DOCUMENT_PRIORITY_FIELD,
+    BATCH_REFERENCE_FIELD,
+];
 
 /// Represents a single field within an Interface5 document.
 ///
@@ -14,7 +73,7 @@ use crate::types::i5_error::I5RequestError;
 /// - `"Name"`
 /// - `"Value"`
 /// - `"ItemNo"`
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct Field {
     #[serde(rename = "Name")]
     name: String,
@@ -38,19 +97,103 @@ impl Field {
             item_number,
         }
     }
+
+    /// The name of the field.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value of the field.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The item number of the field, `0` for header fields.
+    pub fn item_number(&self) -> i32 {
+        self.item_number
+    }
+
+    /// Whether the field is the header field `name`, ignoring case.
+    fn is_header(&self, name: &str) -> bool {
+        self.item_number == 0 && self.name.eq_ignore_ascii_case(name)
+    }
 }
 
 /// Represents a file attachment in an Interface5 document.
 ///
-/// The file content is stored as a base64-encoded string.
-#[derive(Serialize, Debug)]
+/// The file content is stored either as a base64-encoded string or as raw bytes, which
+/// are encoded while the request is serialized. Raw bytes are held in a
+/// [`Bytes`] buffer, so a file shared between documents or requests is not copied. A
+/// file can carry a page number, which determines its position among the files of the
+/// document; the page number itself is not serialized.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct File {
     #[serde(rename = "Name")]
     name: String,
     #[serde(rename = "Key")]
     key: Option<String>,
     #[serde(rename = "Data")]
-    data_base64: String,
+    data: FileData,
+    #[serde(skip)]
+    page: Option<u32>,
+}
+
+/// The content of a [`File`].
+#[derive(Debug, Clone)]
+pub(crate) enum FileData {
+    /// Base64-encoded content supplied by the caller.
+    Base64(String),
+    /// Raw content, encoded on serialization.
+    Bytes(Bytes),
+}
+
+impl FileData {
+    /// The length of the base64-encoded content.
+    fn encoded_len(&self) -> usize {
+        match self {
+            FileData::Base64(data) => data.len(),
+            FileData::Bytes(bytes) => bytes.len().div_ceil(3) * 4,
+        }
+    }
+
+    /// The raw content, decoding base64 content.
+    pub(crate) fn decoded(&self) -> Result<Cow<'_, [u8]>, base64::DecodeError> {
+        match self {
+            FileData::Base64(data) => general_purpose::STANDARD.decode(data).map(Cow::Owned),
+            FileData::Bytes(bytes) => Ok(Cow::Borrowed(bytes.as_ref())),
+        }
+    }
+}
+
+impl PartialEq for FileData {
+    /// Standard base64 encoding is canonical, so encoded data is compared with the
+    /// encoding of raw data.
+    fn eq(&self, other: &FileData) -> bool {
+        match (self, other) {
+            (FileData::Base64(a), FileData::Base64(b)) => a == b,
+            (FileData::Bytes(a), FileData::Bytes(b)) => a == b,
+            (FileData::Base64(encoded), FileData::Bytes(bytes))
+            | (FileData::Bytes(bytes), FileData::Base64(encoded)) => {
+                encoded.len() == bytes.len().div_ceil(3) * 4
+                    && *encoded == general_purpose::STANDARD.encode(bytes)
+            }
+        }
+    }
+}
+
+impl Eq for FileData {}
+
+impl Serialize for FileData {
+    /// Raw content is encoded through [`Base64Display`] straight into the output of
+    /// the serializer, without an intermediate base64 string.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            FileData::Base64(data) => serializer.serialize_str(data),
+            FileData::Bytes(bytes) => {
+                serializer.collect_str(&Base64Display::new(bytes, &general_purpose::STANDARD))
+            }
+        }
+    }
 }
 
 /// Creates a new [`File`] from a base64-encoded data string.
@@ -61,18 +204,108 @@ pub struct File {
 impl File {
     /// Create a new File Object. Data should be a base64 string!
     fn new(name: impl Into<String>, data: String) -> File {
+        File::with_data(name, FileData::Base64(data))
+    }
+
+    /// Creates a new [`File`] from raw content.
+    fn from_bytes(name: impl Into<String>, bytes: Bytes) -> File {
+        File::with_data(name, FileData::Bytes(bytes))
+    }
+
+    fn with_data(name: impl Into<String>, data: FileData) -> File {
         File {
             name: name.into(),
             key: None,
-            data_base64: data,
+            data,
+            page: None,
+        }
+    }
+
+    /// The file name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The page number, if the file was added as a page.
+    pub fn page(&self) -> Option<u32> {
+        self.page
+    }
+
+    /// The key of the file, if any.
+    pub(crate) fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// The content of the file.
+    pub(crate) fn data(&self) -> &FileData {
+        &self.data
+    }
+
+    /// The decoded size of the file content in bytes. For base64 content it is
+    /// computed from the length of the encoded data.
+    pub fn size(&self) -> u64 {
+        match &self.data {
+            FileData::Base64(data) => data.trim_end_matches('=').len() as u64 * 3 / 4,
+            FileData::Bytes(bytes) => bytes.len() as u64,
         }
     }
+
+    /// The lowercase hexadecimal SHA-256 digest of the file content.
+    ///
+    /// Data that is not valid base64 is hashed as is.
+    pub fn content_hash(&self) -> String {
+        match &self.data {
+            FileData::Base64(data) => match general_purpose::STANDARD.decode(data) {
+                Ok(bytes) => util::sha256_hex(&bytes),
+                Err(_) => util::sha256_hex(data.as_bytes()),
+            },
+            FileData::Bytes(bytes) => util::sha256_hex(bytes),
+        }
+    }
+
+    /// Returns `true` if both files have the same content.
+    fn same_content(&self, other: &File) -> bool {
+        self.data == other.data
+    }
+}
+
+/// A file whose content duplicates an earlier file of the same request, as reported by
+/// [`I5Request::duplicate_files`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I5DuplicateFile {
+    document: usize,
+    file: usize,
+    original_document: usize,
+    original_file: usize,
+}
+
+impl I5DuplicateFile {
+    /// The index of the document containing the duplicate.
+    pub fn document(&self) -> usize {
+        self.document
+    }
+
+    /// The index of the duplicate within [`Document::files`].
+    pub fn file(&self) -> usize {
+        self.file
+    }
+
+    /// The index of the document containing the first file with this content.
+    pub fn original_document(&self) -> usize {
+        self.original_document
+    }
+
+    /// The index of the first file with this content within [`Document::files`].
+    pub fn original_file(&self) -> usize {
+        self.original_file
+    }
 }
 
 /// Represents a single document in an Interface5 request.
 ///
-/// Each document can contain multiple fields and files.
-#[derive(Serialize, Debug)]
+/// Each document can contain multiple fields and files. Documents compare equal if
+/// their names, fields and files are equal; validators are not compared.
+#[derive(Serialize, Debug, Clone)]
 pub struct Document {
     #[serde(rename = "Name")]
     name: String,
@@ -80,6 +313,36 @@ pub struct Document {
     fields: Vec<Field>,
     #[serde(rename = "Files")]
     files: Vec<File>,
+    #[serde(skip)]
+    validators: Vec<DocumentValidator>,
+}
+
+/// The signature of a validation rule attached with [`Document::add_validator`].
+type ValidatorFn = dyn Fn(&Document) -> Result<(), String> + Send + Sync;
+
+/// A validation rule attached to a [`Document`] with [`Document::add_validator`].
+#[derive(Clone)]
+struct DocumentValidator(Arc<ValidatorFn>);
+
+impl std::fmt::Debug for DocumentValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DocumentValidator")
+    }
+}
+
+impl PartialEq for Document {
+    fn eq(&self, other: &Document) -> bool {
+        self.name == other.name && self.fields == other.fields && self.files == other.files
+    }
+}
+
+impl Eq for Document {}
+
+impl<K: Into<String>, V: I5FieldValue> Extend<(K, V)> for Document {
+    /// Adds the pairs as header fields, see [`Document::add_header_fields`].
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, fields: I) {
+        self.add_header_fields(fields);
+    }
 }
 
 impl Document {
@@ -92,133 +355,2744 @@ impl Document {
             name: document_name.into(),
             fields: Vec::new(),
             files: Vec::new(),
+            validators: Vec::new(),
         }
     }
 
     /// Adds a header field (item number `0`).
+    ///
+    /// The value can be any [`I5FieldValue`], e.g. a string, number or boolean. The
+    /// field is skipped if the value is `None`.
     pub fn add_header_field(
         &mut self,
         name: impl Into<String>,
-        value: impl Into<String>,
+        value: impl I5FieldValue,
     ) -> &mut Self {
-        self.fields.push(Field::new(name, value, 0));
-        self
+        self.add_item_field(name, value, 0)
     }
 
     /// Adds an item field with a specific item number.
+    ///
+    /// The value can be any [`I5FieldValue`], e.g. a string, number or boolean. The
+    /// field is skipped if the value is `None`.
     pub fn add_item_field(
         &mut self,
         name: impl Into<String>,
-        value: impl Into<String>,
+        value: impl I5FieldValue,
         item_number: i32,
     ) -> &mut Self {
-        self.fields.push(Field::new(name, value, item_number));
+        self.add_field(name, value, I5FieldOptions::new().item_number(item_number))
+    }
+
+    /// Adds a field with the attributes in `options`, e.g. its item number.
+    ///
+    /// The value can be any [`I5FieldValue`], e.g. a string, number or boolean. The
+    /// field is skipped if the value is `None`. See [`I5FieldOptions`] for an example.
+    pub fn add_field(
+        &mut self,
+        name: impl Into<String>,
+        value: impl I5FieldValue,
+        options: I5FieldOptions,
+    ) -> &mut Self {
+        if let Some(value) = value.into_field_value() {
+            self.fields
+                .push(Field::new(name, value, options.item_number));
+        }
         self
     }
 
-    /// Adds a file from a base64-encoded string.
-    pub fn add_base64_file(&mut self, name: impl Into<String>, base64: String) {
-        self.files.push(File::new(name, base64));
+    /// Adds a header field for every name and value pair, e.g. from a map of
+    /// extraction results.
+    ///
+    /// Fields are added in iteration order; use a `BTreeMap` or `Vec` instead of a
+    /// `HashMap` if the order matters. Documents also implement [`Extend`] for pairs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let extracted = HashMap::from([
+    ///     ("InvoiceNo".to_string(), "4711".to_string()),
+    ///     ("Currency".to_string(), "EUR".to_string()),
+    /// ]);
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document.add_header_fields(&extracted);
+    /// document.extend([("Country", "DE")]);
+    /// assert_eq!(document.fields().len(), 3);
+    /// ```
+    pub fn add_header_fields<K: Into<String>, V: I5FieldValue>(
+        &mut self,
+        fields: impl IntoIterator<Item = (K, V)>,
+    ) -> &mut Self {
+        self.add_item_fields(fields, 0)
     }
 
-    /// Adds a file from raw bytes by automatically encoding it to base64.
-    pub fn add_bytes_file(&mut self, name: impl Into<String>, bytes: &[u8]) {
-        let base64_string = general_purpose::STANDARD.encode(bytes);
-        self.add_base64_file(name, base64_string);
+    /// Adds an item field with `item_number` for every name and value pair, e.g. a
+    /// whole position row. Pairs with a `None` value are skipped, as with
+    /// [`Document::add_item_field`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let positions = [("4711", "Screws", 120, 0.05), ("4712", "Nuts", 80, 0.03)];
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// for (row, (article, text, quantity, price)) in (1..).zip(positions) {
+    ///     document.add_item_fields(
+    ///         [
+    ///             ("ArticleNo", article.to_string()),
+    ///             ("Text", text.to_string()),
+    ///             ("Quantity", quantity.to_string()),
+    ///             ("UnitPrice", price.to_string()),
+    ///         ],
+    ///         row,
+    ///     );
+    /// }
+    /// assert_eq!(document.fields().len(), 8);
+    /// assert!(document.fields()[4..].iter().all(|field| field.item_number() == 2));
+    /// ```
+    pub fn add_item_fields<K: Into<String>, V: I5FieldValue>(
+        &mut self,
+        fields: impl IntoIterator<Item = (K, V)>,
+        item_number: i32,
+    ) -> &mut Self {
+        for (name, value) in fields {
+            self.add_item_field(name, value, item_number);
+        }
+        self
     }
-}
 
-/// Represents the complete Interface5 request payload.
-///
-/// A request must contain at least one document, and each document must contain at least
-/// one field or one file to be considered valid.
-#[derive(Serialize, Debug)]
-pub struct I5Reqeust {
-    #[serde(rename = "Name")]
-    name: String,
-    #[serde(rename = "Documents")]
-    documents: Vec<Document>,
-}
+    /// Adds a header field with `amount` written in `format`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{
+    ///     i5_amount::{I5Amount, I5AmountFormat},
+    ///     i5_request::I5Request,
+    /// };
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_amount_field(
+    ///     "GrossAmount",
+    ///     I5Amount::new(123456, 2),
+    ///     &I5AmountFormat::german(),
+    /// );
+    ///
+    /// assert!(request.validate()?.to_json_string()?.contains(r#""Value":"1.234,56""#));
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn add_amount_field(
+        &mut self,
+        name: impl Into<String>,
+        amount: I5Amount,
+        format: &I5AmountFormat,
+    ) -> &mut Self {
+        self.add_header_field(name, amount.format(format))
+    }
 
-impl I5Reqeust {
-    /// Creates a new empty [`I5Reqeust`] with a given name.
-    pub fn new(name: impl Into<String>) -> I5Reqeust {
-        I5Reqeust {
-            name: name.into(),
-            documents: Vec::new(),
+    /// Adds an item field with `amount` written in `format`.
+    pub fn add_item_amount_field(
+        &mut self,
+        name: impl Into<String>,
+        amount: I5Amount,
+        format: &I5AmountFormat,
+        item_number: i32,
+    ) -> &mut Self {
+        self.add_item_field(name, amount.format(format), item_number)
+    }
+
+    /// Adds a header field with the amount of `money` written in `format` and, if
+    /// `currency_field` is given, a header field of that name with the currency code.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{
+    ///     i5_amount::{I5Amount, I5AmountFormat, I5Money},
+    ///     i5_request::I5Request,
+    /// };
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// let gross = I5Money::new(I5Amount::new(123456, 2), "EUR").unwrap();
+    /// document.add_money_field("GrossAmount", &gross, &I5AmountFormat::german(), Some("Currency"));
+    ///
+    /// assert_eq!(document.fields()[0].value(), "1.234,56");
+    /// assert_eq!(document.fields()[1].name(), "Currency");
+    /// assert_eq!(document.fields()[1].value(), "EUR");
+    /// ```
+    pub fn add_money_field(
+        &mut self,
+        name: impl Into<String>,
+        money: &I5Money,
+        format: &I5AmountFormat,
+        currency_field: Option<&str>,
+    ) -> &mut Self {
+        self.add_item_money_field(name, money, format, currency_field, 0)
+    }
+
+    /// Adds an item field with the amount of `money` written in `format` and, if
+    /// `currency_field` is given, an item field of that name with the currency code.
+    pub fn add_item_money_field(
+        &mut self,
+        name: impl Into<String>,
+        money: &I5Money,
+        format: &I5AmountFormat,
+        currency_field: Option<&str>,
+        item_number: i32,
+    ) -> &mut Self {
+        self.add_item_field(name, money.format(format), item_number);
+        if let Some(currency_field) = currency_field {
+            self.add_item_field(currency_field, money.currency(), item_number);
         }
+        self
     }
 
-    /// Adds a new document to the request.
+    /// Adds a header field with the checkbox `value` written in `format`, e.g. the
+    /// [format configured](crate::types::i5_config::I5Config::bool_format) for the
+    /// client.
     ///
-    /// Returns the index of the newly added document.
-    pub fn add_document(&mut self, document_name: impl Into<String>) -> usize {
-        self.documents.push(Document::new(document_name));
-        self.documents.len() - 1
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{i5_bool_format::I5BoolFormat, i5_request::I5Request};
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Order");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document
+    ///     .add_bool_field("Urgent", true, &I5BoolFormat::cross())
+    ///     .add_item_bool_field("Backorder", false, &I5BoolFormat::cross(), 1);
+    ///
+    /// assert_eq!(document.fields()[0].value(), "X");
+    /// assert_eq!(document.fields()[1].value(), "");
+    /// ```
+    pub fn add_bool_field(
+        &mut self,
+        name: impl Into<String>,
+        value: bool,
+        format: &I5BoolFormat,
+    ) -> &mut Self {
+        self.add_header_field(name, format.format(value))
     }
 
-    /// Gets a mutable reference to a document by index.
-    pub fn get_document_mut(&mut self, index: usize) -> Option<&mut Document> {
-        self.documents.get_mut(index)
+    /// Adds an item field with the checkbox `value` written in `format`.
+    pub fn add_item_bool_field(
+        &mut self,
+        name: impl Into<String>,
+        value: bool,
+        format: &I5BoolFormat,
+        item_number: i32,
+    ) -> &mut Self {
+        self.add_item_field(name, format.format(value), item_number)
     }
 
-    /// Gets an immutable reference to a document by index.
-    pub fn get_document(&self, index: usize) -> Option<&Document> {
-        self.documents.get(index)
+    /// Adds a header field with `date` written in `format`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{
+    ///     i5_date::{I5Date, I5DateFormat},
+    ///     i5_request::I5Request,
+    /// };
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_date_field(
+    ///     "InvoiceDate",
+    ///     I5Date::new(2025, 6, 27).unwrap(),
+    ///     I5DateFormat::German,
+    /// );
+    ///
+    /// assert!(request.validate()?.to_json_string()?.contains(r#""Value":"27.06.2025""#));
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn add_date_field(
+        &mut self,
+        name: impl Into<String>,
+        date: impl Into<I5Date>,
+        format: I5DateFormat,
+    ) -> &mut Self {
+        self.add_header_field(name, date.into().format(format))
     }
 
-    /// Checks if the request is structurally valid.
+    /// Adds a header field with the UTC date and time of `time` written in `format`.
     ///
-    /// Requirements:
-    /// - At least one document exists.
-    /// - Each document has at least one field or file.
-    pub fn is_valid(&self) -> bool {
-        // Request needs at least one Document.
-        if self.documents.is_empty() {
-            return false;
+    /// [`I5DateFormat::German`] does not mark the time as UTC; use
+    /// [`add_datetime_field_at`](Self::add_datetime_field_at) for local times.
+    pub fn add_datetime_field(
+        &mut self,
+        name: impl Into<String>,
+        time: SystemTime,
+        format: I5DateFormat,
+    ) -> &mut Self {
+        self.add_header_field(name, format.format_datetime(time))
+    }
+
+    /// Adds a header field with the date and time of `time` at `utc_offset_minutes`
+    /// east of UTC, written in `format`, see [`I5DateFormat::format_datetime_at`].
+    pub fn add_datetime_field_at(
+        &mut self,
+        name: impl Into<String>,
+        time: SystemTime,
+        format: I5DateFormat,
+        utc_offset_minutes: i32,
+    ) -> &mut Self {
+        self.add_header_field(name, format.format_datetime_at(time, utc_offset_minutes))
+    }
+
+    /// Adds a header field with a barcode `value` after checking it against
+    /// `symbology`, e.g. the EAN check digit.
+    ///
+    /// Returns [`I5RequestError::ConversionError`] naming the field if the value
+    /// cannot be encoded in the symbology; the field is not added then.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{i5_barcode::I5BarcodeSymbology, i5_request::I5Request};
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("DeliveryNote");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document.add_barcode_field("ArticleEAN", "4006381333931", I5BarcodeSymbology::Ean13)?;
+    ///
+    /// // A misread digit fails the check digit.
+    /// let misread = document.add_barcode_field("ArticleEAN", "4006381333981", I5BarcodeSymbology::Ean13);
+    /// assert!(misread.is_err());
+    /// assert_eq!(document.fields().len(), 1);
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn add_barcode_field(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+        symbology: I5BarcodeSymbology,
+    ) -> Result<&mut Self, I5RequestError> {
+        let (name, value) = (name.into(), value.into());
+        if let Some(problem) = symbology.problem(&value) {
+            return Err(I5RequestError::ConversionError(format!(
+                "{}: '{}' is not a valid {} barcode: {}",
+                name, value, symbology, problem
+            )));
         }
+        Ok(self.add_header_field(name, value))
+    }
 
-        // Each Document needs at least either one field or one file.
-        for document in &self.documents {
-            let item_numbers: Vec<i32> = document
-                .fields
-                .iter()
-                .map(|field| field.item_number)
-                .collect();
-            if (document.fields.is_empty() && document.files.is_empty())
-                || !is_continuous(&item_numbers)
-            {
-                return false;
+    /// Merges the fields, files and validators of `other` into this document.
+    ///
+    /// Header fields of `other` are added unless this document already has a header
+    /// field with the same name, compared case-insensitively: on a conflict, the value
+    /// of this document is kept and that of `other` is dropped.
+    /// Item fields of `other` are appended as new items:
+    /// its item numbers are renumbered in ascending order to follow the highest item
+    /// number of this document, so the item numbers stay continuous. Files are
+    /// appended in order, with page files inserted by page number. The
+    /// [validators](Document::add_validator) of `other` run after those of this
+    /// document.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let first = request.add_document("Invoice");
+    /// let second = request.add_document("Enrichment");
+    /// request
+    ///     .get_document_mut(first)
+    ///     .unwrap()
+    ///     .add_header_field("No", "4711")
+    ///     .add_item_field("Article", "A", 1);
+    /// request
+    ///     .get_document_mut(second)
+    ///     .unwrap()
+    ///     .add_header_field("No", "ignored")
+    ///     .add_header_field("Supplier", "ACME")
+    ///     .add_item_field("Article", "B", 1)
+    ///     .add_validator(|document| match document.files().is_empty() {
+    ///         true => Err(String::from("the supplier needs the delivery note")),
+    ///         false => Ok(()),
+    ///     });
+    ///
+    /// let enrichment = request.remove_document(second).unwrap();
+    /// request.get_document_mut(first).unwrap().merge(enrichment);
+    /// assert!(!request.is_valid());
+    ///
+    /// request
+    ///     .get_document_mut(first)
+    ///     .unwrap()
+    ///     .add_bytes_file("delivery-note.pdf", b"%PDF-1.7");
+    /// let json = request.validate()?.to_json_string()?;
+    /// assert!(json.contains(r#"{"Name":"Article","Value":"B","ItemNo":2}"#));
+    /// assert!(!json.contains("ignored"));
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn merge(&mut self, other: Document) -> &mut Self {
+        let offset = self.max_item_number();
+
+        let mut item_numbers: Vec<i32> = other
+            .fields
+            .iter()
+            .map(|field| field.item_number)
+            .filter(|item_number| *item_number != 0)
+            .collect();
+        item_numbers.sort_unstable();
+        item_numbers.dedup();
+
+        for mut field in other.fields {
+            if field.item_number == 0 {
+                let present = self.fields.iter().any(|existing| {
+                    existing.item_number == 0 && existing.name.eq_ignore_ascii_case(&field.name)
+                });
+                if present {
+                    continue;
+                }
+            } else {
+                let position = item_numbers
+                    .binary_search(&field.item_number)
+                    .expect("item numbers were collected from these fields");
+                field.item_number = offset + position as i32 + 1;
             }
+            self.fields.push(field);
         }
-
-        true
+        for file in other.files {
+            self.insert_file(file);
+        }
+        self.validators.extend(other.validators);
+        self
     }
 
-    /// Consumes and validates the request.
+    /// Splits the document into documents with at most `max_items` items each.
     ///
-    /// Returns a [`ValidatedI5Request`] on success, or an [`I5RequestError::ValidationError`] if invalid.
-    pub fn validate(self) -> Result<ValidatedI5Request, I5RequestError> {
-        if self.is_valid() {
-            Ok(ValidatedI5Request(self))
-        } else {
-            Err(I5RequestError::ValidationError)
+    /// Every part gets a copy of the header fields and the item fields of up to
+    /// `max_items` consecutive item numbers, renumbered from 1. The files stay with the
+    /// first part and the validators are attached to every part. A document with no
+    /// more than `max_items` items is returned unchanged
+    /// as the only part.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_items` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document.add_header_field("No", "4711");
+    /// for item in 1..=5 {
+    ///     document.add_item_field("Article", format!("A{}", item), item);
+    /// }
+    ///
+    /// let document = request.remove_document(id).unwrap();
+    /// let parts = document.split_items(2);
+    /// assert_eq!(parts.len(), 3);
+    /// for part in parts {
+    ///     request.push_document(part);
+    /// }
+    ///
+    /// let json = request.validate()?.to_json_string()?;
+    /// assert!(json.contains(r#"{"Name":"Article","Value":"A5","ItemNo":1}"#));
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn split_items(self, max_items: usize) -> Vec<Document> {
+        assert!(max_items > 0, "max_items must be greater than 0");
+
+        let mut item_numbers: Vec<i32> = self
+            .fields
+            .iter()
+            .map(|field| field.item_number)
+            .filter(|item_number| *item_number != 0)
+            .collect();
+        item_numbers.sort_unstable();
+        item_numbers.dedup();
+        if item_numbers.len() <= max_items {
+            return vec![self];
+        }
+
+        let (header, items): (Vec<Field>, Vec<Field>) = self
+            .fields
+            .into_iter()
+            .partition(|field| field.item_number == 0);
+        let mut parts: Vec<Document> = item_numbers
+            .chunks(max_items)
+            .map(|_| Document {
+                name: self.name.clone(),
+                fields: header.clone(),
+                files: Vec::new(),
+                validators: self.validators.clone(),
+            })
+            .collect();
+        parts[0].files = self.files;
+
+        for mut field in items {
+            let position = item_numbers
+                .binary_search(&field.item_number)
+                .expect("item numbers were collected from these fields");
+            field.item_number = (position % max_items) as i32 + 1;
+            parts[position / max_items].fields.push(field);
         }
+        parts
     }
-}
 
-/// A wrapper type representing a validated [`I5Reqeust`] that is guaranteed to be ready for serialization and sending.
-pub struct ValidatedI5Request(I5Reqeust);
+    /// Attaches a validation rule that runs whenever the request containing this
+    /// document is checked with [`I5Request::is_valid`] or [`I5Request::validate`].
+    ///
+    /// The rule returns `Err` with a description of the problem if the document is
+    /// invalid. Failures are logged by [`I5Request::validate`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request
+    ///     .get_document_mut(id)
+    ///     .unwrap()
+    ///     .add_header_field("No", "4711")
+    ///     .add_validator(|document| {
+    ///         if document.files().is_empty() {
+    ///             Err(String::from("invoices need the scanned invoice"))
+    ///         } else {
+    ///             Ok(())
+    ///         }
+    ///     });
+    ///
+    /// assert!(!request.is_valid());
+    /// ```
+    pub fn add_validator(
+        &mut self,
+        validator: impl Fn(&Document) -> Result<(), String> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.validators.push(DocumentValidator(Arc::new(validator)));
+        self
+    }
 
-impl ValidatedI5Request {
-    /// Serializes the validated request into a JSON string.
-    pub fn to_json_string(&self) -> Result<String, I5RequestError> {
-        serde_json::to_string(&self.0).map_err(I5RequestError::SerializeError)
+    /// The highest item number of the document, or `0` if it has no item fields.
+    pub fn max_item_number(&self) -> i32 {
+        self.fields
+            .iter()
+            .map(|field| field.item_number)
+            .max()
+            .unwrap_or(0)
+            .max(0)
     }
-}
 
-/// Checks if a given list of integers forms a continuous, gapless sequence (ignoring zeros).
-///
+    /// Compacts the item numbers into a continuous sequence starting at 1, keeping
+    /// their relative order. Header fields are not changed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document
+    ///     .add_item_field("Article", "A", 2)
+    ///     .add_item_field("Article", "B", 5)
+    ///     .add_item_field("Quantity", "1", 5);
+    /// assert!(!request.is_valid());
+    ///
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document.renumber_items();
+    /// assert_eq!(document.max_item_number(), 2);
+    /// assert!(request.is_valid());
+    /// ```
+    pub fn renumber_items(&mut self) -> &mut Self {
+        let mut item_numbers: Vec<i32> = self
+            .fields
+            .iter()
+            .map(|field| field.item_number)
+            .filter(|item_number| *item_number > 0)
+            .collect();
+        item_numbers.sort_unstable();
+        item_numbers.dedup();
+
+        for field in &mut self.fields {
+            if let Ok(position) = item_numbers.binary_search(&field.item_number) {
+                field.item_number = position as i32 + 1;
+            }
+        }
+        self
+    }
+
+    /// Runs the attached validators and returns the descriptions of all failures.
+    fn validator_failures(&self) -> Vec<String> {
+        self.validators
+            .iter()
+            .filter_map(|validator| (validator.0)(self).err())
+            .collect()
+    }
+
+    /// Adds a file from a base64-encoded string.
+    pub fn add_base64_file(&mut self, name: impl Into<String>, base64: String) {
+        self.files.push(File::new(name, base64));
+    }
+
+    /// Adds a file from raw bytes, which are encoded to base64 when the request is
+    /// serialized.
+    pub fn add_bytes_file(&mut self, name: impl Into<String>, bytes: &[u8]) {
+        self.add_shared_file(name, Bytes::copy_from_slice(bytes));
+    }
+
+    /// Adds a file from a shared buffer without copying it. The content is encoded to
+    /// base64 when the request is serialized.
+    ///
+    /// Cloning a [`Bytes`] buffer only increments a reference count, so the same
+    /// large file can be attached to several documents, or kept for a retry, at the
+    /// cost of a single copy in memory. An `Arc<[u8]>` is wrapped with
+    /// [`Bytes::from_owner`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    ///
+    /// use bytes::Bytes;
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let scan: Arc<[u8]> = Arc::from(vec![0u8; 1 << 20]);
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// for number in ["4711", "4712"] {
+    ///     let id = request.add_document("Invoice");
+    ///     let document = request.get_document_mut(id).unwrap();
+    ///     document.add_header_field("InvoiceNo", number);
+    ///     document.add_shared_file("scan.pdf", Bytes::from_owner(scan.clone()));
+    /// }
+    /// assert_eq!(request.total_attachment_bytes(), 2 << 20);
+    /// ```
+    pub fn add_shared_file(&mut self, name: impl Into<String>, data: impl Into<Bytes>) {
+        self.files.push(File::from_bytes(name, data.into()));
+    }
+
+    /// Adds a file from a shared buffer under `name` with the key `key`.
+    pub(crate) fn add_keyed_file(
+        &mut self,
+        name: impl Into<String>,
+        key: Option<String>,
+        data: Bytes,
+    ) {
+        let mut file = File::from_bytes(name, data);
+        file.key = key;
+        self.files.push(file);
+    }
+
+    /// Reads the file at `path` and adds it under its file name.
+    ///
+    /// Returns [`I5RequestError::IoError`] if the file cannot be read.
+    pub fn add_path_file(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, I5RequestError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(I5RequestError::IoError)?;
+        self.add_shared_file(file_name(path), bytes);
+        Ok(self)
+    }
+
+    /// Reads the file at `path` with `tokio::fs` and adds it under its file name. The
+    /// asynchronous counterpart of [`Document::add_path_file`].
+    ///
+    /// Returns [`I5RequestError::IoError`] if the file cannot be read.
+    pub async fn add_path_file_async(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<&mut Self, I5RequestError> {
+        let path = path.as_ref();
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(I5RequestError::IoError)?;
+        self.add_shared_file(file_name(path), bytes);
+        Ok(self)
+    }
+
+    /// Adds every file in `directory` whose name matches `pattern` (`*` and `?`
+    /// wildcards, ignoring case), in the order of their names.
+    ///
+    /// Subdirectories are not searched. Returns the number of files added, or
+    /// [`I5RequestError::IoError`] if the directory or a file cannot be read.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let directory = std::env::temp_dir().join(format!("i5-doc-dir-{}", std::process::id()));
+    /// std::fs::create_dir_all(&directory)?;
+    /// for name in ["page-2.tif", "page-1.tif", "notes.txt"] {
+    ///     std::fs::write(directory.join(name), b"..")?;
+    /// }
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// assert_eq!(document.add_directory_files(&directory, "*.tif")?, 2);
+    ///
+    /// let names: Vec<&str> = document.files().iter().map(|file| file.name()).collect();
+    /// assert_eq!(names, ["page-1.tif", "page-2.tif"]);
+    /// # std::fs::remove_dir_all(&directory)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_directory_files(
+        &mut self,
+        directory: impl AsRef<Path>,
+        pattern: &str,
+    ) -> Result<usize, I5RequestError> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(directory).map_err(I5RequestError::IoError)? {
+            let entry = entry.map_err(I5RequestError::IoError)?;
+            if entry
+                .file_type()
+                .map_err(I5RequestError::IoError)?
+                .is_file()
+            {
+                paths.push(entry.path());
+            }
+        }
+        let paths = matching_paths(paths, pattern);
+        for path in &paths {
+            self.add_path_file(path)?;
+        }
+        Ok(paths.len())
+    }
+
+    /// Adds every file in `directory` whose name matches `pattern` using `tokio::fs`.
+    /// The asynchronous counterpart of [`Document::add_directory_files`].
+    pub async fn add_directory_files_async(
+        &mut self,
+        directory: impl AsRef<Path>,
+        pattern: &str,
+    ) -> Result<usize, I5RequestError> {
+        let mut paths = Vec::new();
+        let mut entries = tokio::fs::read_dir(directory)
+            .await
+            .map_err(I5RequestError::IoError)?;
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(I5RequestError::IoError)?
+        {
+            if entry
+                .file_type()
+                .await
+                .map_err(I5RequestError::IoError)?
+                .is_file()
+            {
+                paths.push(entry.path());
+            }
+        }
+        let paths = matching_paths(paths, pattern);
+        for path in &paths {
+            self.add_path_file_async(path).await?;
+        }
+        Ok(paths.len())
+    }
+
+    /// Maps the file at `path` into memory and adds it under its file name. See
+    /// [`I5MappedFile`](crate::types::i5_mapped_file::I5MappedFile).
+    ///
+    /// Returns [`I5RequestError::IoError`] if the file cannot be mapped.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated until the request and every copy
+    /// of its content are dropped, see
+    /// [`I5MappedFile::open`](crate::types::i5_mapped_file::I5MappedFile::open).
+    #[cfg(feature = "mmap")]
+    pub unsafe fn add_mapped_file(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<&mut Self, I5RequestError> {
+        let path = path.as_ref();
+        // SAFETY: The caller guarantees that the file is not changed while it is
+        // mapped.
+        let map = unsafe { crate::types::i5_mapped_file::I5MappedFile::open(path) }
+            .map_err(I5RequestError::IoError)?;
+        self.add_shared_file(file_name(path), Bytes::from_owner(map));
+        Ok(self)
+    }
+
+    /// Reserves capacity for at least `additional` more fields.
+    pub fn reserve_fields(&mut self, additional: usize) -> &mut Self {
+        self.fields.reserve(additional);
+        self
+    }
+
+    /// Adds a file from a base64-encoded string unless the document already contains a
+    /// file with the same content.
+    ///
+    /// Returns `true` if the file was added and `false` if it was skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    ///
+    /// assert!(document.add_unique_bytes_file("scan.pdf", b"%PDF-1.7"));
+    /// assert!(!document.add_unique_bytes_file("scan (copy).pdf", b"%PDF-1.7"));
+    /// assert_eq!(document.files().len(), 1);
+    /// ```
+    pub fn add_unique_base64_file(&mut self, name: impl Into<String>, base64: String) -> bool {
+        self.push_unique_file(File::new(name, base64))
+    }
+
+    /// Adds a file from raw bytes unless the document already contains a file with the
+    /// same content. See [`Document::add_unique_base64_file`].
+    pub fn add_unique_bytes_file(&mut self, name: impl Into<String>, bytes: &[u8]) -> bool {
+        self.push_unique_file(File::from_bytes(name, Bytes::copy_from_slice(bytes)))
+    }
+
+    /// Appends `file` unless the document already contains a file with the same content.
+    fn push_unique_file(&mut self, file: File) -> bool {
+        if self
+            .files
+            .iter()
+            .any(|existing| existing.same_content(&file))
+        {
+            return false;
+        }
+        self.files.push(file);
+        true
+    }
+
+    /// Adds a page file from a base64-encoded string.
+    ///
+    /// Files with a page number are kept in ascending page order, regardless of the
+    /// order they were added in, and before all files without a page number. Pages
+    /// with the same number keep the order they were added in.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document.add_bytes_file("attachment.xml", b"<xml/>");
+    /// document.add_bytes_page("page-2.tif", b"..", 2);
+    /// document.add_bytes_page("page-1.tif", b"..", 1);
+    ///
+    /// let names: Vec<&str> = document.files().iter().map(|file| file.name()).collect();
+    /// assert_eq!(names, ["page-1.tif", "page-2.tif", "attachment.xml"]);
+    /// ```
+    pub fn add_base64_page(&mut self, name: impl Into<String>, base64: String, page: u32) {
+        let mut file = File::new(name, base64);
+        file.page = Some(page);
+        self.insert_file(file);
+    }
+
+    /// Inserts a file, keeping page files in page order before all other files.
+    fn insert_file(&mut self, file: File) {
+        let Some(page) = file.page else {
+            self.files.push(file);
+            return;
+        };
+        let position = self
+            .files
+            .iter()
+            .position(|existing| existing.page.is_none_or(|existing| existing > page))
+            .unwrap_or(self.files.len());
+        self.files.insert(position, file);
+    }
+
+    /// Adds a page file from raw bytes, which are encoded to base64 when the request is
+    /// serialized. See [`Document::add_base64_page`].
+    pub fn add_bytes_page(&mut self, name: impl Into<String>, bytes: &[u8], page: u32) {
+        let mut file = File::from_bytes(name, Bytes::copy_from_slice(bytes));
+        file.page = Some(page);
+        self.insert_file(file);
+    }
+
+    /// Applies `transforms` to the values of all fields.
+    pub(crate) fn apply_transforms(&mut self, transforms: &I5Transforms) {
+        for field in &mut self.fields {
+            field.value = transforms.apply(&field.name, &field.value);
+        }
+    }
+
+    /// The name of the document.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sets the document class for scenarios with several classes, replacing any class
+    /// set before.
+    ///
+    /// Interface5 reads the class from the header field [`DOCUMENT_CLASS_FIELD`]. The
+    /// classes of a scenario can be listed with
+    /// [`I5Client::document_classes`](crate::request::client::I5Client::document_classes).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::{DOCUMENT_CLASS_FIELD, I5Request};
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document.set_class("CreditNote").set_class("Invoice");
+    ///
+    /// assert_eq!(document.class(), Some("Invoice"));
+    /// assert_eq!(document.fields().len(), 1);
+    /// assert_eq!(document.fields()[0].name(), DOCUMENT_CLASS_FIELD);
+    /// ```
+    pub fn set_class(&mut self, class: impl Into<String>) -> &mut Self {
+        self.set_property(DOCUMENT_CLASS_FIELD, class.into())
+    }
+
+    /// The document class, if set with [`Document::set_class`] or as the header field
+    /// [`DOCUMENT_CLASS_FIELD`].
+    pub fn class(&self) -> Option<&str> {
+        self.property(DOCUMENT_CLASS_FIELD)
+    }
+
+    /// Sets the reference of the document in the sending system, e.g. the ID of the
+    /// record it was exported from, replacing any reference set before.
+    ///
+    /// Interface5 reads the reference from the header field
+    /// [`DOCUMENT_REFERENCE_FIELD`] and returns it with the results of the document,
+    /// so that they can be matched without keeping the document IDs. The reference
+    /// must not be blank.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document.set_external_reference("ERP-2025-000815").set_priority(5);
+    ///
+    /// assert_eq!(document.external_reference(), Some("ERP-2025-000815"));
+    /// assert_eq!(document.priority(), Some(5));
+    /// assert!(request.validate()?.to_json_string()?.contains(r#""Name":"Priority","Value":"5""#));
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn set_external_reference(&mut self, reference: impl Into<String>) -> &mut Self {
+        self.set_property(DOCUMENT_REFERENCE_FIELD, reference.into())
+    }
+
+    /// The external reference, if set with [`Document::set_external_reference`] or as
+    /// the header field [`DOCUMENT_REFERENCE_FIELD`].
+    pub fn external_reference(&self) -> Option<&str> {
+        self.property(DOCUMENT_REFERENCE_FIELD)
+    }
+
+    /// Sets the processing priority of the document for scenarios that route on it,
+    /// replacing any priority set before.
+    ///
+    /// Interface5 reads the priority from the header field [`DOCUMENT_PRIORITY_FIELD`].
+    /// See [`Document::set_external_reference`] for an example.
+    pub fn set_priority(&mut self, priority: u32) -> &mut Self {
+        self.set_property(DOCUMENT_PRIORITY_FIELD, priority.to_string())
+    }
+
+    /// The priority, if set with [`Document::set_priority`] or as the header field
+    /// [`DOCUMENT_PRIORITY_FIELD`] with a number as its value.
+    pub fn priority(&self) -> Option<u32> {
+        self.property(DOCUMENT_PRIORITY_FIELD)?.trim().parse().ok()
+    }
+
+    /// Replaces the header field `name` of a document property with `value`.
+    fn set_property(&mut self, name: &str, value: String) -> &mut Self {
+        self.fields.retain(|field| !field.is_header(name));
+        self.fields.push(Field::new(name, value, 0));
+        self
+    }
+
+    /// The value of the header field `name` of a document property.
+    fn property(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .rfind(|field| field.is_header(name))
+            .map(Field::value)
+    }
+
+    /// The document property fields that are repeated or have an invalid value, with
+    /// the reason.
+    fn property_failures(&self) -> Vec<(&'static str, I5PropertyProblem)> {
+        let mut failures = Vec::new();
+        for name in PROPERTY_FIELDS {
+            let values: Vec<&str> = self
+                .fields
+                .iter()
+                .filter(|field| field.is_header(name))
+                .map(Field::value)
+                .collect();
+            if values.len() > 1 {
+                failures.push((
+                    name,
+                    I5PropertyProblem::Repeated {
+                        count: values.len(),
+                    },
+                ));
+            }
+            let Some(value) = values.last() else {
+                continue;
+            };
+            if value.trim().is_empty() {
+                failures.push((name, I5PropertyProblem::Blank));
+            } else if name == DOCUMENT_PRIORITY_FIELD && value.trim().parse::<u32>().is_err() {
+                failures.push((
+                    name,
+                    I5PropertyProblem::NotANumber {
+                        value: value.to_string(),
+                    },
+                ));
+            }
+        }
+        failures
+    }
+
+    /// The header and item fields of the document in the order they are sent.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// The files of the document in the order they are sent.
+    pub fn files(&self) -> &[File] {
+        &self.files
+    }
+
+    /// The number of header and item fields.
+    pub fn field_count(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// The number of header fields.
+    pub fn header_field_count(&self) -> usize {
+        self.fields
+            .iter()
+            .filter(|field| field.item_number == 0)
+            .count()
+    }
+
+    /// The number of item fields.
+    pub fn item_field_count(&self) -> usize {
+        self.field_count() - self.header_field_count()
+    }
+
+    /// The number of distinct items.
+    pub fn item_count(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| field.item_number)
+            .filter(|item_number| *item_number != 0)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// The number of files.
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// The decoded size of all files in bytes.
+    pub fn total_attachment_bytes(&self) -> u64 {
+        self.files.iter().map(File::size).sum()
+    }
+}
+
+/// Represents the complete Interface5 request payload.
+///
+/// A request must contain at least one document, and each document must contain at least
+/// one field or one file to be considered valid.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct I5Request {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Documents")]
+    documents: Vec<Document>,
+}
+
+/// The former, misspelled name of [`I5Request`].
+#[deprecated(since = "0.1.0", note = "renamed to `I5Request`")]
+pub type I5Reqeust = I5Request;
+
+impl I5Request {
+    /// Creates a new empty [`I5Request`] with a given name.
+    pub fn new(name: impl Into<String>) -> I5Request {
+        I5Request {
+            name: name.into(),
+            documents: Vec::new(),
+        }
+    }
+
+    /// Creates a new empty [`I5Request`] named by `namer`.
+    ///
+    /// Returns the error of the namer if it cannot generate a name.
+    pub fn named_by(namer: &dyn I5BatchNamer) -> Result<I5Request, I5RequestError> {
+        Ok(I5Request::new(namer.next_name()?))
+    }
+
+    /// The name of the request.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The documents of the request.
+    pub fn documents(&self) -> &[Document] {
+        &self.documents
+    }
+
+    /// The number of documents.
+    pub fn document_count(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// The number of header and item fields of all documents.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// for number in ["4711", "4712"] {
+    ///     let id = request.add_document("Invoice");
+    ///     let document = request.get_document_mut(id).unwrap();
+    ///     document.add_header_field("InvoiceNo", number);
+    ///     document.add_bytes_file("scan.pdf", &[0; 100]);
+    /// }
+    ///
+    /// assert_eq!(request.document_count(), 2);
+    /// assert_eq!(request.field_count(), 2);
+    /// assert_eq!(request.file_count(), 2);
+    /// assert_eq!(request.total_attachment_bytes(), 200);
+    /// ```
+    pub fn field_count(&self) -> usize {
+        self.documents.iter().map(Document::field_count).sum()
+    }
+
+    /// The number of files of all documents.
+    pub fn file_count(&self) -> usize {
+        self.documents.iter().map(Document::file_count).sum()
+    }
+
+    /// The decoded size of all files of all documents in bytes.
+    pub fn total_attachment_bytes(&self) -> u64 {
+        self.documents
+            .iter()
+            .map(Document::total_attachment_bytes)
+            .sum()
+    }
+
+    /// Adds a new document to the request.
+    ///
+    /// Returns the index of the newly added document.
+    pub fn add_document(&mut self, document_name: impl Into<String>) -> usize {
+        self.documents.push(Document::new(document_name));
+        self.documents.len() - 1
+    }
+
+    /// Appends an existing document to the request.
+    ///
+    /// Returns the index of the document.
+    pub fn push_document(&mut self, document: Document) -> usize {
+        self.documents.push(document);
+        self.documents.len() - 1
+    }
+
+    /// Removes and returns the document at `index`, shifting later documents down.
+    pub fn remove_document(&mut self, index: usize) -> Option<Document> {
+        (index < self.documents.len()).then(|| self.documents.remove(index))
+    }
+
+    /// Appends all documents of `other` to this request.
+    pub fn merge(&mut self, other: I5Request) -> &mut Self {
+        self.documents.extend(other.documents);
+        self
+    }
+
+    /// Splits every document with more than `max_items` items, see
+    /// [`Document::split_items`]. The parts replace the original document in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_items` is 0.
+    pub fn split_documents(&mut self, max_items: usize) -> &mut Self {
+        let documents = std::mem::take(&mut self.documents);
+        self.documents = documents
+            .into_iter()
+            .flat_map(|document| document.split_items(max_items))
+            .collect();
+        self
+    }
+
+    /// Finds files whose content duplicates an earlier file anywhere in the request.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let first = request.add_document("Invoice");
+    /// let second = request.add_document("Invoice");
+    /// request.get_document_mut(first).unwrap().add_bytes_file("a.pdf", b"same");
+    /// request.get_document_mut(second).unwrap().add_bytes_file("b.pdf", b"same");
+    ///
+    /// let duplicates = request.duplicate_files();
+    /// assert_eq!(duplicates.len(), 1);
+    /// assert_eq!(duplicates[0].document(), 1);
+    /// assert_eq!(duplicates[0].original_document(), 0);
+    ///
+    /// request.remove_duplicate_files();
+    /// assert!(request.duplicate_files().is_empty());
+    /// ```
+    pub fn duplicate_files(&self) -> Vec<I5DuplicateFile> {
+        let mut seen: Vec<(usize, usize, &File)> = Vec::new();
+        let mut duplicates = Vec::new();
+        for (document_index, document) in self.documents.iter().enumerate() {
+            for (file_index, file) in document.files.iter().enumerate() {
+                match seen
+                    .iter()
+                    .find(|(_, _, earlier)| earlier.same_content(file))
+                {
+                    Some((original_document, original_file, _)) => {
+                        duplicates.push(I5DuplicateFile {
+                            document: document_index,
+                            file: file_index,
+                            original_document: *original_document,
+                            original_file: *original_file,
+                        })
+                    }
+                    None => seen.push((document_index, file_index, file)),
+                }
+            }
+        }
+        duplicates
+    }
+
+    /// Removes all files reported by [`I5Request::duplicate_files`], keeping the first
+    /// file with each content.
+    ///
+    /// Returns the removed duplicates, with indices referring to the request before
+    /// the removal. Removing files can leave a document without fields and files.
+    pub fn remove_duplicate_files(&mut self) -> Vec<I5DuplicateFile> {
+        let duplicates = self.duplicate_files();
+        for duplicate in duplicates.iter().rev() {
+            self.documents[duplicate.document]
+                .files
+                .remove(duplicate.file);
+        }
+        duplicates
+    }
+
+    /// Gets a mutable reference to a document by index.
+    pub fn get_document_mut(&mut self, index: usize) -> Option<&mut Document> {
+        self.documents.get_mut(index)
+    }
+
+    /// Gets an immutable reference to a document by index.
+    pub fn get_document(&self, index: usize) -> Option<&Document> {
+        self.documents.get(index)
+    }
+
+    /// Adds a header field to every document that does not already have a header
+    /// field named `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// request.add_document("Invoice");
+    /// let second = request.add_document("Invoice");
+    /// request.get_document_mut(second).unwrap().add_header_field("SOURCE", "Mail");
+    ///
+    /// request.add_default_header_field("SOURCE", "ScannerA");
+    ///
+    /// let json = request.validate()?.to_json_string()?;
+    /// assert!(json.contains(r#""Value":"ScannerA""#));
+    /// assert!(json.contains(r#""Value":"Mail""#));
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn add_default_header_field(&mut self, name: &str, value: &str) -> &mut Self {
+        for document in &mut self.documents {
+            let present = document
+                .fields
+                .iter()
+                .any(|field| field.item_number == 0 && field.name == name);
+            if !present {
+                document.add_header_field(name, value);
+            }
+        }
+        self
+    }
+
+    /// Sets the priority of every document in the request, see
+    /// [`Document::set_priority`].
+    ///
+    /// The batch has no priority of its own, so the priority only reaches documents
+    /// added before the call.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::{BATCH_REFERENCE_FIELD, I5Request};
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// for number in ["4711", "4712"] {
+    ///     let id = request.add_document("Invoice");
+    ///     request.get_document_mut(id).unwrap().add_header_field("No", number);
+    /// }
+    /// request.set_priority(8).set_external_reference("nightly-export-0815");
+    ///
+    /// assert_eq!(request.priority(), Some(8));
+    /// assert_eq!(request.external_reference(), Some("nightly-export-0815"));
+    /// assert_eq!(request.documents()[1].fields()[2].name(), BATCH_REFERENCE_FIELD);
+    /// ```
+    pub fn set_priority(&mut self, priority: u32) -> &mut Self {
+        for document in &mut self.documents {
+            document.set_priority(priority);
+        }
+        self
+    }
+
+    /// The priority shared by all documents, or `None` if the request has no
+    /// documents or they differ in priority.
+    pub fn priority(&self) -> Option<u32> {
+        let (first, rest) = self.documents.split_first()?;
+        let priority = first.priority()?;
+        rest.iter()
+            .all(|document| document.priority() == Some(priority))
+            .then_some(priority)
+    }
+
+    /// Sets the reference of the batch in the sending system, e.g. the ID of an export
+    /// run, on every document in the request, replacing any reference set before.
+    ///
+    /// Interface5 reads it from the header field [`BATCH_REFERENCE_FIELD`], next to the
+    /// [reference of the document](Document::set_external_reference) itself. As with
+    /// [`I5Request::set_priority`], it only reaches documents added before the call.
+    /// The reference must not be blank.
+    pub fn set_external_reference(&mut self, reference: impl Into<String>) -> &mut Self {
+        let reference = reference.into();
+        for document in &mut self.documents {
+            document.set_property(BATCH_REFERENCE_FIELD, reference.clone());
+        }
+        self
+    }
+
+    /// The batch reference shared by all documents, or `None` if the request has no
+    /// documents or they differ in it.
+    pub fn external_reference(&self) -> Option<&str> {
+        let (first, rest) = self.documents.split_first()?;
+        let reference = first.property(BATCH_REFERENCE_FIELD)?;
+        rest.iter()
+            .all(|document| document.property(BATCH_REFERENCE_FIELD) == Some(reference))
+            .then_some(reference)
+    }
+
+    /// Renames the fields of all documents according to `mapping`.
+    pub fn apply_field_mapping(&mut self, mapping: &I5FieldMapping) -> &mut Self {
+        for document in &mut self.documents {
+            for field in &mut document.fields {
+                if let Some(target) = mapping.target(&field.name) {
+                    field.name = target.to_string();
+                }
+            }
+        }
+        self
+    }
+
+    /// Applies `transforms` to the values of all fields of all documents.
+    pub fn apply_transforms(&mut self, transforms: &I5Transforms) -> &mut Self {
+        for document in &mut self.documents {
+            document.apply_transforms(transforms);
+        }
+        self
+    }
+
+    /// Runs `transformer` on every document, e.g. to enrich the documents before they
+    /// are validated.
+    ///
+    /// Returns the first error of the transformer. Documents before the failing one
+    /// keep their changes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{i5_error::I5RequestError, i5_request::{Document, I5Request}};
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("VendorName", "ACME");
+    ///
+    /// let lookup = |document: &mut Document| {
+    ///     let vendor = document.fields().iter().find(|field| field.name() == "VendorName");
+    ///     let number = match vendor.map(|field| field.value()) {
+    ///         Some("ACME") => "70001",
+    ///         _ => return Err(I5RequestError::ConversionError("unknown vendor".into())),
+    ///     };
+    ///     document.add_header_field("VendorNo", number);
+    ///     Ok(())
+    /// };
+    /// request.apply_transformer(&lookup)?;
+    /// assert_eq!(request.documents()[0].fields()[1].value(), "70001");
+    /// # Ok::<(), I5RequestError>(())
+    /// ```
+    pub fn apply_transformer(
+        &mut self,
+        transformer: &(impl I5Transformer + ?Sized),
+    ) -> Result<&mut Self, I5RequestError> {
+        for document in &mut self.documents {
+            transformer.transform(document)?;
+        }
+        Ok(self)
+    }
+
+    /// Sanitizes the values of all fields of all documents with `sanitizer`.
+    ///
+    /// Returns a report of the changed fields, including those that were truncated.
+    pub fn sanitize(&mut self, sanitizer: &I5Sanitizer) -> I5SanitizeReport {
+        let mut report = I5SanitizeReport::default();
+        for (index, document) in self.documents.iter_mut().enumerate() {
+            for field in &mut document.fields {
+                let (value, truncated) = sanitizer.sanitize(&field.value);
+                if value != field.value {
+                    report.push(I5SanitizedField {
+                        document: index,
+                        name: field.name.clone(),
+                        item_number: field.item_number,
+                        original_length: field.value.chars().count(),
+                        truncated,
+                    });
+                    field.value = value;
+                }
+            }
+        }
+        report
+    }
+
+    /// Removes the fields rejected by `rules` from all documents.
+    ///
+    /// The rules are evaluated against the fields as they were before any field was
+    /// removed. Removing fields can make the request invalid, e.g. if a document is
+    /// left without fields and files.
+    pub fn apply_field_rules(&mut self, rules: &I5FieldRules) -> &mut Self {
+        for document in &mut self.documents {
+            let keep: Vec<bool> = document
+                .fields
+                .iter()
+                .map(|field| {
+                    rules.keeps(&field.name, &field.value, |other| {
+                        let find = |item_number: i32| {
+                            document
+                                .fields
+                                .iter()
+                                .find(|candidate| {
+                                    candidate.item_number == item_number
+                                        && candidate.name.eq_ignore_ascii_case(other)
+                                })
+                                .map(|candidate| candidate.value.as_str())
+                        };
+                        find(field.item_number).or_else(|| find(0))
+                    })
+                })
+                .collect();
+
+            let mut keep = keep.into_iter();
+            document.fields.retain(|_| keep.next().unwrap_or(true));
+        }
+        self
+    }
+
+    /// Counts the documents, fields and files of the request and the total size of
+    /// its attachments. See [`I5RequestSummary`].
+    pub fn summary(&self) -> I5RequestSummary {
+        let sum = |count: fn(&Document) -> usize| self.documents.iter().map(count).sum();
+        I5RequestSummary {
+            name: self.name.clone(),
+            documents: self.document_count(),
+            header_fields: sum(Document::header_field_count),
+            item_fields: sum(Document::item_field_count),
+            items: sum(Document::item_count),
+            files: self.file_count(),
+            attachment_bytes: self.total_attachment_bytes(),
+        }
+    }
+
+    /// The length in bytes of the JSON body of the request, computed without
+    /// serializing it.
+    ///
+    /// The result equals the length of [`ValidatedI5Request::to_json_string`]; it can
+    /// be used to split batches or enforce quotas before a body is allocated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// let document = request.get_document_mut(id).unwrap();
+    /// document
+    ///     .add_header_field("Note", "\"quoted\"\n")
+    ///     .add_item_field("Article", "Äpfel\u{1}", 1);
+    /// document.add_bytes_file("scan.pdf", &[0; 1000]);
+    ///
+    /// let size = request.estimated_encoded_size();
+    /// let validated = request.validate()?;
+    /// assert_eq!(size, validated.estimated_encoded_size());
+    /// assert_eq!(size, validated.to_json_string()?.len());
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn estimated_encoded_size(&self) -> usize {
+        // {"Name":…,"Documents":[…]}
+        r#"{"Name":,"Documents":[]}"#.len()
+            + json_string_len(&self.name)
+            + comma_separated(self.documents.iter().map(encoded_document_size))
+    }
+
+    /// Splits the request by documents into requests whose
+    /// [estimated size](I5Request::estimated_encoded_size) is at most `max_bytes`.
+    ///
+    /// Documents keep their order and are never split themselves. A request that
+    /// already fits is returned unchanged; otherwise the parts are named after the
+    /// request with `-1`, `-2`, … appended.
+    ///
+    /// Returns [`I5RequestError::PayloadTooLarge`] if a single document does not fit.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// for number in ["4711", "4712", "4713"] {
+    ///     let id = request.add_document("Invoice");
+    ///     let document = request.get_document_mut(id).unwrap();
+    ///     document.add_header_field("No", number);
+    ///     document.add_bytes_file("scan.pdf", &[0; 3000]);
+    /// }
+    ///
+    /// let parts = request.split_by_size(10_000)?;
+    /// assert_eq!(parts.len(), 2);
+    /// assert_eq!(parts[1].name(), "Batch-2");
+    /// assert!(parts.iter().all(|part| part.estimated_encoded_size() <= 10_000));
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn split_by_size(self, max_bytes: u64) -> Result<Vec<I5Request>, I5RequestError> {
+        if self.estimated_encoded_size() as u64 <= max_bytes {
+            return Ok(vec![self]);
+        }
+
+        // Size the parts for the longest suffix, so renaming never pushes one over.
+        let suffix = format!("-{}", self.documents.len());
+        let empty =
+            r#"{"Name":,"Documents":[]}"#.len() + json_string_len(&self.name) + suffix.len();
+        let mut parts: Vec<Vec<Document>> = Vec::new();
+        let mut size = 0;
+        for document in self.documents {
+            let document_size = encoded_document_size(&document);
+            if (empty + document_size) as u64 > max_bytes {
+                return Err(I5RequestError::PayloadTooLarge {
+                    size: (empty + document_size) as u64,
+                    limit: max_bytes,
+                });
+            }
+            match parts.last_mut() {
+                Some(part) if (size + 1 + document_size) as u64 <= max_bytes => {
+                    size += 1 + document_size;
+                    part.push(document);
+                }
+                _ => {
+                    size = empty + document_size;
+                    parts.push(vec![document]);
+                }
+            }
+        }
+
+        Ok(parts
+            .into_iter()
+            .enumerate()
+            .map(|(index, documents)| I5Request {
+                name: format!("{}-{}", self.name, index + 1),
+                documents,
+            })
+            .collect())
+    }
+
+    /// Compares this request with `other`, e.g. a regenerated batch with the one that
+    /// was sent. See [`I5RequestDiff`].
+    pub fn diff(&self, other: &I5Request) -> I5RequestDiff {
+        I5RequestDiff::between(self, other)
+    }
+
+    /// Checks if the request is structurally valid.
+    ///
+    /// Requirements:
+    /// - At least one document exists.
+    /// - Each document has at least one field or file.
+    /// - The item numbers of each document are continuous.
+    /// - The document properties, e.g. the [priority](Document::set_priority), are set
+    ///   at most once and have valid values.
+    /// - The [validators](Document::add_validator) of each document succeed.
+    ///
+    /// Use [`I5Request::validation_report`] to find out why a request is invalid.
+    pub fn is_valid(&self) -> bool {
+        self.validation_report().is_valid()
+    }
+
+    /// Checks the request like [`I5Request::is_valid`] without consuming it and reports
+    /// every issue found.
+    pub fn validation_report(&self) -> I5ValidationReport {
+        self.validation_report_with(&I5ValidationOptions::default())
+    }
+
+    /// Checks the request with the rules of `options` and reports every issue found.
+    pub fn validation_report_with(&self, options: &I5ValidationOptions) -> I5ValidationReport {
+        let mut report = I5ValidationReport::default();
+
+        // Request needs at least one Document.
+        if self.documents.is_empty() {
+            report.push(I5ValidationIssue::NoDocuments);
+        }
+        if let Some(limit) = options.document_limit()
+            && self.documents.len() > limit
+        {
+            report.push(I5ValidationIssue::TooManyDocuments {
+                count: self.documents.len(),
+                limit,
+            });
+        }
+
+        let references = self.reference_keys(options);
+        if options.checks_strict_names()
+            && let Some(reason) = i5_validation::name_problem(&self.name)
+        {
+            report.push(I5ValidationIssue::InvalidName {
+                document: None,
+                name: self.name.clone(),
+                reason,
+            });
+        }
+
+        for (index, document) in self.documents.iter().enumerate() {
+            if options.checks_strict_names() {
+                if let Some(reason) = i5_validation::name_problem(&document.name) {
+                    report.push(I5ValidationIssue::InvalidName {
+                        document: Some(index),
+                        name: document.name.clone(),
+                        reason,
+                    });
+                }
+                let mut checked = HashSet::new();
+                for field in &document.fields {
+                    if checked.insert(field.name.as_str())
+                        && let Some(reason) = i5_validation::field_name_problem(&field.name)
+                    {
+                        report.push(I5ValidationIssue::InvalidFieldName {
+                            document: index,
+                            field: field.name.clone(),
+                            reason,
+                        });
+                    }
+                }
+            }
+
+            // Each Document needs at least either one field or one file, unless
+            // placeholders are allowed.
+            if document.fields.is_empty()
+                && document.files.is_empty()
+                && !options.allows_empty_documents()
+            {
+                report.push(I5ValidationIssue::EmptyDocument { document: index });
+            }
+
+            let item_numbers: Vec<i32> = document
+                .fields
+                .iter()
+                .map(|field| field.item_number)
+                .collect();
+            if options.rejects_negative_items() {
+                let mut negative: Vec<i32> = item_numbers
+                    .iter()
+                    .copied()
+                    .filter(|number| *number < 0)
+                    .collect();
+                negative.sort_unstable();
+                negative.dedup();
+                for item_number in negative {
+                    report.push(I5ValidationIssue::NegativeItemNumber {
+                        document: index,
+                        item_number,
+                    });
+                }
+            }
+            if !options.allows_item_gaps() && !is_continuous(&item_numbers) {
+                report.push(I5ValidationIssue::MissingItemNumbers {
+                    document: index,
+                    missing: (1..document.max_item_number())
+                        .filter(|number| !item_numbers.contains(number))
+                        .collect(),
+                });
+            }
+
+            for (field, reason) in document.property_failures() {
+                report.push(I5ValidationIssue::InvalidProperty {
+                    document: index,
+                    field: field.to_string(),
+                    reason,
+                });
+            }
+
+            for (field_name, keys) in &references {
+                for field in &document.fields {
+                    if field.name == *field_name
+                        && !field.value.is_empty()
+                        && !keys.contains(field.value.as_str())
+                    {
+                        report.push(I5ValidationIssue::UnresolvedReference {
+                            document: index,
+                            field: field.name.clone(),
+                            value: field.value.clone(),
+                        });
+                    }
+                }
+            }
+
+            for reason in document.validator_failures() {
+                report.push(I5ValidationIssue::ValidatorFailed {
+                    document: index,
+                    reason,
+                });
+            }
+        }
+
+        report
+    }
+
+    /// The fields checked by the references of `options`, each with the keys of the
+    /// documents it may refer to.
+    fn reference_keys<'a>(
+        &'a self,
+        options: &'a I5ValidationOptions,
+    ) -> Vec<(&'a str, HashSet<&'a str>)> {
+        options
+            .references()
+            .iter()
+            .map(|(field_name, key_field)| {
+                let keys = match key_field {
+                    Some(key_field) => self
+                        .documents
+                        .iter()
+                        .flat_map(|document| &document.fields)
+                        .filter(|field| field.item_number == 0 && field.name == *key_field)
+                        .map(Field::value)
+                        .collect(),
+                    None => self.documents.iter().map(Document::name).collect(),
+                };
+                (field_name.as_str(), keys)
+            })
+            .collect()
+    }
+
+    /// Returns a view of the request for logging that masks every field value and
+    /// omits all file data.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("IBAN", "DE02120300000000202051");
+    ///
+    /// let logged = format!("{:?}", request.redacted());
+    /// assert!(!logged.contains("DE02120300000000202051"));
+    /// ```
+    pub fn redacted(&self) -> I5Redacted<'_, I5Request> {
+        self.redacted_with(I5Redaction::all())
+    }
+
+    /// Returns a view of the request for logging with a custom [`I5Redaction`].
+    pub fn redacted_with(&self, redaction: I5Redaction) -> I5Redacted<'_, I5Request> {
+        I5Redacted::new(self, redaction)
+    }
+
+    /// Consumes and validates the request.
+    ///
+    /// Returns a [`ValidatedI5Request`] on success, or an [`I5RequestError::ValidationError`] if invalid.
+    pub fn validate(self) -> Result<ValidatedI5Request, I5RequestError> {
+        self.validate_with(&I5ValidationOptions::default())
+    }
+
+    /// Consumes and validates the request with the rules of `options`, e.g. to accept
+    /// empty placeholder documents. See [`I5ValidationOptions`].
+    pub fn validate_with(
+        self,
+        options: &I5ValidationOptions,
+    ) -> Result<ValidatedI5Request, I5RequestError> {
+        let report = self.validation_report_with(options);
+        if report.is_valid() {
+            options.audit(I5AuditOperation::Validate, &self.name, Ok(()));
+            Ok(ValidatedI5Request {
+                request: Arc::new(self),
+                body: OnceLock::new(),
+                options: Arc::new(options.clone()),
+            })
+        } else {
+            log_validation_failure(&self.name, &report);
+            let error = I5RequestError::ValidationError;
+            options.audit(I5AuditOperation::Validate, &self.name, Err(&error));
+            Err(error)
+        }
+    }
+}
+
+/// Logs the issues that keep the request `name` from being valid.
+fn log_validation_failure(name: &str, report: &I5ValidationReport) {
+    i5_event!(warn, "I5Request validation failed", request = name);
+    for issue in report.issues() {
+        i5_event!(
+            warn,
+            "I5Request validation issue",
+            request = name,
+            reason = issue
+        );
+    }
+}
+
+/// The total size of raw attachments in bytes from which
+/// [`ValidatedI5Request::to_json_string`] encodes them in parallel.
+pub const PARALLEL_ENCODING_THRESHOLD: u64 = 1 << 20;
+
+/// The start of the header line of a request staged with [`ValidatedI5Request::save`],
+/// followed by the hexadecimal SHA-256 digest of the body.
+const STAGED_HEADER: &str = "i5-req staged request v1 sha256=";
+
+/// A file with raw content: the document index, the file index and the content.
+type RawFile<'a> = (usize, usize, &'a Bytes);
+
+/// The content of a file detached for an upload session: the document index, the file
+/// index and the content.
+pub(crate) type RawContent = (usize, usize, Bytes);
+
+impl I5Request {
+    /// Returns a copy of the request with all raw files encoded to base64 in
+    /// parallel, or `None` if parallel encoding is not worth it.
+    fn encode_files_in_parallel(&self) -> Option<I5Request> {
+        let mut raw: Vec<RawFile> = Vec::new();
+        for (document_index, document) in self.documents.iter().enumerate() {
+            for (file_index, file) in document.files.iter().enumerate() {
+                if let FileData::Bytes(bytes) = &file.data {
+                    raw.push((document_index, file_index, bytes));
+                }
+            }
+        }
+        let total: u64 = raw.iter().map(|(_, _, bytes)| bytes.len() as u64).sum();
+        let threads = std::thread::available_parallelism()
+            .map_or(1, |threads| threads.get())
+            .min(raw.len());
+        if threads < 2 || total < PARALLEL_ENCODING_THRESHOLD {
+            return None;
+        }
+
+        // Hand out the largest files first, always to the least loaded thread.
+        raw.sort_by_key(|(_, _, bytes)| std::cmp::Reverse(bytes.len()));
+        let mut queues: Vec<(usize, Vec<RawFile>)> = vec![(0, Vec::new()); threads];
+        for entry in raw {
+            let queue = queues
+                .iter_mut()
+                .min_by_key(|(load, _)| *load)
+                .expect("at least two queues");
+            queue.0 += entry.2.len();
+            queue.1.push(entry);
+        }
+
+        let encoded: Vec<(usize, usize, String)> = std::thread::scope(|scope| {
+            let workers: Vec<_> = queues
+                .into_iter()
+                .map(|(_, queue)| {
+                    scope.spawn(move || {
+                        queue
+                            .into_iter()
+                            .map(|(document, file, bytes)| {
+                                (document, file, general_purpose::STANDARD.encode(bytes))
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("base64 encoding does not panic"))
+                .collect()
+        });
+
+        let mut request = self.clone();
+        for (document, file, data) in encoded {
+            request.documents[document].files[file].data = FileData::Base64(data);
+        }
+        Some(request)
+    }
+}
+
+impl std::fmt::Display for I5Request {
+    /// Writes the [summary](I5Request::summary) of the request.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// A wrapper type representing a validated [`I5Request`] that is guaranteed to be ready for serialization and sending.
+///
+/// The request can be inspected through [`ValidatedI5Request::request`]. To change it,
+/// unwrap it with [`ValidatedI5Request::into_inner`] and validate it again.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_request::I5Request;
+///
+/// let mut request = I5Request::new("Batch");
+/// let id = request.add_document("Invoice");
+/// request.get_document_mut(id).unwrap().add_header_field("InvoiceNo", "4711");
+/// let validated = request.validate()?;
+/// assert_eq!(validated.document_count(), 1);
+///
+/// // A late-arriving file.
+/// let mut request = validated.into_inner();
+/// request.get_document_mut(id).unwrap().add_bytes_file("scan.pdf", b"%PDF-1.7");
+/// let validated = request.validate()?;
+/// assert_eq!(validated.request().documents()[0].files().len(), 1);
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+///
+/// The serialized body is cached on first use, so retries and failover to another
+/// host send the same bytes without encoding the attachments again.
+#[derive(Clone)]
+pub struct ValidatedI5Request {
+    request: Arc<I5Request>,
+    body: OnceLock<Bytes>,
+    options: Arc<I5ValidationOptions>,
+}
+
+impl PartialEq for ValidatedI5Request {
+    fn eq(&self, other: &ValidatedI5Request) -> bool {
+        self.request == other.request
+    }
+}
+
+impl Eq for ValidatedI5Request {}
+
+impl std::fmt::Debug for ValidatedI5Request {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidatedI5Request")
+            .field("request", &self.request)
+            .field("body_cached", &self.body.get().is_some())
+            .finish()
+    }
+}
+
+impl ValidatedI5Request {
+    /// The validated request.
+    pub fn request(&self) -> &I5Request {
+        &self.request
+    }
+
+    /// Unwraps the request, e.g. to amend it before validating it again.
+    pub fn into_inner(self) -> I5Request {
+        Arc::unwrap_or_clone(self.request)
+    }
+
+    /// Serializes the validated request into a JSON string.
+    ///
+    /// This copies the cached body; use [`ValidatedI5Request::body`] to avoid the copy.
+    ///
+    /// If the request carries several files added as raw bytes with a total size of
+    /// at least [`PARALLEL_ENCODING_THRESHOLD`], they are encoded to base64 in
+    /// parallel on scoped threads, one per available CPU core.
+    pub fn to_json_string(&self) -> Result<String, I5RequestError> {
+        let body = self.body()?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// Serializes the validated request into its XML representation, for Interface5
+    /// web inputs that only accept XML, see
+    /// [`I5ContentFormat::Xml`](crate::types::i5_content_format::I5ContentFormat::Xml).
+    ///
+    /// The root element is `Batch`, and every property of the JSON body becomes a
+    /// child element of the same name; a file without a key has no `Key` element.
+    /// Unlike the JSON body, the XML body is not cached.
+    ///
+    /// Returns [`I5RequestError::ContractError`] if a value contains a character XML
+    /// cannot represent, such as most control characters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request
+    ///     .get_document_mut(id)
+    ///     .unwrap()
+    ///     .add_header_field("Vendor", "Smith & Sons")
+    ///     .add_bytes_file("scan.pdf", b"%PDF");
+    /// let xml = request.validate()?.to_xml_string()?;
+    ///
+    /// assert_eq!(
+    ///     xml,
+    ///     concat!(
+    ///         r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+    ///         "<Batch><Name>Batch</Name><Documents><Document><Name>Invoice</Name>",
+    ///         "<Fields><Field><Name>Vendor</Name><Value>Smith &amp; Sons</Value>",
+    ///         "<ItemNo>0</ItemNo></Field></Fields>",
+    ///         "<Files><File><Name>scan.pdf</Name><Data>JVBERg==</Data></File></Files>",
+    ///         "</Document></Documents></Batch>",
+    ///     )
+    /// );
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn to_xml_string(&self) -> Result<String, I5RequestError> {
+        xml::write_request(&self.request)
+    }
+
+    /// The serialized JSON body, see [`ValidatedI5Request::to_json_string`].
+    ///
+    /// The body is serialized on the first call and cached; later calls return the
+    /// cached buffer without copying it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_bytes_file("scan.pdf", &[0; 4096]);
+    /// let validated = request.validate()?;
+    ///
+    /// let first = validated.body()?;
+    /// let second = validated.body()?;
+    /// assert_eq!(first.as_ptr(), second.as_ptr());
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn body(&self) -> Result<Bytes, I5RequestError> {
+        if let Some(body) = self.body.get() {
+            return Ok(body.clone());
+        }
+        let mut body = Vec::new();
+        self.write_body(&mut body)?;
+        Ok(self.body.get_or_init(|| Bytes::from(body)).clone())
+    }
+
+    /// Stages the request in the file `path`, to be sent by another process after
+    /// [`ValidatedI5Request::load`].
+    ///
+    /// The file holds a header line with the SHA-256 digest of the body, followed by
+    /// the [body](ValidatedI5Request::body) itself. It is first written next to `path`
+    /// with `.part` appended and then renamed, so a reader never sees a partial file.
+    /// Returns [`I5RequestError::IoError`] if the file cannot be written.
+    ///
+    /// Staging is recorded in the [audit log](I5ValidationOptions::audit_log) of the
+    /// options the request was validated with, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::{I5Request, ValidatedI5Request};
+    ///
+    /// let path = std::env::temp_dir().join(format!("i5-doc-stage-{}.i5", std::process::id()));
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_bytes_file("scan.pdf", b"%PDF-1.7");
+    /// let validated = request.validate()?;
+    /// validated.save(&path)?;
+    ///
+    /// let loaded = ValidatedI5Request::load(&path)?;
+    /// assert_eq!(loaded, validated);
+    /// assert_eq!(loaded.body()?, validated.body()?);
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), I5RequestError> {
+        let result = self.write_staged(path.as_ref());
+        self.options.audit(
+            I5AuditOperation::Build,
+            &self.request.name,
+            result.as_ref().map(|_| ()),
+        );
+        result
+    }
+
+    fn write_staged(&self, path: &Path) -> Result<(), I5RequestError> {
+        let body = self.body()?;
+        let mut content = format!("{}{}\n", STAGED_HEADER, util::sha256_hex(&body)).into_bytes();
+        content.extend_from_slice(&body);
+
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".part");
+        fs::write(&partial, content)
+            .and_then(|_| fs::rename(&partial, path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&partial);
+                I5RequestError::IoError(e)
+            })?;
+        i5_event!(
+            info,
+            "staged validated request",
+            request = self.request.name,
+            path = path.display()
+        );
+        Ok(())
+    }
+
+    /// Deletes the file at `path` this request was staged at with
+    /// [`ValidatedI5Request::save`], e.g. once it was sent.
+    ///
+    /// The deletion is recorded in the [audit log](I5ValidationOptions::audit_log) of
+    /// the options the request was validated with, if any. Returns
+    /// [`I5RequestError::IoError`] if the file cannot be deleted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::{I5Request, ValidatedI5Request};
+    ///
+    /// let path = std::env::temp_dir().join(format!("i5-doc-remove-{}.i5", std::process::id()));
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("InvoiceNo", "4711");
+    /// request.validate()?.save(&path)?;
+    ///
+    /// let loaded = ValidatedI5Request::load(&path)?;
+    /// // ... send `loaded` ...
+    /// loaded.remove_staged(&path)?;
+    /// assert!(!path.exists());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn remove_staged(&self, path: impl AsRef<Path>) -> Result<(), I5RequestError> {
+        let path = path.as_ref();
+        let result = fs::remove_file(path).map_err(I5RequestError::IoError);
+        self.options.audit(
+            I5AuditOperation::Delete,
+            &self.request.name,
+            result.as_ref().map(|_| ()),
+        );
+        if result.is_ok() {
+            i5_event!(
+                info,
+                "removed staged request",
+                request = self.request.name,
+                path = path.display()
+            );
+        }
+        result
+    }
+
+    /// Loads a request staged with [`ValidatedI5Request::save`] and validates it again.
+    ///
+    /// The staged body is kept, so the loaded request sends exactly the bytes that
+    /// were saved. Returns [`I5RequestError::IoError`] if the file cannot be read,
+    /// [`I5RequestError::ConversionError`] if it is no staged request or its content
+    /// does not match the digest, and [`I5RequestError::ValidationError`] if the
+    /// request is invalid.
+    pub fn load(path: impl AsRef<Path>) -> Result<ValidatedI5Request, I5RequestError> {
+        ValidatedI5Request::load_with(path, &I5ValidationOptions::default())
+    }
+
+    /// Loads a staged request and validates it with the rules of `options`, see
+    /// [`ValidatedI5Request::load`].
+    pub fn load_with(
+        path: impl AsRef<Path>,
+        options: &I5ValidationOptions,
+    ) -> Result<ValidatedI5Request, I5RequestError> {
+        let path = path.as_ref();
+        let error = |message: &str| {
+            I5RequestError::ConversionError(format!("{}: {}", path.display(), message))
+        };
+
+        let content = fs::read(path).map_err(I5RequestError::IoError)?;
+        let (header, body) = content
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .map(|end| (&content[..end], &content[end + 1..]))
+            .ok_or_else(|| error("not a staged request"))?;
+        let digest = std::str::from_utf8(header)
+            .ok()
+            .and_then(|header| header.strip_prefix(STAGED_HEADER))
+            .ok_or_else(|| error("not a staged request"))?;
+        if !digest.eq_ignore_ascii_case(&util::sha256_hex(body)) {
+            return Err(error("the content does not match its SHA-256 digest"));
+        }
+
+        let value: serde_json::Value = serde_json::from_slice(body)
+            .map_err(|e| error(&format!("invalid JSON body: {}", e)))?;
+        let validated = I5Request::try_from(value)?.validate_with(options)?;
+        let _ = validated.body.set(Bytes::copy_from_slice(body));
+        Ok(validated)
+    }
+
+    /// Serializes the request into `buffer`, replacing its content.
+    ///
+    /// High-frequency senders can keep one buffer and reuse its allocation for every
+    /// batch. The cached body is copied if there is one; otherwise the body is
+    /// serialized without being cached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let mut buffer = Vec::new();
+    /// for number in ["4711", "4712"] {
+    ///     let mut request = I5Request::new("Batch");
+    ///     let id = request.add_document("Invoice");
+    ///     request.get_document_mut(id).unwrap().add_header_field("InvoiceNo", number);
+    ///     let validated = request.validate()?;
+    ///
+    ///     validated.serialize_into(&mut buffer)?;
+    ///     assert_eq!(buffer, validated.to_json_string()?.as_bytes());
+    /// }
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn serialize_into(&self, buffer: &mut Vec<u8>) -> Result<(), I5RequestError> {
+        buffer.clear();
+        match self.body.get() {
+            Some(body) => {
+                buffer.extend_from_slice(body);
+                Ok(())
+            }
+            None => self.write_body(buffer),
+        }
+    }
+
+    /// Appends the serialized request to `buffer`, reserving its size up front.
+    fn write_body(&self, buffer: &mut Vec<u8>) -> Result<(), I5RequestError> {
+        buffer.reserve(self.request.estimated_encoded_size());
+        match self.request.encode_files_in_parallel() {
+            Some(encoded) => serde_json::to_writer(&mut *buffer, &encoded),
+            None => serde_json::to_writer(&mut *buffer, &*self.request),
+        }
+        .map_err(I5RequestError::SerializeError)
+    }
+
+    /// The request for a change, discarding the cached body.
+    fn request_mut(&mut self) -> &mut I5Request {
+        self.body = OnceLock::new();
+        Arc::make_mut(&mut self.request)
+    }
+
+    /// The cached body, if the body has been serialized.
+    pub(crate) fn cached_body(&self) -> Option<Bytes> {
+        self.body.get().cloned()
+    }
+
+    /// The validated request, shared with a body that is encoded while it is sent.
+    pub(crate) fn shared_request(&self) -> Arc<I5Request> {
+        Arc::clone(&self.request)
+    }
+
+    /// The request for an upload session, with the content of every file left empty,
+    /// and the contents with the index of their document and file. Base64 content is
+    /// decoded; content that is not valid base64 stays in the request.
+    pub(crate) fn detach_files(&self) -> (ValidatedI5Request, Vec<RawContent>) {
+        let mut request = I5Request::clone(&self.request);
+        let mut detached = Vec::new();
+        for (document_index, document) in request.documents.iter_mut().enumerate() {
+            for (file_index, file) in document.files.iter_mut().enumerate() {
+                let content = match &file.data {
+                    FileData::Bytes(bytes) => bytes.clone(),
+                    FileData::Base64(data) => match general_purpose::STANDARD.decode(data) {
+                        Ok(bytes) => Bytes::from(bytes),
+                        Err(_) => continue,
+                    },
+                };
+                if content.is_empty() {
+                    continue;
+                }
+                file.data = FileData::Base64(String::new());
+                detached.push((document_index, file_index, content));
+            }
+        }
+        let request = ValidatedI5Request {
+            request: Arc::new(request),
+            body: OnceLock::new(),
+            options: Arc::clone(&self.options),
+        };
+        (request, detached)
+    }
+
+    /// Returns a view of the request for logging that masks every field value and
+    /// omits all file data. See [`I5Request::redacted`].
+    pub fn redacted(&self) -> I5Redacted<'_, I5Request> {
+        self.request.redacted()
+    }
+
+    /// Returns a view of the request for logging with a custom [`I5Redaction`].
+    pub fn redacted_with(&self, redaction: I5Redaction) -> I5Redacted<'_, I5Request> {
+        self.request.redacted_with(redaction)
+    }
+
+    /// Adds the given header fields to every document that does not have them yet.
+    ///
+    /// Values are [templates](I5TemplateContext) resolved for each document, with
+    /// `{{filename}}` referring to the first file of the document.
+    pub(crate) fn with_default_header_fields(
+        mut self,
+        fields: &[(String, String)],
+    ) -> Result<Self, I5RequestError> {
+        if fields.is_empty() {
+            return Ok(self);
+        }
+
+        let now = SystemTime::now();
+        for document in &mut self.request_mut().documents {
+            let mut context = I5TemplateContext::new().at(now);
+            if let Some(file) = document.files.first() {
+                context = context.filename(file.name.clone());
+            }
+            for (name, value) in fields {
+                let present = document
+                    .fields
+                    .iter()
+                    .any(|field| field.item_number == 0 && field.name == *name);
+                if !present {
+                    document.add_header_field(name, context.render(value)?);
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Renames fields according to `mapping`.
+    pub(crate) fn with_field_mapping(mut self, mapping: &I5FieldMapping) -> Self {
+        if !mapping.is_empty() {
+            self.request_mut().apply_field_mapping(mapping);
+        }
+        self
+    }
+
+    /// Applies `transforms` to all field values.
+    pub(crate) fn with_transforms(mut self, transforms: &I5Transforms) -> Self {
+        if !transforms.is_empty() {
+            self.request_mut().apply_transforms(transforms);
+        }
+        self
+    }
+
+    /// Sanitizes all field values, logging a warning for every truncated field.
+    pub(crate) fn with_sanitizer(mut self, sanitizer: Option<&I5Sanitizer>) -> Self {
+        if let Some(sanitizer) = sanitizer {
+            let report = self.request_mut().sanitize(sanitizer);
+            for field in report.truncated() {
+                i5_event!(
+                    warn,
+                    "I5Request field value truncated",
+                    request = self.request.name,
+                    document = field.document(),
+                    field = field.name(),
+                    original_length = field.original_length()
+                );
+            }
+        }
+        self
+    }
+
+    /// Removes the fields rejected by `rules`.
+    pub(crate) fn with_field_rules(mut self, rules: &I5FieldRules) -> Self {
+        if !rules.is_empty() {
+            self.request_mut().apply_field_rules(rules);
+        }
+        self
+    }
+
+    /// Checks the request again with the options it was validated with, if a
+    /// processing step changed it since it was copied from `original`.
+    ///
+    /// Returns [`I5RequestError::ValidationError`] if the request is no longer valid.
+    pub(crate) fn revalidate(self, original: &ValidatedI5Request) -> Result<Self, I5RequestError> {
+        if Arc::ptr_eq(&self.request, &original.request) {
+            return Ok(self);
+        }
+        let report = self.request.validation_report_with(&self.options);
+        if report.is_valid() {
+            Ok(self)
+        } else {
+            log_validation_failure(&self.request.name, &report);
+            Err(I5RequestError::ValidationError)
+        }
+    }
+
+    /// The name of the request.
+    pub fn name(&self) -> &str {
+        &self.request.name
+    }
+
+    /// The number of documents in the request.
+    pub fn document_count(&self) -> usize {
+        self.request.document_count()
+    }
+
+    /// The length in bytes of [`ValidatedI5Request::to_json_string`], see
+    /// [`I5Request::estimated_encoded_size`].
+    pub fn estimated_encoded_size(&self) -> usize {
+        self.request.estimated_encoded_size()
+    }
+
+    /// Checks the serialized request against the JSON Schema that `version` of the
+    /// Interface5 WebServiceInput API accepts, see [`I5ApiVersion::schema`].
+    ///
+    /// Validation only checks the structure of the request; the contract also covers
+    /// what the server requires of the values, e.g. non-empty names. Returns
+    /// [`I5RequestError::ContractError`] listing every violation, or saying that no
+    /// schema is bundled for `version`. The body is parsed
+    /// as a whole, so this is meant for tests and diagnostics rather than every
+    /// submission of large batches.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{
+    ///     i5_api_version::I5ApiVersion, i5_error::I5RequestError, i5_request::I5Request,
+    /// };
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("", "4711");
+    /// let validated = request.validate()?;
+    ///
+    /// let err = validated.check_contract(I5ApiVersion::V1).unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Request violates the Interface5 contract: Documents[0].Fields[0].Name: must not be empty"
+    /// );
+    /// # Ok::<(), I5RequestError>(())
+    /// ```
+    pub fn check_contract(&self, version: I5ApiVersion) -> Result<(), I5RequestError> {
+        let schema = version.schema().ok_or_else(|| {
+            I5RequestError::ContractError(format!("no schema is bundled for the {} API", version))
+        })?;
+        let schema: serde_json::Value =
+            serde_json::from_str(schema).expect("bundled schemas are valid JSON");
+        let body: serde_json::Value =
+            serde_json::from_slice(&self.body()?).map_err(I5RequestError::SerializeError)?;
+        let violations = schema::violations(&schema, &body, "the request");
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(I5RequestError::ContractError(violations.join("; ")))
+        }
+    }
+}
+
+impl TryFrom<serde_json::Value> for I5Request {
+    type Error = I5RequestError;
+
+    /// Reads a request from JSON in the Interface5 shape.
+    ///
+    /// The conversion is lenient: keys are matched ignoring case, unknown keys are
+    /// ignored, `Fields`, `Files`, `ItemNo` and `Key` may be missing, field values may
+    /// be numbers or booleans and item numbers may be numeric strings. The request is
+    /// not validated.
+    ///
+    /// Returns [`I5RequestError::ConversionError`] naming the path of the first value
+    /// that cannot be read.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    /// use serde_json::json;
+    ///
+    /// let request = I5Request::try_from(json!({
+    ///     "name": "Batch",
+    ///     "documents": [{
+    ///         "name": "Invoice",
+    ///         "fields": [
+    ///             { "name": "InvoiceNo", "value": 4711 },
+    ///             { "name": "Article", "value": "A-1", "itemNo": "1" }
+    ///         ]
+    ///     }]
+    /// }))?;
+    /// assert!(request.is_valid());
+    ///
+    /// let err = I5Request::try_from(json!({
+    ///     "Name": "Batch",
+    ///     "Documents": [{ "Name": "Invoice", "Fields": [{ "Name": "A", "Value": [] }] }]
+    /// }))
+    /// .unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Invalid request data: Documents[0].Fields[0].Value: expected a string, number or boolean, found an array"
+    /// );
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        let batch = json::object(&value, "")?;
+        let mut request = I5Request::new(json::string(batch, "", "Name")?);
+
+        for (index, document) in json::array(batch, "", "Documents")?.iter().enumerate() {
+            let path = format!("Documents[{}]", index);
+            let document = json::object(document, &path)?;
+            let mut converted = Document::new(json::string(document, &path, "Name")?);
+
+            for (index, field) in json::array(document, &path, "Fields")?.iter().enumerate() {
+                let path = format!("{}.Fields[{}]", path, index);
+                let field = json::object(field, &path)?;
+                converted.fields.push(Field::new(
+                    json::string(field, &path, "Name")?,
+                    json::scalar(field, &path, "Value")?,
+                    json::item_number(field, &path)?,
+                ));
+            }
+
+            for (index, file) in json::array(document, &path, "Files")?.iter().enumerate() {
+                let path = format!("{}.Files[{}]", path, index);
+                let file = json::object(file, &path)?;
+                let mut converted_file = File::new(
+                    json::string(file, &path, "Name")?,
+                    json::string(file, &path, "Data")?,
+                );
+                converted_file.key = json::optional_string(file, &path, "Key")?;
+                converted.files.push(converted_file);
+            }
+
+            request.documents.push(converted);
+        }
+        Ok(request)
+    }
+}
+
+/// Lenient accessors for [`I5Request::try_from`].
+mod json {
+    use serde_json::{Map, Value};
+
+    use crate::types::i5_error::I5RequestError;
+
+    fn describe(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        }
+    }
+
+    fn error(path: &str, key: &str, message: String) -> I5RequestError {
+        let location = match (path.is_empty(), key.is_empty()) {
+            (true, true) => "the request".to_string(),
+            (true, false) => key.to_string(),
+            (false, true) => path.to_string(),
+            (false, false) => format!("{}.{}", path, key),
+        };
+        I5RequestError::ConversionError(format!("{}: {}", location, message))
+    }
+
+    /// Looks up `key` ignoring case.
+    fn get<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+        object
+            .iter()
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_null())
+    }
+
+    pub(super) fn object<'a>(
+        value: &'a Value,
+        path: &str,
+    ) -> Result<&'a Map<String, Value>, I5RequestError> {
+        value.as_object().ok_or_else(|| {
+            error(
+                path,
+                "",
+                format!("expected an object, found {}", describe(value)),
+            )
+        })
+    }
+
+    pub(super) fn string(
+        object: &Map<String, Value>,
+        path: &str,
+        key: &str,
+    ) -> Result<String, I5RequestError> {
+        match get(object, key) {
+            Some(Value::String(value)) => Ok(value.clone()),
+            Some(value) => Err(error(
+                path,
+                key,
+                format!("expected a string, found {}", describe(value)),
+            )),
+            None => Err(error(path, key, "missing".to_string())),
+        }
+    }
+
+    pub(super) fn optional_string(
+        object: &Map<String, Value>,
+        path: &str,
+        key: &str,
+    ) -> Result<Option<String>, I5RequestError> {
+        match get(object, key) {
+            None => Ok(None),
+            Some(_) => string(object, path, key).map(Some),
+        }
+    }
+
+    pub(super) fn scalar(
+        object: &Map<String, Value>,
+        path: &str,
+        key: &str,
+    ) -> Result<String, I5RequestError> {
+        match get(object, key) {
+            Some(Value::String(value)) => Ok(value.clone()),
+            Some(Value::Number(value)) => Ok(value.to_string()),
+            Some(Value::Bool(value)) => Ok(value.to_string()),
+            Some(value) => Err(error(
+                path,
+                key,
+                format!(
+                    "expected a string, number or boolean, found {}",
+                    describe(value)
+                ),
+            )),
+            None => Ok(String::new()),
+        }
+    }
+
+    pub(super) fn item_number(
+        object: &Map<String, Value>,
+        path: &str,
+    ) -> Result<i32, I5RequestError> {
+        let key = "ItemNo";
+        let value = match get(object, key) {
+            None => return Ok(0),
+            Some(value) => value,
+        };
+        let number = match value {
+            Value::Number(number) => number.as_i64(),
+            Value::String(text) => text.trim().parse().ok(),
+            _ => None,
+        };
+        number
+            .and_then(|number| i32::try_from(number).ok())
+            .filter(|number| *number >= 0)
+            .ok_or_else(|| {
+                error(
+                    path,
+                    key,
+                    format!("expected a non-negative integer, found {}", value),
+                )
+            })
+    }
+
+    pub(super) fn array<'a>(
+        object: &'a Map<String, Value>,
+        path: &str,
+        key: &str,
+    ) -> Result<&'a [Value], I5RequestError> {
+        match get(object, key) {
+            Some(Value::Array(values)) => Ok(values),
+            Some(value) => Err(error(
+                path,
+                key,
+                format!("expected an array, found {}", describe(value)),
+            )),
+            None => Ok(&[]),
+        }
+    }
+}
+
+/// The paths whose file name matches `pattern`, sorted by file name.
+fn matching_paths(paths: Vec<PathBuf>, pattern: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = paths
+        .into_iter()
+        .filter(|path| util::wildcard_match(pattern, &file_name(path)))
+        .collect();
+    paths.sort_by_key(|path| file_name(path));
+    paths
+}
+
+/// The last component of `path`, used as the name of an attachment.
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// The length of `document` serialized as JSON.
+fn encoded_document_size(document: &Document) -> usize {
+    // {"Name":…,"Fields":[…],"Files":[…]}
+    r#"{"Name":,"Fields":[],"Files":[]}"#.len()
+        + json_string_len(&document.name)
+        + comma_separated(document.fields.iter().map(|field| {
+            // {"Name":…,"Value":…,"ItemNo":…}
+            r#"{"Name":,"Value":,"ItemNo":}"#.len()
+                + json_string_len(&field.name)
+                + json_string_len(&field.value)
+                + field.item_number.to_string().len()
+        }))
+        + comma_separated(document.files.iter().map(|file| {
+            // {"Name":…,"Key":…,"Data":…}
+            r#"{"Name":,"Key":,"Data":}"#.len()
+                + json_string_len(&file.name)
+                + file.key.as_deref().map_or("null".len(), json_string_len)
+                + match &file.data {
+                    FileData::Base64(data) => json_string_len(data),
+                    FileData::Bytes(_) => file.data.encoded_len() + 2,
+                }
+        }))
+}
+
+/// The length of `value` serialized as a JSON string, including the quotes.
+fn json_string_len(value: &str) -> usize {
+    2 + value
+        .bytes()
+        .map(|byte| match byte {
+            b'"' | b'\\' | b'\x08' | b'\x0c' | b'\n' | b'\r' | b'\t' => 2,
+            0x00..=0x1f => 6,
+            _ => 1,
+        })
+        .sum::<usize>()
+}
+
+/// The total length of `lengths` joined by commas.
+fn comma_separated(lengths: impl Iterator<Item = usize>) -> usize {
+    lengths
+        .enumerate()
+        .map(|(index, length)| if index == 0 { length } else { length + 1 })
+        .sum()
+}
+
+/// Checks if a given list of integers forms a continuous, gapless sequence (ignoring zeros).
+///
 /// This function verifies whether the non-zero, unique values in the input slice form a sequence
 /// without missing integers starting from 1 up to the maximum value found.
 ///
@@ -230,9 +3104,7 @@ impl ValidatedI5Request {
 ///
 /// # Example:
 ///
-/// ```rust
-/// use your_crate_name::is_continuous;
-///
+/// ```rust,ignore
 /// assert!(is_continuous(&[0, 1, 2, 3, 4, 5]));          // ✅ True (Continuous 1-5)
 /// assert!(is_continuous(&[0, 1, 2, 3, 5, 4, 6, 7]));    // ✅ True (1-7, ignoring duplicates and zeros)
 /// assert!(!is_continuous(&[0, 1, 2, 4, 5]));            // ❌ False (Missing 3)
@@ -255,3 +3127,27 @@ fn is_continuous(numbers: &[i32]) -> bool {
         None => true,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_keeps_the_header_fields_of_self_ignoring_case() {
+        let mut document = Document::new("Invoice");
+        document.add_header_field("InvoiceNo", "4711");
+        let mut other = Document::new("Enrichment");
+        other
+            .add_header_field("INVOICENO", "0815")
+            .add_header_field("invoiceno", "0816")
+            .add_header_field("Supplier", "ACME");
+
+        document.merge(other);
+        let fields: Vec<(&str, &str)> = document
+            .fields()
+            .iter()
+            .map(|field| (field.name(), field.value()))
+            .collect();
+        assert_eq!(fields, [("InvoiceNo", "4711"), ("Supplier", "ACME")]);
+    }
+}
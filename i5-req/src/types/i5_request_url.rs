@@ -1,3 +1,37 @@
+use std::{borrow::Cow, fmt, net::Ipv6Addr};
+
+use crate::{
+    types::{i5_api_version::I5ApiVersion, i5_error::I5RequestError},
+    util,
+};
+
+/// A resource of the Interface5 WebServiceInput, the last segment of a request URL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum I5Endpoint {
+    /// `Batches`, which accepts a batch of documents.
+    #[default]
+    Batches,
+    /// `Documents`, which accepts a single document.
+    Documents,
+}
+
+impl I5Endpoint {
+    /// The endpoint as written in URL paths, e.g. `Batches`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            I5Endpoint::Batches => "Batches",
+            I5Endpoint::Documents => "Documents",
+        }
+    }
+}
+
+impl fmt::Display for I5Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Represents the target URL configuration for an Interface5 API request.
 ///
 /// This struct helps build a fully qualified API endpoint URL for sending requests
@@ -9,18 +43,21 @@
 /// - `port`: The network port where Interface5 is running.
 /// - `scenario`: The Interface5 scenario name.
 /// - `tenant`: The Interface5 tenant identifier.
+/// - `version`: The API version in the path, see [`I5RequestUrl::version`].
+/// - `endpoint`: The targeted resource, see [`I5RequestUrl::endpoint`].
+/// - `query`: Query parameters appended to the URL, see [`I5RequestUrl::query`].
 ///
 /// # Example
 ///
 /// ```rust
-/// use your_crate_name::I5RequestUrl;
+/// use i5_req::types::i5_request_url::I5RequestUrl;
 ///
 /// let url = I5RequestUrl::new("localhost", 43001, "Processor", "Default");
 /// let full_url = url.to_url();
 ///
 /// assert_eq!(
 ///     full_url,
-///     "https://localhost:43001/api/v1/Input/Processor/Default/Batches"
+///     "https://localhost:43001/api/v1/Input/Default/Processor/Batches"
 /// );
 /// ```
 pub struct I5RequestUrl {
@@ -28,6 +65,9 @@ pub struct I5RequestUrl {
     tenant: String,
     hostname: String,
     port: i32,
+    version: I5ApiVersion,
+    endpoint: I5Endpoint,
+    query: Vec<(String, String)>,
 }
 
 /// Creates a new [`I5RequestUrl`] instance.
@@ -42,7 +82,7 @@ pub struct I5RequestUrl {
 /// # Example
 ///
 /// ```rust
-/// use your_crate_name::I5RequestUrl;
+/// use i5_req::types::i5_request_url::I5RequestUrl;
 ///
 /// let url = I5RequestUrl::new("localhost", 43001, "Processor", "Default");
 /// ```
@@ -58,29 +98,284 @@ impl I5RequestUrl {
             tenant: tenant.into(),
             hostname: hostname.into(),
             port,
+            version: I5ApiVersion::V1,
+            endpoint: I5Endpoint::Batches,
+            query: Vec::new(),
         }
     }
 
+    /// Targets `version` of the API instead of `v1`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{i5_api_version::I5ApiVersion, i5_request_url::I5RequestUrl};
+    ///
+    /// let url = I5RequestUrl::new("localhost", 43001, "Processor", "Default")
+    ///     .version(I5ApiVersion::V2);
+    /// assert_eq!(
+    ///     url.to_url(),
+    ///     "https://localhost:43001/api/v2/Input/Default/Processor/Batches"
+    /// );
+    /// ```
+    pub fn version(mut self, version: I5ApiVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Targets `endpoint` instead of `Batches`, so the same host, tenant and scenario
+    /// can be used for other resources.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request_url::{I5Endpoint, I5RequestUrl};
+    ///
+    /// let url = I5RequestUrl::new("localhost", 43001, "Processor", "Default")
+    ///     .endpoint(I5Endpoint::Documents);
+    /// assert_eq!(
+    ///     url.to_url(),
+    ///     "https://localhost:43001/api/v1/Input/Default/Processor/Documents"
+    /// );
+    /// ```
+    pub fn endpoint(mut self, endpoint: I5Endpoint) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Appends the query parameter `name=value` to the URL, e.g. `async=true` for
+    /// installations that require it. Names and values are percent-encoded, and
+    /// parameters keep the order in which they were added.
+    ///
+    /// The values of query parameters may carry API keys, so they are replaced by
+    /// `<redacted>` in the URLs recorded in traces, logs and wire dumps and in the
+    /// commands of [`ValidatedI5Request::to_curl`]; the names are kept.
+    ///
+    /// [`ValidatedI5Request::to_curl`]: crate::types::i5_request::ValidatedI5Request::to_curl
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request_url::I5RequestUrl;
+    ///
+    /// let url = I5RequestUrl::new("localhost", 43001, "Processor", "Default")
+    ///     .query("async", "true")
+    ///     .query("source", "scanner 7/a");
+    /// assert_eq!(
+    ///     url.to_url(),
+    ///     "https://localhost:43001/api/v1/Input/Default/Processor/Batches\
+    ///      ?async=true&source=scanner%207%2Fa"
+    /// );
+    /// ```
+    pub fn query(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((name.into(), value.into()));
+        self
+    }
+
     /// Converts the [`I5RequestUrl`] into a fully qualified URL string.
     ///
     /// The generated URL follows this pattern:
     ///
-    /// `https://{hostname}:{port}/api/v1/Input/{tenant}/{scenario}/Batches`
+    /// `https://{hostname}:{port}/api/{version}/Input/{tenant}/{scenario}/{endpoint}`
+    ///
+    /// The tenant and the scenario are percent-encoded, so they cannot add path
+    /// segments or a query. IPv6 literals are enclosed in brackets, and the `%` of a
+    /// zone ID is percent-encoded.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use your_crate_name::I5RequestUrl;
+    /// use i5_req::types::i5_request_url::I5RequestUrl;
     ///
     /// let url = I5RequestUrl::new("localhost", 43001, "Processor", "Default");
     /// assert_eq!(
-    ///     full_url,
-    ///     "https://localhost:43001/api/v1/Input/Processor/Default/Batches"
+    ///     url.to_url(),
+    ///     "https://localhost:43001/api/v1/Input/Default/Processor/Batches"
+    /// );
+    ///
+    /// let url = I5RequestUrl::new("localhost", 43001, "Scan/Invoices", "Tenant A");
+    /// assert_eq!(
+    ///     url.to_url(),
+    ///     "https://localhost:43001/api/v1/Input/Tenant%20A/Scan%2FInvoices/Batches"
+    /// );
+    ///
+    /// let url = I5RequestUrl::new("fd00::12", 43001, "Processor", "Default");
+    /// assert_eq!(
+    ///     url.to_url(),
+    ///     "https://[fd00::12]:43001/api/v1/Input/Default/Processor/Batches"
     /// );
+    ///
+    /// let url = I5RequestUrl::new("fe80::1%eth0", 43001, "Processor", "Default");
+    /// assert!(url.to_url().starts_with("https://[fe80::1%25eth0]:43001/"));
+    ///
+    /// let url = I5RequestUrl::new("[fd00::12]", 43001, "Processor", "Default");
+    /// assert!(url.to_url().starts_with("https://[fd00::12]:43001/"));
+    /// ```
     pub fn to_url(&self) -> String {
+        let mut url = format!(
+            "{}/{}/{}/{}",
+            self.input_url(),
+            util::percent_encode(&self.tenant),
+            util::percent_encode(&self.scenario),
+            self.endpoint
+        );
+        self.push_query(&mut url);
+        url
+    }
+
+    /// Builds the URL of a resource the crate has no first-class support for yet, under
+    /// `https://{hostname}:{port}/api/{version}/Input/{tenant}/`.
+    ///
+    /// Every segment is percent-encoded, so it cannot add path separators or a query;
+    /// the query parameters of this URL are appended. Returns
+    /// [`I5RequestError::ConfigError`] if there are no segments or a segment is empty,
+    /// `.` or `..`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request_url::I5RequestUrl;
+    ///
+    /// let url = I5RequestUrl::new("localhost", 43001, "Processor", "Default");
+    /// let endpoint =
+    ///     url.custom_endpoint([url.scenario(), "Batches", "B 4711"], reqwest::Method::GET)?;
+    /// assert_eq!(endpoint.method(), &reqwest::Method::GET);
+    /// assert_eq!(
+    ///     endpoint.url(),
+    ///     "https://localhost:43001/api/v1/Input/Default/Processor/Batches/B%204711"
+    /// );
+    ///
+    /// assert!(url.custom_endpoint([".."], reqwest::Method::GET).is_err());
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn custom_endpoint<S: AsRef<str>>(
+        &self,
+        path_segments: impl IntoIterator<Item = S>,
+        method: reqwest::Method,
+    ) -> Result<I5CustomEndpoint, I5RequestError> {
+        let mut url = format!(
+            "{}/{}",
+            self.input_url(),
+            util::percent_encode(&self.tenant)
+        );
+        let mut empty = true;
+        for segment in path_segments {
+            let segment = segment.as_ref();
+            if matches!(segment, "" | "." | "..") {
+                return Err(I5RequestError::ConfigError(format!(
+                    "invalid path segment '{}' for a custom endpoint",
+                    segment
+                )));
+            }
+            url.push('/');
+            url.push_str(&util::percent_encode(segment));
+            empty = false;
+        }
+        if empty {
+            return Err(I5RequestError::ConfigError(String::from(
+                "a custom endpoint needs at least one path segment",
+            )));
+        }
+        self.push_query(&mut url);
+        Ok(I5CustomEndpoint { method, url })
+    }
+
+    /// The URL of the input API, `https://{hostname}:{port}/api/{version}/Input`.
+    pub(crate) fn input_url(&self) -> String {
         format!(
-            "https://{}:{}/api/v1/Input/{}/{}/Batches",
-            self.hostname, self.port, self.tenant, self.scenario
+            "https://{}:{}/api/{}/Input",
+            self.authority_host(),
+            self.port,
+            self.version
         )
     }
+
+    /// Appends the query parameters to `url`.
+    fn push_query(&self, url: &mut String) {
+        for (index, (name, value)) in self.query.iter().enumerate() {
+            url.push(if index == 0 { '?' } else { '&' });
+            url.push_str(&util::percent_encode(name));
+            url.push('=');
+            url.push_str(&util::percent_encode(value));
+        }
+    }
+
+    /// The hostname as it appears in a URL: IPv6 literals in brackets.
+    fn authority_host(&self) -> Cow<'_, str> {
+        let hostname = self.hostname.as_str();
+        if hostname.starts_with('[') {
+            return Cow::Borrowed(hostname);
+        }
+        let (address, zone) = match hostname.split_once('%') {
+            Some((address, zone)) => (address, Some(zone)),
+            None => (hostname, None),
+        };
+        if address.parse::<Ipv6Addr>().is_err() {
+            return Cow::Borrowed(hostname);
+        }
+        Cow::Owned(match zone {
+            Some(zone) => format!("[{}%25{}]", address, zone),
+            None => format!("[{}]", address),
+        })
+    }
+
+    /// The hostname or IP address of the target Interface5 instance.
+    pub fn hostname(&self) -> &str {
+        &self.hostname
+    }
+
+    /// The network port where Interface5 is running.
+    pub fn port(&self) -> i32 {
+        self.port
+    }
+
+    /// The Interface5 scenario name.
+    pub fn scenario(&self) -> &str {
+        &self.scenario
+    }
+
+    /// The Interface5 tenant identifier.
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+
+    /// The targeted API version.
+    pub fn api_version(&self) -> I5ApiVersion {
+        self.version
+    }
+
+    /// The targeted resource.
+    pub fn target_endpoint(&self) -> I5Endpoint {
+        self.endpoint
+    }
+
+    /// The query parameters as name and value, before encoding.
+    pub fn query_params(&self) -> &[(String, String)] {
+        &self.query
+    }
+}
+
+/// The method and URL of a resource built with [`I5RequestUrl::custom_endpoint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5CustomEndpoint {
+    method: reqwest::Method,
+    url: String,
+}
+
+impl I5CustomEndpoint {
+    /// The HTTP method to call the resource with.
+    pub fn method(&self) -> &reqwest::Method {
+        &self.method
+    }
+
+    /// The fully qualified URL.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl fmt::Display for I5CustomEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.url)
+    }
 }
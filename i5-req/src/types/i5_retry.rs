@@ -0,0 +1,332 @@
+use std::{
+    collections::VecDeque,
+    fmt, io,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::types::{
+    i5_backoff::{I5Backoff, I5ConstantBackoff, I5Jitter},
+    i5_error::I5RequestError,
+};
+
+/// How a client retries submissions that failed transiently.
+///
+/// A submission is a `POST` that creates a batch, so by default it is only retried if
+/// it certainly did not: if it could not connect or was answered with `408`, `429` or
+/// `503`, see [`I5RetryPolicy::is_safe_to_retry`]. Submissions that timed out, lost
+/// their connection or were answered with another `5xx` status may have created the
+/// batch anyway and are only retried with
+/// [`I5RetryPolicy::retry_possibly_delivered`].
+///
+/// Every retry keeps the correlation ID of the first attempt. The wait before a retry
+/// is computed by an [`I5Backoff`], one second by default, and randomized by the
+/// [jitter](I5RetryPolicy::jitter); if the server asked for a longer wait with a
+/// `Retry-After` header, that wait is used instead. Without a policy, submissions
+/// are not retried.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{
+///     i5_backoff::{I5ExponentialBackoff, I5Jitter},
+///     i5_config::I5Config,
+///     i5_retry::{I5RetryBudget, I5RetryPolicy},
+/// };
+/// use std::time::Duration;
+///
+/// // At most 10% retries over the last minute, shared by every client of the config.
+/// let budget = I5RetryBudget::new(0.1, Duration::from_secs(60));
+/// let config = I5Config::new("i5.company.local", 43001, "Invoices", "Default").retry(
+///     I5RetryPolicy::new(3)
+///         .backoff(I5ExponentialBackoff::new(Duration::from_millis(500)))
+///         .jitter(I5Jitter::Full)
+///         .budget(budget),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct I5RetryPolicy {
+    max_retries: u32,
+    backoff: Arc<dyn I5Backoff>,
+    jitter: I5Jitter,
+    budget: Option<I5RetryBudget>,
+    possibly_delivered: bool,
+}
+
+impl I5RetryPolicy {
+    /// Creates a policy that retries a submission up to `max_retries` times, one
+    /// second apart.
+    pub fn new(max_retries: u32) -> I5RetryPolicy {
+        I5RetryPolicy {
+            max_retries,
+            backoff: Arc::new(I5ConstantBackoff::new(Duration::from_secs(1))),
+            jitter: I5Jitter::None,
+            budget: None,
+            possibly_delivered: false,
+        }
+    }
+
+    /// Waits `delay` before every retry, a shorthand for an [`I5ConstantBackoff`].
+    pub fn delay(self, delay: Duration) -> Self {
+        self.backoff(I5ConstantBackoff::new(delay))
+    }
+
+    /// Computes the wait before every retry with `backoff`.
+    pub fn backoff(mut self, backoff: impl I5Backoff + 'static) -> Self {
+        self.backoff = Arc::new(backoff);
+        self
+    }
+
+    /// Randomizes the wait computed by the backoff, so hundreds of clients that lost
+    /// their connection at the same moment do not hit the recovering server in
+    /// lockstep. No jitter is applied by default.
+    pub fn jitter(mut self, jitter: I5Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Limits the retries of all submissions to `budget`, on top of the retries per
+    /// submission.
+    pub fn budget(mut self, budget: I5RetryBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Also retries submissions that may have reached Interface5: those that timed
+    /// out or lost their connection after connecting, and those answered with a `5xx`
+    /// status other than `503`. Off by default, as a retry then creates the batch a
+    /// second time if the first attempt was processed after all; only turn it on if
+    /// the scenario rejects or tolerates duplicate batches.
+    pub fn retry_possibly_delivered(mut self, retry: bool) -> Self {
+        self.possibly_delivered = retry;
+        self
+    }
+
+    /// Whether `error` means the submission certainly did not create a batch, so it
+    /// can be retried without the risk of a duplicate: it could not connect, or was
+    /// answered with `408`, `429` or `503`.
+    pub fn is_safe_to_retry(error: &I5RequestError) -> bool {
+        match error {
+            I5RequestError::RequestError(err) => err.is_connect(),
+            I5RequestError::ServerError(err) => matches!(err.status(), 408 | 429 | 503),
+            I5RequestError::IoError(err) => err.kind() == io::ErrorKind::ConnectionRefused,
+            _ => false,
+        }
+    }
+
+    /// Whether `error` is transient: connection failures, timeouts and the statuses
+    /// `408`, `429` and `5xx`. Rejected content, bad credentials and local errors are
+    /// not. Unless [`I5RetryPolicy::retry_possibly_delivered`] is set, only the errors
+    /// of [`I5RetryPolicy::is_safe_to_retry`] are retried.
+    pub fn is_transient(error: &I5RequestError) -> bool {
+        match error {
+            I5RequestError::RequestError(err) => err.is_connect() || err.is_timeout(),
+            I5RequestError::ServerError(err) => matches!(err.status(), 408 | 429 | 500..=599),
+            I5RequestError::IoError(err) => matches!(
+                err.kind(),
+                io::ErrorKind::TimedOut
+                    | io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::UnexpectedEof
+            ),
+            _ => false,
+        }
+    }
+
+    /// Records a new submission in the budget.
+    pub(crate) fn start(&self) {
+        if let Some(budget) = &self.budget {
+            budget.record_request();
+        }
+    }
+
+    /// The wait before retrying a submission that failed with `error` after `retries`
+    /// retries, the last after waiting `previous`, or `None` if it must not be retried.
+    pub(crate) fn next_delay(
+        &self,
+        retries: u32,
+        previous: Duration,
+        error: &I5RequestError,
+    ) -> Option<Duration> {
+        let retryable = if self.possibly_delivered {
+            I5RetryPolicy::is_transient(error)
+        } else {
+            I5RetryPolicy::is_safe_to_retry(error)
+        };
+        if retries >= self.max_retries || !retryable {
+            return None;
+        }
+        if let Some(budget) = &self.budget
+            && !budget.try_acquire()
+        {
+            return None;
+        }
+        let delay = self.jitter.apply(self.backoff.delay(retries + 1, previous));
+        let requested = match error {
+            I5RequestError::ServerError(err) => err.retry_after(),
+            _ => None,
+        };
+        Some(requested.map_or(delay, |requested| delay.max(requested)))
+    }
+}
+
+/// A limit on the share of retries among all submissions over a sliding window.
+///
+/// A flapping Interface5 node makes every sender retry at once; the budget caps the
+/// extra load at `max_ratio` retries per submission, plus a small allowance so a quiet
+/// client can still retry. Once the budget is spent, failed submissions are returned
+/// at once. Clones share their window, so one budget can cover several clients.
+#[derive(Clone)]
+pub struct I5RetryBudget {
+    max_ratio: f64,
+    window: Duration,
+    min_retries: u32,
+    history: Arc<Mutex<History>>,
+}
+
+/// The submissions and retries within the window of an [`I5RetryBudget`].
+#[derive(Default)]
+struct History {
+    requests: VecDeque<Instant>,
+    retries: VecDeque<Instant>,
+}
+
+impl I5RetryBudget {
+    /// Creates a budget that allows `max_ratio` retries per submission over the
+    /// preceding `window`, and at least 10 retries per window.
+    pub fn new(max_ratio: f64, window: Duration) -> I5RetryBudget {
+        I5RetryBudget {
+            max_ratio: max_ratio.max(0.0),
+            window,
+            min_retries: 10,
+            history: Arc::default(),
+        }
+    }
+
+    /// The number of retries allowed per window regardless of the ratio.
+    pub fn min_retries(mut self, min_retries: u32) -> Self {
+        self.min_retries = min_retries;
+        self
+    }
+
+    /// The number of retries still allowed in the current window.
+    pub fn remaining(&self) -> u32 {
+        let mut history = self.history();
+        self.prune(&mut history, Instant::now());
+        self.allowed(&history)
+            .saturating_sub(history.retries.len() as u32)
+    }
+
+    fn record_request(&self) {
+        let now = Instant::now();
+        let mut history = self.history();
+        self.prune(&mut history, now);
+        history.requests.push_back(now);
+    }
+
+    /// Takes one retry from the budget, if any is left.
+    fn try_acquire(&self) -> bool {
+        let now = Instant::now();
+        let mut history = self.history();
+        self.prune(&mut history, now);
+        if history.retries.len() as u32 >= self.allowed(&history) {
+            return false;
+        }
+        history.retries.push_back(now);
+        true
+    }
+
+    fn allowed(&self, history: &History) -> u32 {
+        let ratio = (history.requests.len() as f64 * self.max_ratio) as u32;
+        self.min_retries.saturating_add(ratio)
+    }
+
+    fn prune(&self, history: &mut History, now: Instant) {
+        let expired = |at: &Instant| now.duration_since(*at) > self.window;
+        while history.requests.front().is_some_and(expired) {
+            history.requests.pop_front();
+        }
+        while history.retries.front().is_some_and(expired) {
+            history.retries.pop_front();
+        }
+    }
+
+    fn history(&self) -> std::sync::MutexGuard<'_, History> {
+        self.history
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl fmt::Debug for I5RetryBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("I5RetryBudget")
+            .field("max_ratio", &self.max_ratio)
+            .field("window", &self.window)
+            .field("min_retries", &self.min_retries)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::i5_server_error::I5ServerError;
+
+    fn status(status: u16, retry_after: Option<&str>) -> I5RequestError {
+        I5RequestError::ServerError(
+            I5ServerError::from_response(status, "").retry_after_header(retry_after),
+        )
+    }
+
+    #[test]
+    fn only_undelivered_submissions_are_retried_by_default() {
+        let policy = I5RetryPolicy::new(3);
+        let previous = Duration::ZERO;
+        for retried in [408, 429, 503] {
+            assert!(
+                policy
+                    .next_delay(0, previous, &status(retried, None))
+                    .is_some()
+            );
+        }
+        for kept in [400, 500, 502, 504] {
+            assert!(
+                policy
+                    .next_delay(0, previous, &status(kept, None))
+                    .is_none()
+            );
+        }
+        let reset = I5RequestError::IoError(io::ErrorKind::ConnectionReset.into());
+        assert!(policy.next_delay(0, previous, &reset).is_none());
+
+        let policy = policy.retry_possibly_delivered(true);
+        assert!(policy.next_delay(0, previous, &status(500, None)).is_some());
+        assert!(policy.next_delay(0, previous, &reset).is_some());
+        assert!(policy.next_delay(0, previous, &status(400, None)).is_none());
+    }
+
+    #[test]
+    fn retry_after_extends_the_backoff() {
+        let policy = I5RetryPolicy::new(3).delay(Duration::from_secs(1));
+        let previous = Duration::ZERO;
+        assert_eq!(
+            policy.next_delay(0, previous, &status(503, Some("7"))),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            policy.next_delay(0, previous, &status(429, Some("0"))),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            policy.next_delay(0, previous, &status(429, Some("soon"))),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            policy.next_delay(3, previous, &status(503, Some("7"))),
+            None
+        );
+    }
+}
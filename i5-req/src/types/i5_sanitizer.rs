@@ -0,0 +1,211 @@
+use icu_normalizer::ComposingNormalizerBorrowed;
+
+/// Cleans up field values before they are sent, e.g. text pasted from OCR results.
+///
+/// By default a sanitizer
+///
+/// - normalizes values to Unicode NFC,
+/// - replaces non-breaking and other special spaces with a plain space,
+/// - removes zero-width characters, soft hyphens, bidirectional controls, byte order
+///   marks and U+FFFD replacement characters left over from broken encodings,
+/// - removes control characters except tab, line feed and carriage return.
+///
+/// Each step can be switched off. Values can additionally be truncated to a maximum
+/// number of characters with [`I5Sanitizer::max_length`].
+///
+/// The sanitizer is applied to every document sent through an
+/// [`I5Client`](crate::request::client::I5Client) configured with
+/// [`I5Config::sanitizer`](crate::types::i5_config::I5Config::sanitizer), or explicitly
+/// with [`I5Request::sanitize`], which reports the changed fields.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_sanitizer::I5Sanitizer;
+///
+/// let sanitizer = I5Sanitizer::new().max_length(10);
+///
+/// assert_eq!(sanitizer.sanitize("Mu\u{0308}ller\u{00A0}GmbH").0, "Müller Gmb");
+/// assert_eq!(sanitizer.sanitize("47\u{200B}11").0, "4711");
+/// ```
+///
+/// [`I5Request::sanitize`]: crate::types::i5_request::I5Request::sanitize
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5Sanitizer {
+    normalize: bool,
+    replace_spaces: bool,
+    remove_invisible: bool,
+    remove_control: bool,
+    max_length: Option<usize>,
+}
+
+impl Default for I5Sanitizer {
+    fn default() -> Self {
+        I5Sanitizer {
+            normalize: true,
+            replace_spaces: true,
+            remove_invisible: true,
+            remove_control: true,
+            max_length: None,
+        }
+    }
+}
+
+impl I5Sanitizer {
+    /// Creates a sanitizer with all cleanup steps enabled and no length limit.
+    pub fn new() -> I5Sanitizer {
+        I5Sanitizer::default()
+    }
+
+    /// Enables or disables NFC normalization.
+    pub fn normalize(mut self, enabled: bool) -> Self {
+        self.normalize = enabled;
+        self
+    }
+
+    /// Enables or disables replacing special spaces with a plain space.
+    pub fn replace_spaces(mut self, enabled: bool) -> Self {
+        self.replace_spaces = enabled;
+        self
+    }
+
+    /// Enables or disables removing invisible characters.
+    pub fn remove_invisible(mut self, enabled: bool) -> Self {
+        self.remove_invisible = enabled;
+        self
+    }
+
+    /// Enables or disables removing control characters.
+    pub fn remove_control(mut self, enabled: bool) -> Self {
+        self.remove_control = enabled;
+        self
+    }
+
+    /// Truncates values to at most `max_chars` characters.
+    pub fn max_length(mut self, max_chars: usize) -> Self {
+        self.max_length = Some(max_chars);
+        self
+    }
+
+    /// Sanitizes `value`.
+    ///
+    /// Returns the sanitized value and whether it had to be truncated.
+    pub fn sanitize(&self, value: &str) -> (String, bool) {
+        let mut sanitized: String = value
+            .chars()
+            .filter_map(|c| {
+                if self.replace_spaces && is_special_space(c) {
+                    Some(' ')
+                } else if (self.remove_invisible && is_invisible(c))
+                    || (self.remove_control && c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+                {
+                    None
+                } else {
+                    Some(c)
+                }
+            })
+            .collect();
+
+        if self.normalize {
+            sanitized = ComposingNormalizerBorrowed::new_nfc()
+                .normalize(&sanitized)
+                .into_owned();
+        }
+
+        let mut truncated = false;
+        if let Some(max_length) = self.max_length
+            && let Some((index, _)) = sanitized.char_indices().nth(max_length)
+        {
+            sanitized.truncate(index);
+            truncated = true;
+        }
+        (sanitized, truncated)
+    }
+}
+
+fn is_special_space(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00A0}' | '\u{1680}' | '\u{2000}'..='\u{200A}' | '\u{202F}' | '\u{205F}' | '\u{3000}'
+    )
+}
+
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{FEFF}'
+            | '\u{FFFD}'
+    )
+}
+
+/// The fields changed by [`I5Request::sanitize`].
+///
+/// [`I5Request::sanitize`]: crate::types::i5_request::I5Request::sanitize
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5SanitizeReport {
+    changes: Vec<I5SanitizedField>,
+}
+
+impl I5SanitizeReport {
+    pub(crate) fn push(&mut self, change: I5SanitizedField) {
+        self.changes.push(change);
+    }
+
+    /// All changed fields, in document order.
+    pub fn changes(&self) -> &[I5SanitizedField] {
+        &self.changes
+    }
+
+    /// The fields that were truncated.
+    pub fn truncated(&self) -> impl Iterator<Item = &I5SanitizedField> {
+        self.changes.iter().filter(|change| change.truncated)
+    }
+
+    /// Returns `true` if no field was changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// A field whose value was changed by an [`I5Sanitizer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5SanitizedField {
+    pub(crate) document: usize,
+    pub(crate) name: String,
+    pub(crate) item_number: i32,
+    pub(crate) original_length: usize,
+    pub(crate) truncated: bool,
+}
+
+impl I5SanitizedField {
+    /// The index of the document.
+    pub fn document(&self) -> usize {
+        self.document
+    }
+
+    /// The name of the field.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The item number of the field, `0` for header fields.
+    pub fn item_number(&self) -> i32 {
+        self.item_number
+    }
+
+    /// The length of the original value in characters.
+    pub fn original_length(&self) -> usize {
+        self.original_length
+    }
+
+    /// Whether the value was truncated.
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+}
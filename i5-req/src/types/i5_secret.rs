@@ -0,0 +1,56 @@
+use std::fmt::{Debug, Display};
+
+/// A secret value such as a password, token or key.
+///
+/// `Debug` and `Display` never print the value, so secrets stored on client or
+/// configuration types cannot leak through accidental `{:?}` logging. Use
+/// [`I5Secret::expose`] where the plain value is really needed.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_secret::I5Secret;
+///
+/// let password = I5Secret::new("hunter2");
+/// assert_eq!(format!("{:?}", password), "I5Secret(***)");
+/// assert_eq!(password.to_string(), "***");
+/// assert_eq!(password.expose(), "hunter2");
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct I5Secret(String);
+
+impl I5Secret {
+    /// Wraps a secret value.
+    pub fn new(secret: impl Into<String>) -> I5Secret {
+        I5Secret(secret.into())
+    }
+
+    /// Returns the plain secret value.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for I5Secret {
+    fn from(secret: String) -> Self {
+        I5Secret(secret)
+    }
+}
+
+impl From<&str> for I5Secret {
+    fn from(secret: &str) -> Self {
+        I5Secret(secret.to_string())
+    }
+}
+
+impl Debug for I5Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "I5Secret(***)")
+    }
+}
+
+impl Display for I5Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "***")
+    }
+}
@@ -0,0 +1,204 @@
+use serde_json::Value;
+use std::{fmt::Display, time::Duration};
+
+use crate::util;
+
+/// The known failure categories reported by an Interface5 WebServiceInput.
+///
+/// Interface5 answers rejected batches with an error payload carrying a code and a
+/// message. The codes that callers commonly need to react to are mapped to dedicated
+/// variants, everything else ends up in [`I5ServerErrorKind::Other`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum I5ServerErrorKind {
+    /// The scenario in the request URL does not exist on the server.
+    UnknownScenario,
+    /// The tenant in the request URL does not exist on the server.
+    UnknownTenant,
+    /// The tenant exists but is disabled.
+    TenantDisabled,
+    /// A field declared as mandatory in the scenario is missing.
+    MandatoryFieldMissing,
+    /// The request was rejected because of missing or invalid credentials.
+    Unauthorized,
+    /// Any other error reported by the server.
+    Other,
+}
+
+impl I5ServerErrorKind {
+    /// Returns the stable string code of this kind.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnknownScenario => "I5_SERVER_UNKNOWN_SCENARIO",
+            Self::UnknownTenant => "I5_SERVER_UNKNOWN_TENANT",
+            Self::TenantDisabled => "I5_SERVER_TENANT_DISABLED",
+            Self::MandatoryFieldMissing => "I5_SERVER_MANDATORY_FIELD_MISSING",
+            Self::Unauthorized => "I5_SERVER_UNAUTHORIZED",
+            Self::Other => "I5_SERVER_OTHER",
+        }
+    }
+
+    /// Maps a server side error code to a kind, ignoring case and separators.
+    fn from_server_code(code: &str) -> Option<I5ServerErrorKind> {
+        let normalized: String = code
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+
+        match normalized.as_str() {
+            "unknownscenario" | "scenarionotfound" | "invalidscenario" => {
+                Some(Self::UnknownScenario)
+            }
+            "unknowntenant" | "tenantnotfound" | "invalidtenant" => Some(Self::UnknownTenant),
+            "tenantdisabled" | "tenantinactive" => Some(Self::TenantDisabled),
+            "mandatoryfieldmissing" | "requiredfieldmissing" | "missingmandatoryfield" => {
+                Some(Self::MandatoryFieldMissing)
+            }
+            "unauthorized" | "forbidden" | "accessdenied" => Some(Self::Unauthorized),
+            _ => None,
+        }
+    }
+}
+
+/// An error payload returned by Interface5 for a rejected request.
+///
+/// Created from the HTTP status and the raw response body via
+/// [`I5ServerError::from_response`]. The body is parsed leniently: if it is JSON with a
+/// `Code`/`ErrorCode` and `Message` entry the values are used for the mapping, otherwise
+/// the plain body text becomes the message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5ServerError {
+    status: u16,
+    kind: I5ServerErrorKind,
+    code: Option<String>,
+    message: String,
+    field: Option<String>,
+    retry_after: Option<Duration>,
+}
+
+impl I5ServerError {
+    /// Builds an [`I5ServerError`] from a non-success HTTP status and its response body.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_server_error::{I5ServerError, I5ServerErrorKind};
+    ///
+    /// let err = I5ServerError::from_response(
+    ///     400,
+    ///     r#"{"Code": "MandatoryFieldMissing", "Message": "Field missing", "Field": "InvoiceNumber"}"#,
+    /// );
+    /// assert_eq!(err.kind(), I5ServerErrorKind::MandatoryFieldMissing);
+    /// assert_eq!(err.field(), Some("InvoiceNumber"));
+    /// ```
+    pub fn from_response(status: u16, body: &str) -> I5ServerError {
+        let payload: Option<Value> = serde_json::from_str(body).ok();
+        let lookup = |keys: &[&str]| -> Option<String> {
+            let object = payload.as_ref()?.as_object()?;
+            keys.iter().find_map(|key| {
+                object
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                    .and_then(|(_, value)| match value {
+                        Value::String(text) => Some(text.clone()),
+                        Value::Number(number) => Some(number.to_string()),
+                        _ => None,
+                    })
+            })
+        };
+
+        let code = lookup(&["Code", "ErrorCode", "Error"]);
+        let message = lookup(&["Message", "ErrorMessage", "Detail"])
+            .unwrap_or_else(|| body.trim().to_string());
+        let field = lookup(&["Field", "FieldName"]);
+
+        let kind = code
+            .as_deref()
+            .and_then(I5ServerErrorKind::from_server_code)
+            .or_else(|| Self::kind_from_message(&message))
+            .unwrap_or(match status {
+                401 | 403 => I5ServerErrorKind::Unauthorized,
+                _ => I5ServerErrorKind::Other,
+            });
+
+        I5ServerError {
+            status,
+            kind,
+            code,
+            message,
+            field,
+            retry_after: None,
+        }
+    }
+
+    /// Records the wait requested by the `Retry-After` header of the response, given
+    /// in seconds or as an HTTP date. Values that are neither are ignored.
+    pub(crate) fn retry_after_header(mut self, value: Option<&str>) -> Self {
+        self.retry_after = value.and_then(util::parse_retry_after);
+        self
+    }
+
+    /// Falls back to recognizing the failure from the message text.
+    fn kind_from_message(message: &str) -> Option<I5ServerErrorKind> {
+        let message = message.to_lowercase();
+        let unknown = message.contains("unknown") || message.contains("not found");
+
+        if message.contains("scenario") && unknown {
+            Some(I5ServerErrorKind::UnknownScenario)
+        } else if message.contains("tenant") && message.contains("disabled") {
+            Some(I5ServerErrorKind::TenantDisabled)
+        } else if message.contains("tenant") && unknown {
+            Some(I5ServerErrorKind::UnknownTenant)
+        } else if message.contains("mandatory") && message.contains("missing") {
+            Some(I5ServerErrorKind::MandatoryFieldMissing)
+        } else {
+            None
+        }
+    }
+
+    /// The HTTP status code of the response.
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// The recognized error category.
+    pub fn kind(&self) -> I5ServerErrorKind {
+        self.kind
+    }
+
+    /// The raw error code reported by the server, if any.
+    pub fn server_code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// The error message reported by the server.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The affected field name, if the server reported one.
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    /// How long the server asked to wait before trying again, from the `Retry-After`
+    /// header, usually sent with `429` and `503`.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+}
+
+impl Display for I5ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Interface5 rejected the request ({}", self.status)?;
+        if let Some(code) = &self.code {
+            write!(f, ", {}", code)?;
+        }
+        write!(f, "): {}", self.message)?;
+        if let Some(field) = &self.field {
+            write!(f, " [field: {}]", field)?;
+        }
+        Ok(())
+    }
+}
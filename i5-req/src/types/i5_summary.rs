@@ -0,0 +1,117 @@
+use std::fmt;
+
+/// Counts describing an [`I5Request`], returned by [`I5Request::summary`].
+///
+/// The [`Display`](fmt::Display) output contains the batch name and the counts but no
+/// field values or file contents, so it is safe for operator logs. `I5Request` itself
+/// displays as its summary.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_request::I5Request;
+///
+/// let mut request = I5Request::new("SCAN-42");
+/// let id = request.add_document("Invoice");
+/// let document = request.get_document_mut(id).unwrap();
+/// document
+///     .add_header_field("InvoiceNo", "4711")
+///     .add_item_field("Article", "A-1", 1)
+///     .add_item_field("Quantity", "2", 1);
+/// document.add_bytes_file("scan.pdf", &[0; 2048]);
+///
+/// assert_eq!(request.summary().attachment_bytes(), 2048);
+/// assert_eq!(
+///     request.to_string(),
+///     "batch 'SCAN-42': 1 document, 1 header field, 2 item fields (1 item), 1 file (2.0 KiB)"
+/// );
+/// ```
+///
+/// [`I5Request`]: crate::types::i5_request::I5Request
+/// [`I5Request::summary`]: crate::types::i5_request::I5Request::summary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5RequestSummary {
+    pub(crate) name: String,
+    pub(crate) documents: usize,
+    pub(crate) header_fields: usize,
+    pub(crate) item_fields: usize,
+    pub(crate) items: usize,
+    pub(crate) files: usize,
+    pub(crate) attachment_bytes: u64,
+}
+
+impl I5RequestSummary {
+    /// The batch name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of documents.
+    pub fn documents(&self) -> usize {
+        self.documents
+    }
+
+    /// The number of header fields of all documents.
+    pub fn header_fields(&self) -> usize {
+        self.header_fields
+    }
+
+    /// The number of item fields of all documents.
+    pub fn item_fields(&self) -> usize {
+        self.item_fields
+    }
+
+    /// The number of items of all documents.
+    pub fn items(&self) -> usize {
+        self.items
+    }
+
+    /// The number of files of all documents.
+    pub fn files(&self) -> usize {
+        self.files
+    }
+
+    /// The decoded size of all files in bytes.
+    pub fn attachment_bytes(&self) -> u64 {
+        self.attachment_bytes
+    }
+}
+
+impl fmt::Display for I5RequestSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "batch '{}': {}, {}, {} ({}), {} ({})",
+            self.name,
+            plural(self.documents, "document"),
+            plural(self.header_fields, "header field"),
+            plural(self.item_fields, "item field"),
+            plural(self.items, "item"),
+            plural(self.files, "file"),
+            format_size(self.attachment_bytes)
+        )
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Formats a byte count with binary units, e.g. `512 B` or `1.5 MiB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
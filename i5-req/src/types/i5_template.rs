@@ -0,0 +1,121 @@
+use std::{env, time::SystemTime};
+
+use crate::{types::i5_error::I5RequestError, util};
+
+/// The values used to resolve template placeholders in field values.
+///
+/// A template is plain text with `{{...}}` placeholders:
+///
+/// | Placeholder      | Replaced with                                        |
+/// |------------------|------------------------------------------------------|
+/// | `{{now}}`        | The current time as an RFC 3339 UTC timestamp        |
+/// | `{{now:FORMAT}}` | The current UTC time formatted with `FORMAT`         |
+/// | `{{uuid}}`       | A new random UUID, different for every placeholder   |
+/// | `{{filename}}`   | The name of the first file of the document           |
+/// | `{{env:NAME}}`   | The value of the environment variable `NAME`         |
+///
+/// `FORMAT` may contain `%Y`, `%y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%f` (milliseconds),
+/// `%s` (Unix seconds) and `%%`.
+///
+/// Templates are resolved for each document when an
+/// [`I5Client`](crate::request::client::I5Client) adds its
+/// [default fields](crate::types::i5_config::I5Config::default_field), and can be
+/// resolved explicitly with [`I5TemplateContext::render`].
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// use i5_req::types::i5_template::I5TemplateContext;
+///
+/// let context = I5TemplateContext::new()
+///     .at(UNIX_EPOCH + Duration::from_secs(1_751_011_200))
+///     .filename("scan-0001.pdf");
+///
+/// assert_eq!(
+///     context.render("{{now:%Y-%m-%d}} {{filename}}")?,
+///     "2025-06-27 scan-0001.pdf"
+/// );
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct I5TemplateContext {
+    now: SystemTime,
+    filename: Option<String>,
+}
+
+impl I5TemplateContext {
+    /// Creates a context for the current time without a file name.
+    pub fn new() -> I5TemplateContext {
+        I5TemplateContext {
+            now: SystemTime::now(),
+            filename: None,
+        }
+    }
+
+    /// Sets the time used for `{{now}}` placeholders.
+    pub fn at(mut self, now: SystemTime) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// Sets the file name used for `{{filename}}` placeholders.
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// Resolves all placeholders in `template`.
+    ///
+    /// Text without placeholders is returned unchanged. Returns
+    /// [`I5RequestError::ConfigError`] for unknown or unterminated placeholders, an
+    /// unsupported date format, `{{filename}}` without a file name or `{{env:NAME}}`
+    /// for an unset variable.
+    pub fn render(&self, template: &str) -> Result<String, I5RequestError> {
+        let error = |message: String| {
+            I5RequestError::ConfigError(format!("template '{}': {}", template, message))
+        };
+
+        let mut output = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after
+                .find("}}")
+                .ok_or_else(|| error(String::from("unterminated placeholder")))?;
+            output.push_str(&self.resolve(after[..end].trim()).map_err(error)?);
+            rest = &after[end + 2..];
+        }
+        output.push_str(rest);
+        Ok(output)
+    }
+
+    fn resolve(&self, placeholder: &str) -> Result<String, String> {
+        let (name, argument) = match placeholder.split_once(':') {
+            Some((name, argument)) => (name.trim(), Some(argument)),
+            None => (placeholder, None),
+        };
+
+        match (name, argument) {
+            ("now", None) => Ok(util::format_rfc3339(self.now)),
+            ("now", Some(format)) => util::format_strftime(self.now, format)
+                .map_err(|specifier| format!("unsupported date format {}", specifier)),
+            ("uuid", None) => Ok(util::uuid_v4()),
+            ("filename", None) => self
+                .filename
+                .clone()
+                .ok_or_else(|| String::from("{{filename}} requires a document with a file")),
+            ("env", Some(variable)) => env::var(variable.trim())
+                .map_err(|_| format!("environment variable {} is not set", variable.trim())),
+            _ => Err(format!("unknown placeholder {{{{{}}}}}", placeholder)),
+        }
+    }
+}
+
+impl Default for I5TemplateContext {
+    fn default() -> Self {
+        I5TemplateContext::new()
+    }
+}
@@ -0,0 +1,91 @@
+use std::{fmt, str::FromStr};
+
+use crate::types::i5_error::I5RequestError;
+
+/// The TLS implementation the clients connect with, set with
+/// [`I5Config::tls_backend`].
+///
+/// Each backend is compiled in with a cargo feature: `native-tls` (enabled by default)
+/// uses the TLS library of the operating system, e.g. SChannel with the Windows
+/// certificate store or OpenSSL, and `rustls` uses rustls, which needs no system
+/// library and suits statically linked builds, e.g. for musl containers. Without an
+/// explicit choice, the native backend is used if it is compiled in, otherwise rustls;
+/// building without either feature fails.
+///
+/// The certificate options apply to either backend: the
+/// [root certificates](crate::types::i5_config::I5Config::add_root_certificate), the
+/// [TLS versions](crate::types::i5_config::I5Config::min_tls_version) and
+/// [`I5Config::accept_invalid_certs`](crate::types::i5_config::I5Config::accept_invalid_certs).
+/// Rustls trusts the CA bundle of the system, read from `SSL_CERT_FILE` or the usual
+/// locations such as `/etc/ssl/certs/ca-certificates.crt`, but not the Windows or
+/// macOS certificate stores. [Pinned](crate::types::i5_config::I5Config::pin_public_key)
+/// connections always use rustls.
+///
+/// [`I5Config::tls_backend`]: crate::types::i5_config::I5Config::tls_backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum I5TlsBackend {
+    /// The TLS library of the operating system, with the `native-tls` feature.
+    Native,
+    /// rustls, with the `rustls` feature.
+    Rustls,
+}
+
+impl I5TlsBackend {
+    /// The backend used without an explicit choice.
+    pub fn compiled_default() -> I5TlsBackend {
+        if cfg!(feature = "native-tls") {
+            I5TlsBackend::Native
+        } else {
+            I5TlsBackend::Rustls
+        }
+    }
+
+    /// Whether the backend is compiled in.
+    pub fn is_available(&self) -> bool {
+        match self {
+            I5TlsBackend::Native => cfg!(feature = "native-tls"),
+            I5TlsBackend::Rustls => cfg!(feature = "rustls"),
+        }
+    }
+
+    /// The backend as written in configuration, `native` or `rustls`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            I5TlsBackend::Native => "native",
+            I5TlsBackend::Rustls => "rustls",
+        }
+    }
+
+    /// The cargo feature compiling the backend in.
+    pub(crate) fn feature(&self) -> &'static str {
+        match self {
+            I5TlsBackend::Native => "native-tls",
+            I5TlsBackend::Rustls => "rustls",
+        }
+    }
+}
+
+impl FromStr for I5TlsBackend {
+    type Err = I5RequestError;
+
+    /// Parses `native`, `native-tls` or `rustls`, ignoring case.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] for unknown backends.
+    fn from_str(text: &str) -> Result<I5TlsBackend, I5RequestError> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "native" | "native-tls" => Ok(I5TlsBackend::Native),
+            "rustls" => Ok(I5TlsBackend::Rustls),
+            _ => Err(I5RequestError::ConfigError(format!(
+                "unknown TLS backend '{}', expected native or rustls",
+                text
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for I5TlsBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,77 @@
+use std::{fmt, str::FromStr};
+
+use crate::types::i5_error::I5RequestError;
+
+/// A version of the TLS protocol, for the bounds set with
+/// [`I5Config::min_tls_version`] and [`I5Config::max_tls_version`].
+///
+/// Versions are ordered, so `I5TlsVersion::Tls1_2 < I5TlsVersion::Tls1_3`.
+///
+/// [`I5Config::min_tls_version`]: crate::types::i5_config::I5Config::min_tls_version
+/// [`I5Config::max_tls_version`]: crate::types::i5_config::I5Config::max_tls_version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum I5TlsVersion {
+    /// TLS 1.0.
+    Tls1_0,
+    /// TLS 1.1.
+    Tls1_1,
+    /// TLS 1.2.
+    Tls1_2,
+    /// TLS 1.3.
+    Tls1_3,
+}
+
+impl I5TlsVersion {
+    /// The version as written in configuration, e.g. `1.2`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            I5TlsVersion::Tls1_0 => "1.0",
+            I5TlsVersion::Tls1_1 => "1.1",
+            I5TlsVersion::Tls1_2 => "1.2",
+            I5TlsVersion::Tls1_3 => "1.3",
+        }
+    }
+
+    /// The version as understood by `reqwest`.
+    pub(crate) fn to_reqwest(self) -> reqwest::tls::Version {
+        match self {
+            I5TlsVersion::Tls1_0 => reqwest::tls::Version::TLS_1_0,
+            I5TlsVersion::Tls1_1 => reqwest::tls::Version::TLS_1_1,
+            I5TlsVersion::Tls1_2 => reqwest::tls::Version::TLS_1_2,
+            I5TlsVersion::Tls1_3 => reqwest::tls::Version::TLS_1_3,
+        }
+    }
+}
+
+impl FromStr for I5TlsVersion {
+    type Err = I5RequestError;
+
+    /// Parses a version such as `1.2`, `TLS1.2` or `TLSv1.2`, ignoring case.
+    ///
+    /// Returns [`I5RequestError::ConfigError`] for unknown versions.
+    fn from_str(text: &str) -> Result<I5TlsVersion, I5RequestError> {
+        let lower = text.trim().to_ascii_lowercase();
+        let number = lower
+            .strip_prefix("tlsv")
+            .or_else(|| lower.strip_prefix("tls"))
+            .unwrap_or(&lower);
+        match number.trim() {
+            "1.0" | "1" => Ok(I5TlsVersion::Tls1_0),
+            "1.1" => Ok(I5TlsVersion::Tls1_1),
+            "1.2" => Ok(I5TlsVersion::Tls1_2),
+            "1.3" => Ok(I5TlsVersion::Tls1_3),
+            _ => Err(I5RequestError::ConfigError(format!(
+                "unknown TLS version '{}', expected 1.0, 1.1, 1.2 or 1.3",
+                text
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for I5TlsVersion {
+    /// Writes the version as `TLS 1.2`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TLS {}", self.as_str())
+    }
+}
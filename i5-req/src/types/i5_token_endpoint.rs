@@ -0,0 +1,86 @@
+use crate::{types::i5_secret::I5Secret, util};
+
+/// An OAuth 2.0 token endpoint the clients obtain bearer tokens from with the client
+/// credentials grant, for Interface5 servers behind an identity provider.
+///
+/// A client configured with an [endpoint](crate::types::i5_config::I5Config::token_endpoint)
+/// requests a token before its first submission and caches it until shortly before
+/// the `expires_in` of the token response. If Interface5 nevertheless answers a
+/// submission with `401`, the token is requested again and the submission retried
+/// once with the new one. Credentials set in the
+/// [send options](crate::request::options::I5SendOptions::auth) are used as they are.
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{i5_config::I5Config, i5_token_endpoint::I5TokenEndpoint};
+///
+/// let endpoint = I5TokenEndpoint::new(
+///     "https://login.example.com/oauth2/token",
+///     "svc-scanner",
+///     "s3cret",
+/// )
+/// .scope("interface5.input");
+/// let config = I5Config::new("i5.example.com", 43001, "Scan", "Default").token_endpoint(endpoint);
+///
+/// assert_eq!(config.token_source().unwrap().client_id(), "svc-scanner");
+/// assert!(!format!("{:?}", config).contains("s3cret"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I5TokenEndpoint {
+    url: String,
+    client_id: String,
+    client_secret: I5Secret,
+    scope: Option<String>,
+}
+
+impl I5TokenEndpoint {
+    /// Creates an endpoint at `url` for the client `client_id`.
+    pub fn new(
+        url: impl Into<String>,
+        client_id: impl Into<String>,
+        client_secret: impl Into<I5Secret>,
+    ) -> I5TokenEndpoint {
+        I5TokenEndpoint {
+            url: url.into(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            scope: None,
+        }
+    }
+
+    /// Requests tokens for `scope`, a space-separated list of scopes.
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    /// The URL of the endpoint.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The ID of the client.
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// The requested scope, if any.
+    pub fn requested_scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+
+    /// The `application/x-www-form-urlencoded` body of a token request.
+    pub(crate) fn form(&self) -> String {
+        let mut form = format!(
+            "grant_type=client_credentials&client_id={}&client_secret={}",
+            util::percent_encode(&self.client_id),
+            util::percent_encode(self.client_secret.expose())
+        );
+        if let Some(scope) = &self.scope {
+            form.push_str("&scope=");
+            form.push_str(&util::percent_encode(scope));
+        }
+        form
+    }
+}
@@ -0,0 +1,131 @@
+use serde::Deserialize;
+
+use crate::util;
+
+/// A single transformation of a field value.
+///
+/// In configuration files, transformations without parameters are written as strings
+/// (`"trim"`, `"uppercase"`, `"lowercase"`) and the others as tables, e.g.
+/// `{ left_pad = { width = 10, fill = "0" } }`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+#[non_exhaustive]
+pub enum I5Transform {
+    /// Removes leading and trailing whitespace.
+    Trim,
+    /// Converts the value to uppercase.
+    Uppercase,
+    /// Converts the value to lowercase.
+    Lowercase,
+    /// Replaces every occurrence of the literal text `from` with `to`.
+    Replace { from: String, to: String },
+    /// Pads the value on the left with `fill` up to `width` characters.
+    LeftPad {
+        width: usize,
+        #[serde(default = "default_fill")]
+        fill: char,
+    },
+    /// Keeps `length` characters starting at character `start`, or all remaining
+    /// characters if `length` is not set.
+    Substring {
+        start: usize,
+        #[serde(default)]
+        length: Option<usize>,
+    },
+}
+
+fn default_fill() -> char {
+    ' '
+}
+
+impl I5Transform {
+    /// Applies the transformation to `value`.
+    pub fn apply(&self, value: &str) -> String {
+        match self {
+            I5Transform::Trim => value.trim().to_string(),
+            I5Transform::Uppercase => value.to_uppercase(),
+            I5Transform::Lowercase => value.to_lowercase(),
+            I5Transform::Replace { from, .. } if from.is_empty() => value.to_string(),
+            I5Transform::Replace { from, to } => value.replace(from.as_str(), to),
+            I5Transform::LeftPad { width, fill } => {
+                let length = value.chars().count();
+                let mut padded: String =
+                    std::iter::repeat_n(*fill, width.saturating_sub(length)).collect();
+                padded.push_str(value);
+                padded
+            }
+            I5Transform::Substring { start, length } => {
+                let rest = value.chars().skip(*start);
+                match length {
+                    Some(length) => rest.take(*length).collect(),
+                    None => rest.collect(),
+                }
+            }
+        }
+    }
+}
+
+/// Chains of [`I5Transform`]s attached to field names.
+///
+/// Field names are matched by pattern (`*` and `?` wildcards, ignoring case). When
+/// several patterns match a field, their chains are applied in the order they were
+/// added. Transformations are applied to every document sent through an
+/// [`I5Client`](crate::request::client::I5Client) configured with
+/// [`I5Config::transforms`](crate::types::i5_config::I5Config::transforms), or
+/// explicitly with [`I5Request::apply_transforms`].
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::i5_transform::{I5Transform, I5Transforms};
+///
+/// let transforms = I5Transforms::new()
+///     .field("*IBAN*", [I5Transform::Trim, I5Transform::Uppercase])
+///     .field("CostCenter", [I5Transform::LeftPad { width: 6, fill: '0' }]);
+///
+/// assert_eq!(transforms.apply("IBAN", " de02 "), "DE02");
+/// assert_eq!(transforms.apply("CostCenter", "4711"), "004711");
+/// assert_eq!(transforms.apply("Other", " x "), " x ");
+/// ```
+///
+/// [`I5Request::apply_transforms`]: crate::types::i5_request::I5Request::apply_transforms
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5Transforms {
+    rules: Vec<(String, Vec<I5Transform>)>,
+}
+
+impl I5Transforms {
+    /// Creates an empty set of transformations.
+    pub fn new() -> I5Transforms {
+        I5Transforms::default()
+    }
+
+    /// Attaches a chain of transformations to all fields whose name matches `pattern`.
+    pub fn field(
+        mut self,
+        pattern: impl Into<String>,
+        transforms: impl IntoIterator<Item = I5Transform>,
+    ) -> Self {
+        self.rules
+            .push((pattern.into(), transforms.into_iter().collect()));
+        self
+    }
+
+    /// Returns `true` if no transformations are configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Applies all chains matching the field `name` to `value`.
+    pub fn apply(&self, name: &str, value: &str) -> String {
+        let mut value = value.to_string();
+        for (pattern, chain) in &self.rules {
+            if util::wildcard_match(pattern, name) {
+                for transform in chain {
+                    value = transform.apply(&value);
+                }
+            }
+        }
+        value
+    }
+}
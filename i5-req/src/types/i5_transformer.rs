@@ -0,0 +1,63 @@
+use crate::types::{i5_error::I5RequestError, i5_request::Document, i5_transform::I5Transforms};
+
+/// A reusable step that enriches or normalizes a [`Document`] before it is validated,
+/// e.g. adding OCR results, looking up vendor numbers or cleaning up field values.
+///
+/// Transformers run on every document of a request with
+/// [`I5Request::apply_transformer`] and on every input of an
+/// [`I5Pipeline`](crate::request::pipeline::I5Pipeline) with
+/// [`I5Pipeline::transformer`](crate::request::pipeline::I5Pipeline::transformer).
+/// Closures taking a `&mut Document` are transformers, and so are [`I5Transforms`].
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{
+///     i5_error::I5RequestError,
+///     i5_request::{Document, I5Request},
+///     i5_transformer::I5Transformer,
+/// };
+///
+/// /// Adds the cost center of the scanning site to every document.
+/// struct CostCenter(&'static str);
+///
+/// impl I5Transformer for CostCenter {
+///     fn transform(&self, document: &mut Document) -> Result<(), I5RequestError> {
+///         document.add_header_field("CostCenter", self.0);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut request = I5Request::new("Batch");
+/// request.add_document("Invoice");
+/// request.add_document("Invoice");
+/// request.apply_transformer(&CostCenter("4100"))?;
+/// assert!(request.documents().iter().all(|document| document.field_count() == 1));
+/// # Ok::<(), I5RequestError>(())
+/// ```
+///
+/// [`I5Request::apply_transformer`]: crate::types::i5_request::I5Request::apply_transformer
+pub trait I5Transformer {
+    /// Transforms `document` in place.
+    ///
+    /// An error rejects the document; a transformer should not leave a document half
+    /// changed when it fails.
+    fn transform(&self, document: &mut Document) -> Result<(), I5RequestError>;
+}
+
+impl<F> I5Transformer for F
+where
+    F: Fn(&mut Document) -> Result<(), I5RequestError>,
+{
+    fn transform(&self, document: &mut Document) -> Result<(), I5RequestError> {
+        self(document)
+    }
+}
+
+impl I5Transformer for I5Transforms {
+    /// Applies the transformations to all field values; this never fails.
+    fn transform(&self, document: &mut Document) -> Result<(), I5RequestError> {
+        document.apply_transforms(self);
+        Ok(())
+    }
+}
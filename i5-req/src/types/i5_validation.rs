@@ -0,0 +1,780 @@
+use std::{fmt, sync::Arc};
+
+#[cfg(feature = "l10n")]
+use crate::types::i5_locale::I5MessageCatalog;
+use crate::{
+    events::i5_event,
+    types::{
+        i5_api_version::I5ApiVersion,
+        i5_audit::{I5AuditLog, I5AuditOperation},
+        i5_error::I5RequestError,
+    },
+};
+
+/// The longest field name accepted by [`I5ValidationOptions::strict_names`].
+pub const MAX_STRICT_FIELD_NAME_LEN: usize = 64;
+
+/// The longest batch or document name accepted by
+/// [`I5ValidationOptions::strict_names`].
+pub const MAX_STRICT_NAME_LEN: usize = 128;
+
+/// A problem that keeps an [`I5Request`] from being sent.
+///
+/// [`I5Request`]: crate::types::i5_request::I5Request
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum I5ValidationIssue {
+    /// The request has no documents.
+    NoDocuments,
+
+    /// An earlier request of the same
+    /// [`I5BatchSet`](crate::types::i5_batch_set::I5BatchSet) has this name.
+    DuplicateBatchName {
+        /// The name of the request.
+        name: String,
+    },
+
+    /// The request has more documents than
+    /// [`I5ValidationOptions::max_documents`] allows.
+    TooManyDocuments {
+        /// The number of documents.
+        count: usize,
+        /// The largest number of documents allowed.
+        limit: usize,
+    },
+
+    /// The document has neither fields nor files.
+    EmptyDocument {
+        /// The index of the document.
+        document: usize,
+    },
+
+    /// The item numbers of the document have gaps.
+    MissingItemNumbers {
+        /// The index of the document.
+        document: usize,
+        /// The missing item numbers, in ascending order.
+        missing: Vec<i32>,
+    },
+
+    /// A field has a negative item number, which
+    /// [`I5ValidationOptions::reject_negative_items`] rejects.
+    NegativeItemNumber {
+        /// The index of the document.
+        document: usize,
+        /// The item number.
+        item_number: i32,
+    },
+
+    /// A document property such as the
+    /// [priority](crate::types::i5_request::Document::set_priority) is repeated or has
+    /// an invalid value.
+    InvalidProperty {
+        /// The index of the document.
+        document: usize,
+        /// The name of the header field holding the property.
+        field: String,
+        /// What is wrong with the property.
+        reason: I5PropertyProblem,
+    },
+
+    /// The name of a field breaks the rules of
+    /// [`I5ValidationOptions::strict_names`].
+    InvalidFieldName {
+        /// The index of the document.
+        document: usize,
+        /// The name of the field.
+        field: String,
+        /// Which rule the name breaks.
+        reason: I5NameProblem,
+    },
+
+    /// The name of the batch or of a document breaks the rules of
+    /// [`I5ValidationOptions::strict_names`].
+    InvalidName {
+        /// The index of the document, or `None` for the name of the batch.
+        document: Option<usize>,
+        /// The name.
+        name: String,
+        /// Which rule the name breaks.
+        reason: I5NameProblem,
+    },
+
+    /// A field names a document that is not part of the request, see
+    /// [`I5ValidationOptions::reference_by_key`].
+    UnresolvedReference {
+        /// The index of the referencing document.
+        document: usize,
+        /// The name of the referencing field.
+        field: String,
+        /// The reference that matches no document.
+        value: String,
+    },
+
+    /// A [validator](crate::types::i5_request::Document::add_validator) of the document
+    /// failed.
+    ValidatorFailed {
+        /// The index of the document.
+        document: usize,
+        /// The reason returned by the validator.
+        reason: String,
+    },
+
+    /// The scenario does not define a field of the document, see
+    /// [`I5FieldDefinitions`](crate::types::i5_field_definition::I5FieldDefinitions).
+    UnknownField {
+        /// The index of the document.
+        document: usize,
+        /// The name of the field.
+        field: String,
+    },
+
+    /// A field the scenario requires is missing from the document.
+    MissingField {
+        /// The index of the document.
+        document: usize,
+        /// The name of the field.
+        field: String,
+    },
+
+    /// A value is longer than the scenario allows.
+    ValueTooLong {
+        /// The index of the document.
+        document: usize,
+        /// The name of the field.
+        field: String,
+        /// The maximum length in characters.
+        max_length: usize,
+    },
+}
+
+impl I5ValidationIssue {
+    /// The index of the affected document, or `None` if the issue concerns the whole
+    /// request.
+    pub fn document(&self) -> Option<usize> {
+        match self {
+            I5ValidationIssue::NoDocuments
+            | I5ValidationIssue::DuplicateBatchName { .. }
+            | I5ValidationIssue::TooManyDocuments { .. } => None,
+            I5ValidationIssue::InvalidName { document, .. } => *document,
+            I5ValidationIssue::EmptyDocument { document }
+            | I5ValidationIssue::MissingItemNumbers { document, .. }
+            | I5ValidationIssue::NegativeItemNumber { document, .. }
+            | I5ValidationIssue::InvalidProperty { document, .. }
+            | I5ValidationIssue::InvalidFieldName { document, .. }
+            | I5ValidationIssue::UnresolvedReference { document, .. }
+            | I5ValidationIssue::ValidatorFailed { document, .. }
+            | I5ValidationIssue::UnknownField { document, .. }
+            | I5ValidationIssue::MissingField { document, .. }
+            | I5ValidationIssue::ValueTooLong { document, .. } => Some(*document),
+        }
+    }
+
+    /// Returns the stable string code of this issue.
+    ///
+    /// Like [`I5RequestError::code`], codes are never reused or renamed and do not
+    /// depend on the language a message is shown in.
+    ///
+    /// | Code                      | Variant                                    |
+    /// |---------------------------|--------------------------------------------|
+    /// | `I5_NO_DOCUMENTS`         | [`I5ValidationIssue::NoDocuments`]         |
+    /// | `I5_DUPLICATE_BATCH_NAME` | [`I5ValidationIssue::DuplicateBatchName`]  |
+    /// | `I5_TOO_MANY_DOCUMENTS`   | [`I5ValidationIssue::TooManyDocuments`]    |
+    /// | `I5_EMPTY_DOCUMENT`       | [`I5ValidationIssue::EmptyDocument`]       |
+    /// | `I5_MISSING_ITEM_NUMBERS` | [`I5ValidationIssue::MissingItemNumbers`]  |
+    /// | `I5_NEGATIVE_ITEM_NUMBER` | [`I5ValidationIssue::NegativeItemNumber`]  |
+    /// | `I5_INVALID_PROPERTY`     | [`I5ValidationIssue::InvalidProperty`]     |
+    /// | `I5_INVALID_FIELD_NAME`   | [`I5ValidationIssue::InvalidFieldName`]    |
+    /// | `I5_INVALID_NAME`         | [`I5ValidationIssue::InvalidName`]         |
+    /// | `I5_UNRESOLVED_REFERENCE` | [`I5ValidationIssue::UnresolvedReference`] |
+    /// | `I5_VALIDATOR_FAILED`     | [`I5ValidationIssue::ValidatorFailed`]     |
+    /// | `I5_UNKNOWN_FIELD`        | [`I5ValidationIssue::UnknownField`]        |
+    /// | `I5_MISSING_FIELD`        | [`I5ValidationIssue::MissingField`]        |
+    /// | `I5_VALUE_TOO_LONG`       | [`I5ValidationIssue::ValueTooLong`]        |
+    ///
+    /// [`I5RequestError::code`]: crate::types::i5_error::I5RequestError::code
+    pub fn code(&self) -> &'static str {
+        match self {
+            I5ValidationIssue::NoDocuments => "I5_NO_DOCUMENTS",
+            I5ValidationIssue::DuplicateBatchName { .. } => "I5_DUPLICATE_BATCH_NAME",
+            I5ValidationIssue::TooManyDocuments { .. } => "I5_TOO_MANY_DOCUMENTS",
+            I5ValidationIssue::EmptyDocument { .. } => "I5_EMPTY_DOCUMENT",
+            I5ValidationIssue::MissingItemNumbers { .. } => "I5_MISSING_ITEM_NUMBERS",
+            I5ValidationIssue::NegativeItemNumber { .. } => "I5_NEGATIVE_ITEM_NUMBER",
+            I5ValidationIssue::InvalidProperty { .. } => "I5_INVALID_PROPERTY",
+            I5ValidationIssue::InvalidFieldName { .. } => "I5_INVALID_FIELD_NAME",
+            I5ValidationIssue::InvalidName { .. } => "I5_INVALID_NAME",
+            I5ValidationIssue::UnresolvedReference { .. } => "I5_UNRESOLVED_REFERENCE",
+            I5ValidationIssue::ValidatorFailed { .. } => "I5_VALIDATOR_FAILED",
+            I5ValidationIssue::UnknownField { .. } => "I5_UNKNOWN_FIELD",
+            I5ValidationIssue::MissingField { .. } => "I5_MISSING_FIELD",
+            I5ValidationIssue::ValueTooLong { .. } => "I5_VALUE_TOO_LONG",
+        }
+    }
+
+    /// The message of this issue from `catalog`, or its English text if the catalog
+    /// has none, see [`i5_locale`](crate::types::i5_locale).
+    #[cfg(feature = "l10n")]
+    pub fn localized(&self, catalog: &dyn I5MessageCatalog) -> String {
+        catalog.issue(self).unwrap_or_else(|| self.to_string())
+    }
+}
+
+impl fmt::Display for I5ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            I5ValidationIssue::NoDocuments => write!(f, "the request has no documents"),
+            I5ValidationIssue::DuplicateBatchName { name } => {
+                write!(f, "another request of the set is named '{}'", name)
+            }
+            I5ValidationIssue::TooManyDocuments { count, limit } => write!(
+                f,
+                "the request has {} documents, at most {} are allowed",
+                count, limit
+            ),
+            I5ValidationIssue::EmptyDocument { document } => {
+                write!(f, "document {} has neither fields nor files", document)
+            }
+            I5ValidationIssue::MissingItemNumbers { document, missing } => {
+                let missing: Vec<String> = missing.iter().map(i32::to_string).collect();
+                write!(
+                    f,
+                    "document {} is missing item numbers {}",
+                    document,
+                    missing.join(", ")
+                )
+            }
+            I5ValidationIssue::NegativeItemNumber {
+                document,
+                item_number,
+            } => write!(
+                f,
+                "document {} has the negative item number {}",
+                document, item_number
+            ),
+            I5ValidationIssue::InvalidProperty {
+                document,
+                field,
+                reason,
+            } => write!(f, "document {}: {} {}", document, field, reason),
+            I5ValidationIssue::InvalidFieldName {
+                document,
+                field,
+                reason,
+            } => write!(
+                f,
+                "document {}: the field name '{}' {}",
+                document, field, reason
+            ),
+            I5ValidationIssue::InvalidName {
+                document: None,
+                name,
+                reason,
+            } => write!(f, "the batch name '{}' {}", name, reason),
+            I5ValidationIssue::InvalidName {
+                document: Some(document),
+                name,
+                reason,
+            } => write!(f, "document {}: the name '{}' {}", document, name, reason),
+            I5ValidationIssue::UnresolvedReference {
+                document,
+                field,
+                value,
+            } => write!(
+                f,
+                "document {}: {} refers to '{}', which is not in the request",
+                document, field, value
+            ),
+            I5ValidationIssue::ValidatorFailed { document, reason } => {
+                write!(f, "document {}: {}", document, reason)
+            }
+            I5ValidationIssue::UnknownField { document, field } => {
+                write!(f, "document {} has the unknown field {}", document, field)
+            }
+            I5ValidationIssue::MissingField { document, field } => {
+                write!(f, "document {} is missing the field {}", document, field)
+            }
+            I5ValidationIssue::ValueTooLong {
+                document,
+                field,
+                max_length,
+            } => write!(
+                f,
+                "document {}: {} is longer than {} characters",
+                document, field, max_length
+            ),
+        }
+    }
+}
+
+/// The rules of a single validation run, see [`I5Request::validate_with`].
+///
+/// The default options check every rule listed at [`I5Request::is_valid`].
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{i5_request::I5Request, i5_validation::I5ValidationOptions};
+///
+/// // A placeholder whose fields and files arrive with a later batch.
+/// let mut request = I5Request::new("Batch");
+/// request.add_document("Invoice");
+///
+/// let options = I5ValidationOptions::new().allow_empty_documents(true);
+/// assert!(!request.validation_report().is_valid());
+/// assert!(request.validation_report_with(&options).is_valid());
+/// let validated = request.validate_with(&options)?;
+/// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+/// ```
+///
+/// [`I5Request::validate_with`]: crate::types::i5_request::I5Request::validate_with
+/// [`I5Request::is_valid`]: crate::types::i5_request::I5Request::is_valid
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5ValidationOptions {
+    allow_empty_documents: bool,
+    allow_item_gaps: bool,
+    reject_negative_items: bool,
+    max_documents: Option<usize>,
+    strict_names: bool,
+    references: Vec<(String, Option<String>)>,
+    audit_log: Option<AuditLogRef>,
+}
+
+/// The audit log of an [`I5ValidationOptions`], compared by identity.
+#[derive(Clone)]
+struct AuditLogRef(Arc<I5AuditLog>);
+
+impl PartialEq for AuditLogRef {
+    fn eq(&self, other: &AuditLogRef) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for AuditLogRef {}
+
+impl fmt::Debug for AuditLogRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("I5AuditLog").field(&self.0.path()).finish()
+    }
+}
+
+impl I5ValidationOptions {
+    /// Creates the default options, which check every rule.
+    pub fn new() -> I5ValidationOptions {
+        I5ValidationOptions::default()
+    }
+
+    /// Accepts documents without fields and files instead of reporting
+    /// [`I5ValidationIssue::EmptyDocument`]. The request still needs a document.
+    pub fn allow_empty_documents(mut self, allow: bool) -> Self {
+        self.allow_empty_documents = allow;
+        self
+    }
+
+    /// Whether documents without fields and files are accepted.
+    pub fn allows_empty_documents(&self) -> bool {
+        self.allow_empty_documents
+    }
+
+    /// The rules the given version of the Interface5 API enforces, so that a request
+    /// passing them is not rejected by the server for its structure:
+    ///
+    /// | Version              | Rules                                                |
+    /// |----------------------|------------------------------------------------------|
+    /// | [`I5ApiVersion::V1`] | the default rules, and item numbers are not negative |
+    /// | [`I5ApiVersion::V2`] | those of `v1`, as no differing contract is known yet |
+    ///
+    /// [`I5Client::validate`](crate::request::client::I5Client::validate) checks the
+    /// rules of the [configured version](crate::types::i5_config::I5Config::api_version).
+    /// Limits of a particular installation, such as the
+    /// [number of documents](I5ValidationOptions::max_documents), can be added to the
+    /// rule set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{
+    ///     i5_api_version::I5ApiVersion, i5_request::I5Request,
+    ///     i5_validation::I5ValidationOptions,
+    /// };
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request
+    ///     .get_document_mut(id)
+    ///     .unwrap()
+    ///     .add_item_field("Article", "A", 1)
+    ///     .add_item_field("Article", "B", -1);
+    ///
+    /// let v1 = I5ValidationOptions::for_version(I5ApiVersion::V1);
+    /// assert!(request.validation_report().is_valid());
+    /// assert_eq!(
+    ///     request.validation_report_with(&v1).to_string(),
+    ///     "document 0 has the negative item number -1"
+    /// );
+    /// ```
+    pub fn for_version(version: I5ApiVersion) -> I5ValidationOptions {
+        match version {
+            I5ApiVersion::V1 | I5ApiVersion::V2 => {
+                I5ValidationOptions::new().reject_negative_items(true)
+            }
+        }
+    }
+
+    /// Accepts gaps in the item numbers of a document instead of reporting
+    /// [`I5ValidationIssue::MissingItemNumbers`].
+    pub fn allow_item_gaps(mut self, allow: bool) -> Self {
+        self.allow_item_gaps = allow;
+        self
+    }
+
+    /// Whether gaps in the item numbers are accepted.
+    pub fn allows_item_gaps(&self) -> bool {
+        self.allow_item_gaps
+    }
+
+    /// Reports [`I5ValidationIssue::NegativeItemNumber`] for fields with a negative
+    /// item number.
+    pub fn reject_negative_items(mut self, reject: bool) -> Self {
+        self.reject_negative_items = reject;
+        self
+    }
+
+    /// Whether negative item numbers are rejected.
+    pub fn rejects_negative_items(&self) -> bool {
+        self.reject_negative_items
+    }
+
+    /// Reports [`I5ValidationIssue::TooManyDocuments`] for requests with more than
+    /// `limit` documents, or allows any number for `None`, the default.
+    pub fn max_documents(mut self, limit: Option<usize>) -> Self {
+        self.max_documents = limit;
+        self
+    }
+
+    /// The largest number of documents allowed, if limited.
+    pub fn document_limit(&self) -> Option<usize> {
+        self.max_documents
+    }
+
+    /// The strict profile: the default rules and
+    /// [strict names](I5ValidationOptions::strict_names).
+    pub fn strict() -> I5ValidationOptions {
+        I5ValidationOptions::new().strict_names(true)
+    }
+
+    /// Checks the names of the batch, the documents and the fields, which Interface5
+    /// otherwise only rejects when it processes the batch:
+    ///
+    /// - Field names consist of ASCII letters, digits and `_`, do not start with a
+    ///   digit and are at most [`MAX_STRICT_FIELD_NAME_LEN`] characters long.
+    /// - Batch and document names are not empty, at most [`MAX_STRICT_NAME_LEN`]
+    ///   characters long, have no leading or trailing whitespace and contain neither
+    ///   control characters nor any of `\ / : * ? " < > |`.
+    ///
+    /// Every offending name is reported once per document, as
+    /// [`I5ValidationIssue::InvalidFieldName`] or [`I5ValidationIssue::InvalidName`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{
+    ///     i5_request::I5Request,
+    ///     i5_validation::{I5ValidationIssue, I5ValidationOptions},
+    /// };
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request.get_document_mut(id).unwrap().add_header_field("1st Reminder", "yes");
+    ///
+    /// let report = request.validation_report_with(&I5ValidationOptions::strict());
+    /// assert_eq!(
+    ///     report.to_string(),
+    ///     "document 0: the field name '1st Reminder' starts with a digit"
+    /// );
+    /// assert!(matches!(report.issues()[0], I5ValidationIssue::InvalidFieldName { .. }));
+    /// ```
+    pub fn strict_names(mut self, strict: bool) -> Self {
+        self.strict_names = strict;
+        self
+    }
+
+    /// Whether the names are checked strictly.
+    pub fn checks_strict_names(&self) -> bool {
+        self.strict_names
+    }
+
+    /// Checks that the value of every field named `field` matches the header field
+    /// `key_field` of a document in the same request, e.g. that the appendix keys
+    /// listed by a cover sheet belong to documents of the batch. Unmatched values are
+    /// reported as [`I5ValidationIssue::UnresolvedReference`]; empty values are
+    /// ignored.
+    ///
+    /// Several references can be checked. A document may list several references in
+    /// item fields.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::{i5_request::I5Request, i5_validation::I5ValidationOptions};
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let cover = request.add_document("CoverSheet");
+    /// request
+    ///     .get_document_mut(cover)
+    ///     .unwrap()
+    ///     .add_item_field("Appendix", "A-1", 1)
+    ///     .add_item_field("Appendix", "A-2", 2);
+    /// let appendix = request.add_document("Appendix");
+    /// request.get_document_mut(appendix).unwrap().add_header_field("AppendixKey", "A-1");
+    ///
+    /// let options = I5ValidationOptions::new().reference_by_key("Appendix", "AppendixKey");
+    /// assert_eq!(
+    ///     request.validation_report_with(&options).to_string(),
+    ///     "document 0: Appendix refers to 'A-2', which is not in the request"
+    /// );
+    /// ```
+    pub fn reference_by_key(
+        mut self,
+        field: impl Into<String>,
+        key_field: impl Into<String>,
+    ) -> Self {
+        self.references.push((field.into(), Some(key_field.into())));
+        self
+    }
+
+    /// Checks that the value of every field named `field` is the name of a document in
+    /// the same request, see [`I5ValidationOptions::reference_by_key`].
+    pub fn reference_by_name(mut self, field: impl Into<String>) -> Self {
+        self.references.push((field.into(), None));
+        self
+    }
+
+    /// The checked references: the referencing field and the header field holding the
+    /// keys, or `None` for document names.
+    pub fn references(&self) -> &[(String, Option<String>)] {
+        &self.references
+    }
+
+    /// Records every validation with these options in `audit_log`, and the staging
+    /// and removal of the validated request with [`ValidatedI5Request::save`] and
+    /// [`ValidatedI5Request::remove_staged`].
+    ///
+    /// [`ValidatedI5Request::save`]: crate::types::i5_request::ValidatedI5Request::save
+    /// [`ValidatedI5Request::remove_staged`]: crate::types::i5_request::ValidatedI5Request::remove_staged
+    pub fn audit_log(mut self, audit_log: Arc<I5AuditLog>) -> Self {
+        self.audit_log = Some(AuditLogRef(audit_log));
+        self
+    }
+
+    /// Appends `operation` on the request `name` to the audit log, if configured.
+    ///
+    /// Audit failures are reported as tracing errors and do not change the result of
+    /// the operation.
+    pub(crate) fn audit(
+        &self,
+        operation: I5AuditOperation,
+        name: &str,
+        outcome: Result<(), &I5RequestError>,
+    ) {
+        if let Some(AuditLogRef(audit_log)) = &self.audit_log
+            && let Err(err) = audit_log.record(operation, name, outcome)
+        {
+            i5_event!(error, "failed to write audit log entry", error = err);
+        }
+    }
+}
+
+/// Which rule of [`I5ValidationOptions::strict_names`] a name breaks, reported by
+/// [`I5ValidationIssue::InvalidFieldName`] and [`I5ValidationIssue::InvalidName`].
+///
+/// The [`Display`](fmt::Display) text completes a sentence about the name, e.g.
+/// `starts with a digit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum I5NameProblem {
+    /// The name is empty.
+    Empty,
+    /// The field name starts with a digit.
+    LeadingDigit,
+    /// The batch or document name starts or ends with whitespace.
+    SurroundingWhitespace,
+    /// The name contains a character that is not allowed.
+    InvalidCharacter(char),
+    /// The name is longer than allowed.
+    TooLong {
+        /// The number of characters of the name.
+        length: usize,
+        /// The most characters allowed.
+        max: usize,
+    },
+}
+
+impl fmt::Display for I5NameProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            I5NameProblem::Empty => write!(f, "is empty"),
+            I5NameProblem::LeadingDigit => write!(f, "starts with a digit"),
+            I5NameProblem::SurroundingWhitespace => {
+                write!(f, "has leading or trailing whitespace")
+            }
+            I5NameProblem::InvalidCharacter(c) => write!(f, "contains '{}'", c.escape_default()),
+            I5NameProblem::TooLong { length, max } => write!(
+                f,
+                "is {} characters long, at most {} are allowed",
+                length, max
+            ),
+        }
+    }
+}
+
+/// What is wrong with a document property, reported by
+/// [`I5ValidationIssue::InvalidProperty`].
+///
+/// The [`Display`](fmt::Display) text completes a sentence about the property, e.g.
+/// `is blank`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum I5PropertyProblem {
+    /// The property is set more than once.
+    Repeated {
+        /// How often it is set.
+        count: usize,
+    },
+    /// The property is empty or only whitespace.
+    Blank,
+    /// The property must be a number, such as the priority, but is not.
+    NotANumber {
+        /// The value of the property.
+        value: String,
+    },
+}
+
+impl fmt::Display for I5PropertyProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            I5PropertyProblem::Repeated { count } => write!(f, "is set {} times", count),
+            I5PropertyProblem::Blank => write!(f, "is blank"),
+            I5PropertyProblem::NotANumber { value } => write!(f, "'{}' is not a number", value),
+        }
+    }
+}
+
+/// Which rule of [`I5ValidationOptions::strict_names`] the field name `name` breaks.
+pub(crate) fn field_name_problem(name: &str) -> Option<I5NameProblem> {
+    let length = name.chars().count();
+    if name.is_empty() {
+        Some(I5NameProblem::Empty)
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        Some(I5NameProblem::LeadingDigit)
+    } else if let Some(c) = name
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && *c != '_')
+    {
+        Some(I5NameProblem::InvalidCharacter(c))
+    } else if length > MAX_STRICT_FIELD_NAME_LEN {
+        Some(I5NameProblem::TooLong {
+            length,
+            max: MAX_STRICT_FIELD_NAME_LEN,
+        })
+    } else {
+        None
+    }
+}
+
+/// Which rule of [`I5ValidationOptions::strict_names`] the batch or document name
+/// `name` breaks.
+pub(crate) fn name_problem(name: &str) -> Option<I5NameProblem> {
+    let length = name.chars().count();
+    if name.is_empty() {
+        Some(I5NameProblem::Empty)
+    } else if name.trim() != name {
+        Some(I5NameProblem::SurroundingWhitespace)
+    } else if let Some(c) = name
+        .chars()
+        .find(|c| c.is_control() || "\\/:*?\"<>|".contains(*c))
+    {
+        Some(I5NameProblem::InvalidCharacter(c))
+    } else if length > MAX_STRICT_NAME_LEN {
+        Some(I5NameProblem::TooLong {
+            length,
+            max: MAX_STRICT_NAME_LEN,
+        })
+    } else {
+        None
+    }
+}
+
+/// The result of [`I5Request::validation_report`].
+///
+/// # Example
+///
+/// ```rust
+/// use i5_req::types::{i5_request::I5Request, i5_validation::I5ValidationIssue};
+///
+/// let mut request = I5Request::new("Batch");
+/// let id = request.add_document("Invoice");
+/// request
+///     .get_document_mut(id)
+///     .unwrap()
+///     .add_item_field("Article", "A", 1)
+///     .add_item_field("Article", "C", 3);
+///
+/// let report = request.validation_report();
+/// assert!(!report.is_valid());
+/// assert_eq!(
+///     report.issues(),
+///     [I5ValidationIssue::MissingItemNumbers { document: 0, missing: vec![2] }]
+/// );
+///
+/// // The request is still there and can be repaired.
+/// request.get_document_mut(id).unwrap().renumber_items();
+/// assert!(request.validation_report().is_valid());
+/// ```
+///
+/// [`I5Request::validation_report`]: crate::types::i5_request::I5Request::validation_report
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct I5ValidationReport {
+    issues: Vec<I5ValidationIssue>,
+}
+
+impl I5ValidationReport {
+    pub(crate) fn push(&mut self, issue: I5ValidationIssue) {
+        self.issues.push(issue);
+    }
+
+    /// All issues, in document order.
+    pub fn issues(&self) -> &[I5ValidationIssue] {
+        &self.issues
+    }
+
+    /// Returns `true` if no issue was found.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The messages of all issues from `catalog`, separated by `; ` like the
+    /// [`Display`](fmt::Display) text, see [`I5ValidationIssue::localized`].
+    #[cfg(feature = "l10n")]
+    pub fn localized(&self, catalog: &dyn I5MessageCatalog) -> String {
+        let messages: Vec<String> = self
+            .issues
+            .iter()
+            .map(|issue| issue.localized(catalog))
+            .collect();
+        messages.join("; ")
+    }
+}
+
+impl fmt::Display for I5ValidationReport {
+    /// Lists the issues separated by `; `.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, issue) in self.issues.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,424 @@
+//! Batch definitions in YAML.
+//!
+//! A YAML batch definition describes a request the way a functional consultant writes
+//! it by hand: header fields as a mapping, item rows as a list of mappings and files
+//! as paths, without base64 or item numbers.
+//!
+//! ```yaml
+//! name: Test batch 1            # optional, defaults to the name of the YAML file
+//! documents:
+//!   - name: Invoice
+//!     fields:
+//!       InvoiceNo: 0042         # every value is read as text
+//!       Vendor: "Smith & Sons"
+//!     items:                    # item 1, item 2, ...
+//!       - Article: A-1
+//!         Quantity: 2
+//!       - Article: A-2
+//!     files:
+//!       - scans/invoice.pdf     # a path, relative to the YAML file
+//!       - path: scans/invoice.xml
+//!         name: Invoice.xml     # defaults to the file name of the path
+//!         key: XML
+//! ```
+//!
+//! Keys are matched ignoring case. Unknown keys are rejected, so a misspelt `feilds`
+//! is reported instead of silently dropping the fields.
+
+use bytes::Bytes;
+use serde_json::{Map, Value, json};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    types::{
+        i5_error::I5RequestError,
+        i5_request::{Document, I5Request},
+    },
+    util, yaml,
+};
+
+impl I5Request {
+    /// Reads the YAML batch definition `yaml`, resolving relative file paths against
+    /// `base`. The definition must name the batch.
+    ///
+    /// Returns [`I5RequestError::ConversionError`] naming the line of a syntax error or
+    /// the path of an invalid entry, and [`I5RequestError::IoError`] if a file cannot
+    /// be read. The request is not validated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let request = I5Request::from_yaml_str(
+    ///     "
+    /// name: Test batch
+    /// documents:
+    ///   - name: Invoice
+    ///     fields:
+    ///       InvoiceNo: 0042
+    ///     items:
+    ///       - Article: A-1
+    ///       - Article: A-2
+    /// ",
+    ///     ".",
+    /// )?;
+    /// let fields = request.documents()[0].fields();
+    /// assert_eq!((fields[0].name(), fields[0].value()), ("InvoiceNo", "0042"));
+    /// assert_eq!((fields[2].value(), fields[2].item_number()), ("A-2", 2));
+    ///
+    /// let err = I5Request::from_yaml_str("name: Batch\ndocuments:\n  - feilds: {}\n", ".")
+    ///     .unwrap_err();
+    /// assert_eq!(err.to_string(), "Invalid request data: documents[0]: unknown key 'feilds'");
+    /// # Ok::<(), i5_req::types::i5_error::I5RequestError>(())
+    /// ```
+    pub fn from_yaml_str(yaml: &str, base: impl AsRef<Path>) -> Result<I5Request, I5RequestError> {
+        read(yaml, base.as_ref(), None)
+    }
+
+    /// Reads the YAML batch definition at `path`, resolving relative file paths against
+    /// its directory. A definition without a name is named after the file without its
+    /// extension.
+    ///
+    /// Returns the errors of [`I5Request::from_yaml_str`], and
+    /// [`I5RequestError::IoError`] if the definition cannot be read.
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> Result<I5Request, I5RequestError> {
+        let path = path.as_ref();
+        let yaml = fs::read_to_string(path).map_err(I5RequestError::IoError)?;
+        read(
+            &yaml,
+            path.parent().unwrap_or(Path::new(".")),
+            Some(&file_stem(path)),
+        )
+    }
+
+    /// Writes the request as a YAML batch definition to `path`, and the content of its
+    /// files into the folder `{name}.files` next to it, where `{name}` is the file name
+    /// of `path` without its extension. Existing files are overwritten. Reading the
+    /// definition back yields the header fields of every document before its item
+    /// fields.
+    ///
+    /// Returns [`I5RequestError::ConversionError`] if a document has two header
+    /// fields, or an item two fields, with the same name, which a YAML mapping cannot
+    /// hold, a field has a negative item number, or a file holds invalid base64 data. Returns [`I5RequestError::IoError`]
+    /// if a file cannot be written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use i5_req::types::i5_request::I5Request;
+    ///
+    /// let directory = std::env::temp_dir().join(format!("i5-doc-yaml-{}", std::process::id()));
+    /// std::fs::create_dir_all(&directory)?;
+    ///
+    /// let mut request = I5Request::new("Batch");
+    /// let id = request.add_document("Invoice");
+    /// request
+    ///     .get_document_mut(id)
+    ///     .unwrap()
+    ///     .add_header_field("InvoiceNo", "4711")
+    ///     .add_item_field("Article", "A-1", 1)
+    ///     .add_bytes_file("scan.pdf", b"%PDF-1.7");
+    ///
+    /// let path = directory.join("batch.yaml");
+    /// request.save_yaml(&path)?;
+    /// assert_eq!(I5Request::from_yaml_file(&path)?, request);
+    /// # std::fs::remove_dir_all(&directory)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn save_yaml(&self, path: impl AsRef<Path>) -> Result<(), I5RequestError> {
+        let path = path.as_ref();
+        let folder = format!("{}.files", file_stem(path));
+        let base = path.parent().unwrap_or(Path::new("."));
+
+        let mut documents = Vec::new();
+        for (document_index, document) in self.documents().iter().enumerate() {
+            let context = format!("documents[{}]", document_index);
+            let mut definition = Map::new();
+            definition.insert(String::from("name"), json!(document.name()));
+
+            let mut fields = Map::new();
+            let mut items: Vec<Map<String, Value>> = Vec::new();
+            for field in document.fields() {
+                let item_number = usize::try_from(field.item_number()).map_err(|_| {
+                    I5RequestError::ConversionError(format!(
+                        "{}.items: the field '{}' has the negative item number {}",
+                        context,
+                        field.name(),
+                        field.item_number()
+                    ))
+                })?;
+                let (row, context) = if item_number == 0 {
+                    (&mut fields, format!("{}.fields", context))
+                } else {
+                    if items.len() < item_number {
+                        items.resize_with(item_number, Map::new);
+                    }
+                    let context = format!("{}.items[{}]", context, item_number - 1);
+                    (&mut items[item_number - 1], context)
+                };
+                if row
+                    .insert(field.name().to_string(), json!(field.value()))
+                    .is_some()
+                {
+                    return Err(I5RequestError::ConversionError(format!(
+                        "{}: the field '{}' occurs more than once",
+                        context,
+                        field.name()
+                    )));
+                }
+            }
+            if !fields.is_empty() {
+                definition.insert(String::from("fields"), Value::Object(fields));
+            }
+            if !items.is_empty() {
+                definition.insert(
+                    String::from("items"),
+                    Value::Array(items.into_iter().map(Value::Object).collect()),
+                );
+            }
+
+            let mut files = Vec::new();
+            for (file_index, file) in document.files().iter().enumerate() {
+                let relative = format!(
+                    "{}/{:04}/{:04}_{}",
+                    folder,
+                    document_index + 1,
+                    file_index + 1,
+                    util::safe_file_name(file.name())
+                );
+                let content = file.data().decoded().map_err(|err| {
+                    I5RequestError::ConversionError(format!(
+                        "{}.files[{}]: invalid base64: {}",
+                        context, file_index, err
+                    ))
+                })?;
+                let target = base.join(&relative);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).map_err(I5RequestError::IoError)?;
+                }
+                fs::write(&target, content).map_err(I5RequestError::IoError)?;
+
+                let mut entry = Map::new();
+                entry.insert(String::from("path"), json!(relative));
+                entry.insert(String::from("name"), json!(file.name()));
+                if let Some(key) = file.key() {
+                    entry.insert(String::from("key"), json!(key));
+                }
+                files.push(Value::Object(entry));
+            }
+            if !files.is_empty() {
+                definition.insert(String::from("files"), Value::Array(files));
+            }
+            documents.push(Value::Object(definition));
+        }
+
+        let definition = json!({ "name": self.name(), "documents": documents });
+        fs::write(path, yaml::write(&definition)).map_err(I5RequestError::IoError)
+    }
+}
+
+/// Reads a batch definition, naming it `default_name` if it has no name.
+fn read(yaml: &str, base: &Path, default_name: Option<&str>) -> Result<I5Request, I5RequestError> {
+    let value = yaml::parse(yaml).map_err(|e| I5RequestError::ConversionError(e.to_string()))?;
+    let batch = match &value {
+        Value::Null => &Map::new(),
+        value => object(value, "the batch definition")?,
+    };
+    check_keys(batch, "the batch definition", &["name", "documents"])?;
+
+    let name = match (get(batch, "name"), default_name) {
+        (Some(name), _) => text(name, "name")?,
+        (None, Some(name)) => name.to_string(),
+        (None, None) => {
+            return Err(I5RequestError::ConversionError(String::from(
+                "name: missing",
+            )));
+        }
+    };
+    let mut request = I5Request::new(name);
+
+    for (index, source) in list(batch, "", "documents")?.iter().enumerate() {
+        let context = format!("documents[{}]", index);
+        let source = object(source, &context)?;
+        check_keys(source, &context, &["name", "fields", "items", "files"])?;
+        let name = get(source, "name")
+            .ok_or_else(|| I5RequestError::ConversionError(format!("{}.name: missing", context)))
+            .and_then(|name| text(name, &format!("{}.name", context)))?;
+        let id = request.add_document(name);
+        let document = request
+            .get_document_mut(id)
+            .expect("document was just added");
+
+        if let Some(fields) = get(source, "fields") {
+            read_fields(document, fields, &format!("{}.fields", context), 0)?;
+        }
+        for (item, fields) in list(source, &context, "items")?.iter().enumerate() {
+            let item_number = i32::try_from(item + 1).map_err(|_| {
+                I5RequestError::ConversionError(format!("{}.items: too many items", context))
+            })?;
+            let context = format!("{}.items[{}]", context, item);
+            read_fields(document, fields, &context, item_number)?;
+        }
+        for (file, entry) in list(source, &context, "files")?.iter().enumerate() {
+            read_file(
+                document,
+                entry,
+                base,
+                &format!("{}.files[{}]", context, file),
+            )?;
+        }
+    }
+    Ok(request)
+}
+
+fn read_fields(
+    document: &mut Document,
+    fields: &Value,
+    context: &str,
+    item_number: i32,
+) -> Result<(), I5RequestError> {
+    if fields.is_null() {
+        return Ok(());
+    }
+    for (name, value) in object(fields, context)? {
+        let value = match value {
+            Value::Null => String::new(),
+            value => text(value, &format!("{}.{}", context, name))?,
+        };
+        document.add_item_field(name.as_str(), value, item_number);
+    }
+    Ok(())
+}
+
+/// Adds the file `entry`, a path or a mapping with a `path`, a `name` and a `key`.
+fn read_file(
+    document: &mut Document,
+    entry: &Value,
+    base: &Path,
+    context: &str,
+) -> Result<(), I5RequestError> {
+    let (path, name, key) = match entry {
+        Value::String(path) => (path.clone(), None, None),
+        entry => {
+            let entry = object(entry, context)?;
+            check_keys(entry, context, &["path", "name", "key"])?;
+            let optional = |key: &str| {
+                get(entry, key)
+                    .map(|value| text(value, &format!("{}.{}", context, key)))
+                    .transpose()
+            };
+            let path = optional("path")?.ok_or_else(|| {
+                I5RequestError::ConversionError(format!("{}.path: missing", context))
+            })?;
+            (path, optional("name")?, optional("key")?)
+        }
+    };
+    let path: PathBuf = base.join(path.trim());
+    let content = fs::read(&path).map_err(I5RequestError::IoError)?;
+    let name = name.unwrap_or_else(|| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    document.add_keyed_file(name, key, Bytes::from(content));
+    Ok(())
+}
+
+/// Looks up `key` ignoring case.
+fn get<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    object
+        .iter()
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value)
+}
+
+/// Rejects keys of `object` other than `known`.
+fn check_keys(
+    object: &Map<String, Value>,
+    context: &str,
+    known: &[&str],
+) -> Result<(), I5RequestError> {
+    match object
+        .keys()
+        .find(|key| !known.iter().any(|known| key.eq_ignore_ascii_case(known)))
+    {
+        Some(key) => Err(I5RequestError::ConversionError(format!(
+            "{}: unknown key '{}'",
+            context, key
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn object<'a>(value: &'a Value, context: &str) -> Result<&'a Map<String, Value>, I5RequestError> {
+    value
+        .as_object()
+        .ok_or_else(|| I5RequestError::ConversionError(format!("{}: expected a mapping", context)))
+}
+
+/// The list under `key`, which may be missing or empty.
+fn list<'a>(
+    object: &'a Map<String, Value>,
+    context: &str,
+    key: &str,
+) -> Result<&'a [Value], I5RequestError> {
+    match get(object, key) {
+        None | Some(Value::Null) => Ok(&[]),
+        Some(Value::Array(values)) => Ok(values),
+        Some(_) => Err(I5RequestError::ConversionError(format!(
+            "{}: expected a list",
+            if context.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", context, key)
+            }
+        ))),
+    }
+}
+
+fn text(value: &Value, context: &str) -> Result<String, I5RequestError> {
+    match value {
+        Value::String(text) => Ok(text.clone()),
+        _ => Err(I5RequestError::ConversionError(format!(
+            "{}: expected a single value",
+            context
+        ))),
+    }
+}
+
+/// The file name of `path` without its extension.
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_yaml_rejects_negative_item_numbers() {
+        let mut request = I5Request::new("Batch");
+        let id = request.add_document("Invoice");
+        request
+            .get_document_mut(id)
+            .unwrap()
+            .add_item_field("Article", "A-1", -1);
+
+        let path = std::env::temp_dir().join(format!("i5-yaml-{}.yaml", std::process::id()));
+        let err = request.save_yaml(&path).unwrap_err();
+        assert!(!path.exists());
+        match err {
+            I5RequestError::ConversionError(message) => assert_eq!(
+                message,
+                "documents[0].items: the field 'Article' has the negative item number -1"
+            ),
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+}
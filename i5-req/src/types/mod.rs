@@ -1,3 +1,45 @@
+pub mod i5_amount;
+pub mod i5_api_version;
+pub mod i5_audit;
+pub mod i5_auth;
+pub mod i5_backoff;
+pub mod i5_barcode;
+pub mod i5_batch_name;
+pub mod i5_batch_set;
+pub mod i5_bool_format;
+pub mod i5_config;
+pub mod i5_content_format;
+pub mod i5_date;
+pub mod i5_diff;
+pub mod i5_discovery;
 pub mod i5_error;
+pub mod i5_field_definition;
+pub mod i5_field_mapping;
+pub mod i5_field_options;
+pub mod i5_field_rules;
+pub mod i5_field_value;
+pub mod i5_import;
+pub mod i5_ip_family;
+#[cfg(feature = "keyring")]
+pub mod i5_keyring;
+#[cfg(feature = "l10n")]
+pub mod i5_locale;
+#[cfg(feature = "mmap")]
+pub mod i5_mapped_file;
+pub mod i5_receipt;
+pub mod i5_redaction;
 pub mod i5_request;
 pub mod i5_request_url;
+pub mod i5_retry;
+pub mod i5_sanitizer;
+pub mod i5_secret;
+pub mod i5_server_error;
+pub mod i5_summary;
+pub mod i5_template;
+pub mod i5_tls_backend;
+pub mod i5_tls_version;
+pub mod i5_token_endpoint;
+pub mod i5_transform;
+pub mod i5_transformer;
+pub mod i5_validation;
+pub mod i5_yaml;
@@ -0,0 +1,328 @@
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Fills a buffer of `N` bytes from the operating system random source.
+///
+/// Falls back to a time and counter based value if the random source is unavailable,
+/// so the result is always unique within the process.
+pub(crate) fn random_bytes<const N: usize>() -> [u8; N] {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut bytes = [0u8; N];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let seed = (nanos as u64) ^ count.rotate_left(32) ^ std::process::id() as u64;
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = (seed.rotate_left((index % 8) as u32 * 8) >> 56) as u8 ^ index as u8;
+        }
+    }
+    bytes
+}
+
+/// Percent-encodes `text` for a URL, keeping only the unreserved characters of
+/// RFC 3986.
+pub(crate) fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(char::from(byte))
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// `url` with the value of every query parameter replaced by `<redacted>`, for URLs
+/// recorded in logs and traces, as installations may pass API keys in the query.
+pub(crate) fn redact_query(url: &str) -> Cow<'_, str> {
+    let Some((base, query)) = url.split_once('?') else {
+        return Cow::Borrowed(url);
+    };
+    let params: Vec<Cow<'_, str>> = query
+        .split('&')
+        .map(|param| match param.split_once('=') {
+            Some((name, _)) => Cow::Owned(format!("{}=<redacted>", name)),
+            None => Cow::Borrowed(param),
+        })
+        .collect();
+    Cow::Owned(format!("{}?{}", base, params.join("&")))
+}
+
+/// Encodes bytes as lowercase hexadecimal.
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Generates a random RFC 4122 version 4 UUID in its hyphenated string form.
+pub(crate) fn uuid_v4() -> String {
+    let mut bytes = random_bytes::<16>();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = hex(&bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Matches `text` against a simple wildcard `pattern`, ignoring ASCII case.
+///
+/// `*` matches any sequence of characters (including none), `?` matches exactly one
+/// character. All other characters match themselves.
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let text: Vec<char> = text.chars().map(|c| c.to_ascii_lowercase()).collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Converts days since the Unix epoch into a proleptic Gregorian `(year, month, day)`.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Converts a proleptic Gregorian date into days since the Unix epoch.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// A point in time broken down into its UTC calendar date and time of day.
+pub(crate) struct UtcDateTime {
+    pub(crate) unix_seconds: i64,
+    pub(crate) year: i64,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+    pub(crate) millisecond: u32,
+}
+
+impl UtcDateTime {
+    pub(crate) fn new(time: SystemTime) -> UtcDateTime {
+        let (unix_seconds, millisecond) = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => (duration.as_secs() as i64, duration.subsec_millis()),
+            Err(err) => {
+                let duration = err.duration();
+                let mut seconds = -(duration.as_secs() as i64);
+                let mut millis = duration.subsec_millis();
+                if millis > 0 {
+                    seconds -= 1;
+                    millis = 1000 - millis;
+                }
+                (seconds, millis)
+            }
+        };
+
+        let (year, month, day) = civil_from_days(unix_seconds.div_euclid(86_400));
+        let second_of_day = unix_seconds.rem_euclid(86_400) as u32;
+        UtcDateTime {
+            unix_seconds,
+            year,
+            month,
+            day,
+            hour: second_of_day / 3600,
+            minute: second_of_day % 3600 / 60,
+            second: second_of_day % 60,
+            millisecond,
+        }
+    }
+}
+
+/// Parses the value of a `Retry-After` header, either a number of seconds or an
+/// IMF-fixdate such as `Wed, 21 Oct 2015 07:28:00 GMT`, into the wait from now. A
+/// date in the past is no wait at all.
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let (_, date) = value.split_once(", ")?;
+    let parts: Vec<&str> = date.split(' ').collect();
+    let [day, month, year, time, "GMT"] = parts[..] else {
+        return None;
+    };
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]
+    .iter()
+    .position(|name| *name == month)? as u32
+        + 1;
+    let mut clock = time.split(':').map(|part| part.parse::<i64>().ok());
+    let (Some(Some(hour)), Some(Some(minute)), Some(Some(second)), None) =
+        (clock.next(), clock.next(), clock.next(), clock.next())
+    else {
+        return None;
+    };
+    let at = days_from_civil(year.parse().ok()?, month, day.parse().ok()?) * 86_400
+        + hour * 3600
+        + minute * 60
+        + second;
+    let now = UtcDateTime::new(SystemTime::now()).unix_seconds;
+    Some(Duration::from_secs(
+        u64::try_from(at - now).unwrap_or_default(),
+    ))
+}
+
+/// Formats a point in time as an RFC 3339 UTC timestamp with millisecond precision,
+/// e.g. `2025-06-27T08:15:00.123Z`.
+pub(crate) fn format_rfc3339(time: SystemTime) -> String {
+    let utc = UtcDateTime::new(time);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        utc.year, utc.month, utc.day, utc.hour, utc.minute, utc.second, utc.millisecond
+    )
+}
+
+/// Formats a point in time in UTC using a `strftime`-style `format`.
+///
+/// Supports `%Y`, `%y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%f` (milliseconds), `%s` (Unix
+/// seconds) and `%%`. Returns the unsupported specifier on error.
+pub(crate) fn format_strftime(time: SystemTime, format: &str) -> Result<String, String> {
+    let utc = UtcDateTime::new(time);
+    let mut output = String::with_capacity(format.len() + 8);
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => output.push_str(&format!("{:04}", utc.year)),
+            Some('y') => output.push_str(&format!("{:02}", utc.year.rem_euclid(100))),
+            Some('m') => output.push_str(&format!("{:02}", utc.month)),
+            Some('d') => output.push_str(&format!("{:02}", utc.day)),
+            Some('H') => output.push_str(&format!("{:02}", utc.hour)),
+            Some('M') => output.push_str(&format!("{:02}", utc.minute)),
+            Some('S') => output.push_str(&format!("{:02}", utc.second)),
+            Some('f') => output.push_str(&format!("{:03}", utc.millisecond)),
+            Some('s') => output.push_str(&utc.unix_seconds.to_string()),
+            Some('%') => output.push('%'),
+            Some(other) => return Err(format!("%{}", other)),
+            None => return Err(String::from("%")),
+        }
+    }
+    Ok(output)
+}
+
+/// Computes the lowercase hexadecimal SHA-256 digest of `data`.
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    hex(ring::digest::digest(&ring::digest::SHA256, data).as_ref())
+}
+
+/// `name` as a file name: path separators, characters Windows rejects and control
+/// characters are replaced by `_`, and names made only of dots become `_`.
+pub(crate) fn safe_file_name(name: &str) -> Cow<'_, str> {
+    let unsafe_char = |c: char| {
+        c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+    };
+    if name.trim_matches('.').is_empty() {
+        return Cow::Borrowed("_");
+    }
+    if !name.contains(unsafe_char) {
+        return Cow::Borrowed(name);
+    }
+    Cow::Owned(
+        name.chars()
+            .map(|c| if unsafe_char(c) { '_' } else { c })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_query_masks_values_and_keeps_names() {
+        assert_eq!(
+            redact_query("https://i5:43001/api/v1/Input/T/S/Batches"),
+            "https://i5:43001/api/v1/Input/T/S/Batches"
+        );
+        assert_eq!(
+            redact_query("https://i5:43001/Batches?async=true&api_key=s%3Dcret&flag&empty="),
+            "https://i5:43001/Batches?async=<redacted>&api_key=<redacted>&flag&empty=<redacted>"
+        );
+    }
+
+    #[test]
+    fn parse_retry_after_reads_seconds_and_http_dates() {
+        assert_eq!(parse_retry_after(" 120 "), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        let in_a_minute = UtcDateTime::new(SystemTime::now() + Duration::from_secs(60));
+        let months = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        let date = format!(
+            "Mon, {:02} {} {} {:02}:{:02}:{:02} GMT",
+            in_a_minute.day,
+            months[in_a_minute.month as usize - 1],
+            in_a_minute.year,
+            in_a_minute.hour,
+            in_a_minute.minute,
+            in_a_minute.second
+        );
+        let wait = parse_retry_after(&date).unwrap();
+        assert!((58..=60).contains(&wait.as_secs()), "{:?}", wait);
+        assert_eq!(parse_retry_after("-5"), None);
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28 GMT"), None);
+        assert_eq!(parse_retry_after("tomorrow"), None);
+    }
+}
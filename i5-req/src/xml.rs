@@ -0,0 +1,342 @@
+//! A small XML reader and writer.
+//!
+//! The reader supports the subset of XML written by capture systems: elements,
+//! attributes, comments, CDATA and the predefined and numeric entities. Text content
+//! is dropped, and declarations and processing instructions are skipped. The writer
+//! produces the XML representation of a request body.
+
+use base64::{Engine, engine::general_purpose};
+
+use crate::types::{
+    i5_error::I5RequestError,
+    i5_request::{FileData, I5Request},
+};
+
+/// An element with its attributes and child elements; text is dropped.
+#[derive(Debug, Default)]
+pub(crate) struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+}
+
+impl Element {
+    /// The value of the attribute `name`, matched ignoring case.
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The child elements named `name`, matched ignoring case.
+    pub(crate) fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children
+            .iter()
+            .filter(move |child| child.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The elements named `name` below this one in document order, without
+    /// descending into a match.
+    pub(crate) fn descendants<'a>(&'a self, name: &str) -> Vec<&'a Element> {
+        let mut found = Vec::new();
+        let mut stack: Vec<&Element> = self.children.iter().rev().collect();
+        while let Some(element) = stack.pop() {
+            if element.name.eq_ignore_ascii_case(name) {
+                found.push(element);
+            } else {
+                stack.extend(element.children.iter().rev());
+            }
+        }
+        found
+    }
+}
+
+fn error(offset: usize, message: &str) -> I5RequestError {
+    I5RequestError::ConversionError(format!("XML at byte {}: {}", offset, message))
+}
+
+/// Parses `text` and returns a synthetic element holding the root element.
+pub(crate) fn parse(text: &str) -> Result<Element, I5RequestError> {
+    let mut stack = vec![Element::default()];
+    let mut rest = text.trim_start_matches('\u{feff}');
+    let offset = |rest: &str| text.len() - rest.len();
+
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open..];
+        let skip = |rest: &str, end: &str| {
+            rest.find(end)
+                .map(|at| at + end.len())
+                .ok_or_else(|| error(offset(rest), "unterminated markup"))
+        };
+        if rest.starts_with("<!--") {
+            rest = &rest[skip(rest, "-->")?..];
+        } else if rest.starts_with("<![CDATA[") {
+            rest = &rest[skip(rest, "]]>")?..];
+        } else if rest.starts_with("<?") {
+            rest = &rest[skip(rest, "?>")?..];
+        } else if rest.starts_with("<!") {
+            rest = &rest[skip(rest, ">")?..];
+        } else if let Some(tag) = rest.strip_prefix("</") {
+            let end = tag
+                .find('>')
+                .ok_or_else(|| error(offset(rest), "unterminated tag"))?;
+            let name = tag[..end].trim();
+            let element = stack.pop().filter(|_| !stack.is_empty());
+            match element {
+                Some(element) if element.name == name => {
+                    stack.last_mut().expect("root").children.push(element)
+                }
+                _ => return Err(error(offset(rest), "mismatched closing tag")),
+            }
+            rest = &tag[end + 1..];
+        } else {
+            let (element, closed, consumed) =
+                start_tag(&rest[1..]).ok_or_else(|| error(offset(rest), "malformed start tag"))?;
+            rest = &rest[1 + consumed..];
+            if closed {
+                stack.last_mut().expect("root").children.push(element);
+            } else {
+                stack.push(element);
+            }
+        }
+    }
+    if stack.len() != 1 {
+        return Err(error(text.len(), "unclosed element"));
+    }
+    Ok(stack.pop().expect("root"))
+}
+
+/// Reads a start tag after its `<`: the element, whether it closes itself and the
+/// number of bytes consumed.
+fn start_tag(tag: &str) -> Option<(Element, bool, usize)> {
+    let name_end = tag.find(|c: char| c.is_whitespace() || c == '/' || c == '>')?;
+    let mut element = Element {
+        name: tag[..name_end].to_string(),
+        ..Element::default()
+    };
+    if element.name.is_empty() {
+        return None;
+    }
+    let mut rest = &tag[name_end..];
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Some((element, true, tag.len() - after.len()));
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Some((element, false, tag.len() - after.len()));
+        }
+        let equals = rest.find('=')?;
+        let key = rest[..equals].trim().to_string();
+        rest = rest[equals + 1..].trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let end = rest[1..].find(quote)? + 1;
+        element.attributes.push((key, unescape(&rest[1..end])?));
+        rest = &rest[end + 1..];
+    }
+}
+
+/// Replaces the predefined and numeric entities in `text`.
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        unescaped.push_str(&rest[..at]);
+        let end = rest[at..].find(';')? + at;
+        let entity = &rest[at + 1..end];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => entity.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        };
+        unescaped.push(c);
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+    Some(unescaped)
+}
+
+/// Writes the XML representation of `request`: a `Batch` element with one child
+/// element per property of the JSON body, named like the property. A file without a
+/// key has no `Key` element.
+///
+/// Returns [`I5RequestError::ContractError`] naming the path of a value with a
+/// character that XML 1.0 cannot represent, such as most control characters.
+pub(crate) fn write_request(request: &I5Request) -> Result<String, I5RequestError> {
+    let mut out = String::with_capacity(request.estimated_encoded_size());
+    out.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    out.push_str("<Batch>");
+    text_element(&mut out, "Name", request.name(), "")?;
+    out.push_str("<Documents>");
+    for (document_index, document) in request.documents().iter().enumerate() {
+        let path = format!("Documents[{}]", document_index);
+        out.push_str("<Document>");
+        text_element(&mut out, "Name", document.name(), &path)?;
+        out.push_str("<Fields>");
+        for (index, field) in document.fields().iter().enumerate() {
+            let path = format!("{}.Fields[{}]", path, index);
+            out.push_str("<Field>");
+            text_element(&mut out, "Name", field.name(), &path)?;
+            text_element(&mut out, "Value", field.value(), &path)?;
+            text_element(&mut out, "ItemNo", &field.item_number().to_string(), &path)?;
+            out.push_str("</Field>");
+        }
+        out.push_str("</Fields><Files>");
+        for (index, file) in document.files().iter().enumerate() {
+            let path = format!("{}.Files[{}]", path, index);
+            out.push_str("<File>");
+            text_element(&mut out, "Name", file.name(), &path)?;
+            if let Some(key) = file.key() {
+                text_element(&mut out, "Key", key, &path)?;
+            }
+            out.push_str("<Data>");
+            match file.data() {
+                FileData::Base64(data) => escape_into(&mut out, data, &path, "Data")?,
+                FileData::Bytes(bytes) => general_purpose::STANDARD.encode_string(bytes, &mut out),
+            }
+            out.push_str("</Data></File>");
+        }
+        out.push_str("</Files></Document>");
+    }
+    out.push_str("</Documents></Batch>");
+    Ok(out)
+}
+
+/// Writes `<{name}>{text}</{name}>` with `text` escaped.
+fn text_element(
+    out: &mut String,
+    name: &str,
+    text: &str,
+    path: &str,
+) -> Result<(), I5RequestError> {
+    out.push('<');
+    out.push_str(name);
+    out.push('>');
+    escape_into(out, text, path, name)?;
+    out.push_str("</");
+    out.push_str(name);
+    out.push('>');
+    Ok(())
+}
+
+/// Appends `text` with markup characters escaped. Carriage returns are written as
+/// character references, so parsers do not normalize them to line feeds.
+fn escape_into(out: &mut String, text: &str, path: &str, key: &str) -> Result<(), I5RequestError> {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\r' => out.push_str("&#13;"),
+            '\t' | '\n' => out.push(c),
+            c if c < ' ' || c == '\u{fffe}' || c == '\u{ffff}' => {
+                let location = if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                return Err(I5RequestError::ContractError(format!(
+                    "{}: contains U+{:04X}, which XML cannot represent",
+                    location, c as u32
+                )));
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(text: &str) -> String {
+        match parse(text) {
+            Err(I5RequestError::ConversionError(message)) => message,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reads_elements_attributes_and_entities() {
+        let text = "\u{feff}<?xml version=\"1.0\"?>\n\
+            <!DOCTYPE batch>\n\
+            <!-- exported <batch> -->\n\
+            <Batch Name=\"A &amp; B &#x41;&#66;\" id='4711'>\n\
+              <![CDATA[ <Document name=\"skipped\"/> ]]>\n\
+              <Document name=\"Invoice\"><Field name=\"&lt;No&gt;\" value='&quot;1&apos;'/></Document>\n\
+              <Group><Document name=\"Nested\"><Document name=\"Inner\"/></Document></Group>\n\
+            </Batch >";
+        let root = parse(text).unwrap();
+        let batch = root.children("batch").next().unwrap();
+        assert_eq!(batch.attribute("name"), Some("A & B AB"));
+        assert_eq!(batch.attribute("ID"), Some("4711"));
+        assert_eq!(batch.attribute("missing"), None);
+
+        let documents: Vec<_> = batch.children("Document").collect();
+        assert_eq!(documents.len(), 1);
+        let field = documents[0].children("Field").next().unwrap();
+        assert_eq!(field.attribute("name"), Some("<No>"));
+        assert_eq!(field.attribute("value"), Some("\"1'"));
+
+        let names: Vec<_> = batch
+            .descendants("document")
+            .iter()
+            .map(|document| document.attribute("name").unwrap())
+            .collect();
+        assert_eq!(names, ["Invoice", "Nested"]);
+    }
+
+    #[test]
+    fn reports_malformed_documents() {
+        assert_eq!(error("<a><b></a>"), "XML at byte 6: mismatched closing tag");
+        assert_eq!(error("</a>"), "XML at byte 0: mismatched closing tag");
+        assert_eq!(error("<a><b/>"), "XML at byte 7: unclosed element");
+        assert_eq!(error("<a><!-- open"), "XML at byte 3: unterminated markup");
+        assert_eq!(error("<a></a"), "XML at byte 3: unterminated tag");
+        assert_eq!(error("<a x=1/>"), "XML at byte 0: malformed start tag");
+        assert_eq!(error("< a/>"), "XML at byte 0: malformed start tag");
+        assert_eq!(
+            error("<a x=\"&bogus;\"/>"),
+            "XML at byte 0: malformed start tag"
+        );
+    }
+
+    #[test]
+    fn written_requests_escape_markup_and_reject_control_characters() {
+        let mut request = I5Request::new("A & <B>");
+        let id = request.add_document("Invoice");
+        request
+            .get_document_mut(id)
+            .unwrap()
+            .add_header_field("Note", "line\r\nbreak")
+            .add_bytes_file("scan.pdf", b"%PDF");
+        let xml = write_request(&request).unwrap();
+        assert!(xml.contains("<Name>A &amp; &lt;B&gt;</Name>"));
+        assert!(xml.contains("<Value>line&#13;\nbreak</Value>"));
+        assert!(xml.contains("<Name>scan.pdf</Name><Data>JVBERg==</Data>"));
+        assert!(parse(&xml).is_ok());
+
+        request
+            .get_document_mut(id)
+            .unwrap()
+            .add_item_field("Code", "a\u{1}b", 1);
+        match write_request(&request) {
+            Err(I5RequestError::ContractError(message)) => assert_eq!(
+                message,
+                "Documents[0].Fields[1].Value: contains U+0001, which XML cannot represent"
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
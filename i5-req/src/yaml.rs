@@ -0,0 +1,636 @@
+//! A small YAML reader and writer for batch definitions.
+//!
+//! Supports the subset of YAML used by hand-written batch files: block mappings and
+//! sequences, plain, single-quoted and double-quoted scalars, flow sequences of
+//! scalars, the empty flow collections `[]` and `{}`, comments and the `---` document
+//! marker. Anchors, tags, block scalars and multiple documents are not supported.
+//!
+//! Every scalar is read as a string, so values such as `0042` or `1.50` keep their
+//! exact text; the writer quotes strings that other YAML tools would read as numbers,
+//! booleans or null. Configuration files are read with [`parse_typed`] instead, which
+//! reads such plain scalars as numbers, booleans and null.
+
+use serde_json::{Map, Value};
+
+/// A YAML syntax error with the 1-based line and column where it was detected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct YamlError {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) message: String,
+}
+
+impl std::fmt::Display for YamlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// Parses a YAML document into JSON strings, arrays and objects. An empty document is
+/// `null`.
+pub(crate) fn parse(input: &str) -> Result<Value, YamlError> {
+    parse_with(input, false)
+}
+
+/// Parses a YAML document like [`parse`], but reads plain scalars such as `43001`,
+/// `1.5`, `true` or `~` as numbers, booleans and null. Quoted scalars stay strings.
+pub(crate) fn parse_typed(input: &str) -> Result<Value, YamlError> {
+    parse_with(input, true)
+}
+
+fn parse_with(input: &str, typed: bool) -> Result<Value, YamlError> {
+    let mut lines = Vec::new();
+    for (index, text) in input.trim_start_matches('\u{feff}').lines().enumerate() {
+        let number = index + 1;
+        let content = strip_comment(text);
+        let trimmed = content.trim_start_matches(' ');
+        if trimmed.starts_with('\t') {
+            return Err(YamlError {
+                line: number,
+                column: content.len() - trimmed.len() + 1,
+                message: String::from("tabs are not allowed in indentation"),
+            });
+        }
+        let trimmed = trimmed.trim_end();
+        if trimmed.is_empty() || trimmed == "---" || trimmed == "..." {
+            continue;
+        }
+        lines.push(Line {
+            number,
+            indent: content.len() - content.trim_start_matches(' ').len(),
+            text: trimmed.to_string(),
+        });
+    }
+
+    let mut parser = Parser {
+        lines,
+        position: 0,
+        typed,
+    };
+    let Some(first) = parser.lines.first() else {
+        return Ok(Value::Null);
+    };
+    let value = parser.node(first.indent)?;
+    match parser.lines.get(parser.position) {
+        Some(line) => Err(line.error(0, "unexpected indentation")),
+        None => Ok(value),
+    }
+}
+
+/// A non-empty line without its comment.
+struct Line {
+    number: usize,
+    indent: usize,
+    text: String,
+}
+
+impl Line {
+    fn error(&self, offset: usize, message: &str) -> YamlError {
+        YamlError {
+            line: self.number,
+            column: self.indent + offset + 1,
+            message: message.to_string(),
+        }
+    }
+}
+
+struct Parser {
+    lines: Vec<Line>,
+    position: usize,
+    /// Whether plain scalars are read as numbers, booleans and null.
+    typed: bool,
+}
+
+impl Parser {
+    /// Parses the node whose first line is the current line, indented by `indent`.
+    fn node(&mut self, indent: usize) -> Result<Value, YamlError> {
+        let line = &self.lines[self.position];
+        if is_sequence_entry(&line.text) {
+            self.sequence(indent)
+        } else if split_key(&line.text).is_some() {
+            self.mapping(indent)
+        } else {
+            let value = scalar(line, &line.text, 0, self.typed)?;
+            self.position += 1;
+            Ok(value)
+        }
+    }
+
+    fn sequence(&mut self, indent: usize) -> Result<Value, YamlError> {
+        let mut items = Vec::new();
+        while let Some(line) = self.lines.get(self.position) {
+            if line.indent != indent || !is_sequence_entry(&line.text) {
+                break;
+            }
+            let rest = line.text[1..].trim_start();
+            if rest.is_empty() {
+                self.position += 1;
+                items.push(self.nested(indent, false)?);
+            } else {
+                // Continue with the entry as if it started on a line of its own.
+                let (offset, rest) = (line.text.len() - rest.len(), rest.to_string());
+                let line = &mut self.lines[self.position];
+                line.indent += offset;
+                line.text = rest;
+                let indent = line.indent;
+                items.push(self.node(indent)?);
+            }
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn mapping(&mut self, indent: usize) -> Result<Value, YamlError> {
+        let mut map = Map::new();
+        while let Some(line) = self.lines.get(self.position) {
+            if line.indent != indent {
+                break;
+            }
+            let Some((key, value)) = split_key(&line.text) else {
+                return Err(line.error(0, "expected a key followed by ':'"));
+            };
+            let key = match scalar(line, key, 0, false)? {
+                Value::String(key) => key,
+                _ => return Err(line.error(0, "keys must be scalars")),
+            };
+            if map.contains_key(&key) {
+                return Err(line.error(0, &format!("duplicate key '{}'", key)));
+            }
+            let value = if value.trim().is_empty() {
+                self.position += 1;
+                self.nested(indent, true)?
+            } else {
+                let offset = line.text.len() - value.trim_start().len();
+                let value = scalar(line, value.trim(), offset, self.typed)?;
+                self.position += 1;
+                value
+            };
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+
+    /// Parses the value of a key or sequence entry that continues on the next line:
+    /// a more indented node, a sequence at the same indentation if `same_indent_sequence`
+    /// is set, or `null`.
+    fn nested(&mut self, indent: usize, same_indent_sequence: bool) -> Result<Value, YamlError> {
+        match self.lines.get(self.position) {
+            Some(next) if next.indent > indent => {
+                let indent = next.indent;
+                self.node(indent)
+            }
+            Some(next)
+                if same_indent_sequence
+                    && next.indent == indent
+                    && is_sequence_entry(&next.text) =>
+            {
+                self.sequence(indent)
+            }
+            _ => Ok(Value::Null),
+        }
+    }
+}
+
+fn is_sequence_entry(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Splits `text` at the `:` ending a mapping key, outside quotes.
+fn split_key(text: &str) -> Option<(&str, &str)> {
+    if text.starts_with('[') || text.starts_with('{') {
+        return None;
+    }
+    let mut quote = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((at, c)) = chars.next() {
+        match (quote, c) {
+            (None, '"' | '\'') if at == 0 => quote = Some(c),
+            (Some('"'), '\\') => {
+                chars.next();
+            }
+            (Some(open), c) if c == open => quote = None,
+            (None, ':') if chars.peek().is_none_or(|(_, next)| *next == ' ') => {
+                return Some((&text[..at], &text[at + 1..]));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `text` without a comment: a `#` at the start or after whitespace, outside quotes.
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    let mut chars = text.char_indices();
+    while let Some((at, c)) = chars.next() {
+        match (quote, c) {
+            (None, '"' | '\'') if matches!(previous, ' ' | '-' | ':' | '[' | ',') => {
+                quote = Some(c)
+            }
+            (Some('"'), '\\') => {
+                chars.next();
+            }
+            (Some(open), c) if c == open => quote = None,
+            (None, '#') if previous == ' ' => return &text[..at],
+            _ => {}
+        }
+        previous = c;
+    }
+    text
+}
+
+/// Reads the scalar or flow collection `text`, found at `offset` in `line`.
+fn scalar(line: &Line, text: &str, offset: usize, typed: bool) -> Result<Value, YamlError> {
+    match text {
+        "[]" => return Ok(Value::Array(Vec::new())),
+        "{}" => return Ok(Value::Object(Map::new())),
+        _ => {}
+    }
+    if let Some(inner) = text.strip_prefix('[') {
+        let inner = inner
+            .strip_suffix(']')
+            .ok_or_else(|| line.error(offset, "unterminated flow sequence"))?;
+        let mut items = Vec::new();
+        for item in split_flow(inner) {
+            match scalar(line, item.trim(), offset, typed)? {
+                Value::Array(_) | Value::Object(_) => {
+                    return Err(line.error(offset, "nested flow collections are not supported"));
+                }
+                item => items.push(item),
+            }
+        }
+        return Ok(Value::Array(items));
+    }
+    if text.starts_with('{') {
+        return Err(line.error(offset, "flow mappings are not supported"));
+    }
+    if let Some(inner) = text.strip_prefix('\'') {
+        let inner = inner
+            .strip_suffix('\'')
+            .filter(|_| text.len() > 1)
+            .ok_or_else(|| line.error(offset, "unterminated single-quoted string"))?;
+        return Ok(Value::String(inner.replace("''", "'")));
+    }
+    if let Some(inner) = text.strip_prefix('"') {
+        let inner = inner
+            .strip_suffix('"')
+            .filter(|_| text.len() > 1)
+            .ok_or_else(|| line.error(offset, "unterminated double-quoted string"))?;
+        return unescape(inner)
+            .map(Value::String)
+            .ok_or_else(|| line.error(offset, "invalid escape sequence"));
+    }
+    Ok(if typed {
+        plain(text)
+    } else {
+        Value::String(text.to_string())
+    })
+}
+
+/// Reads a plain scalar as a boolean, null, an integer or a float if it looks like one,
+/// and as a string otherwise.
+fn plain(text: &str) -> Value {
+    match text {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        "null" | "~" => return Value::Null,
+        _ => {}
+    }
+    let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+    if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+        return Value::String(text.to_string());
+    }
+    if let Ok(integer) = text.parse::<i64>() {
+        return Value::from(integer);
+    }
+    text.parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map_or_else(|| Value::String(text.to_string()), Value::Number)
+}
+
+/// Splits the content of a flow sequence at commas outside quotes.
+fn split_flow(inner: &str) -> Vec<&str> {
+    if inner.trim().is_empty() {
+        return Vec::new();
+    }
+    let mut items = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    let mut chars = inner.char_indices();
+    while let Some((at, c)) = chars.next() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some('"'), '\\') => {
+                chars.next();
+            }
+            (Some(open), c) if c == open => quote = None,
+            (None, ',') => {
+                items.push(&inner[start..at]);
+                start = at + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&inner[start..]);
+    items
+}
+
+/// Resolves the escape sequences of a double-quoted string.
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        unescaped.push(match chars.next()? {
+            '0' => '\0',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            ' ' => ' ',
+            'x' => hex(&mut chars, 2)?,
+            'u' => hex(&mut chars, 4)?,
+            'U' => hex(&mut chars, 8)?,
+            _ => return None,
+        });
+    }
+    Some(unescaped)
+}
+
+fn hex(chars: &mut std::str::Chars<'_>, digits: usize) -> Option<char> {
+    let code: String = chars.by_ref().take(digits).collect();
+    if code.len() != digits {
+        return None;
+    }
+    char::from_u32(u32::from_str_radix(&code, 16).ok()?)
+}
+
+/// Writes strings, arrays and objects as block-style YAML. Other values are written as
+/// their JSON text.
+pub(crate) fn write(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) if !map.is_empty() => write_mapping(&mut out, map, 0, false),
+        Value::Array(items) if !items.is_empty() => write_sequence(&mut out, items, 0),
+        value => {
+            out.push_str(&inline(value));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Writes `map` indented by `indent`. The first key of a sequence entry follows the
+/// dash, so it is not indented if `after_dash` is set.
+fn write_mapping(out: &mut String, map: &Map<String, Value>, indent: usize, after_dash: bool) {
+    for (index, (key, value)) in map.iter().enumerate() {
+        if index > 0 || !after_dash {
+            out.push_str(&" ".repeat(indent));
+        }
+        out.push_str(&quote(key));
+        out.push(':');
+        write_value(out, value, indent);
+    }
+}
+
+fn write_sequence(out: &mut String, items: &[Value], indent: usize) {
+    for item in items {
+        out.push_str(&" ".repeat(indent));
+        out.push('-');
+        match item {
+            Value::Object(map) if !map.is_empty() => {
+                out.push(' ');
+                write_mapping(out, map, indent + 2, true);
+            }
+            Value::Array(items) if !items.is_empty() => {
+                out.push('\n');
+                write_sequence(out, items, indent + 2);
+            }
+            item => {
+                out.push(' ');
+                out.push_str(&inline(item));
+                out.push('\n');
+            }
+        }
+    }
+}
+
+/// Writes the value of a key, after its `:`.
+fn write_value(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            out.push('\n');
+            write_mapping(out, map, indent + 2, false);
+        }
+        Value::Array(items) if !items.is_empty() => {
+            out.push('\n');
+            write_sequence(out, items, indent + 2);
+        }
+        value => {
+            out.push(' ');
+            out.push_str(&inline(value));
+            out.push('\n');
+        }
+    }
+}
+
+fn inline(value: &Value) -> String {
+    match value {
+        Value::String(text) => quote(text),
+        Value::Array(_) => String::from("[]"),
+        Value::Object(_) => String::from("{}"),
+        value => value.to_string(),
+    }
+}
+
+/// `text` as a plain scalar if it reads back unchanged in any YAML tool, and as a
+/// double-quoted string otherwise.
+fn quote(text: &str) -> String {
+    let plain = !text.is_empty()
+        && text.trim() == text
+        && !text.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        && !text.contains(": ")
+        && !text.contains(" #")
+        && !text.ends_with(':')
+        && !text.chars().any(char::is_control)
+        && !is_special(text);
+    if plain {
+        return text.to_string();
+    }
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Whether YAML 1.1 or 1.2 tools read the plain scalar `text` as something other than
+/// a string.
+fn is_special(text: &str) -> bool {
+    let lower = text.to_ascii_lowercase();
+    matches!(
+        lower.as_str(),
+        "~" | "null"
+            | "true"
+            | "false"
+            | "yes"
+            | "no"
+            | "on"
+            | "off"
+            | "y"
+            | "n"
+            | ".nan"
+            | ".inf"
+            | "-.inf"
+            | "+.inf"
+    ) || text.starts_with(|c: char| c.is_ascii_digit() || c == '+' || c == '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn error(input: &str) -> String {
+        parse(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn reads_block_collections_and_scalars() {
+        let input = "\
+\u{feff}--- # batch
+name: 'It''s 4711'
+documents:
+- name: \"Invoice \\u00e4\\t#1\"
+  fields:
+    No: 0042 # kept as written
+    Total: 1.50
+    Note: a # b
+    Url: http://host/a#b
+  tags: [a, 'b, c', \"d\"]
+  files: []
+  meta: {}
+  empty:
+-
+  - nested
+...
+";
+        assert_eq!(
+            parse(input).unwrap(),
+            json!({
+                "name": "It's 4711",
+                "documents": [
+                    {
+                        "name": "Invoice ä\t#1",
+                        "fields": {
+                            "No": "0042",
+                            "Total": "1.50",
+                            "Note": "a",
+                            "Url": "http://host/a#b"
+                        },
+                        "tags": ["a", "b, c", "d"],
+                        "files": [],
+                        "meta": {},
+                        "empty": null
+                    },
+                    ["nested"]
+                ]
+            })
+        );
+        assert_eq!(parse("# only a comment\n").unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn typed_parsing_reads_plain_scalars() {
+        let input = "port: 8443\nratio: -1.5\non: true\nproxy: ~\nname: '8443'\nhost: 10.0.0.1\n";
+        assert_eq!(
+            parse_typed(input).unwrap(),
+            json!({
+                "port": 8443,
+                "ratio": -1.5,
+                "on": true,
+                "proxy": null,
+                "name": "8443",
+                "host": "10.0.0.1"
+            })
+        );
+        assert_eq!(parse_typed("1: a").unwrap(), json!({ "1": "a" }));
+    }
+
+    #[test]
+    fn reports_the_position_of_errors() {
+        assert_eq!(
+            error("a: 1\n\tb: 2\n"),
+            "line 2, column 1: tabs are not allowed in indentation"
+        );
+        assert_eq!(error("a: 1\na: 2\n"), "line 2, column 1: duplicate key 'a'");
+        assert_eq!(
+            error("a:\n  b: 1\n c: 2\n"),
+            "line 3, column 2: unexpected indentation"
+        );
+        assert_eq!(
+            error("a: 1\nplain\n"),
+            "line 2, column 1: expected a key followed by ':'"
+        );
+        assert_eq!(
+            error("a: 'open\n"),
+            "line 1, column 4: unterminated single-quoted string"
+        );
+        assert_eq!(
+            error("a: \"\\q\"\n"),
+            "line 1, column 4: invalid escape sequence"
+        );
+        assert_eq!(
+            error("a: [1, [2]]\n"),
+            "line 1, column 4: nested flow collections are not supported"
+        );
+        assert_eq!(
+            error("a: [1, 2\n"),
+            "line 1, column 4: unterminated flow sequence"
+        );
+        assert_eq!(
+            error("a: {b: 1}\n"),
+            "line 1, column 4: flow mappings are not supported"
+        );
+    }
+
+    #[test]
+    fn written_documents_read_back_unchanged() {
+        let value = json!({
+            "name": "Batch: 4711",
+            "documents": [
+                {
+                    "fields": { "No": "0042", "Flag": "yes", "Note": "a #b", "Empty": "" },
+                    "lines": ["-1", "two\nlines", "tab\there", "quote \" \\"],
+                    "files": [],
+                    "meta": {}
+                },
+                [["deep"]]
+            ]
+        });
+        let written = write(&value);
+        assert!(written.contains("\"No\": \"0042\"\n"));
+        assert!(written.contains("Flag: \"yes\"\n"));
+        assert_eq!(parse(&written).unwrap(), value);
+    }
+}